@@ -15,8 +15,8 @@ where
     RB: DataAPI<Data = Storage<f64, BB>>,
     DA: DimAPI,
     DB: DimAPI,
-    BA: DeviceAPI<f64, RawVec = Vec<f64>>,
-    BB: DeviceAPI<f64, RawVec = Vec<f64>>,
+    BA: DeviceAPI<f64, RawVec = CpuRawVec<f64>>,
+    BB: DeviceAPI<f64, RawVec = CpuRawVec<f64>>,
 {
     let la = a.layout().reverse_axes();
     let lb = b.layout().reverse_axes();
@@ -25,8 +25,8 @@ where
     }
     let it_la = IterLayoutColMajor::new(&la).unwrap();
     let it_lb = IterLayoutColMajor::new(&lb).unwrap();
-    let data_a = a.data().storage().rawvec();
-    let data_b = b.data().storage().rawvec();
+    let data_a = a.data().storage().rawvec().as_raw_slice();
+    let data_b = b.data().storage().rawvec().as_raw_slice();
     let atol = 1e-8;
     let rtol = 1e-5;
     for (idx_a, idx_b) in izip!(it_la, it_lb) {
@@ -0,0 +1,4 @@
+use crate::device_cpu_serial::assignment::*;
+use crate::prelude_dev::*;
+
+impl_assignment_cpu_serial!(DeviceCpuCounting, record);
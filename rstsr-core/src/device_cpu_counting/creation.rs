@@ -0,0 +1,91 @@
+use crate::prelude_dev::*;
+use num::{complex::ComplexFloat, Float, Num};
+
+impl DeviceCpuCounting {
+    fn wrap_alloc<T>(
+        &self,
+        name: &'static str,
+        storage: Storage<T, DeviceCpuSerial>,
+    ) -> Storage<T, DeviceCpuCounting>
+    where
+        T: Clone,
+    {
+        let bytes = storage.rawvec().len() * core::mem::size_of::<T>();
+        self.record(DeviceCallKind::Alloc, name, vec![], bytes);
+        storage_from_serial(storage, self)
+    }
+}
+
+impl<T> DeviceCreationAnyAPI<T> for DeviceCpuCounting
+where
+    T: Clone + Debug,
+{
+    unsafe fn empty_impl(&self, len: usize) -> Result<Storage<T, DeviceCpuCounting>> {
+        let storage = self.inner().empty_impl(len)?;
+        Ok(self.wrap_alloc("empty_impl", storage))
+    }
+
+    fn full_impl(&self, len: usize, fill: T) -> Result<Storage<T, DeviceCpuCounting>> {
+        let storage = self.inner().full_impl(len, fill)?;
+        Ok(self.wrap_alloc("full_impl", storage))
+    }
+
+    fn outof_cpu_vec(&self, vec: Vec<T>) -> Result<Storage<T, DeviceCpuCounting>> {
+        // takes ownership without copy
+        let bytes = vec.len() * core::mem::size_of::<T>();
+        self.record(DeviceCallKind::Conversion, "outof_cpu_vec", vec![], bytes);
+        let storage = self.inner().outof_cpu_vec(vec)?;
+        Ok(storage_from_serial(storage, self))
+    }
+
+    fn from_cpu_vec(&self, vec: &[T]) -> Result<Storage<T, DeviceCpuCounting>> {
+        let storage = self.inner().from_cpu_vec(vec)?;
+        Ok(self.wrap_alloc("from_cpu_vec", storage))
+    }
+}
+
+impl<T> DeviceCreationNumAPI<T> for DeviceCpuCounting
+where
+    T: Num + Clone + Debug,
+{
+    fn zeros_impl(&self, len: usize) -> Result<Storage<T, DeviceCpuCounting>> {
+        let storage = self.inner().zeros_impl(len)?;
+        Ok(self.wrap_alloc("zeros_impl", storage))
+    }
+
+    fn ones_impl(&self, len: usize) -> Result<Storage<T, DeviceCpuCounting>> {
+        let storage = self.inner().ones_impl(len)?;
+        Ok(self.wrap_alloc("ones_impl", storage))
+    }
+
+    fn arange_int_impl(&self, len: usize) -> Result<Storage<T, DeviceCpuCounting>> {
+        let storage = self.inner().arange_int_impl(len)?;
+        Ok(self.wrap_alloc("arange_int_impl", storage))
+    }
+}
+
+impl<T> DeviceCreationComplexFloatAPI<T> for DeviceCpuCounting
+where
+    T: ComplexFloat + Clone + Debug,
+{
+    fn linspace_impl(
+        &self,
+        start: T,
+        end: T,
+        n: usize,
+        endpoint: bool,
+    ) -> Result<Storage<T, DeviceCpuCounting>> {
+        let storage = self.inner().linspace_impl(start, end, n, endpoint)?;
+        Ok(self.wrap_alloc("linspace_impl", storage))
+    }
+}
+
+impl<T> DeviceCreationFloatAPI<T> for DeviceCpuCounting
+where
+    T: Float + Clone + Debug,
+{
+    fn arange_impl(&self, start: T, end: T, step: T) -> Result<Storage<T, DeviceCpuCounting>> {
+        let storage = self.inner().arange_impl(start, end, step)?;
+        Ok(self.wrap_alloc("arange_impl", storage))
+    }
+}
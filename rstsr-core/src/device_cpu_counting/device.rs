@@ -0,0 +1,280 @@
+extern crate alloc;
+
+use crate::prelude_dev::*;
+use alloc::rc::Rc;
+use core::cell::RefCell;
+
+/// Category of device call recorded by [`DeviceCpuCounting`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeviceCallKind {
+    /// Storage creation (`DeviceCreation*API`, `DeviceStorageAPI::new`).
+    Alloc,
+    /// Assignment (`OpAssignAPI`, `OpAssignArbitaryAPI`).
+    Assign,
+    /// Elementwise operation (`DeviceOp_*_API`).
+    Op,
+    /// Reduction (`OpSumAPI`).
+    Reduce,
+    /// Matrix multiplication (`DeviceMatMulAPI`, `DeviceGEMMAPI`, etc.).
+    MatMul,
+    /// Storage moved between devices (`DeviceStorageConversionAPI`).
+    Conversion,
+    /// Storage copied (`Clone` of storage, `DeviceStorageAPI::to_cpu_vec`).
+    Copy,
+}
+
+/// One device call recorded by [`DeviceCpuCounting`].
+#[derive(Clone, Debug)]
+pub struct DeviceCallRecord {
+    pub kind: DeviceCallKind,
+    /// Name of the trait method, such as `op_mutc_refa_refb_func` or `gemm`.
+    pub name: &'static str,
+    /// Layouts of arguments; the output (or mutated) layout comes first.
+    pub layouts: Vec<Layout<IxD>>,
+    /// Number of bytes allocated, moved or copied; zero for computation.
+    pub bytes: usize,
+}
+
+/// CPU device that records every device call, for testing backend-agnostic
+/// code.
+///
+/// All computation is delegated to the wrapped [`DeviceCpuSerial`]. Clones of
+/// the device share the same log, so calls made by tensors created from this
+/// device (and their results) are all visible from any handle.
+///
+/// ```
+/// use rstsr::device_cpu_counting::{DeviceCallKind, DeviceCpuCounting};
+/// use rstsr::Tensor;
+///
+/// let device = DeviceCpuCounting::new();
+/// let a = Tensor::<f64, _, _>::zeros([2, 3], &device);
+/// let b = Tensor::<f64, _, _>::ones([2, 3], &device);
+/// device.reset();
+/// let _c = &a + &b;
+/// assert_eq!(device.count(DeviceCallKind::Alloc), 1);
+/// assert_eq!(device.count(DeviceCallKind::Op), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DeviceCpuCounting {
+    inner: DeviceCpuSerial,
+    log: Rc<RefCell<Vec<DeviceCallRecord>>>,
+}
+
+impl DeviceCpuCounting {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The serial device that performs computation.
+    pub fn inner(&self) -> &DeviceCpuSerial {
+        &self.inner
+    }
+
+    /// Records of all device calls since creation or last
+    /// [`reset`](Self::reset).
+    pub fn log(&self) -> Vec<DeviceCallRecord> {
+        self.log.borrow().clone()
+    }
+
+    /// Number of device calls of `kind`.
+    pub fn count(&self, kind: DeviceCallKind) -> usize {
+        self.log.borrow().iter().filter(|r| r.kind == kind).count()
+    }
+
+    /// Number of device calls with method name `name`.
+    pub fn count_name(&self, name: &str) -> usize {
+        self.log.borrow().iter().filter(|r| r.name == name).count()
+    }
+
+    /// Total bytes allocated by creation calls.
+    pub fn bytes_allocated(&self) -> usize {
+        let log = self.log.borrow();
+        log.iter().filter(|r| r.kind == DeviceCallKind::Alloc).map(|r| r.bytes).sum()
+    }
+
+    /// Clear the log.
+    pub fn reset(&self) {
+        self.log.borrow_mut().clear();
+    }
+
+    pub(crate) fn record(
+        &self,
+        kind: DeviceCallKind,
+        name: &'static str,
+        layouts: Vec<Layout<IxD>>,
+        bytes: usize,
+    ) {
+        self.log.borrow_mut().push(DeviceCallRecord { kind, name, layouts, bytes });
+    }
+}
+
+impl DeviceBaseAPI for DeviceCpuCounting {
+    fn same_device(&self, _other: &Self) -> bool {
+        // all handles compute on the same CPU; log is not part of identity
+        true
+    }
+}
+
+impl DeviceMemoryAPI for DeviceCpuCounting {
+    fn memory(&self) -> &DeviceMemory {
+        self.inner.memory()
+    }
+
+    fn memory_mut(&mut self) -> &mut DeviceMemory {
+        self.inner.memory_mut()
+    }
+}
+
+impl<T> DeviceRawVecAPI<T> for DeviceCpuCounting
+where
+    T: Clone,
+{
+    type RawVec = CpuRawVec<T>;
+}
+
+impl<T> DeviceStorageAPI<T> for DeviceCpuCounting
+where
+    T: Clone,
+{
+    fn new(vector: Self::RawVec, device: Self) -> Storage<T, Self> {
+        let bytes = vector.len() * core::mem::size_of::<T>();
+        device.record(DeviceCallKind::Alloc, "new", vec![], bytes);
+        let device = device.acquire_memory_unchecked::<T>(vector.len());
+        Storage::<T, Self> { rawvec: vector, device }
+    }
+
+    fn clone_storage(storage: &Storage<T, Self>) -> Storage<T, Self> {
+        let bytes = storage.rawvec.len() * core::mem::size_of::<T>();
+        storage.device.record(DeviceCallKind::Copy, "clone", vec![], bytes);
+        let device = storage.device.acquire_memory_unchecked::<T>(storage.rawvec.len());
+        Storage::<T, Self> { rawvec: storage.rawvec.clone(), device }
+    }
+
+    fn try_clone_storage(storage: &Storage<T, Self>) -> Result<Storage<T, Self>> {
+        let device = storage.device.acquire_memory::<T>(storage.rawvec.len())?;
+        let bytes = storage.rawvec.len() * core::mem::size_of::<T>();
+        storage.device.record(DeviceCallKind::Copy, "try_clone", vec![], bytes);
+        Ok(Storage::<T, Self> { rawvec: storage.rawvec.clone(), device })
+    }
+
+    fn len(storage: &Storage<T, Self>) -> usize {
+        storage.rawvec.len()
+    }
+
+    fn to_cpu_vec(storage: &Storage<T, Self>) -> Result<Vec<T>> {
+        let bytes = storage.rawvec.len() * core::mem::size_of::<T>();
+        storage.device.record(DeviceCallKind::Copy, "to_cpu_vec", vec![], bytes);
+        Ok(storage.rawvec.to_vec())
+    }
+
+    fn into_cpu_vec(storage: Storage<T, Self>) -> Result<Vec<T>> {
+        let bytes = storage.rawvec.len() * core::mem::size_of::<T>();
+        storage.device.record(DeviceCallKind::Conversion, "into_cpu_vec", vec![], bytes);
+        Ok(storage.rawvec.into_vec())
+    }
+
+    #[inline]
+    fn get_index(storage: &Storage<T, Self>, index: usize) -> T {
+        storage.rawvec.as_raw_slice()[index].clone()
+    }
+
+    #[inline]
+    fn get_index_ptr(storage: &Storage<T, Self>, index: usize) -> *const T {
+        &storage.rawvec.as_raw_slice()[index] as *const T
+    }
+
+    #[inline]
+    fn get_index_mut_ptr(storage: &mut Storage<T, Self>, index: usize) -> *mut T {
+        &mut storage.rawvec.as_raw_slice_mut()[index] as *mut T
+    }
+
+    #[inline]
+    fn set_index(storage: &mut Storage<T, Self>, index: usize, value: T) {
+        storage.rawvec.as_raw_slice_mut()[index] = value;
+    }
+}
+
+impl<T> DeviceAPI<T> for DeviceCpuCounting where T: Clone {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_no_temporaries() {
+        let device = DeviceCpuCounting::new();
+        let a = Tensor::<f64, _, _>::linspace(0.0, 5.0, 6, &device);
+        let a = a.into_shape_assume_contig([2, 3]).unwrap();
+        let mut b = Tensor::<f64, _, _>::ones([2, 3], &device);
+        device.reset();
+
+        // in-place operation and view arithmetic do not allocate
+        b += &a;
+        let _ = a.view().reverse_axes();
+        assert_eq!(device.count(DeviceCallKind::Alloc), 0);
+        assert_eq!(device.count_name("op_muta_refb_func"), 1);
+
+        // binary operation allocates exactly its output
+        let _c = &a * &b;
+        assert_eq!(device.count(DeviceCallKind::Alloc), 1);
+        assert_eq!(device.bytes_allocated(), 6 * core::mem::size_of::<f64>());
+        let log = device.log();
+        let op = log.last().unwrap();
+        assert_eq!(op.name, "op_mutc_refa_refb_func");
+        assert_eq!(op.layouts.len(), 3);
+
+        // matmul dispatches to gemm with the given layouts
+        device.reset();
+        let _c = &a % &b.reverse_axes();
+        assert_eq!(device.count_name("gemm"), 1);
+        let gemm = device.log().into_iter().find(|r| r.name == "gemm").unwrap();
+        assert_eq!(
+            gemm.layouts[2].stride(),
+            b.reverse_axes().layout().to_dim::<IxD>().unwrap().stride()
+        );
+
+        // moving to another CPU device does not copy
+        device.reset();
+        let a_cpu = a.into_device(&DeviceCpuSerial::default()).unwrap();
+        assert_eq!(device.count(DeviceCallKind::Conversion), 1);
+        assert_eq!(device.count(DeviceCallKind::Copy), 0);
+        let _ = a_cpu.into_device(&device).unwrap();
+        assert_eq!(device.count(DeviceCallKind::Conversion), 2);
+        assert_eq!(device.count(DeviceCallKind::Alloc), 0);
+        assert_eq!(device.count(DeviceCallKind::Copy), 0);
+    }
+
+    #[test]
+    fn test_storage_new_is_alloc() {
+        let device = DeviceCpuCounting::new();
+        let storage = Storage::new(vec![1.0_f64; 4].into(), device.clone());
+        assert_eq!(storage.len(), 4);
+        assert_eq!(device.count(DeviceCallKind::Alloc), 1);
+        assert_eq!(device.count(DeviceCallKind::Conversion), 0);
+        assert_eq!(device.bytes_allocated(), 4 * core::mem::size_of::<f64>());
+    }
+
+    #[test]
+    fn test_storage_clone_is_copy() {
+        let device = DeviceCpuCounting::new();
+        let a = Tensor::<f64, _, _>::zeros([4], &device);
+        device.reset();
+        let _b = a.clone();
+        assert_eq!(device.count(DeviceCallKind::Alloc), 0);
+        assert_eq!(device.count(DeviceCallKind::Copy), 1);
+        assert_eq!(device.count_name("clone"), 1);
+        assert_eq!(device.log()[0].bytes, 4 * core::mem::size_of::<f64>());
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let mut device = DeviceCpuCounting::new();
+        device.set_memory_limit(Some(100));
+        let tracker = device.memory_tracker().unwrap().clone();
+        let a = Tensor::<f64, _, _>::zeros([10], &device);
+        assert_eq!(tracker.live_bytes(), 80);
+        assert!(DeviceCreationNumAPI::<f64>::zeros_impl(&device, 10).is_err());
+        drop(a);
+        assert_eq!(tracker.live_bytes(), 0);
+    }
+}
@@ -0,0 +1,12 @@
+//! Matrix multiplication for counting CPU backend.
+
+#![allow(clippy::too_many_arguments)]
+
+use core::ops::{Add, Mul};
+use num::complex::ComplexFloat;
+use num::Zero;
+
+use crate::device_cpu_serial::matmul::*;
+use crate::prelude_dev::*;
+
+impl_matmul_cpu_serial!(DeviceCpuCounting, record);
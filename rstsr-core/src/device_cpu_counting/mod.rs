@@ -0,0 +1,15 @@
+//! Backend for CPU that records device calls, for testing.
+//!
+//! This module is only compiled with feature `counting` (or in tests of this
+//! crate).
+//!
+//! Computation is delegated to
+//! [`DeviceCpuSerial`](crate::device_cpu_serial::DeviceCpuSerial).
+
+pub mod assignment;
+pub mod creation;
+pub mod device;
+pub mod matmul;
+pub mod operators;
+
+pub use device::*;
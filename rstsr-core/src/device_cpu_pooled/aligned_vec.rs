@@ -0,0 +1,365 @@
+//! Aligned vector with buffers from caching pool.
+
+extern crate std;
+
+use crate::prelude_dev::*;
+use core::alloc::Layout as AllocLayout;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+use std::alloc::{alloc, dealloc, handle_alloc_error};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Alignment (in bytes) of buffers of [`AlignedVec`].
+pub const CPU_ALIGNMENT: usize = 64;
+
+/* #region buffer pool */
+
+#[derive(Debug)]
+struct Block(NonNull<u8>);
+
+// SAFETY: blocks in pool are not referenced by anyone else
+unsafe impl Send for Block {}
+
+#[derive(Debug, Default)]
+struct BufferPoolState {
+    /// Free blocks by (size class, alignment).
+    free: BTreeMap<(usize, usize), Vec<Block>>,
+    cached_bytes: usize,
+    max_cached_bytes: Option<usize>,
+    hits: usize,
+    misses: usize,
+}
+
+/// Statistics of [`BufferPool`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BufferPoolStats {
+    /// Number of allocations served by cached buffers.
+    pub hits: usize,
+    /// Number of allocations served by system allocator.
+    pub misses: usize,
+    /// Bytes of freed buffers kept for reuse.
+    pub cached_bytes: usize,
+}
+
+/// Caching pool of aligned buffers.
+///
+/// Buffer sizes are rounded up to size classes (see [`size_class`]). Freed
+/// buffers are kept by size class, and reused by later allocations of the
+/// same size class, instead of returned to system allocator.
+#[derive(Debug, Default)]
+pub struct BufferPool {
+    state: Mutex<BufferPoolState>,
+}
+
+impl BufferPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, BufferPoolState> {
+        // pool state is always consistent, even if some thread panicked
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn stats(&self) -> BufferPoolStats {
+        let state = self.state();
+        BufferPoolStats { hits: state.hits, misses: state.misses, cached_bytes: state.cached_bytes }
+    }
+
+    /// Set maximum bytes of freed buffers kept for reuse (`None` for no
+    /// limit). Buffers freed beyond this are returned to system allocator.
+    pub fn set_max_cached_bytes(&self, max_cached_bytes: Option<usize>) {
+        self.state().max_cached_bytes = max_cached_bytes;
+    }
+
+    /// Return all cached buffers to system allocator.
+    pub fn clear(&self) {
+        let mut state = self.state();
+        for ((size, align), blocks) in core::mem::take(&mut state.free) {
+            let layout = AllocLayout::from_size_align(size, align).unwrap();
+            for block in blocks {
+                unsafe { dealloc(block.0.as_ptr(), layout) };
+            }
+        }
+        state.cached_bytes = 0;
+    }
+
+    fn allocate(&self, layout: AllocLayout) -> NonNull<u8> {
+        let key = (layout.size(), layout.align());
+        {
+            let mut state = self.state();
+            if let Some(block) = state.free.get_mut(&key).and_then(|blocks| blocks.pop()) {
+                state.cached_bytes -= layout.size();
+                state.hits += 1;
+                return block.0;
+            }
+            state.misses += 1;
+        }
+        alloc_aligned(layout)
+    }
+
+    fn release(&self, ptr: NonNull<u8>, layout: AllocLayout) {
+        let mut state = self.state();
+        let cached_bytes = state.cached_bytes + layout.size();
+        if state.max_cached_bytes.is_some_and(|max| cached_bytes > max) {
+            drop(state);
+            unsafe { dealloc(ptr.as_ptr(), layout) };
+        } else {
+            state.cached_bytes = cached_bytes;
+            state.free.entry((layout.size(), layout.align())).or_default().push(Block(ptr));
+        }
+    }
+}
+
+impl Drop for BufferPool {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Size class (in bytes) of pooled buffers of `bytes`; `None` on overflow.
+///
+/// Size classes are multiples of [`CPU_ALIGNMENT`]. Between two consecutive
+/// powers of two, there are 8 size classes, so rounding wastes less than 1/8
+/// of a buffer (or less than [`CPU_ALIGNMENT`] bytes for small buffers).
+pub fn size_class(bytes: usize) -> Option<usize> {
+    let step = (bytes.checked_next_power_of_two()? / 16).max(CPU_ALIGNMENT);
+    Some(bytes.checked_add(step - 1)? & !(step - 1))
+}
+
+fn alloc_aligned(layout: AllocLayout) -> NonNull<u8> {
+    let ptr = unsafe { alloc(layout) };
+    NonNull::new(ptr).unwrap_or_else(|| handle_alloc_error(layout))
+}
+
+/* #endregion */
+
+/* #region aligned vec */
+
+/// Fixed-length vector, whose buffer is aligned to [`CPU_ALIGNMENT`] bytes and
+/// optionally comes from (and returns to) a [`BufferPool`].
+pub struct AlignedVec<T> {
+    ptr: NonNull<T>,
+    len: usize,
+    cap: usize,
+    pool: Option<Arc<BufferPool>>,
+}
+
+// SAFETY: AlignedVec owns its elements, similar to Vec
+unsafe impl<T: Send> Send for AlignedVec<T> {}
+unsafe impl<T: Sync> Sync for AlignedVec<T> {}
+
+impl<T> AlignedVec<T> {
+    /// Allocation layout of buffer of `cap` elements; `None` if no allocation
+    /// is required.
+    ///
+    /// Buffers from pool are rounded up to size class, so that they can be
+    /// reused by allocations of similar sizes; otherwise the exact size is
+    /// allocated.
+    fn buffer_layout(cap: usize, pooled: bool) -> Option<AllocLayout> {
+        let bytes = core::mem::size_of::<T>().checked_mul(cap).expect("capacity overflow");
+        if bytes == 0 {
+            return None;
+        }
+        let size = match pooled {
+            true => size_class(bytes).expect("capacity overflow"),
+            false => bytes,
+        };
+        let align = CPU_ALIGNMENT.max(core::mem::align_of::<T>());
+        return Some(AllocLayout::from_size_align(size, align).expect("capacity overflow"));
+    }
+
+    pub fn new() -> Self {
+        AlignedVec { ptr: NonNull::dangling(), len: 0, cap: 0, pool: None }
+    }
+
+    /// Empty vector with capacity of `cap` elements, with buffer from `pool`
+    /// (or system allocator if `None`).
+    pub fn with_capacity_in(cap: usize, pool: Option<Arc<BufferPool>>) -> Self {
+        let ptr = match Self::buffer_layout(cap, pool.is_some()) {
+            None => NonNull::dangling(),
+            Some(layout) => match &pool {
+                Some(pool) => pool.allocate(layout).cast(),
+                None => alloc_aligned(layout).cast(),
+            },
+        };
+        AlignedVec { ptr, len: 0, cap, pool }
+    }
+
+    /// Vector of `len` elements generated by `f(index)`.
+    pub fn from_fn_in(
+        len: usize,
+        pool: Option<Arc<BufferPool>>,
+        mut f: impl FnMut(usize) -> T,
+    ) -> Self {
+        let mut vec = Self::with_capacity_in(len, pool);
+        for i in 0..len {
+            // length is updated per element, so only initialized elements are
+            // dropped if `f` panics
+            unsafe { vec.ptr.as_ptr().add(i).write(f(i)) };
+            vec.len = i + 1;
+        }
+        return vec;
+    }
+
+    pub fn from_slice_in(slc: &[T], pool: Option<Arc<BufferPool>>) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn_in(slc.len(), pool, |i| slc[i].clone())
+    }
+
+    pub fn from_vec_in(vec: Vec<T>, pool: Option<Arc<BufferPool>>) -> Self {
+        let len = vec.len();
+        let mut iter = vec.into_iter();
+        Self::from_fn_in(len, pool, |_| iter.next().unwrap())
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// # Safety
+    ///
+    /// `len` must not exceed capacity, and elements in `old_len..len` must be
+    /// initialized (or never read if `T` has no drop glue).
+    pub unsafe fn set_len(&mut self, len: usize) {
+        self.len = len;
+    }
+
+    pub fn pool(&self) -> Option<&Arc<BufferPool>> {
+        self.pool.as_ref()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T> Drop for AlignedVec<T> {
+    fn drop(&mut self) {
+        unsafe { core::ptr::drop_in_place(self.as_mut_slice()) };
+        if let Some(layout) = Self::buffer_layout(self.cap, self.pool.is_some()) {
+            let ptr = self.ptr.cast::<u8>();
+            match &self.pool {
+                Some(pool) => pool.release(ptr, layout),
+                None => unsafe { dealloc(ptr.as_ptr(), layout) },
+            }
+        }
+    }
+}
+
+impl<T> Default for AlignedVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for AlignedVec<T> {
+    fn clone(&self) -> Self {
+        Self::from_slice_in(self, self.pool.clone())
+    }
+}
+
+impl<T> Deref for AlignedVec<T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> DerefMut for AlignedVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T> AsRef<[T]> for AlignedVec<T> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> AsMut<[T]> for AlignedVec<T> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T> CpuRawVecAPI<T> for AlignedVec<T> {
+    fn as_raw_slice(&self) -> CpuRawSlice<'_, T> {
+        CpuRawSlice::from(self.as_slice())
+    }
+
+    fn as_raw_slice_mut(&mut self) -> CpuRawSliceMut<'_, T> {
+        CpuRawSliceMut::from(self.as_mut_slice())
+    }
+}
+
+impl<T: Debug> Debug for AlignedVec<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(self.as_slice(), f)
+    }
+}
+
+/* #endregion */
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_aligned_vec_pool() {
+        let pool = Arc::new(BufferPool::new());
+        let a = AlignedVec::<f64>::from_fn_in(100, Some(pool.clone()), |i| i as f64);
+        assert_eq!(a.as_ptr() as usize % CPU_ALIGNMENT, 0);
+        assert_eq!(a[99], 99.0);
+        let ptr_a = a.as_ptr();
+        drop(a);
+        // 800 bytes rounded to size class of 832 bytes
+        assert_eq!(pool.stats(), BufferPoolStats { hits: 0, misses: 1, cached_bytes: 832 });
+
+        // same size class reuses buffer
+        let b = AlignedVec::<f32>::from_fn_in(200, Some(pool.clone()), |_| 1.0);
+        assert_eq!(b.as_ptr() as usize, ptr_a as usize);
+        assert_eq!(pool.stats(), BufferPoolStats { hits: 1, misses: 1, cached_bytes: 0 });
+        let c = b.clone();
+        assert_eq!(c.as_slice(), b.as_slice());
+        drop((b, c));
+        assert_eq!(pool.stats().cached_bytes, 1664);
+
+        pool.set_max_cached_bytes(Some(0));
+        pool.clear();
+        let d: AlignedVec<u8> = AlignedVec::from_vec_in(vec![1, 2, 3], Some(pool.clone()));
+        drop(d);
+        assert_eq!(pool.stats().cached_bytes, 0);
+    }
+
+    #[test]
+    fn test_size_class() {
+        assert_eq!(size_class(1), Some(64));
+        assert_eq!(size_class(64), Some(64));
+        assert_eq!(size_class(65), Some(128));
+        assert_eq!(size_class(1024), Some(1024));
+        assert_eq!(size_class(1025), Some(1152));
+        assert_eq!(size_class(usize::MAX), None);
+        for bytes in (1..1 << 20).step_by(97) {
+            let class = size_class(bytes).unwrap();
+            assert_eq!(class % CPU_ALIGNMENT, 0);
+            assert!(class >= bytes);
+            assert!(class - bytes < (bytes / 8).max(CPU_ALIGNMENT));
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::prelude_dev::*;
+use num::{complex::ComplexFloat, Float, Num};
+
+impl<T> DeviceCreationAnyAPI<T> for DeviceCpuPooled
+where
+    T: Clone + Debug,
+{
+    unsafe fn empty_impl(&self, len: usize) -> Result<Storage<T, Self>> {
+        let device = self.acquire_memory::<T>(len)?;
+        let mut rawvec = AlignedVec::with_capacity_in(len, self.pool().cloned());
+        unsafe { rawvec.set_len(len) };
+        Ok(Storage { rawvec, device })
+    }
+
+    fn full_impl(&self, len: usize, fill: T) -> Result<Storage<T, Self>> {
+        let device = self.acquire_memory::<T>(len)?;
+        let rawvec = AlignedVec::from_fn_in(len, self.pool().cloned(), |_| fill.clone());
+        Ok(Storage { rawvec, device })
+    }
+
+    fn outof_cpu_vec(&self, vec: Vec<T>) -> Result<Storage<T, Self>> {
+        let device = self.acquire_memory::<T>(vec.len())?;
+        let rawvec = AlignedVec::from_vec_in(vec, self.pool().cloned());
+        Ok(Storage { rawvec, device })
+    }
+
+    fn from_cpu_vec(&self, vec: &[T]) -> Result<Storage<T, Self>> {
+        let device = self.acquire_memory::<T>(vec.len())?;
+        let rawvec = AlignedVec::from_slice_in(vec, self.pool().cloned());
+        Ok(Storage { rawvec, device })
+    }
+}
+
+impl<T> DeviceCreationNumAPI<T> for DeviceCpuPooled
+where
+    T: Num + Clone + Debug,
+{
+    fn zeros_impl(&self, len: usize) -> Result<Storage<T, Self>> {
+        let device = self.acquire_memory::<T>(len)?;
+        let rawvec = AlignedVec::from_fn_in(len, self.pool().cloned(), |_| T::zero());
+        Ok(Storage { rawvec, device })
+    }
+
+    fn ones_impl(&self, len: usize) -> Result<Storage<T, Self>> {
+        let device = self.acquire_memory::<T>(len)?;
+        let rawvec = AlignedVec::from_fn_in(len, self.pool().cloned(), |_| T::one());
+        Ok(Storage { rawvec, device })
+    }
+
+    fn arange_int_impl(&self, len: usize) -> Result<Storage<T, Self>> {
+        let device = self.acquire_memory::<T>(len)?;
+        let mut v = T::zero();
+        let rawvec = AlignedVec::from_fn_in(len, self.pool().cloned(), |_| {
+            let next = v.clone() + T::one();
+            core::mem::replace(&mut v, next)
+        });
+        Ok(Storage { rawvec, device })
+    }
+}
+
+/// Copy storage created by serial device into buffer from pool, keeping its
+/// accounted bytes.
+fn storage_from_serial_pooled<T>(
+    storage: Storage<T, DeviceCpuSerial>,
+    device: &DeviceCpuPooled,
+) -> Storage<T, DeviceCpuPooled>
+where
+    T: Clone,
+{
+    let Storage { rawvec, device: mut serial } = storage;
+    let mut device = device.clone();
+    *device.memory_mut() = core::mem::take(serial.memory_mut());
+    let rawvec = AlignedVec::from_vec_in(rawvec.into_vec(), device.pool().cloned());
+    Storage { rawvec, device }
+}
+
+// for creation of ranges, we use functions from DeviceCpuSerial
+impl<T> DeviceCreationComplexFloatAPI<T> for DeviceCpuPooled
+where
+    T: ComplexFloat + Clone + Debug,
+{
+    fn linspace_impl(
+        &self,
+        start: T,
+        end: T,
+        n: usize,
+        endpoint: bool,
+    ) -> Result<Storage<T, Self>> {
+        let storage =
+            DeviceCpuSerial::with_memory_of(self).linspace_impl(start, end, n, endpoint)?;
+        Ok(storage_from_serial_pooled(storage, self))
+    }
+}
+
+impl<T> DeviceCreationFloatAPI<T> for DeviceCpuPooled
+where
+    T: Float + Clone + Debug,
+{
+    fn arange_impl(&self, start: T, end: T, step: T) -> Result<Storage<T, Self>> {
+        let storage = DeviceCpuSerial::with_memory_of(self).arange_impl(start, end, step)?;
+        Ok(storage_from_serial_pooled(storage, self))
+    }
+}
@@ -0,0 +1,177 @@
+extern crate std;
+
+use crate::prelude_dev::*;
+use std::sync::Arc;
+
+/// CPU device with 64-byte aligned storage, whose buffers are reused through
+/// a caching pool.
+///
+/// Clones of the device share the same [`BufferPool`]. Storages freed are
+/// returned to the pool, so temporaries of the same size in iterative
+/// algorithms do not hit the system allocator after the first iteration.
+///
+/// Computation is performed by serial CPU routines, the same as
+/// [`DeviceCpuSerial`].
+#[derive(Clone, Debug)]
+pub struct DeviceCpuPooled {
+    /// `None` for aligned allocation without caching.
+    pool: Option<Arc<BufferPool>>,
+    memory: DeviceMemory,
+}
+
+impl DeviceCpuPooled {
+    /// Device with a new buffer pool.
+    pub fn new() -> Self {
+        Self::with_pool(Some(Arc::new(BufferPool::new())))
+    }
+
+    /// Device on buffer pool `pool`, or without caching if `None`.
+    pub fn with_pool(pool: Option<Arc<BufferPool>>) -> Self {
+        DeviceCpuPooled { pool, memory: DeviceMemory::default() }
+    }
+
+    pub fn pool(&self) -> Option<&Arc<BufferPool>> {
+        self.pool.as_ref()
+    }
+}
+
+impl Default for DeviceCpuPooled {
+    fn default() -> Self {
+        DeviceCpuPooled::new()
+    }
+}
+
+impl DeviceBaseAPI for DeviceCpuPooled {
+    fn same_device(&self, other: &Self) -> bool {
+        match (&self.pool, &other.pool) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl DeviceMemoryAPI for DeviceCpuPooled {
+    fn memory(&self) -> &DeviceMemory {
+        &self.memory
+    }
+
+    fn memory_mut(&mut self) -> &mut DeviceMemory {
+        &mut self.memory
+    }
+}
+
+impl<T> DeviceRawVecAPI<T> for DeviceCpuPooled
+where
+    T: Clone,
+{
+    type RawVec = AlignedVec<T>;
+}
+
+impl<T> DeviceStorageAPI<T> for DeviceCpuPooled
+where
+    T: Clone,
+{
+    fn new(vector: Self::RawVec, device: Self) -> Storage<T, Self> {
+        let device = device.acquire_memory_unchecked::<T>(vector.len());
+        Storage::<T, Self> { rawvec: vector, device }
+    }
+
+    fn len(storage: &Storage<T, Self>) -> usize {
+        storage.rawvec.len()
+    }
+
+    fn to_cpu_vec(storage: &Storage<T, Self>) -> Result<Vec<T>> {
+        Ok(storage.rawvec.to_vec())
+    }
+
+    fn into_cpu_vec(storage: Storage<T, Self>) -> Result<Vec<T>> {
+        // buffer is returned to pool after copy
+        Ok(storage.rawvec.to_vec())
+    }
+
+    #[inline]
+    fn get_index(storage: &Storage<T, Self>, index: usize) -> T {
+        storage.rawvec.as_raw_slice()[index].clone()
+    }
+
+    #[inline]
+    fn get_index_ptr(storage: &Storage<T, Self>, index: usize) -> *const T {
+        &storage.rawvec.as_raw_slice()[index] as *const T
+    }
+
+    #[inline]
+    fn get_index_mut_ptr(storage: &mut Storage<T, Self>, index: usize) -> *mut T {
+        &mut storage.rawvec.as_raw_slice_mut()[index] as *mut T
+    }
+
+    #[inline]
+    fn set_index(storage: &mut Storage<T, Self>, index: usize, value: T) {
+        storage.rawvec.as_raw_slice_mut()[index] = value;
+    }
+}
+
+impl<T> DeviceAPI<T> for DeviceCpuPooled where T: Clone {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pooled_temporaries() {
+        let device = DeviceCpuPooled::new();
+        let pool = device.pool().unwrap().clone();
+        let a = Tensor::<f64, _, _>::linspace(0.0, 1.0, 100, &device);
+        let mut x = Tensor::<f64, _, _>::zeros([100], &device);
+        assert_eq!(a.data().storage().rawvec().as_ptr() as usize % CPU_ALIGNMENT, 0);
+
+        // temporaries of iterations are served by pool after first iteration
+        for _ in 0..5 {
+            let t = &a * 2.0 + &x;
+            x = &t - &a;
+        }
+        // two temporaries per iteration; only `a`, `x` and temporaries of the
+        // first iteration are allocated by system allocator
+        let stats = pool.stats();
+        assert_eq!(stats.misses, 4);
+        assert_eq!(stats.hits, 8);
+
+        // conversion from vec-based device allocates from pool
+        let x_cpu = x.into_device(&DeviceCpuSerial::default()).unwrap();
+        let x = x_cpu.into_device(&device).unwrap();
+        assert_eq!(x.data().storage().rawvec().pool().map(Arc::as_ptr), Some(Arc::as_ptr(&pool)));
+        assert_eq!(pool.stats().hits, 9);
+        let a = a.into_device(&DeviceCpuSerial::default()).unwrap();
+        let a = a.into_device(&device).unwrap();
+        let (x, a) = (x.data().storage().rawvec(), a.data().storage().rawvec());
+        assert!(x.iter().zip(a.iter()).all(|(x, a)| (x - 5.0 * a).abs() < 1e-10));
+    }
+
+    #[test]
+    fn test_pooled_same_device() {
+        let device = DeviceCpuPooled::new();
+        assert!(device.same_device(&device.clone()));
+        assert!(!device.same_device(&DeviceCpuPooled::new()));
+        assert!(!device.same_device(&DeviceCpuPooled::with_pool(None)));
+        assert!(DeviceCpuPooled::with_pool(None).same_device(&DeviceCpuPooled::with_pool(None)));
+
+        // storages of different pools can not be mixed
+        let a = Tensor::<f64, _, _>::zeros([4], &device);
+        let b = Tensor::<f64, _, _>::zeros([4], &DeviceCpuPooled::new());
+        assert!(crate::tensor::operators::add(&a, &b).is_err());
+    }
+
+    #[test]
+    fn test_pooled_memory_limit() {
+        let mut device = DeviceCpuPooled::new();
+        device.set_memory_limit(Some(1000));
+        let tracker = device.memory_tracker().unwrap().clone();
+        let a = Tensor::<f64, _, _>::linspace(0.0, 1.0, 100, &device);
+        assert_eq!(tracker.live_bytes(), 800);
+        assert!(DeviceCreationNumAPI::<f64>::zeros_impl(&device, 100).is_err());
+        let b = a.clone();
+        assert_eq!(tracker.live_bytes(), 1600);
+        drop((a, b));
+        assert_eq!(tracker.live_bytes(), 0);
+    }
+}
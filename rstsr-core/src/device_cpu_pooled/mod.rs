@@ -0,0 +1,14 @@
+//! Backend for CPU with 64-byte aligned buffers from a caching pool.
+//!
+//! Computation is performed by serial CPU routines, the same as
+//! [`DeviceCpuSerial`](crate::device_cpu_serial::DeviceCpuSerial).
+
+pub mod aligned_vec;
+pub mod assignment;
+pub mod creation;
+pub mod device;
+pub mod matmul;
+pub mod operators;
+
+pub use aligned_vec::*;
+pub use device::*;
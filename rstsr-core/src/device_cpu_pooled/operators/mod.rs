@@ -0,0 +1,6 @@
+pub mod op_with_func;
+
+use crate::device_cpu_serial::operators::{op_binary, op_ternary};
+
+op_binary::impl_op_binary_cpu!(DeviceCpuPooled);
+op_ternary::impl_op_ternary_cpu!(DeviceCpuPooled);
@@ -0,0 +1,5 @@
+use crate::device_cpu_serial::operators::op_with_func::*;
+use crate::prelude_dev::*;
+use num::Zero;
+
+impl_op_with_func_cpu_serial!(DeviceCpuPooled);
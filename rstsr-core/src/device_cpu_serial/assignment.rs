@@ -4,9 +4,9 @@ use crate::prelude_dev::*;
 const CONTIG_SWITCH: usize = 16;
 
 pub fn assign_arbitary_cpu_serial<T, DC, DA>(
-    c: &mut [T],
+    mut c: CpuRawSliceMut<'_, T>,
     lc: &Layout<DC>,
-    a: &[T],
+    a: CpuRawSlice<'_, T>,
     la: &Layout<DA>,
 ) -> Result<()>
 where
@@ -47,7 +47,12 @@ where
     return Ok(());
 }
 
-pub fn assign_cpu_serial<T, D>(c: &mut [T], lc: &Layout<D>, a: &[T], la: &Layout<D>) -> Result<()>
+pub fn assign_cpu_serial<T, D>(
+    mut c: CpuRawSliceMut<'_, T>,
+    lc: &Layout<D>,
+    a: CpuRawSlice<'_, T>,
+    la: &Layout<D>,
+) -> Result<()>
 where
     T: Clone,
     D: DimAPI,
@@ -72,7 +77,7 @@ where
     return Ok(());
 }
 
-pub fn fill_cpu_serial<T, D>(c: &mut [T], lc: &Layout<D>, fill: T) -> Result<()>
+pub fn fill_cpu_serial<T, D>(mut c: CpuRawSliceMut<'_, T>, lc: &Layout<D>, fill: T) -> Result<()>
 where
     T: Clone,
     D: DimAPI,
@@ -97,44 +102,70 @@ where
     return Ok(());
 }
 
-impl<T, DC, DA> OpAssignArbitaryAPI<T, DC, DA> for DeviceCpuSerial
-where
-    T: Clone,
-    DC: DimAPI,
-    DA: DimAPI,
-{
-    fn assign_arbitary(
-        &self,
-        c: &mut Storage<T, Self>,
-        lc: &Layout<DC>,
-        a: &Storage<T, Self>,
-        la: &Layout<DA>,
-    ) -> Result<()> {
-        let c = c.rawvec_mut();
-        let a = a.rawvec();
-        return assign_arbitary_cpu_serial(c, lc, a, la);
-    }
-}
+/// Implement assignment traits for a CPU device whose raw vector derefs to
+/// `[T]`, by the serial routines of this module.
+///
+/// If `$record` is given, `self.$record(kind, name, layouts, bytes)` is
+/// called before each routine (see `DeviceCpuCounting`, enabled by feature
+/// `counting`).
+macro_rules! impl_assignment_cpu_serial {
+    ($Device:ty $(, $record:ident)?) => {
+        impl<T, DC, DA> OpAssignArbitaryAPI<T, DC, DA> for $Device
+        where
+            T: Clone,
+            DC: DimAPI,
+            DA: DimAPI,
+        {
+            fn assign_arbitary(
+                &self,
+                c: &mut Storage<T, Self>,
+                lc: &Layout<DC>,
+                a: &Storage<T, Self>,
+                la: &Layout<DA>,
+            ) -> Result<()> {
+                $(
+                    let layouts = vec![lc.to_dim::<IxD>()?, la.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::Assign, "assign_arbitary", layouts, 0);
+                )?
+                let c = c.rawvec_mut().as_raw_slice_mut();
+                let a = a.rawvec().as_raw_slice();
+                return assign_arbitary_cpu_serial(c, lc, a, la);
+            }
+        }
 
-impl<T, D> OpAssignAPI<T, D> for DeviceCpuSerial
-where
-    T: Clone,
-    D: DimAPI,
-{
-    fn assign(
-        &self,
-        c: &mut Storage<T, Self>,
-        lc: &Layout<D>,
-        a: &Storage<T, Self>,
-        la: &Layout<D>,
-    ) -> Result<()> {
-        let c = c.rawvec_mut();
-        let a = a.rawvec();
-        return assign_cpu_serial(c, lc, a, la);
-    }
+        impl<T, D> OpAssignAPI<T, D> for $Device
+        where
+            T: Clone,
+            D: DimAPI,
+        {
+            fn assign(
+                &self,
+                c: &mut Storage<T, Self>,
+                lc: &Layout<D>,
+                a: &Storage<T, Self>,
+                la: &Layout<D>,
+            ) -> Result<()> {
+                $(
+                    let layouts = vec![lc.to_dim::<IxD>()?, la.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::Assign, "assign", layouts, 0);
+                )?
+                let c = c.rawvec_mut().as_raw_slice_mut();
+                let a = a.rawvec().as_raw_slice();
+                return assign_cpu_serial(c, lc, a, la);
+            }
 
-    fn fill(&self, c: &mut Storage<T, Self>, lc: &Layout<D>, fill: T) -> Result<()> {
-        let c = c.rawvec_mut();
-        return fill_cpu_serial(c, lc, fill);
-    }
+            fn fill(&self, c: &mut Storage<T, Self>, lc: &Layout<D>, fill: T) -> Result<()> {
+                $(
+                    let layouts = vec![lc.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::Assign, "fill", layouts, 0);
+                )?
+                let c = c.rawvec_mut().as_raw_slice_mut();
+                return fill_cpu_serial(c, lc, fill);
+            }
+        }
+    };
 }
+#[cfg(any(test, feature = "counting", feature = "std"))]
+pub(crate) use impl_assignment_cpu_serial;
+
+impl_assignment_cpu_serial!(DeviceCpuSerial);
@@ -4,62 +4,69 @@ use num::{complex::ComplexFloat, Float, Num};
 impl<T> DeviceCreationAnyAPI<T> for DeviceCpuSerial
 where
     T: Clone + Debug,
-    DeviceCpuSerial: DeviceRawVecAPI<T, RawVec = Vec<T>>,
+    DeviceCpuSerial: DeviceRawVecAPI<T, RawVec = CpuRawVec<T>>,
 {
     #[allow(clippy::uninit_vec)]
     unsafe fn empty_impl(&self, len: usize) -> Result<Storage<T, DeviceCpuSerial>> {
+        let device = self.acquire_memory::<T>(len)?;
         let mut rawvec: Vec<T> = Vec::with_capacity(len);
         unsafe {
             rawvec.set_len(len);
         }
-        Ok(Storage::<T, DeviceCpuSerial> { rawvec, device: self.clone() })
+        Ok(Storage::<T, DeviceCpuSerial> { rawvec: rawvec.into(), device })
     }
 
     fn full_impl(&self, len: usize, fill: T) -> Result<Storage<T, DeviceCpuSerial>> {
+        let device = self.acquire_memory::<T>(len)?;
         let rawvec = vec![fill; len];
-        Ok(Storage::<T, DeviceCpuSerial> { rawvec, device: self.clone() })
+        Ok(Storage::<T, DeviceCpuSerial> { rawvec: rawvec.into(), device })
     }
 
     fn outof_cpu_vec(&self, vec: Vec<T>) -> Result<Storage<T, DeviceCpuSerial>> {
-        Ok(Storage::<T, DeviceCpuSerial> { rawvec: vec, device: self.clone() })
+        let device = self.acquire_memory::<T>(vec.len())?;
+        Ok(Storage::<T, DeviceCpuSerial> { rawvec: vec.into(), device })
     }
 
     fn from_cpu_vec(&self, vec: &[T]) -> Result<Storage<T, DeviceCpuSerial>> {
+        let device = self.acquire_memory::<T>(vec.len())?;
         let rawvec = vec.to_vec();
-        Ok(Storage::<T, DeviceCpuSerial> { rawvec, device: self.clone() })
+        Ok(Storage::<T, DeviceCpuSerial> { rawvec: rawvec.into(), device })
     }
 }
 
 impl<T> DeviceCreationNumAPI<T> for DeviceCpuSerial
 where
     T: Num + Clone + Debug,
-    DeviceCpuSerial: DeviceRawVecAPI<T, RawVec = Vec<T>>,
+    DeviceCpuSerial: DeviceRawVecAPI<T, RawVec = CpuRawVec<T>>,
 {
     fn zeros_impl(&self, len: usize) -> Result<Storage<T, DeviceCpuSerial>> {
+        let device = self.acquire_memory::<T>(len)?;
         let rawvec = vec![T::zero(); len];
-        Ok(Storage::<T, DeviceCpuSerial> { rawvec, device: self.clone() })
+        Ok(Storage::<T, DeviceCpuSerial> { rawvec: rawvec.into(), device })
     }
 
     fn ones_impl(&self, len: usize) -> Result<Storage<T, DeviceCpuSerial>> {
+        let device = self.acquire_memory::<T>(len)?;
         let rawvec = vec![T::one(); len];
-        Ok(Storage::<T, DeviceCpuSerial> { rawvec, device: self.clone() })
+        Ok(Storage::<T, DeviceCpuSerial> { rawvec: rawvec.into(), device })
     }
 
     fn arange_int_impl(&self, len: usize) -> Result<Storage<T, DeviceCpuSerial>> {
+        let device = self.acquire_memory::<T>(len)?;
         let mut rawvec = Vec::with_capacity(len);
         let mut v = T::zero();
         for _ in 0..len {
             rawvec.push(v.clone());
             v = v + T::one();
         }
-        Ok(Storage::<T, DeviceCpuSerial> { rawvec, device: self.clone() })
+        Ok(Storage::<T, DeviceCpuSerial> { rawvec: rawvec.into(), device })
     }
 }
 
 impl<T> DeviceCreationComplexFloatAPI<T> for DeviceCpuSerial
 where
     T: ComplexFloat + Clone + Debug,
-    DeviceCpuSerial: DeviceRawVecAPI<T, RawVec = Vec<T>>,
+    DeviceCpuSerial: DeviceRawVecAPI<T, RawVec = CpuRawVec<T>>,
 {
     fn linspace_impl(
         &self,
@@ -68,11 +75,13 @@ where
         n: usize,
         endpoint: bool,
     ) -> Result<Storage<T, DeviceCpuSerial>> {
+        let device = self.acquire_memory::<T>(n)?;
+
         // handle special cases
         if n == 0 {
-            return Ok(Storage::<T, DeviceCpuSerial> { rawvec: vec![], device: self.clone() });
+            return Ok(Storage::<T, DeviceCpuSerial> { rawvec: vec![].into(), device });
         } else if n == 1 {
-            return Ok(Storage::<T, DeviceCpuSerial> { rawvec: vec![start], device: self.clone() });
+            return Ok(Storage::<T, DeviceCpuSerial> { rawvec: vec![start].into(), device });
         }
 
         let mut rawvec = Vec::with_capacity(n);
@@ -85,26 +94,27 @@ where
             rawvec.push(v);
             v = v + step;
         }
-        Ok(Storage::<T, DeviceCpuSerial> { rawvec, device: self.clone() })
+        Ok(Storage::<T, DeviceCpuSerial> { rawvec: rawvec.into(), device })
     }
 }
 
 impl<T> DeviceCreationFloatAPI<T> for DeviceCpuSerial
 where
     T: Float + Clone + Debug,
-    DeviceCpuSerial: DeviceRawVecAPI<T, RawVec = Vec<T>>,
+    DeviceCpuSerial: DeviceRawVecAPI<T, RawVec = CpuRawVec<T>>,
 {
     fn arange_impl(&self, start: T, end: T, step: T) -> Result<Storage<T, DeviceCpuSerial>> {
         rstsr_assert!(step != T::zero(), InvalidValue)?;
         let n = ((end - start) / step).ceil();
         rstsr_pattern!(n, T::zero().., ValueOutOfRange)?;
         let n = n.to_usize().unwrap();
+        let device = self.acquire_memory::<T>(n)?;
 
         let mut rawvec: Vec<T> = (0..n).map(|i| start + step * T::from(i).unwrap()).collect();
         if rawvec.last().is_some_and(|&v| v == end) {
             rawvec.pop();
         }
-        Ok(Storage::<T, DeviceCpuSerial> { rawvec, device: self.clone() })
+        Ok(Storage::<T, DeviceCpuSerial> { rawvec: rawvec.into(), device })
     }
 }
 
@@ -115,7 +125,7 @@ mod test {
         use super::*;
         use num::Complex;
 
-        let device = DeviceCpuSerial {};
+        let device = DeviceCpuSerial::default();
         let storage: Storage<f64> = device.zeros_impl(10).unwrap();
         println!("{:?}", storage);
         let storage: Storage<f64> = device.ones_impl(10).unwrap();
@@ -1,32 +1,74 @@
 use crate::prelude_dev::*;
 
-#[derive(Clone, Debug)]
-pub struct DeviceCpuSerial;
+#[derive(Clone, Debug, Default)]
+pub struct DeviceCpuSerial {
+    memory: DeviceMemory,
+}
+
+impl DeviceCpuSerial {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-impl Default for DeviceCpuSerial {
-    fn default() -> Self {
-        DeviceCpuSerial
+    /// Serial device accounting memory in the tracker of `device`.
+    ///
+    /// Other CPU devices allocate storage by serial routines on this device,
+    /// then move it by [`storage_from_serial`].
+    #[cfg(any(feature = "rayon", feature = "std"))]
+    pub(crate) fn with_memory_of<B>(device: &B) -> Self
+    where
+        B: DeviceMemoryAPI,
+    {
+        DeviceCpuSerial { memory: device.memory().clone() }
     }
 }
 
+/// Move storage allocated by serial device to `device`, keeping its
+/// accounted bytes.
+#[cfg(any(test, feature = "counting", feature = "rayon"))]
+pub(crate) fn storage_from_serial<T, B>(
+    storage: Storage<T, DeviceCpuSerial>,
+    device: &B,
+) -> Storage<T, B>
+where
+    T: Clone,
+    B: DeviceMemoryAPI + DeviceRawVecAPI<T, RawVec = CpuRawVec<T>>,
+{
+    let Storage { rawvec, device: mut serial } = storage;
+    let mut device = device.clone();
+    *device.memory_mut() = core::mem::take(&mut serial.memory);
+    Storage { rawvec, device }
+}
+
 impl DeviceBaseAPI for DeviceCpuSerial {
     fn same_device(&self, _other: &Self) -> bool {
         true
     }
 }
 
+impl DeviceMemoryAPI for DeviceCpuSerial {
+    fn memory(&self) -> &DeviceMemory {
+        &self.memory
+    }
+
+    fn memory_mut(&mut self) -> &mut DeviceMemory {
+        &mut self.memory
+    }
+}
+
 impl<T> DeviceRawVecAPI<T> for DeviceCpuSerial
 where
     T: Clone,
 {
-    type RawVec = Vec<T>;
+    type RawVec = CpuRawVec<T>;
 }
 
 impl<T> DeviceStorageAPI<T> for DeviceCpuSerial
 where
     T: Clone,
 {
-    fn new(vector: Vec<T>, device: DeviceCpuSerial) -> Storage<T, DeviceCpuSerial> {
+    fn new(vector: CpuRawVec<T>, device: DeviceCpuSerial) -> Storage<T, DeviceCpuSerial> {
+        let device = device.acquire_memory_unchecked::<T>(vector.len());
         Storage::<T, DeviceCpuSerial> { rawvec: vector, device }
     }
 
@@ -35,31 +77,31 @@ where
     }
 
     fn to_cpu_vec(storage: &Storage<T, DeviceCpuSerial>) -> Result<Vec<T>> {
-        Ok(storage.rawvec.clone())
+        Ok(storage.rawvec.to_vec())
     }
 
     fn into_cpu_vec(storage: Storage<T, DeviceCpuSerial>) -> Result<Vec<T>> {
-        Ok(storage.rawvec)
+        Ok(storage.rawvec.into_vec())
     }
 
     #[inline]
     fn get_index(storage: &Storage<T, DeviceCpuSerial>, index: usize) -> T {
-        storage.rawvec[index].clone()
+        storage.rawvec.as_raw_slice()[index].clone()
     }
 
     #[inline]
     fn get_index_ptr(storage: &Storage<T, DeviceCpuSerial>, index: usize) -> *const T {
-        &storage.rawvec[index] as *const T
+        &storage.rawvec.as_raw_slice()[index] as *const T
     }
 
     #[inline]
     fn get_index_mut_ptr(storage: &mut Storage<T, DeviceCpuSerial>, index: usize) -> *mut T {
-        &mut storage.rawvec[index] as *mut T
+        &mut storage.rawvec.as_raw_slice_mut()[index] as *mut T
     }
 
     #[inline]
     fn set_index(storage: &mut Storage<T, Self>, index: usize, value: T) {
-        storage.rawvec[index] = value;
+        storage.rawvec.as_raw_slice_mut()[index] = value;
     }
 }
 
@@ -69,12 +111,15 @@ impl<T> DeviceAPI<T> for DeviceCpuSerial where T: Clone {}
 // on CPU
 impl<T, B1, B2> DeviceStorageConversionAPI<B2> for Storage<T, B1>
 where
-    B1: DeviceStorageAPI<T, RawVec = Vec<T>>,
-    B2: DeviceStorageAPI<T, RawVec = Vec<T>>,
+    B1: DeviceStorageAPI<T>,
+    B2: DeviceStorageAPI<T> + DeviceCreationAnyAPI<T>,
 {
     type T = T;
     fn into_device(self, device: &B2) -> Result<Storage<T, B2>> {
-        Ok(Storage::<T, B2>::new(self.rawvec, device.clone()))
+        // call device API, so that devices are aware of storage conversion,
+        // and allocate the storage in the way of target device
+        let vec = B1::into_cpu_vec(self)?;
+        device.outof_cpu_vec(vec)
     }
 }
 
@@ -84,21 +129,21 @@ mod tests {
 
     #[test]
     fn test_cpu_device_same_device() {
-        let device1 = DeviceCpuSerial;
-        let device2 = DeviceCpuSerial;
+        let device1 = DeviceCpuSerial::default();
+        let device2 = DeviceCpuSerial::default();
         assert!(device1.same_device(&device2));
     }
 
     #[test]
     fn test_cpu_storage_to_vec() {
-        let storage = Storage { rawvec: vec![1, 2, 3], device: DeviceCpuSerial };
+        let storage = Storage { rawvec: vec![1, 2, 3].into(), device: DeviceCpuSerial::default() };
         let vec = storage.to_rawvec();
         assert_eq!(vec, vec![1, 2, 3]);
     }
 
     #[test]
     fn test_cpu_storage_into_vec() {
-        let storage = Storage { rawvec: vec![1, 2, 3], device: DeviceCpuSerial };
+        let storage = Storage { rawvec: vec![1, 2, 3].into(), device: DeviceCpuSerial::default() };
         let vec = storage.into_rawvec();
         assert_eq!(vec, vec![1, 2, 3]);
     }
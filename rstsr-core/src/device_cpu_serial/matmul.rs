@@ -2,11 +2,45 @@
 //!
 //! **This implementation is not optimized!**
 
+#![allow(clippy::too_many_arguments)]
+
 use core::ops::{Add, Mul};
+use num::complex::ComplexFloat;
+use num::{One, Zero};
 
 use crate::prelude_dev::*;
 
-impl<TA, TB, TC, DA, DB, DC> DeviceMatMulAPI<TA, TB, TC, DA, DB, DC> for DeviceCpuSerial
+/// `beta * c`, without reading `c` when `beta` is zero.
+///
+/// Output buffers are usually allocated uninitialized, and `0 * NaN` would
+/// otherwise leak garbage into the result.
+#[inline]
+fn scale_beta<TC>(beta: &TC, c: &TC) -> TC
+where
+    TC: Clone + Mul<TC, Output = TC> + Zero,
+{
+    if beta.is_zero() {
+        TC::zero()
+    } else {
+        beta.clone() * c.clone()
+    }
+}
+
+/// Matrix multiplication by [`DeviceGEMMAPI`], [`DeviceGEMVAPI`] and
+/// [`DeviceInnerDotAPI`] of a device.
+///
+/// Batched dimensions are iterated sequentially.
+pub fn matmul_by_gemm<B, TA, TB, TC, DA, DB, DC>(
+    device: &B,
+    c: &mut Storage<TC, B>,
+    lc: &Layout<DC>,
+    a: &Storage<TA, B>,
+    la: &Layout<DA>,
+    b: &Storage<TB, B>,
+    lb: &Layout<DB>,
+    alpha: TC,
+    beta: TC,
+) -> Result<()>
 where
     TA: Clone,
     TB: Clone,
@@ -16,349 +50,844 @@ where
     DC: DimAPI,
     TA: Mul<TB, Output = TC>,
     TC: Mul<TC, Output = TC> + Add<TC, Output = TC>,
-    Self: DeviceGEMMAPI<TA, TB, TC>,
-    Self: DeviceGEMVAPI<TA, TB, TC>,
-    Self: DeviceInnerDotAPI<TA, TB, TC>,
+    B: DeviceGEMMAPI<TA, TB, TC>,
+    B: DeviceGEMVAPI<TA, TB, TC>,
+    B: DeviceInnerDotAPI<TA, TB, TC>,
 {
-    fn matmul(
-        &self,
-        c: &mut Storage<TC, Self>,
-        lc: &Layout<DC>,
-        a: &Storage<TA, Self>,
-        la: &Layout<DA>,
-        b: &Storage<TB, Self>,
-        lb: &Layout<DB>,
-        alpha: TC,
-        beta: TC,
-    ) -> Result<()> {
-        match (la.ndim(), lb.ndim(), lc.ndim()) {
-            (1, 1, 0) => {
-                // rule 1: vector inner dot
-                let la = &la.clone().into_dim::<Ix1>().unwrap();
-                let lb = &lb.clone().into_dim::<Ix1>().unwrap();
-                let lc = &lc.clone().into_dim::<Ix0>().unwrap();
-                self.inner_dot(c, lc, a, la, b, lb, alpha, beta)?;
-            },
-            (2, 2, 2) => {
-                // rule 2: matrix multiplication
-                let la = &la.clone().into_dim::<Ix2>().unwrap();
-                let lb = &lb.clone().into_dim::<Ix2>().unwrap();
-                let lc = &lc.clone().into_dim::<Ix2>().unwrap();
-                self.gemm(c, lc, a, la, b, lb, alpha, beta)?;
-            },
-            (2, 1, 1) => {
-                // rule 4 special: 2 x 1
-                let la = &la.clone().into_dim::<Ix2>().unwrap();
-                let lb = &lb.clone().into_dim::<Ix1>().unwrap();
-                let lc = &lc.clone().into_dim::<Ix1>().unwrap();
-                self.gemv(c, lc, a, la, b, lb, alpha, beta)?;
-            },
-            (1, 2, 1) => {
-                // rule 3 special: 1 x 2
-                let la = &la.clone().into_dim::<Ix1>().unwrap();
-                let lb = &lb.clone().into_dim::<Ix2>().unwrap();
-                let lc = &lc.clone().into_dim::<Ix1>().unwrap();
-                self.gevm(c, lc, a, la, b, lb, alpha, beta)?;
-            },
-            (1, 2.., _) => {
-                // rule 3: | `        K` | `..., K, N` | `   ..., N` |
-                rstsr_assert_eq!(lb.ndim(), lc.ndim() + 1, InvalidLayout)?;
-                let la = &la.clone().into_dim::<Ix1>().unwrap();
-                let (lb_rest, lb_matmul) = lb.dim_split_at(-2)?;
-                let (lc_rest, lc_matmul) = lc.dim_split_at(-1)?;
-                let lb_matmul = &mut lb_matmul.into_dim::<Ix2>()?;
-                let lc_matmul = &mut lc_matmul.into_dim::<Ix1>()?;
-                let l_rest = translate_to_col_major(&[&lc_rest, &lb_rest], TensorIterOrder::K)?;
-                let (lc_rest, lb_rest) = (&l_rest[0], &l_rest[1]);
-                let itb_rest = IterLayoutColMajor::new(lb_rest)?;
-                let itc_rest = IterLayoutColMajor::new(lc_rest)?;
-                for (ib_rest, ic_rest) in izip!(itb_rest, itc_rest) {
-                    unsafe { lb_matmul.set_offset(ib_rest) };
-                    unsafe { lc_matmul.set_offset(ic_rest) };
-                    self.gevm(c, lc_matmul, a, la, b, lb_matmul, alpha.clone(), beta.clone())?;
-                }
-            },
-            (2.., 1, _) => {
-                // rule 4: | `..., M, K` | `        K` | `   ..., M` |
-                rstsr_assert_eq!(la.ndim(), lc.ndim() + 1, InvalidLayout)?;
-                let lb = &lb.clone().into_dim::<Ix1>().unwrap();
-                let (la_rest, la_matmul) = la.dim_split_at(-2)?;
-                let (lc_rest, lc_matmul) = lc.dim_split_at(-1)?;
-                let la_matmul = &mut la_matmul.into_dim::<Ix2>()?;
-                let lc_matmul = &mut lc_matmul.into_dim::<Ix1>()?;
-                let l_rest = translate_to_col_major(&[&lc_rest, &la_rest], TensorIterOrder::K)?;
-                let (lc_rest, la_rest) = (&l_rest[0], &l_rest[1]);
-                let ita_rest = IterLayoutColMajor::new(la_rest)?;
-                let itc_rest = IterLayoutColMajor::new(lc_rest)?;
-                for (ib_rest, ic_rest) in izip!(ita_rest, itc_rest) {
-                    unsafe { la_matmul.set_offset(ib_rest) };
-                    unsafe { lc_matmul.set_offset(ic_rest) };
-                    self.gemv(c, lc_matmul, a, la_matmul, b, lb, alpha.clone(), beta.clone())?;
-                }
-            },
-            (2, 3.., _) => {
-                // rule 5: | `     M, K` | `..., K, N` | `..., M, N` |
-                rstsr_assert_eq!(lb.ndim(), lc.ndim(), InvalidLayout)?;
-                let la = &la.clone().into_dim::<Ix2>().unwrap();
-                let (lb_rest, lb_matmul) = lb.dim_split_at(-2)?;
-                let (lc_rest, lc_matmul) = lc.dim_split_at(-2)?;
-                let lb_matmul = &mut lb_matmul.into_dim::<Ix2>()?;
-                let lc_matmul = &mut lc_matmul.into_dim::<Ix2>()?;
-                let l_rest = translate_to_col_major(&[&lc_rest, &lb_rest], TensorIterOrder::K)?;
-                let (lc_rest, lb_rest) = (&l_rest[0], &l_rest[1]);
-                let itb_rest = IterLayoutColMajor::new(lb_rest)?;
-                let itc_rest = IterLayoutColMajor::new(lc_rest)?;
-                for (ib_rest, ic_rest) in izip!(itb_rest, itc_rest) {
-                    unsafe { lb_matmul.set_offset(ib_rest) };
-                    unsafe { lc_matmul.set_offset(ic_rest) };
-                    self.gemm(c, lc_matmul, a, la, b, lb_matmul, alpha.clone(), beta.clone())?;
-                }
-            },
-            (3.., 2, _) => {
-                // rule 6: | `..., M, K` | `     K, N` | `..., M, N` |
-                rstsr_assert_eq!(la.ndim(), lc.ndim(), InvalidLayout)?;
-                let lb = &lb.clone().into_dim::<Ix2>().unwrap();
-                let (la_rest, la_matmul) = la.dim_split_at(-2)?;
-                let (lc_rest, lc_matmul) = lc.dim_split_at(-2)?;
-                let la_matmul = &mut la_matmul.into_dim::<Ix2>()?;
-                let lc_matmul = &mut lc_matmul.into_dim::<Ix2>()?;
-                let l_rest = translate_to_col_major(&[&lc_rest, &la_rest], TensorIterOrder::K)?;
-                let (lc_rest, la_rest) = (&l_rest[0], &l_rest[1]);
-                let ita_rest = IterLayoutColMajor::new(la_rest)?;
-                let itc_rest = IterLayoutColMajor::new(lc_rest)?;
-                for (ib_rest, ic_rest) in izip!(ita_rest, itc_rest) {
-                    unsafe { la_matmul.set_offset(ib_rest) };
-                    unsafe { lc_matmul.set_offset(ic_rest) };
-                    self.gemm(c, lc_matmul, a, la_matmul, b, lb, alpha.clone(), beta.clone())?;
-                }
-            },
-            (3.., 3.., _) => {
-                // rule 7: | `..., M, K` | `..., K, N` | `..., M, N` |
-                rstsr_assert_eq!(la.ndim(), lc.ndim(), InvalidLayout)?;
-                rstsr_assert_eq!(lb.ndim(), lc.ndim(), InvalidLayout)?;
-                let (la_rest, la_matmul) = la.dim_split_at(-2)?;
-                let (lb_rest, lb_matmul) = lb.dim_split_at(-2)?;
-                let (lc_rest, lc_matmul) = lc.dim_split_at(-2)?;
-                let la_matmul = &mut la_matmul.into_dim::<Ix2>()?;
-                let lb_matmul = &mut lb_matmul.into_dim::<Ix2>()?;
-                let lc_matmul = &mut lc_matmul.into_dim::<Ix2>()?;
-                let l_rest =
-                    translate_to_col_major(&[&lc_rest, &la_rest, &lb_rest], TensorIterOrder::K)?;
-                let (lc_rest, la_rest, lb_rest) = (&l_rest[0], &l_rest[1], &l_rest[2]);
-                let ita_rest = IterLayoutColMajor::new(la_rest)?;
-                let itb_rest = IterLayoutColMajor::new(lb_rest)?;
-                let itc_rest = IterLayoutColMajor::new(lc_rest)?;
-                for (ia_rest, ib_rest, ic_rest) in izip!(ita_rest, itb_rest, itc_rest) {
-                    unsafe { la_matmul.set_offset(ia_rest) };
-                    unsafe { lb_matmul.set_offset(ib_rest) };
-                    unsafe { lc_matmul.set_offset(ic_rest) };
-                    self.gemm(
-                        c,
-                        lc_matmul,
-                        a,
-                        la_matmul,
-                        b,
-                        lb_matmul,
-                        alpha.clone(),
-                        beta.clone(),
-                    )?;
-                }
-            },
-            // handle other cases
-            (0, _, _) | (_, 0, _) // zero-dimension input
-            | (1, 1, 1..) // rule 1 invalid
-            | (2, 2, 3..) | (2, 2, 0..2) // rule 2 invalid
-            => {
-                rstsr_raise!(
-                    InvalidLayout,
-                    "Invalid ndim for matmul: {}, {}, {}",
-                    la.ndim(),
-                    lb.ndim(),
-                    lc.ndim()
+    match (la.ndim(), lb.ndim(), lc.ndim()) {
+        (1, 1, 0) => {
+            // rule 1: vector inner dot
+            let la = &la.clone().into_dim::<Ix1>().unwrap();
+            let lb = &lb.clone().into_dim::<Ix1>().unwrap();
+            let lc = &lc.clone().into_dim::<Ix0>().unwrap();
+            device.inner_dot(c, lc, a, la, b, lb, alpha, beta)?;
+        },
+        (2, 2, 2) => {
+            // rule 2: matrix multiplication
+            let la = &la.clone().into_dim::<Ix2>().unwrap();
+            let lb = &lb.clone().into_dim::<Ix2>().unwrap();
+            let lc = &lc.clone().into_dim::<Ix2>().unwrap();
+            device.gemm(c, lc, a, la, b, lb, alpha, beta)?;
+        },
+        (2, 1, 1) => {
+            // rule 4 special: 2 x 1
+            let la = &la.clone().into_dim::<Ix2>().unwrap();
+            let lb = &lb.clone().into_dim::<Ix1>().unwrap();
+            let lc = &lc.clone().into_dim::<Ix1>().unwrap();
+            device.gemv(c, lc, a, la, b, lb, alpha, beta)?;
+        },
+        (1, 2, 1) => {
+            // rule 3 special: 1 x 2
+            let la = &la.clone().into_dim::<Ix1>().unwrap();
+            let lb = &lb.clone().into_dim::<Ix2>().unwrap();
+            let lc = &lc.clone().into_dim::<Ix1>().unwrap();
+            device.gevm(c, lc, a, la, b, lb, alpha, beta)?;
+        },
+        (1, 2.., _) => {
+            // rule 3: | `        K` | `..., K, N` | `   ..., N` |
+            rstsr_assert_eq!(lb.ndim(), lc.ndim() + 1, InvalidLayout)?;
+            let la = &la.clone().into_dim::<Ix1>().unwrap();
+            let (lb_rest, lb_matmul) = lb.dim_split_at(-2)?;
+            let (lc_rest, lc_matmul) = lc.dim_split_at(-1)?;
+            let lb_matmul = &mut lb_matmul.into_dim::<Ix2>()?;
+            let lc_matmul = &mut lc_matmul.into_dim::<Ix1>()?;
+            let l_rest = translate_to_col_major(&[&lc_rest, &lb_rest], TensorIterOrder::K)?;
+            let (lc_rest, lb_rest) = (&l_rest[0], &l_rest[1]);
+            let itb_rest = IterLayoutColMajor::new(lb_rest)?;
+            let itc_rest = IterLayoutColMajor::new(lc_rest)?;
+            for (ib_rest, ic_rest) in izip!(itb_rest, itc_rest) {
+                unsafe { lb_matmul.set_offset(ib_rest) };
+                unsafe { lc_matmul.set_offset(ic_rest) };
+                device.gevm(c, lc_matmul, a, la, b, lb_matmul, alpha.clone(), beta.clone())?;
+            }
+        },
+        (2.., 1, _) => {
+            // rule 4: | `..., M, K` | `        K` | `   ..., M` |
+            rstsr_assert_eq!(la.ndim(), lc.ndim() + 1, InvalidLayout)?;
+            let lb = &lb.clone().into_dim::<Ix1>().unwrap();
+            let (la_rest, la_matmul) = la.dim_split_at(-2)?;
+            let (lc_rest, lc_matmul) = lc.dim_split_at(-1)?;
+            let la_matmul = &mut la_matmul.into_dim::<Ix2>()?;
+            let lc_matmul = &mut lc_matmul.into_dim::<Ix1>()?;
+            let l_rest = translate_to_col_major(&[&lc_rest, &la_rest], TensorIterOrder::K)?;
+            let (lc_rest, la_rest) = (&l_rest[0], &l_rest[1]);
+            let ita_rest = IterLayoutColMajor::new(la_rest)?;
+            let itc_rest = IterLayoutColMajor::new(lc_rest)?;
+            for (ib_rest, ic_rest) in izip!(ita_rest, itc_rest) {
+                unsafe { la_matmul.set_offset(ib_rest) };
+                unsafe { lc_matmul.set_offset(ic_rest) };
+                device.gemv(c, lc_matmul, a, la_matmul, b, lb, alpha.clone(), beta.clone())?;
+            }
+        },
+        (2, 3.., _) => {
+            // rule 5: | `     M, K` | `..., K, N` | `..., M, N` |
+            rstsr_assert_eq!(lb.ndim(), lc.ndim(), InvalidLayout)?;
+            let la = &la.clone().into_dim::<Ix2>().unwrap();
+            let (lb_rest, lb_matmul) = lb.dim_split_at(-2)?;
+            let (lc_rest, lc_matmul) = lc.dim_split_at(-2)?;
+            let lb_matmul = &mut lb_matmul.into_dim::<Ix2>()?;
+            let lc_matmul = &mut lc_matmul.into_dim::<Ix2>()?;
+            let l_rest = translate_to_col_major(&[&lc_rest, &lb_rest], TensorIterOrder::K)?;
+            let (lc_rest, lb_rest) = (&l_rest[0], &l_rest[1]);
+            let itb_rest = IterLayoutColMajor::new(lb_rest)?;
+            let itc_rest = IterLayoutColMajor::new(lc_rest)?;
+            for (ib_rest, ic_rest) in izip!(itb_rest, itc_rest) {
+                unsafe { lb_matmul.set_offset(ib_rest) };
+                unsafe { lc_matmul.set_offset(ic_rest) };
+                device.gemm(c, lc_matmul, a, la, b, lb_matmul, alpha.clone(), beta.clone())?;
+            }
+        },
+        (3.., 2, _) => {
+            // rule 6: | `..., M, K` | `     K, N` | `..., M, N` |
+            rstsr_assert_eq!(la.ndim(), lc.ndim(), InvalidLayout)?;
+            let lb = &lb.clone().into_dim::<Ix2>().unwrap();
+            let (la_rest, la_matmul) = la.dim_split_at(-2)?;
+            let (lc_rest, lc_matmul) = lc.dim_split_at(-2)?;
+            let la_matmul = &mut la_matmul.into_dim::<Ix2>()?;
+            let lc_matmul = &mut lc_matmul.into_dim::<Ix2>()?;
+            let l_rest = translate_to_col_major(&[&lc_rest, &la_rest], TensorIterOrder::K)?;
+            let (lc_rest, la_rest) = (&l_rest[0], &l_rest[1]);
+            let ita_rest = IterLayoutColMajor::new(la_rest)?;
+            let itc_rest = IterLayoutColMajor::new(lc_rest)?;
+            for (ib_rest, ic_rest) in izip!(ita_rest, itc_rest) {
+                unsafe { la_matmul.set_offset(ib_rest) };
+                unsafe { lc_matmul.set_offset(ic_rest) };
+                device.gemm(c, lc_matmul, a, la_matmul, b, lb, alpha.clone(), beta.clone())?;
+            }
+        },
+        (3.., 3.., _) => {
+            // rule 7: | `..., M, K` | `..., K, N` | `..., M, N` |
+            rstsr_assert_eq!(la.ndim(), lc.ndim(), InvalidLayout)?;
+            rstsr_assert_eq!(lb.ndim(), lc.ndim(), InvalidLayout)?;
+            let (la_rest, la_matmul) = la.dim_split_at(-2)?;
+            let (lb_rest, lb_matmul) = lb.dim_split_at(-2)?;
+            let (lc_rest, lc_matmul) = lc.dim_split_at(-2)?;
+            let la_matmul = &mut la_matmul.into_dim::<Ix2>()?;
+            let lb_matmul = &mut lb_matmul.into_dim::<Ix2>()?;
+            let lc_matmul = &mut lc_matmul.into_dim::<Ix2>()?;
+            let l_rest =
+                translate_to_col_major(&[&lc_rest, &la_rest, &lb_rest], TensorIterOrder::K)?;
+            let (lc_rest, la_rest, lb_rest) = (&l_rest[0], &l_rest[1], &l_rest[2]);
+            let ita_rest = IterLayoutColMajor::new(la_rest)?;
+            let itb_rest = IterLayoutColMajor::new(lb_rest)?;
+            let itc_rest = IterLayoutColMajor::new(lc_rest)?;
+            for (ia_rest, ib_rest, ic_rest) in izip!(ita_rest, itb_rest, itc_rest) {
+                unsafe { la_matmul.set_offset(ia_rest) };
+                unsafe { lb_matmul.set_offset(ib_rest) };
+                unsafe { lc_matmul.set_offset(ic_rest) };
+                device.gemm(
+                    c,
+                    lc_matmul,
+                    a,
+                    la_matmul,
+                    b,
+                    lb_matmul,
+                    alpha.clone(),
+                    beta.clone(),
                 )?;
-            },
-        }
-        return Ok(());
+            }
+        },
+        // handle other cases
+        (0, _, _) | (_, 0, _) // zero-dimension input
+        | (1, 1, 1..) // rule 1 invalid
+        | (2, 2, 3..) | (2, 2, 0..2) // rule 2 invalid
+        => {
+            rstsr_raise!(
+                InvalidLayout,
+                "Invalid ndim for matmul: {}, {}, {}",
+                la.ndim(),
+                lb.ndim(),
+                lc.ndim()
+            )?;
+        },
     }
+    return Ok(());
 }
 
-impl<TA, TB, TC> DeviceGEMMAPI<TA, TB, TC> for DeviceCpuSerial
+/// Naive general matrix multiplication `c = alpha * a * b + beta * c`.
+pub fn gemm_naive<TA, TB, TC>(
+    mut vc: CpuRawSliceMut<'_, TC>,
+    lc: &Layout<Ix2>,
+    va: CpuRawSlice<'_, TA>,
+    la: &Layout<Ix2>,
+    vb: CpuRawSlice<'_, TB>,
+    lb: &Layout<Ix2>,
+    alpha: TC,
+    beta: TC,
+) -> Result<()>
 where
     TA: Clone,
     TB: Clone,
     TC: Clone,
     TA: Mul<TB, Output = TC>,
-    TC: Mul<TC, Output = TC> + Add<TC, Output = TC>,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
 {
-    fn gemm(
-        &self,
-        c: &mut Storage<TC, Self>,
-        lc: &Layout<Ix2>,
-        a: &Storage<TA, Self>,
-        la: &Layout<Ix2>,
-        b: &Storage<TB, Self>,
-        lb: &Layout<Ix2>,
-        alpha: TC,
-        beta: TC,
-    ) -> Result<()> {
-        // shape check
-        let sc = lc.shape();
-        let sa = la.shape();
-        let sb = lb.shape();
-        debug_assert_eq!(sc[0], sa[0]);
-        debug_assert_eq!(sa[1], sb[0]);
-        debug_assert_eq!(sc[1], sb[1]);
-        let (m, n, k) = (sc[0], sc[1], sa[1]);
-
-        // naive iteration: assuming c-prefer
-        let vc = c.rawvec_mut();
-        let va = a.rawvec();
-        let vb = b.rawvec();
-        unsafe {
-            for i_n in 0..m {
-                for i_m in 0..n {
+    // shape check
+    let sc = lc.shape();
+    let sa = la.shape();
+    let sb = lb.shape();
+    debug_assert_eq!(sc[0], sa[0]);
+    debug_assert_eq!(sa[1], sb[0]);
+    debug_assert_eq!(sc[1], sb[1]);
+    let (m, n, k) = (sc[0], sc[1], sa[1]);
+
+    // naive iteration: assuming c-prefer
+    unsafe {
+        for i_n in 0..n {
+            for i_m in 0..m {
+                let idx_c = lc.index_uncheck(&[i_m, i_n]) as usize;
+                vc[idx_c] = scale_beta(&beta, &vc[idx_c]);
+            }
+            for i_k in 0..k {
+                let idx_b = lb.index_uncheck(&[i_k, i_n]) as usize;
+                for i_m in 0..m {
                     let idx_c = lc.index_uncheck(&[i_m, i_n]) as usize;
-                    vc[idx_c] = beta.clone() * vc[idx_c].clone();
-                }
-                for i_k in 0..k {
-                    let idx_b = lb.index_uncheck(&[i_k, i_n]) as usize;
-                    for i_m in 0..n {
-                        let idx_c = lc.index_uncheck(&[i_m, i_n]) as usize;
-                        let idx_a = la.index_uncheck(&[i_m, i_k]) as usize;
-                        vc[idx_c] = alpha.clone() * (va[idx_a].clone() * vb[idx_b].clone())
-                            + vc[idx_c].clone();
-                    }
+                    let idx_a = la.index_uncheck(&[i_m, i_k]) as usize;
+                    vc[idx_c] =
+                        alpha.clone() * (va[idx_a].clone() * vb[idx_b].clone()) + vc[idx_c].clone();
                 }
             }
         }
-        return Ok(());
     }
+    return Ok(());
 }
 
-impl<TA, TB, TC> DeviceGEMVAPI<TA, TB, TC> for DeviceCpuSerial
+/// Naive matrix-vector multiplication `c = alpha * a * b + beta * c`.
+pub fn gemv_naive<TA, TB, TC>(
+    mut vc: CpuRawSliceMut<'_, TC>,
+    lc: &Layout<Ix1>,
+    va: CpuRawSlice<'_, TA>,
+    la: &Layout<Ix2>,
+    vb: CpuRawSlice<'_, TB>,
+    lb: &Layout<Ix1>,
+    alpha: TC,
+    beta: TC,
+) -> Result<()>
 where
     TA: Clone,
     TB: Clone,
     TC: Clone,
     TA: Mul<TB, Output = TC>,
-    TC: Mul<TC, Output = TC> + Add<TC, Output = TC>,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
 {
-    fn gemv(
-        &self,
-        c: &mut Storage<TC, Self>,
-        lc: &Layout<Ix1>,
-        a: &Storage<TA, Self>,
-        la: &Layout<Ix2>,
-        b: &Storage<TB, Self>,
-        lb: &Layout<Ix1>,
-        alpha: TC,
-        beta: TC,
-    ) -> Result<()> {
-        // shape check
-        let sc = lc.shape();
-        let sa = la.shape();
-        let sb = lb.shape();
-        debug_assert_eq!(sc[0], sa[0]);
-        debug_assert_eq!(sa[1], sb[0]);
-        let (n, k) = (sa[0], sa[1]);
-
-        // naive iteration: assuming c-prefer
-        let vc = c.rawvec_mut();
-        let va = a.rawvec();
-        let vb = b.rawvec();
-        unsafe {
+    // shape check
+    let sc = lc.shape();
+    let sa = la.shape();
+    let sb = lb.shape();
+    debug_assert_eq!(sc[0], sa[0]);
+    debug_assert_eq!(sa[1], sb[0]);
+    let (n, k) = (sa[0], sa[1]);
+
+    // naive iteration: assuming c-prefer
+    unsafe {
+        for i_n in 0..n {
+            let idx_c = lc.index_uncheck(&[i_n]) as usize;
+            vc[idx_c] = scale_beta(&beta, &vc[idx_c]);
+            for i_k in 0..k {
+                let idx_a = la.index_uncheck(&[i_n, i_k]) as usize;
+                let idx_b = lb.index_uncheck(&[i_k]) as usize;
+                vc[idx_c] =
+                    alpha.clone() * (va[idx_a].clone() * vb[idx_b].clone()) + vc[idx_c].clone();
+            }
+        }
+    }
+    return Ok(());
+}
+
+/// Naive vector-matrix multiplication `c = alpha * a * b + beta * c`.
+pub fn gevm_naive<TA, TB, TC>(
+    mut vc: CpuRawSliceMut<'_, TC>,
+    lc: &Layout<Ix1>,
+    va: CpuRawSlice<'_, TA>,
+    la: &Layout<Ix1>,
+    vb: CpuRawSlice<'_, TB>,
+    lb: &Layout<Ix2>,
+    alpha: TC,
+    beta: TC,
+) -> Result<()>
+where
+    TA: Clone,
+    TB: Clone,
+    TC: Clone,
+    TA: Mul<TB, Output = TC>,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+{
+    // shape check
+    let sc = lc.shape();
+    let sa = la.shape();
+    let sb = lb.shape();
+    debug_assert_eq!(sc[0], sb[1]);
+    debug_assert_eq!(sa[0], sb[0]);
+    let (n, k) = (sb[1], sb[0]);
+
+    // naive iteration: assuming c-prefer
+    unsafe {
+        for i_n in 0..n {
+            let idx_c = lc.index_uncheck(&[i_n]) as usize;
+            vc[idx_c] = scale_beta(&beta, &vc[idx_c]);
+            for i_k in 0..k {
+                let idx_a = la.index_uncheck(&[i_k]) as usize;
+                let idx_b = lb.index_uncheck(&[i_k, i_n]) as usize;
+                vc[idx_c] =
+                    alpha.clone() * (va[idx_a].clone() * vb[idx_b].clone()) + vc[idx_c].clone();
+            }
+        }
+    }
+    return Ok(());
+}
+
+/// Naive vector inner dot `c = alpha * a . b + beta * c`.
+pub fn inner_dot_naive<TA, TB, TC>(
+    mut vc: CpuRawSliceMut<'_, TC>,
+    lc: &Layout<Ix0>,
+    va: CpuRawSlice<'_, TA>,
+    la: &Layout<Ix1>,
+    vb: CpuRawSlice<'_, TB>,
+    lb: &Layout<Ix1>,
+    alpha: TC,
+    beta: TC,
+) -> Result<()>
+where
+    TA: Clone,
+    TB: Clone,
+    TC: Clone,
+    TA: Mul<TB, Output = TC>,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+{
+    // shape check
+    let sa = la.shape();
+    let sb = lb.shape();
+    rstsr_assert_eq!(sa[0], sb[0], InvalidLayout)?;
+    let n = sa[0];
+
+    // naive iteration
+    unsafe {
+        let idx_c = lc.index_uncheck(&[]) as usize;
+        let mut sum = scale_beta(&beta, &vc[idx_c]);
+        for i in 0..n {
+            let idx_a = la.index_uncheck(&[i]) as usize;
+            let idx_b = lb.index_uncheck(&[i]) as usize;
+            sum = sum + alpha.clone() * (va[idx_a].clone() * vb[idx_b].clone());
+        }
+        vc[idx_c] = sum;
+    }
+    return Ok(());
+}
+
+/* #region symm, syrk, herk */
+
+/// Naive symmetric matrix multiplication.
+///
+/// Only the triangle `uplo` of `a` is referenced; the other triangle is
+/// taken from its mirror.
+/// - side L: `c = alpha * a * b + beta * c`, where `a` is `[m, m]`
+/// - side R: `c = alpha * b * a + beta * c`, where `a` is `[n, n]`
+pub fn symm_naive<TA, TB, TC>(
+    mut c: CpuRawSliceMut<'_, TC>,
+    lc: &Layout<Ix2>,
+    a: CpuRawSlice<'_, TA>,
+    la: &Layout<Ix2>,
+    b: CpuRawSlice<'_, TB>,
+    lb: &Layout<Ix2>,
+    side: TensorSide,
+    uplo: TensorUpLo,
+    alpha: TC,
+    beta: TC,
+) -> Result<()>
+where
+    TA: Clone,
+    TB: Clone,
+    TC: Clone,
+    TA: Mul<TB, Output = TC>,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+{
+    // shape check
+    let sc = lc.shape();
+    let sa = la.shape();
+    let sb = lb.shape();
+    rstsr_assert_eq!(sa[0], sa[1], InvalidLayout, "Symmetric matrix should be square.")?;
+    rstsr_assert_eq!(sc, sb, InvalidLayout)?;
+    match side {
+        TensorSide::L => rstsr_assert_eq!(sa[1], sb[0], InvalidLayout)?,
+        TensorSide::R => rstsr_assert_eq!(sb[1], sa[0], InvalidLayout)?,
+    };
+    let (m, n, k) = (sc[0], sc[1], sa[0]);
+
+    // index of element in a that is referenced
+    let idx_sym = |i: usize, j: usize| -> usize {
+        let (i, j) = match (uplo, i >= j) {
+            (TensorUpLo::L, true) | (TensorUpLo::U, false) => (i, j),
+            _ => (j, i),
+        };
+        unsafe { la.index_uncheck(&[i, j]) as usize }
+    };
+
+    unsafe {
+        for i_m in 0..m {
             for i_n in 0..n {
-                let idx_c = lc.index_uncheck(&[i_n]) as usize;
-                vc[idx_c] = beta.clone() * vc[idx_c].clone();
+                let idx_c = lc.index_uncheck(&[i_m, i_n]) as usize;
+                let mut val = scale_beta(&beta, &c[idx_c]);
                 for i_k in 0..k {
-                    let idx_a = la.index_uncheck(&[i_n, i_k]) as usize;
-                    let idx_b = lb.index_uncheck(&[i_k]) as usize;
-                    vc[idx_c] =
-                        alpha.clone() * (va[idx_a].clone() * vb[idx_b].clone()) + vc[idx_c].clone();
+                    let prod = match side {
+                        TensorSide::L => {
+                            let idx_a = idx_sym(i_m, i_k);
+                            let idx_b = lb.index_uncheck(&[i_k, i_n]) as usize;
+                            a[idx_a].clone() * b[idx_b].clone()
+                        },
+                        TensorSide::R => {
+                            // only `TA * TB` is defined; scalar multiplication is
+                            // assumed to be commutative here
+                            let idx_a = idx_sym(i_k, i_n);
+                            let idx_b = lb.index_uncheck(&[i_m, i_k]) as usize;
+                            a[idx_a].clone() * b[idx_b].clone()
+                        },
+                    };
+                    val = alpha.clone() * prod + val;
                 }
+                c[idx_c] = val;
             }
         }
-        return Ok(());
     }
+    return Ok(());
+}
 
-    fn gevm(
-        &self,
-        c: &mut Storage<TC, Self>,
-        lc: &Layout<Ix1>,
-        a: &Storage<TA, Self>,
-        la: &Layout<Ix1>,
-        b: &Storage<TB, Self>,
-        lb: &Layout<Ix2>,
-        alpha: TC,
-        beta: TC,
-    ) -> Result<()> {
-        // shape check
-        let sc = lc.shape();
-        let sa = la.shape();
-        let sb = lb.shape();
-        debug_assert_eq!(sc[0], sb[1]);
-        debug_assert_eq!(sa[0], sb[0]);
-        let (n, k) = (sb[1], sb[0]);
-
-        // naive iteration: assuming c-prefer
-        let vc = c.rawvec_mut();
-        let va = a.rawvec();
-        let vb = b.rawvec();
-        unsafe {
-            for i_n in 0..n {
-                let idx_c = lc.index_uncheck(&[i_n]) as usize;
-                vc[idx_c] = beta.clone() * vc[idx_c].clone();
-                for i_k in 0..k {
-                    let idx_a = la.index_uncheck(&[i_k]) as usize;
-                    let idx_b = lb.index_uncheck(&[i_k, i_n]) as usize;
-                    vc[idx_c] =
-                        alpha.clone() * (va[idx_a].clone() * vb[idx_b].clone()) + vc[idx_c].clone();
+/// Naive symmetric rank-k update `c = alpha * a * a^T + beta * c`.
+///
+/// Only the triangle `uplo` of `c` is referenced and updated.
+pub fn syrk_naive<TA, TC>(
+    c: CpuRawSliceMut<'_, TC>,
+    lc: &Layout<Ix2>,
+    a: CpuRawSlice<'_, TA>,
+    la: &Layout<Ix2>,
+    uplo: TensorUpLo,
+    alpha: TC,
+    beta: TC,
+) -> Result<()>
+where
+    TA: Clone,
+    TC: Clone,
+    TA: Mul<TA, Output = TC>,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+{
+    rank_k_naive(c, lc, a, la, uplo, alpha, beta, |x| x.clone())
+}
+
+/// Naive hermitian rank-k update `c = alpha * a * a^H + beta * c`.
+///
+/// Only the triangle `uplo` of `c` is referenced and updated.
+pub fn herk_naive<T>(
+    c: CpuRawSliceMut<'_, T>,
+    lc: &Layout<Ix2>,
+    a: CpuRawSlice<'_, T>,
+    la: &Layout<Ix2>,
+    uplo: TensorUpLo,
+    alpha: T,
+    beta: T,
+) -> Result<()>
+where
+    T: ComplexFloat,
+{
+    rank_k_naive(c, lc, a, la, uplo, alpha, beta, |x| x.conj())
+}
+
+fn rank_k_naive<TA, TC, F>(
+    mut c: CpuRawSliceMut<'_, TC>,
+    lc: &Layout<Ix2>,
+    a: CpuRawSlice<'_, TA>,
+    la: &Layout<Ix2>,
+    uplo: TensorUpLo,
+    alpha: TC,
+    beta: TC,
+    f_rhs: F,
+) -> Result<()>
+where
+    TA: Clone,
+    TC: Clone,
+    TA: Mul<TA, Output = TC>,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+    F: Fn(&TA) -> TA,
+{
+    // shape check
+    let sc = lc.shape();
+    let sa = la.shape();
+    rstsr_assert_eq!(sc[0], sc[1], InvalidLayout)?;
+    rstsr_assert_eq!(sc[0], sa[0], InvalidLayout)?;
+    let (n, k) = (sa[0], sa[1]);
+
+    unsafe {
+        for i in 0..n {
+            let range_j = match uplo {
+                TensorUpLo::L => 0..i + 1,
+                TensorUpLo::U => i..n,
+            };
+            for j in range_j {
+                let idx_c = lc.index_uncheck(&[i, j]) as usize;
+                let mut val = scale_beta(&beta, &c[idx_c]);
+                for p in 0..k {
+                    let idx_a = la.index_uncheck(&[i, p]) as usize;
+                    let idx_b = la.index_uncheck(&[j, p]) as usize;
+                    val = alpha.clone() * (a[idx_a].clone() * f_rhs(&a[idx_b])) + val;
                 }
+                c[idx_c] = val;
             }
         }
-        return Ok(());
     }
+    return Ok(());
 }
 
-impl<TA, TB, TC> DeviceInnerDotAPI<TA, TB, TC> for DeviceCpuSerial
+/// Naive matrix multiplication `c = alpha * a * b + beta * c` that only
+/// computes triangle `uplo` of square `c`.
+///
+/// This is useful when output is known to be symmetric; the other triangle of
+/// `c` is not referenced.
+#[allow(clippy::too_many_arguments)]
+pub fn gemm_tri_naive<TA, TB, TC>(
+    mut c: CpuRawSliceMut<'_, TC>,
+    lc: &Layout<Ix2>,
+    a: CpuRawSlice<'_, TA>,
+    la: &Layout<Ix2>,
+    b: CpuRawSlice<'_, TB>,
+    lb: &Layout<Ix2>,
+    uplo: TensorUpLo,
+    alpha: TC,
+    beta: TC,
+) -> Result<()>
 where
     TA: Clone,
     TB: Clone,
     TC: Clone,
     TA: Mul<TB, Output = TC>,
-    TC: Mul<TC, Output = TC> + Add<TC, Output = TC>,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+{
+    // shape check
+    let sc = lc.shape();
+    let sa = la.shape();
+    let sb = lb.shape();
+    rstsr_assert_eq!(sc[0], sc[1], InvalidLayout)?;
+    rstsr_assert_eq!(sc[0], sa[0], InvalidLayout)?;
+    rstsr_assert_eq!(sa[1], sb[0], InvalidLayout)?;
+    rstsr_assert_eq!(sc[1], sb[1], InvalidLayout)?;
+    let (n, k) = (sa[0], sa[1]);
+
+    unsafe {
+        for i in 0..n {
+            let range_j = match uplo {
+                TensorUpLo::L => 0..i + 1,
+                TensorUpLo::U => i..n,
+            };
+            for j in range_j {
+                let idx_c = lc.index_uncheck(&[i, j]) as usize;
+                let mut val = scale_beta(&beta, &c[idx_c]);
+                for p in 0..k {
+                    let idx_a = la.index_uncheck(&[i, p]) as usize;
+                    let idx_b = lb.index_uncheck(&[p, j]) as usize;
+                    val = alpha.clone() * (a[idx_a].clone() * b[idx_b].clone()) + val;
+                }
+                c[idx_c] = val;
+            }
+        }
+    }
+    return Ok(());
+}
+
+/* #endregion */
+
+/* #region impl matmul for CPU serial devices */
+
+/// Implement matrix multiplication traits for a CPU device whose raw vector
+/// derefs to `[T]`, by the naive routines of this module.
+///
+/// If `$record` is given, `self.$record(kind, name, layouts, bytes)` is
+/// called before each routine (see `DeviceCpuCounting`, enabled by feature
+/// `counting`).
+macro_rules! impl_matmul_cpu_serial {
+    ($Device:ty $(, $record:ident)?) => {
+        impl<TA, TB, TC, DA, DB, DC> DeviceMatMulAPI<TA, TB, TC, DA, DB, DC> for $Device
+        where
+            TA: Clone,
+            TB: Clone,
+            TC: Clone,
+            DA: DimAPI,
+            DB: DimAPI,
+            DC: DimAPI,
+            TA: Mul<TB, Output = TC>,
+            TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+        {
+            fn matmul(
+                &self,
+                c: &mut Storage<TC, Self>,
+                lc: &Layout<DC>,
+                a: &Storage<TA, Self>,
+                la: &Layout<DA>,
+                b: &Storage<TB, Self>,
+                lb: &Layout<DB>,
+                alpha: TC,
+                beta: TC,
+            ) -> Result<()> {
+                $(
+                    let layouts = vec![lc.to_dim::<IxD>()?, la.to_dim::<IxD>()?, lb.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::MatMul, "matmul", layouts, 0);
+                )?
+                matmul_by_gemm(self, c, lc, a, la, b, lb, alpha, beta)
+            }
+        }
+
+        impl<TA, TB, TC> DeviceGEMMAPI<TA, TB, TC> for $Device
+        where
+            TA: Clone,
+            TB: Clone,
+            TC: Clone,
+            TA: Mul<TB, Output = TC>,
+            TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+        {
+            fn gemm(
+                &self,
+                c: &mut Storage<TC, Self>,
+                lc: &Layout<Ix2>,
+                a: &Storage<TA, Self>,
+                la: &Layout<Ix2>,
+                b: &Storage<TB, Self>,
+                lb: &Layout<Ix2>,
+                alpha: TC,
+                beta: TC,
+            ) -> Result<()> {
+                $(
+                    let layouts = vec![lc.to_dim::<IxD>()?, la.to_dim::<IxD>()?, lb.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::MatMul, "gemm", layouts, 0);
+                )?
+                gemm_naive(c.rawvec_mut().as_raw_slice_mut(), lc, a.rawvec().as_raw_slice(), la, b.rawvec().as_raw_slice(), lb, alpha, beta)
+            }
+        }
+
+        impl<TA, TB, TC> DeviceGEMVAPI<TA, TB, TC> for $Device
+        where
+            TA: Clone,
+            TB: Clone,
+            TC: Clone,
+            TA: Mul<TB, Output = TC>,
+            TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+        {
+            fn gemv(
+                &self,
+                c: &mut Storage<TC, Self>,
+                lc: &Layout<Ix1>,
+                a: &Storage<TA, Self>,
+                la: &Layout<Ix2>,
+                b: &Storage<TB, Self>,
+                lb: &Layout<Ix1>,
+                alpha: TC,
+                beta: TC,
+            ) -> Result<()> {
+                $(
+                    let layouts = vec![lc.to_dim::<IxD>()?, la.to_dim::<IxD>()?, lb.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::MatMul, "gemv", layouts, 0);
+                )?
+                gemv_naive(c.rawvec_mut().as_raw_slice_mut(), lc, a.rawvec().as_raw_slice(), la, b.rawvec().as_raw_slice(), lb, alpha, beta)
+            }
+
+            fn gevm(
+                &self,
+                c: &mut Storage<TC, Self>,
+                lc: &Layout<Ix1>,
+                a: &Storage<TA, Self>,
+                la: &Layout<Ix1>,
+                b: &Storage<TB, Self>,
+                lb: &Layout<Ix2>,
+                alpha: TC,
+                beta: TC,
+            ) -> Result<()> {
+                $(
+                    let layouts = vec![lc.to_dim::<IxD>()?, la.to_dim::<IxD>()?, lb.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::MatMul, "gevm", layouts, 0);
+                )?
+                gevm_naive(c.rawvec_mut().as_raw_slice_mut(), lc, a.rawvec().as_raw_slice(), la, b.rawvec().as_raw_slice(), lb, alpha, beta)
+            }
+        }
+
+        impl<TA, TB, TC> DeviceInnerDotAPI<TA, TB, TC> for $Device
+        where
+            TA: Clone,
+            TB: Clone,
+            TC: Clone,
+            TA: Mul<TB, Output = TC>,
+            TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+        {
+            fn inner_dot(
+                &self,
+                c: &mut Storage<TC, Self>,
+                lc: &Layout<Ix0>,
+                a: &Storage<TA, Self>,
+                la: &Layout<Ix1>,
+                b: &Storage<TB, Self>,
+                lb: &Layout<Ix1>,
+                alpha: TC,
+                beta: TC,
+            ) -> Result<()> {
+                $(
+                    let layouts = vec![lc.to_dim::<IxD>()?, la.to_dim::<IxD>()?, lb.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::MatMul, "inner_dot", layouts, 0);
+                )?
+                inner_dot_naive(c.rawvec_mut().as_raw_slice_mut(), lc, a.rawvec().as_raw_slice(), la, b.rawvec().as_raw_slice(), lb, alpha, beta)
+            }
+        }
+
+        impl<TA, TB, TC> DeviceSYMMAPI<TA, TB, TC> for $Device
+        where
+            TA: Clone,
+            TB: Clone,
+            TC: Clone,
+            TA: Mul<TB, Output = TC>,
+            TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+        {
+            fn symm(
+                &self,
+                c: &mut Storage<TC, Self>,
+                lc: &Layout<Ix2>,
+                a: &Storage<TA, Self>,
+                la: &Layout<Ix2>,
+                b: &Storage<TB, Self>,
+                lb: &Layout<Ix2>,
+                side: TensorSide,
+                uplo: TensorUpLo,
+                alpha: TC,
+                beta: TC,
+            ) -> Result<()> {
+                $(
+                    let layouts = vec![lc.to_dim::<IxD>()?, la.to_dim::<IxD>()?, lb.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::MatMul, "symm", layouts, 0);
+                )?
+                let vc = c.rawvec_mut().as_raw_slice_mut();
+                let va = a.rawvec().as_raw_slice();
+                let vb = b.rawvec().as_raw_slice();
+                symm_naive(vc, lc, va, la, vb, lb, side, uplo, alpha, beta)
+            }
+        }
+
+        impl<TA, TC> DeviceSYRKAPI<TA, TC> for $Device
+        where
+            TA: Clone,
+            TC: Clone,
+            TA: Mul<TA, Output = TC>,
+            TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+        {
+            fn syrk(
+                &self,
+                c: &mut Storage<TC, Self>,
+                lc: &Layout<Ix2>,
+                a: &Storage<TA, Self>,
+                la: &Layout<Ix2>,
+                uplo: TensorUpLo,
+                alpha: TC,
+                beta: TC,
+            ) -> Result<()> {
+                $(
+                    let layouts = vec![lc.to_dim::<IxD>()?, la.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::MatMul, "syrk", layouts, 0);
+                )?
+                syrk_naive(c.rawvec_mut().as_raw_slice_mut(), lc, a.rawvec().as_raw_slice(), la, uplo, alpha, beta)
+            }
+        }
+
+        impl<T> DeviceHERKAPI<T, T> for $Device
+        where
+            T: ComplexFloat,
+        {
+            fn herk(
+                &self,
+                c: &mut Storage<T, Self>,
+                lc: &Layout<Ix2>,
+                a: &Storage<T, Self>,
+                la: &Layout<Ix2>,
+                uplo: TensorUpLo,
+                alpha: T,
+                beta: T,
+            ) -> Result<()> {
+                $(
+                    let layouts = vec![lc.to_dim::<IxD>()?, la.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::MatMul, "herk", layouts, 0);
+                )?
+                herk_naive(c.rawvec_mut().as_raw_slice_mut(), lc, a.rawvec().as_raw_slice(), la, uplo, alpha, beta)
+            }
+        }
+    };
+}
+#[cfg(any(test, feature = "counting", feature = "std"))]
+pub(crate) use impl_matmul_cpu_serial;
+
+impl_matmul_cpu_serial!(DeviceCpuSerial);
+
+/* #endregion */
+
+/* #region congruence */
+
+impl<T> DeviceCongruenceAPI<T> for DeviceCpuSerial
+where
+    T: Clone + Zero + One,
+    T: Mul<T, Output = T> + Add<T, Output = T>,
 {
-    fn inner_dot(
+    fn congruence(
         &self,
-        c: &mut Storage<TC, Self>,
-        lc: &Layout<Ix0>,
-        a: &Storage<TA, Self>,
-        la: &Layout<Ix1>,
-        b: &Storage<TB, Self>,
-        lb: &Layout<Ix1>,
-        alpha: TC,
-        beta: TC,
+        c: &mut Storage<T, Self>,
+        lc: &Layout<IxD>,
+        a: &Storage<T, Self>,
+        la: &Layout<Ix2>,
+        b: &Storage<T, Self>,
+        lb: &Layout<IxD>,
+        uplo: Option<TensorUpLo>,
     ) -> Result<()> {
-        // shape check
-        let sa = la.shape();
-        let sb = lb.shape();
-        rstsr_assert_eq!(sa[0], sb[0], InvalidLayout)?;
-        let n = sa[0];
-
-        // naive iteration
-        let vc = c.rawvec_mut();
-        let va = a.rawvec();
-        let vb = b.rawvec();
-        unsafe {
-            let idx_c = lc.index_uncheck(&[]) as usize;
-            let mut sum = beta * vc[idx_c].clone();
-            for i in 0..n {
-                let idx_a = la.index_uncheck(&[i]) as usize;
-                let idx_b = lb.index_uncheck(&[i]) as usize;
-                sum = sum + alpha.clone() * (va[idx_a].clone() * vb[idx_b].clone());
+        let (lb_rest, lb_matmul, lc_rest, lc_matmul) = layout_congruence_split(la, lb, lc)?;
+        let lb_matmul = &mut lb_matmul.clone();
+        let lc_matmul = &mut lc_matmul.clone();
+        let (n, m) = (la.shape()[0], la.shape()[1]);
+        let la_t = &la.reverse_axes();
+
+        // intermediate t = b a
+        let lt = &[n, m].c();
+        let mut t = Storage::new(vec![T::zero(); n * m].into(), self.clone());
+
+        let l_rest = translate_to_col_major(&[&lc_rest, &lb_rest], TensorIterOrder::K)?;
+        let (lc_rest, lb_rest) = (&l_rest[0], &l_rest[1]);
+        let itb_rest = IterLayoutColMajor::new(lb_rest)?;
+        let itc_rest = IterLayoutColMajor::new(lc_rest)?;
+        for (ib_rest, ic_rest) in izip!(itb_rest, itc_rest) {
+            unsafe { lb_matmul.set_offset(ib_rest) };
+            unsafe { lc_matmul.set_offset(ic_rest) };
+            match uplo {
+                // symmetric b: symm for t, and only lower triangle of a^T t is computed
+                // and then copied to upper triangle
+                Some(uplo) => {
+                    let side = TensorSide::L;
+                    self.symm(&mut t, lt, b, lb_matmul, a, la, side, uplo, T::one(), T::zero())?;
+                    let mut sc = c.rawvec_mut().as_raw_slice_mut();
+                    let (sa, st) = (a.rawvec().as_raw_slice(), t.rawvec().as_raw_slice());
+                    let uplo = TensorUpLo::L;
+                    gemm_tri_naive(
+                        sc.reborrow(),
+                        lc_matmul,
+                        sa,
+                        la_t,
+                        st,
+                        lt,
+                        uplo,
+                        T::one(),
+                        T::zero(),
+                    )?;
+                    for i in 0..m {
+                        for j in 0..i {
+                            let idx_ij = unsafe { lc_matmul.index_uncheck(&[i, j]) as usize };
+                            let idx_ji = unsafe { lc_matmul.index_uncheck(&[j, i]) as usize };
+                            sc[idx_ji] = sc[idx_ij].clone();
+                        }
+                    }
+                },
+                None => {
+                    self.gemm(&mut t, lt, b, lb_matmul, a, la, T::one(), T::zero())?;
+                    self.gemm(c, lc_matmul, a, la_t, &t, lt, T::one(), T::zero())?;
+                },
             }
-            vc[0] = sum;
         }
         return Ok(());
     }
 }
+
+/* #endregion */
@@ -3,3 +3,6 @@ pub mod op_ternary;
 pub mod op_with_func;
 
 pub use op_with_func::*;
+
+op_binary::impl_op_binary_cpu!(DeviceCpuSerial);
+op_ternary::impl_op_ternary_cpu!(DeviceCpuSerial);
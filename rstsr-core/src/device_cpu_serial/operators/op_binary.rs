@@ -1,11 +1,9 @@
-use crate::prelude_dev::*;
-
 macro_rules! impl_op_muta_refb_assign {
-    ($DeviceOpAPI:ident, $Op:ident, $func:expr) => {
-        impl<TA, TB, D> $DeviceOpAPI<TA, TB, D> for DeviceCpuSerial
+    ([$Device:ty $(, $Bound:ident)*], $DeviceOpAPI:ident, $Op:ident, $func:expr) => {
+        impl<TA, TB, D> $DeviceOpAPI<TA, TB, D> for $Device
         where
-            TA: Clone + $Op<TB>,
-            TB: Clone,
+            TA: Clone + core::ops::$Op<TB> $(+ $Bound)*,
+            TB: Clone $(+ $Bound)*,
             D: DimAPI,
         {
             fn op_muta_refb(
@@ -29,29 +27,14 @@ macro_rules! impl_op_muta_refb_assign {
         }
     };
 }
-
-#[rustfmt::skip]
-mod impl_op_muta_refb_assign {
-    use super::*;
-    use core::ops::*;
-    impl_op_muta_refb_assign!(DeviceAddAssignAPI   , AddAssign   , |a, b| *a +=  b.clone());
-    impl_op_muta_refb_assign!(DeviceSubAssignAPI   , SubAssign   , |a, b| *a -=  b.clone());
-    impl_op_muta_refb_assign!(DeviceMulAssignAPI   , MulAssign   , |a, b| *a *=  b.clone());
-    impl_op_muta_refb_assign!(DeviceDivAssignAPI   , DivAssign   , |a, b| *a /=  b.clone());
-    impl_op_muta_refb_assign!(DeviceRemAssignAPI   , RemAssign   , |a, b| *a %=  b.clone());
-    impl_op_muta_refb_assign!(DeviceBitOrAssignAPI , BitOrAssign , |a, b| *a |=  b.clone());
-    impl_op_muta_refb_assign!(DeviceBitAndAssignAPI, BitAndAssign, |a, b| *a &=  b.clone());
-    impl_op_muta_refb_assign!(DeviceBitXorAssignAPI, BitXorAssign, |a, b| *a ^=  b.clone());
-    impl_op_muta_refb_assign!(DeviceShlAssignAPI   , ShlAssign   , |a, b| *a <<= b.clone());
-    impl_op_muta_refb_assign!(DeviceShrAssignAPI   , ShrAssign   , |a, b| *a >>= b.clone());
-}
+pub(crate) use impl_op_muta_refb_assign;
 
 macro_rules! impl_op_muta_refb_l_consume {
-    ($DeviceOpAPI:ident, $Op:ident, $func:expr) => {
-        impl<TA, TB, D> $DeviceOpAPI<TA, TB, D> for DeviceCpuSerial
+    ([$Device:ty $(, $Bound:ident)*], $DeviceOpAPI:ident, $Op:ident, $func:expr) => {
+        impl<TA, TB, D> $DeviceOpAPI<TA, TB, D> for $Device
         where
-            TA: Clone + $Op<TB, Output = TA>,
-            TB: Clone,
+            TA: Clone + core::ops::$Op<TB, Output = TA> $(+ $Bound)*,
+            TB: Clone $(+ $Bound)*,
             D: DimAPI,
         {
             fn op_muta_refb(
@@ -75,29 +58,14 @@ macro_rules! impl_op_muta_refb_l_consume {
         }
     };
 }
-
-#[rustfmt::skip]
-mod impl_op_muta_refb_l_consume {
-    use super::*;
-    use core::ops::*;
-    impl_op_muta_refb_l_consume!(DeviceLConsumeAddAPI   , Add   , |a, b| *a = a.clone() +  b.clone());
-    impl_op_muta_refb_l_consume!(DeviceLConsumeSubAPI   , Sub   , |a, b| *a = a.clone() -  b.clone());
-    impl_op_muta_refb_l_consume!(DeviceLConsumeMulAPI   , Mul   , |a, b| *a = a.clone() *  b.clone());
-    impl_op_muta_refb_l_consume!(DeviceLConsumeDivAPI   , Div   , |a, b| *a = a.clone() /  b.clone());
-    impl_op_muta_refb_l_consume!(DeviceLConsumeRemAPI   , Rem   , |a, b| *a = a.clone() %  b.clone());
-    impl_op_muta_refb_l_consume!(DeviceLConsumeBitOrAPI , BitOr , |a, b| *a = a.clone() |  b.clone());
-    impl_op_muta_refb_l_consume!(DeviceLConsumeBitAndAPI, BitAnd, |a, b| *a = a.clone() &  b.clone());
-    impl_op_muta_refb_l_consume!(DeviceLConsumeBitXorAPI, BitXor, |a, b| *a = a.clone() ^  b.clone());
-    impl_op_muta_refb_l_consume!(DeviceLConsumeShlAPI   , Shl   , |a, b| *a = a.clone() << b.clone());
-    impl_op_muta_refb_l_consume!(DeviceLConsumeShrAPI   , Shr   , |a, b| *a = a.clone() >> b.clone());
-}
+pub(crate) use impl_op_muta_refb_l_consume;
 
 macro_rules! impl_op_muta_refb_r_consume {
-    ($DeviceOpAPI:ident, $Op:ident, $func:expr) => {
-        impl<TA, TB, D> $DeviceOpAPI<TA, TB, D> for DeviceCpuSerial
+    ([$Device:ty $(, $Bound:ident)*], $DeviceOpAPI:ident, $Op:ident, $func:expr) => {
+        impl<TA, TB, D> $DeviceOpAPI<TA, TB, D> for $Device
         where
-            TA: Clone + $Op<TB, Output = TB>,
-            TB: Clone,
+            TA: Clone + core::ops::$Op<TB, Output = TB> $(+ $Bound)*,
+            TB: Clone $(+ $Bound)*,
             D: DimAPI,
         {
             fn op_muta_refb(
@@ -121,29 +89,14 @@ macro_rules! impl_op_muta_refb_r_consume {
         }
     };
 }
-
-#[rustfmt::skip]
-mod impl_op_muta_refb_r_consume {
-    use super::*;
-    use core::ops::*;
-    impl_op_muta_refb_r_consume!(DeviceRConsumeAddAPI   , Add   , |a, b| *a = b.clone() +  a.clone());
-    impl_op_muta_refb_r_consume!(DeviceRConsumeSubAPI   , Sub   , |a, b| *a = b.clone() -  a.clone());
-    impl_op_muta_refb_r_consume!(DeviceRConsumeMulAPI   , Mul   , |a, b| *a = b.clone() *  a.clone());
-    impl_op_muta_refb_r_consume!(DeviceRConsumeDivAPI   , Div   , |a, b| *a = b.clone() /  a.clone());
-    impl_op_muta_refb_r_consume!(DeviceRConsumeRemAPI   , Rem   , |a, b| *a = b.clone() %  a.clone());
-    impl_op_muta_refb_r_consume!(DeviceRConsumeBitOrAPI , BitOr , |a, b| *a = b.clone() |  a.clone());
-    impl_op_muta_refb_r_consume!(DeviceRConsumeBitAndAPI, BitAnd, |a, b| *a = b.clone() &  a.clone());
-    impl_op_muta_refb_r_consume!(DeviceRConsumeBitXorAPI, BitXor, |a, b| *a = b.clone() ^  a.clone());
-    impl_op_muta_refb_r_consume!(DeviceRConsumeShlAPI   , Shl   , |a, b| *a = b.clone() << a.clone());
-    impl_op_muta_refb_r_consume!(DeviceRConsumeShrAPI   , Shr   , |a, b| *a = b.clone() >> a.clone());
-}
+pub(crate) use impl_op_muta_refb_r_consume;
 
 macro_rules! impl_op_muta_refb_unary {
-    ($DeviceOpAPI:ident, $Op:ident, $op_muta_refb_func:ident, $func:expr, $func_inplace:expr) => {
-        impl<TA, TB, D> $DeviceOpAPI<TA, TB, D> for DeviceCpuSerial
+    ([$Device:ty $(, $Bound:ident)*], $DeviceOpAPI:ident, $Op:ident, $op_muta_refb_func:ident, $func:expr, $func_inplace:expr) => {
+        impl<TA, TB, D> $DeviceOpAPI<TA, TB, D> for $Device
         where
-            TA: Clone,
-            TB: Clone,
+            TA: Clone $(+ $Bound)*,
+            TB: Clone $(+ $Bound)*,
             D: DimAPI,
         {
             fn op_muta_refb(
@@ -154,25 +107,66 @@ macro_rules! impl_op_muta_refb_unary {
                 lb: &Layout<D>,
             ) -> Result<()>
             where
-                TB: $Op<Output = TA>,
+                TB: core::ops::$Op<Output = TA>,
             {
                 self.op_muta_refb_func(a, la, b, lb, &mut $func)
             }
 
             fn op_muta(&self, a: &mut Storage<TA, Self>, la: &Layout<D>) -> Result<()>
             where
-                TA: $Op<Output = TA>,
+                TA: core::ops::$Op<Output = TA>,
             {
                 self.op_muta_func(a, la, &mut $func_inplace)
             }
         }
     };
 }
+pub(crate) use impl_op_muta_refb_unary;
 
+/// Implement operator traits of assignment (`a += b`), consuming (`a + b`
+/// storing into `a` or `b`) and unary (`-a`) operations for CPU device, by its
+/// elementwise functions (`DeviceOp_MutA_RefB_API` etc.).
+///
+/// Additional bounds of element types (e.g. `Send, Sync` for parallel devices)
+/// can be given after device type.
 #[rustfmt::skip]
-mod impl_op_muta_refb_unary {
-    use super::*;
-    use core::ops::*;
-    impl_op_muta_refb_unary!(DeviceNegAPI, Neg, op_muta_refb_neg, |a, b| *a = -b.clone(), |a| *a = -a.clone());
-    impl_op_muta_refb_unary!(DeviceNotAPI, Not, op_muta_refb_not, |a, b| *a = !b.clone(), |a| *a = !a.clone());
+macro_rules! impl_op_binary_cpu {
+    ($Device:ty $(, $Bound:ident)*) => {
+        const _: () = {
+            use $crate::prelude_dev::*;
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_assign!([$Device $(, $Bound)*], DeviceAddAssignAPI   , AddAssign   , |a, b| *a +=  b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_assign!([$Device $(, $Bound)*], DeviceSubAssignAPI   , SubAssign   , |a, b| *a -=  b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_assign!([$Device $(, $Bound)*], DeviceMulAssignAPI   , MulAssign   , |a, b| *a *=  b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_assign!([$Device $(, $Bound)*], DeviceDivAssignAPI   , DivAssign   , |a, b| *a /=  b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_assign!([$Device $(, $Bound)*], DeviceRemAssignAPI   , RemAssign   , |a, b| *a %=  b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_assign!([$Device $(, $Bound)*], DeviceBitOrAssignAPI , BitOrAssign , |a, b| *a |=  b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_assign!([$Device $(, $Bound)*], DeviceBitAndAssignAPI, BitAndAssign, |a, b| *a &=  b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_assign!([$Device $(, $Bound)*], DeviceBitXorAssignAPI, BitXorAssign, |a, b| *a ^=  b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_assign!([$Device $(, $Bound)*], DeviceShlAssignAPI   , ShlAssign   , |a, b| *a <<= b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_assign!([$Device $(, $Bound)*], DeviceShrAssignAPI   , ShrAssign   , |a, b| *a >>= b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_l_consume!([$Device $(, $Bound)*], DeviceLConsumeAddAPI   , Add   , |a, b| *a = a.clone() +  b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_l_consume!([$Device $(, $Bound)*], DeviceLConsumeSubAPI   , Sub   , |a, b| *a = a.clone() -  b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_l_consume!([$Device $(, $Bound)*], DeviceLConsumeMulAPI   , Mul   , |a, b| *a = a.clone() *  b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_l_consume!([$Device $(, $Bound)*], DeviceLConsumeDivAPI   , Div   , |a, b| *a = a.clone() /  b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_l_consume!([$Device $(, $Bound)*], DeviceLConsumeRemAPI   , Rem   , |a, b| *a = a.clone() %  b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_l_consume!([$Device $(, $Bound)*], DeviceLConsumeBitOrAPI , BitOr , |a, b| *a = a.clone() |  b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_l_consume!([$Device $(, $Bound)*], DeviceLConsumeBitAndAPI, BitAnd, |a, b| *a = a.clone() &  b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_l_consume!([$Device $(, $Bound)*], DeviceLConsumeBitXorAPI, BitXor, |a, b| *a = a.clone() ^  b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_l_consume!([$Device $(, $Bound)*], DeviceLConsumeShlAPI   , Shl   , |a, b| *a = a.clone() << b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_l_consume!([$Device $(, $Bound)*], DeviceLConsumeShrAPI   , Shr   , |a, b| *a = a.clone() >> b.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_r_consume!([$Device $(, $Bound)*], DeviceRConsumeAddAPI   , Add   , |a, b| *a = b.clone() +  a.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_r_consume!([$Device $(, $Bound)*], DeviceRConsumeSubAPI   , Sub   , |a, b| *a = b.clone() -  a.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_r_consume!([$Device $(, $Bound)*], DeviceRConsumeMulAPI   , Mul   , |a, b| *a = b.clone() *  a.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_r_consume!([$Device $(, $Bound)*], DeviceRConsumeDivAPI   , Div   , |a, b| *a = b.clone() /  a.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_r_consume!([$Device $(, $Bound)*], DeviceRConsumeRemAPI   , Rem   , |a, b| *a = b.clone() %  a.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_r_consume!([$Device $(, $Bound)*], DeviceRConsumeBitOrAPI , BitOr , |a, b| *a = b.clone() |  a.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_r_consume!([$Device $(, $Bound)*], DeviceRConsumeBitAndAPI, BitAnd, |a, b| *a = b.clone() &  a.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_r_consume!([$Device $(, $Bound)*], DeviceRConsumeBitXorAPI, BitXor, |a, b| *a = b.clone() ^  a.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_r_consume!([$Device $(, $Bound)*], DeviceRConsumeShlAPI   , Shl   , |a, b| *a = b.clone() << a.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_r_consume!([$Device $(, $Bound)*], DeviceRConsumeShrAPI   , Shr   , |a, b| *a = b.clone() >> a.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_unary!([$Device $(, $Bound)*], DeviceNegAPI, Neg, op_muta_refb_neg, |a, b| *a = -b.clone(), |a| *a = -a.clone());
+            $crate::device_cpu_serial::operators::op_binary::impl_op_muta_refb_unary!([$Device $(, $Bound)*], DeviceNotAPI, Not, op_muta_refb_not, |a, b| *a = !b.clone(), |a| *a = !a.clone());
+        };
+    };
 }
+pub(crate) use impl_op_binary_cpu;
@@ -1,12 +1,10 @@
-use crate::prelude_dev::*;
-
 macro_rules! impl_op_mutc_refa_refb_operator {
-    ($DeviceOpAPI:ident, $Op:ident, $func:expr) => {
-        impl<TA, TB, TC, D> $DeviceOpAPI<TA, TB, TC, D> for DeviceCpuSerial
+    ([$Device:ty $(, $Bound:ident)*], $DeviceOpAPI:ident, $Op:ident, $func:expr) => {
+        impl<TA, TB, TC, D> $DeviceOpAPI<TA, TB, TC, D> for $Device
         where
-            TA: Clone + $Op<TB, Output = TC>,
-            TB: Clone,
-            TC: Clone,
+            TA: Clone + core::ops::$Op<TB, Output = TC> $(+ $Bound)*,
+            TB: Clone $(+ $Bound)*,
+            TC: Clone $(+ $Bound)*,
             D: DimAPI,
         {
             fn op_mutc_refa_refb(
@@ -45,19 +43,29 @@ macro_rules! impl_op_mutc_refa_refb_operator {
         }
     };
 }
+pub(crate) use impl_op_mutc_refa_refb_operator;
 
+/// Implement operator traits of binary operations (`c = a + b`) for CPU
+/// device, by its elementwise functions (`DeviceOp_MutC_RefA_RefB_API` etc.).
+///
+/// Additional bounds of element types (e.g. `Send, Sync` for parallel devices)
+/// can be given after device type.
 #[rustfmt::skip]
-mod impl_op_mutc_refa_refb_operator {
-    use super::*;
-    use core::ops::*;
-    impl_op_mutc_refa_refb_operator!(DeviceAddAPI   , Add   , |c, a, b| *c = a.clone() +  b.clone());
-    impl_op_mutc_refa_refb_operator!(DeviceSubAPI   , Sub   , |c, a, b| *c = a.clone() -  b.clone());
-    impl_op_mutc_refa_refb_operator!(DeviceMulAPI   , Mul   , |c, a, b| *c = a.clone() *  b.clone());
-    impl_op_mutc_refa_refb_operator!(DeviceDivAPI   , Div   , |c, a, b| *c = a.clone() /  b.clone());
-    impl_op_mutc_refa_refb_operator!(DeviceRemAPI   , Rem   , |c, a, b| *c = a.clone() %  b.clone());
-    impl_op_mutc_refa_refb_operator!(DeviceBitOrAPI , BitOr , |c, a, b| *c = a.clone() |  b.clone());
-    impl_op_mutc_refa_refb_operator!(DeviceBitAndAPI, BitAnd, |c, a, b| *c = a.clone() &  b.clone());
-    impl_op_mutc_refa_refb_operator!(DeviceBitXorAPI, BitXor, |c, a, b| *c = a.clone() ^  b.clone());
-    impl_op_mutc_refa_refb_operator!(DeviceShlAPI   , Shl   , |c, a, b| *c = a.clone() << b.clone());
-    impl_op_mutc_refa_refb_operator!(DeviceShrAPI   , Shr   , |c, a, b| *c = a.clone() >> b.clone());
+macro_rules! impl_op_ternary_cpu {
+    ($Device:ty $(, $Bound:ident)*) => {
+        const _: () = {
+            use $crate::prelude_dev::*;
+            $crate::device_cpu_serial::operators::op_ternary::impl_op_mutc_refa_refb_operator!([$Device $(, $Bound)*], DeviceAddAPI   , Add   , |c, a, b| *c = a.clone() +  b.clone());
+            $crate::device_cpu_serial::operators::op_ternary::impl_op_mutc_refa_refb_operator!([$Device $(, $Bound)*], DeviceSubAPI   , Sub   , |c, a, b| *c = a.clone() -  b.clone());
+            $crate::device_cpu_serial::operators::op_ternary::impl_op_mutc_refa_refb_operator!([$Device $(, $Bound)*], DeviceMulAPI   , Mul   , |c, a, b| *c = a.clone() *  b.clone());
+            $crate::device_cpu_serial::operators::op_ternary::impl_op_mutc_refa_refb_operator!([$Device $(, $Bound)*], DeviceDivAPI   , Div   , |c, a, b| *c = a.clone() /  b.clone());
+            $crate::device_cpu_serial::operators::op_ternary::impl_op_mutc_refa_refb_operator!([$Device $(, $Bound)*], DeviceRemAPI   , Rem   , |c, a, b| *c = a.clone() %  b.clone());
+            $crate::device_cpu_serial::operators::op_ternary::impl_op_mutc_refa_refb_operator!([$Device $(, $Bound)*], DeviceBitOrAPI , BitOr , |c, a, b| *c = a.clone() |  b.clone());
+            $crate::device_cpu_serial::operators::op_ternary::impl_op_mutc_refa_refb_operator!([$Device $(, $Bound)*], DeviceBitAndAPI, BitAnd, |c, a, b| *c = a.clone() &  b.clone());
+            $crate::device_cpu_serial::operators::op_ternary::impl_op_mutc_refa_refb_operator!([$Device $(, $Bound)*], DeviceBitXorAPI, BitXor, |c, a, b| *c = a.clone() ^  b.clone());
+            $crate::device_cpu_serial::operators::op_ternary::impl_op_mutc_refa_refb_operator!([$Device $(, $Bound)*], DeviceShlAPI   , Shl   , |c, a, b| *c = a.clone() << b.clone());
+            $crate::device_cpu_serial::operators::op_ternary::impl_op_mutc_refa_refb_operator!([$Device $(, $Bound)*], DeviceShrAPI   , Shr   , |c, a, b| *c = a.clone() >> b.clone());
+        };
+    };
 }
+pub(crate) use impl_op_ternary_cpu;
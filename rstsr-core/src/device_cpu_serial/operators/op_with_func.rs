@@ -55,11 +55,11 @@ where
 /* #region op_func definition */
 
 pub fn op_mutc_refa_refb_func_cpu_serial<TA, TB, TC, D>(
-    c: &mut [TC],
+    mut c: CpuRawSliceMut<'_, TC>,
     lc: &Layout<D>,
-    a: &[TA],
+    a: CpuRawSlice<'_, TA>,
     la: &Layout<D>,
-    b: &[TB],
+    b: CpuRawSlice<'_, TB>,
     lb: &Layout<D>,
     mut f: impl FnMut(&mut TC, &TA, &TB),
 ) -> Result<()>
@@ -93,9 +93,9 @@ where
 }
 
 pub fn op_mutc_refa_numb_func_cpu_serial<TA, TB, TC, D>(
-    c: &mut [TC],
+    mut c: CpuRawSliceMut<'_, TC>,
     lc: &Layout<D>,
-    a: &[TA],
+    a: CpuRawSlice<'_, TA>,
     la: &Layout<D>,
     b: TB,
     mut f: impl FnMut(&mut TC, &TA, &TB),
@@ -128,10 +128,10 @@ where
 }
 
 pub fn op_mutc_numa_refb_func_cpu_serial<TA, TB, TC, D>(
-    c: &mut [TC],
+    mut c: CpuRawSliceMut<'_, TC>,
     lc: &Layout<D>,
     a: TA,
-    b: &[TB],
+    b: CpuRawSlice<'_, TB>,
     lb: &Layout<D>,
     mut f: impl FnMut(&mut TC, &TA, &TB),
 ) -> Result<()>
@@ -163,9 +163,9 @@ where
 }
 
 pub fn op_muta_refb_func_cpu_serial<TA, TB, D>(
-    a: &mut [TA],
+    mut a: CpuRawSliceMut<'_, TA>,
     la: &Layout<D>,
-    b: &[TB],
+    b: CpuRawSlice<'_, TB>,
     lb: &Layout<D>,
     mut f: impl FnMut(&mut TA, &TB),
 ) -> Result<()>
@@ -197,7 +197,7 @@ where
 }
 
 pub fn op_muta_numb_func_cpu_serial<TA, TB, D>(
-    a: &mut [TA],
+    mut a: CpuRawSliceMut<'_, TA>,
     la: &Layout<D>,
     b: TB,
     mut f: impl FnMut(&mut TA, &TB),
@@ -225,7 +225,7 @@ where
 }
 
 pub fn op_muta_func_cpu_serial<T, D>(
-    a: &mut [T],
+    mut a: CpuRawSliceMut<'_, T>,
     la: &Layout<D>,
     mut f: impl FnMut(&mut T),
 ) -> Result<()>
@@ -251,142 +251,237 @@ where
     return Ok(());
 }
 
-/* #endregion */
-
-/* #region impl op_func for DeviceCpuSerial */
-
-impl<TA, TB, TC, D, F> DeviceOp_MutC_RefA_RefB_API<TA, TB, TC, D, F> for DeviceCpuSerial
+pub fn sum_cpu_serial<T, D>(a: CpuRawSlice<'_, T>, la: &Layout<D>) -> Result<T>
 where
-    TA: Clone,
-    TB: Clone,
-    TC: Clone,
+    T: Zero + core::ops::Add<Output = T> + Clone,
     D: DimAPI,
-    F: FnMut(&mut TC, &TA, &TB) + ?Sized,
 {
-    fn op_mutc_refa_refb_func(
-        &self,
-        c: &mut Storage<TC, Self>,
-        lc: &Layout<D>,
-        a: &Storage<TA, Self>,
-        la: &Layout<D>,
-        b: &Storage<TB, Self>,
-        lb: &Layout<D>,
-        f: &mut F,
-    ) -> Result<()> {
-        op_mutc_refa_refb_func_cpu_serial(c.rawvec_mut(), lc, a.rawvec(), la, b.rawvec(), lb, f)
-    }
-}
+    let layout = translate_to_col_major_unary(la, TensorIterOrder::K)?;
+    let (layout_contig, size_contig) = translate_to_col_major_with_contig(&[&layout]);
 
-impl<TA, TB, TC, D, F> DeviceOp_MutC_RefA_NumB_API<TA, TB, TC, D, F> for DeviceCpuSerial
-where
-    TA: Clone,
-    TC: Clone,
-    D: DimAPI,
-    F: FnMut(&mut TC, &TA, &TB) + ?Sized,
-{
-    fn op_mutc_refa_numb_func(
-        &self,
-        c: &mut Storage<TC, Self>,
-        lc: &Layout<D>,
-        a: &Storage<TA, Self>,
-        la: &Layout<D>,
-        b: TB,
-        f: &mut F,
-    ) -> Result<()> {
-        op_mutc_refa_numb_func_cpu_serial(c.rawvec_mut(), lc, a.rawvec(), la, b, f)
+    if size_contig >= CONTIG_SWITCH {
+        let mut sum = T::zero();
+        let iter_a = IterLayoutColMajor::new(&layout_contig[0])?;
+        for idx_a in iter_a {
+            let slc = &a[idx_a..idx_a + size_contig];
+            sum = sum + unrolled_fold(slc, || T::zero(), |acc, x| acc + x.clone());
+        }
+        return Ok(sum);
+    } else {
+        let iter_a = IterLayoutColMajor::new(&layout)?;
+        let sum = iter_a.fold(T::zero(), |acc, idx| acc + a[idx].clone());
+        return Ok(sum);
     }
 }
 
-impl<TA, TB, TC, D, F> DeviceOp_MutC_NumA_RefB_API<TA, TB, TC, D, F> for DeviceCpuSerial
+pub fn sum_axes_cpu_serial<T>(
+    mut c: CpuRawSliceMut<'_, T>,
+    lc: &Layout<IxD>,
+    a: CpuRawSlice<'_, T>,
+    la: &Layout<IxD>,
+    naxes: usize,
+) -> Result<()>
 where
-    TB: Clone,
-    TC: Clone,
-    D: DimAPI,
-    F: FnMut(&mut TC, &TA, &TB) + ?Sized,
+    T: Zero + core::ops::Add<Output = T> + Clone,
 {
-    fn op_mutc_numa_refb_func(
-        &self,
-        c: &mut Storage<TC, Self>,
-        lc: &Layout<D>,
-        a: TA,
-        b: &Storage<TB, Self>,
-        lb: &Layout<D>,
-        f: &mut F,
-    ) -> Result<()> {
-        op_mutc_numa_refb_func_cpu_serial(c.rawvec_mut(), lc, a, b.rawvec(), lb, f)
-    }
-}
+    rstsr_assert!(naxes <= la.ndim(), InvalidLayout)?;
+    let (la_outer, la_inner) = la.dim_split_at((la.ndim() - naxes) as isize)?;
+    rstsr_assert_eq!(lc.shape(), la_outer.shape(), InvalidLayout)?;
 
-impl<TA, TB, D, F> DeviceOp_MutA_RefB_API<TA, TB, D, F> for DeviceCpuSerial
-where
-    TA: Clone,
-    TB: Clone,
-    D: DimAPI,
-    F: FnMut(&mut TA, &TB) + ?Sized,
-{
-    fn op_muta_refb_func(
-        &self,
-        a: &mut Storage<TA, Self>,
-        la: &Layout<D>,
-        b: &Storage<TB, Self>,
-        lb: &Layout<D>,
-        f: &mut F,
-    ) -> Result<()> {
-        op_muta_refb_func_cpu_serial(a.rawvec_mut(), la, b.rawvec(), lb, f)
+    let iter_c = IterLayoutColMajor::new(lc)?;
+    let iter_a = IterLayoutColMajor::new(&la_outer)?;
+    for (idx_c, idx_a) in izip!(iter_c, iter_a) {
+        let la_sum = unsafe {
+            Layout::new_unchecked(la_inner.shape().clone(), la_inner.stride().clone(), idx_a)
+        };
+        c[idx_c] = sum_cpu_serial(a, &la_sum)?;
     }
+    return Ok(());
 }
 
-impl<TA, TB, D, F> DeviceOp_MutA_NumB_API<TA, TB, D, F> for DeviceCpuSerial
-where
-    TA: Clone,
-    D: DimAPI,
-    F: FnMut(&mut TA, &TB) + ?Sized,
-{
-    fn op_muta_numb_func(
-        &self,
-        a: &mut Storage<TA, Self>,
-        la: &Layout<D>,
-        b: TB,
-        f: &mut F,
-    ) -> Result<()> {
-        op_muta_numb_func_cpu_serial(a.rawvec_mut(), la, b, f)
-    }
-}
+/* #endregion */
 
-impl<T, D, F> DeviceOp_MutA_API<T, D, F> for DeviceCpuSerial
-where
-    T: Clone,
-    D: DimAPI,
-    F: FnMut(&mut T) + ?Sized,
-{
-    fn op_muta_func(&self, a: &mut Storage<T, Self>, la: &Layout<D>, f: &mut F) -> Result<()> {
-        op_muta_func_cpu_serial(a.rawvec_mut(), la, f)
-    }
-}
+/* #region impl op_func for CPU serial devices */
 
-/* #endregion */
+/// Implement elementwise and reduction traits for a CPU device whose raw
+/// vector derefs to `[T]`, by the serial routines of this module.
+///
+/// If `$record` is given, `self.$record(kind, name, layouts, bytes)` is
+/// called before each routine (see `DeviceCpuCounting`, enabled by feature
+/// `counting`).
+macro_rules! impl_op_with_func_cpu_serial {
+    ($Device:ty $(, $record:ident)?) => {
+        impl<TA, TB, TC, D, F> DeviceOp_MutC_RefA_RefB_API<TA, TB, TC, D, F> for $Device
+        where
+            TA: Clone,
+            TB: Clone,
+            TC: Clone,
+            D: DimAPI,
+            F: FnMut(&mut TC, &TA, &TB) + ?Sized,
+        {
+            fn op_mutc_refa_refb_func(
+                &self,
+                c: &mut Storage<TC, Self>,
+                lc: &Layout<D>,
+                a: &Storage<TA, Self>,
+                la: &Layout<D>,
+                b: &Storage<TB, Self>,
+                lb: &Layout<D>,
+                f: &mut F,
+            ) -> Result<()> {
+                $(
+                    let layouts = vec![lc.to_dim::<IxD>()?, la.to_dim::<IxD>()?, lb.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::Op, "op_mutc_refa_refb_func", layouts, 0);
+                )?
+                op_mutc_refa_refb_func_cpu_serial(c.rawvec_mut().as_raw_slice_mut(), lc, a.rawvec().as_raw_slice(), la, b.rawvec().as_raw_slice(), lb, f)
+            }
+        }
 
-impl<T, D> OpSumAPI<T, D> for DeviceCpuSerial
-where
-    T: Zero + core::ops::Add<Output = T> + Clone,
-    D: DimAPI,
-{
-    fn sum(&self, a: &Storage<T, Self>, la: &Layout<D>) -> Result<T> {
-        let layout = translate_to_col_major_unary(la, TensorIterOrder::K)?;
-        let (layout_contig, size_contig) = translate_to_col_major_with_contig(&[&layout]);
-
-        if size_contig >= CONTIG_SWITCH {
-            let mut sum = T::zero();
-            let iter_a = IterLayoutColMajor::new(&layout_contig[0])?;
-            for idx_a in iter_a {
-                let slc = &a.rawvec[idx_a..idx_a + size_contig];
-                sum = sum + unrolled_fold(slc, || T::zero(), |acc, x| acc + x.clone());
+        impl<TA, TB, TC, D, F> DeviceOp_MutC_RefA_NumB_API<TA, TB, TC, D, F> for $Device
+        where
+            TA: Clone,
+            TC: Clone,
+            D: DimAPI,
+            F: FnMut(&mut TC, &TA, &TB) + ?Sized,
+        {
+            fn op_mutc_refa_numb_func(
+                &self,
+                c: &mut Storage<TC, Self>,
+                lc: &Layout<D>,
+                a: &Storage<TA, Self>,
+                la: &Layout<D>,
+                b: TB,
+                f: &mut F,
+            ) -> Result<()> {
+                $(
+                    let layouts = vec![lc.to_dim::<IxD>()?, la.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::Op, "op_mutc_refa_numb_func", layouts, 0);
+                )?
+                op_mutc_refa_numb_func_cpu_serial(c.rawvec_mut().as_raw_slice_mut(), lc, a.rawvec().as_raw_slice(), la, b, f)
             }
-            return Ok(sum);
-        } else {
-            let iter_a = IterLayoutColMajor::new(&layout)?;
-            let sum = iter_a.fold(T::zero(), |acc, idx| acc + a.rawvec[idx].clone());
-            return Ok(sum);
         }
-    }
+
+        impl<TA, TB, TC, D, F> DeviceOp_MutC_NumA_RefB_API<TA, TB, TC, D, F> for $Device
+        where
+            TB: Clone,
+            TC: Clone,
+            D: DimAPI,
+            F: FnMut(&mut TC, &TA, &TB) + ?Sized,
+        {
+            fn op_mutc_numa_refb_func(
+                &self,
+                c: &mut Storage<TC, Self>,
+                lc: &Layout<D>,
+                a: TA,
+                b: &Storage<TB, Self>,
+                lb: &Layout<D>,
+                f: &mut F,
+            ) -> Result<()> {
+                $(
+                    let layouts = vec![lc.to_dim::<IxD>()?, lb.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::Op, "op_mutc_numa_refb_func", layouts, 0);
+                )?
+                op_mutc_numa_refb_func_cpu_serial(c.rawvec_mut().as_raw_slice_mut(), lc, a, b.rawvec().as_raw_slice(), lb, f)
+            }
+        }
+
+        impl<TA, TB, D, F> DeviceOp_MutA_RefB_API<TA, TB, D, F> for $Device
+        where
+            TA: Clone,
+            TB: Clone,
+            D: DimAPI,
+            F: FnMut(&mut TA, &TB) + ?Sized,
+        {
+            fn op_muta_refb_func(
+                &self,
+                a: &mut Storage<TA, Self>,
+                la: &Layout<D>,
+                b: &Storage<TB, Self>,
+                lb: &Layout<D>,
+                f: &mut F,
+            ) -> Result<()> {
+                $(
+                    let layouts = vec![la.to_dim::<IxD>()?, lb.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::Op, "op_muta_refb_func", layouts, 0);
+                )?
+                op_muta_refb_func_cpu_serial(a.rawvec_mut().as_raw_slice_mut(), la, b.rawvec().as_raw_slice(), lb, f)
+            }
+        }
+
+        impl<TA, TB, D, F> DeviceOp_MutA_NumB_API<TA, TB, D, F> for $Device
+        where
+            TA: Clone,
+            D: DimAPI,
+            F: FnMut(&mut TA, &TB) + ?Sized,
+        {
+            fn op_muta_numb_func(
+                &self,
+                a: &mut Storage<TA, Self>,
+                la: &Layout<D>,
+                b: TB,
+                f: &mut F,
+            ) -> Result<()> {
+                $(
+                    let layouts = vec![la.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::Op, "op_muta_numb_func", layouts, 0);
+                )?
+                op_muta_numb_func_cpu_serial(a.rawvec_mut().as_raw_slice_mut(), la, b, f)
+            }
+        }
+
+        impl<T, D, F> DeviceOp_MutA_API<T, D, F> for $Device
+        where
+            T: Clone,
+            D: DimAPI,
+            F: FnMut(&mut T) + ?Sized,
+        {
+            fn op_muta_func(&self, a: &mut Storage<T, Self>, la: &Layout<D>, f: &mut F) -> Result<()> {
+                $(
+                    let layouts = vec![la.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::Op, "op_muta_func", layouts, 0);
+                )?
+                op_muta_func_cpu_serial(a.rawvec_mut().as_raw_slice_mut(), la, f)
+            }
+        }
+
+        impl<T, D> OpSumAPI<T, D> for $Device
+        where
+            T: Zero + core::ops::Add<Output = T> + Clone,
+            D: DimAPI,
+        {
+            fn sum(&self, a: &Storage<T, Self>, la: &Layout<D>) -> Result<T> {
+                $(
+                    let layouts = vec![la.to_dim::<IxD>()?];
+                    self.$record(DeviceCallKind::Reduce, "sum", layouts, 0);
+                )?
+                sum_cpu_serial(a.rawvec().as_raw_slice(), la)
+            }
+        }
+
+        impl<T> OpSumAxesAPI<T> for $Device
+        where
+            T: Zero + core::ops::Add<Output = T> + Clone,
+        {
+            fn sum_axes(
+                &self,
+                c: &mut Storage<T, Self>,
+                lc: &Layout<IxD>,
+                a: &Storage<T, Self>,
+                la: &Layout<IxD>,
+                naxes: usize,
+            ) -> Result<()> {
+                $(
+                    let layouts = vec![lc.clone(), la.clone()];
+                    self.$record(DeviceCallKind::Reduce, "sum_axes", layouts, 0);
+                )?
+                sum_axes_cpu_serial(c.rawvec_mut().as_raw_slice_mut(), lc, a.rawvec().as_raw_slice(), la, naxes)
+            }
+        }
+    };
 }
+#[cfg(any(test, feature = "counting", feature = "std"))]
+pub(crate) use impl_op_with_func_cpu_serial;
+
+impl_op_with_func_cpu_serial!(DeviceCpuSerial);
+
+/* #endregion */
@@ -13,10 +13,10 @@ where
         a: &Storage<T, Self>,
         la: &Layout<DA>,
     ) -> Result<()> {
-        let c = c.rawvec_mut();
-        let a = a.rawvec();
+        let c = c.rawvec_mut().as_raw_slice_mut();
+        let a = a.rawvec().as_raw_slice();
         let nthreads = self.get_num_threads();
-        assign_arbitary_cpu_rayon(c, lc, a, la, nthreads)
+        self.install(|| assign_arbitary_cpu_rayon(c, lc, a, la, nthreads))?
     }
 }
 
@@ -32,15 +32,15 @@ where
         a: &Storage<T, Self>,
         la: &Layout<D>,
     ) -> Result<()> {
-        let c = c.rawvec_mut();
-        let a = a.rawvec();
+        let c = c.rawvec_mut().as_raw_slice_mut();
+        let a = a.rawvec().as_raw_slice();
         let nthreads = self.get_num_threads();
-        assign_cpu_rayon(c, lc, a, la, nthreads)
+        self.install(|| assign_cpu_rayon(c, lc, a, la, nthreads))?
     }
 
     fn fill(&self, c: &mut Storage<T, Self>, lc: &Layout<D>, fill: T) -> Result<()> {
-        let c = c.rawvec_mut();
+        let c = c.rawvec_mut().as_raw_slice_mut();
         let nthreads = self.get_num_threads();
-        fill_cpu_rayon(c, lc, fill, nthreads)
+        self.install(|| fill_cpu_rayon(c, lc, fill, nthreads))?
     }
 }
@@ -0,0 +1,312 @@
+//! Matrix congruence `c = a^T b a` for faer device.
+//!
+//! The contraction order, `(b a)` or `(a^T b)` first, is chosen by the FLOP
+//! count of each order. For batched `b`, if the batch dimensions can be merged
+//! into rows (or columns) of `b`, the first contraction is performed as one
+//! large GEMM instead of many small ones.
+
+use super::matmul::{gemm_faer_dispatch, same_type};
+use super::matmul_impl::*;
+use crate::prelude_dev::*;
+use core::ops::{Add, Mul};
+use num::{Complex, One, Zero};
+
+/// Matrix multiplication `c = a b` where `c` is known to be symmetric.
+///
+/// Only lower triangle is computed by faer, and then copied to upper triangle.
+/// For types not supported by faer, full matrix multiplication is performed.
+fn gemm_symm_output_faer_dispatch<T>(
+    c: &mut Storage<T, DeviceFaer>,
+    lc: &Layout<Ix2>,
+    a: &Storage<T, DeviceFaer>,
+    la: &Layout<Ix2>,
+    b: &Storage<T, DeviceFaer>,
+    lb: &Layout<Ix2>,
+    nthreads: usize,
+) -> Result<()>
+where
+    T: Clone + Send + Sync + 'static,
+    T: Mul<T, Output = T> + Add<T, Output = T> + Zero + One,
+{
+    macro_rules! impl_gemm_tri_dispatch {
+        ($ty: ty, $fn_name: ident) => {
+            if same_type::<T, $ty>() {
+                let (a_ptr, b_ptr) = (a.rawvec().as_ptr(), b.rawvec().as_ptr());
+                let c_ptr = c.rawvec_mut().as_raw_slice_mut().as_mut_ptr();
+                let a_slice = unsafe { CpuRawSlice::from_raw_parts(a_ptr as *const $ty, a.len()) };
+                let b_slice = unsafe { CpuRawSlice::from_raw_parts(b_ptr as *const $ty, b.len()) };
+                let mut c_slice =
+                    unsafe { CpuRawSliceMut::from_raw_parts(c_ptr as *mut $ty, c.len()) };
+                let (alpha, beta) = (<$ty>::one(), <$ty>::zero());
+                let uplo = TensorUpLo::L;
+                let c_tri = c_slice.reborrow();
+                $fn_name(c_tri, lc, a_slice, la, b_slice, lb, uplo, alpha, beta, nthreads)?;
+                return symmetrize_lower(c_slice, lc);
+            }
+        };
+    }
+
+    impl_gemm_tri_dispatch!(f32, gemm_tri_faer_f32);
+    impl_gemm_tri_dispatch!(f64, gemm_tri_faer_f64);
+    impl_gemm_tri_dispatch!(Complex<f32>, gemm_tri_faer_c32);
+    impl_gemm_tri_dispatch!(Complex<f64>, gemm_tri_faer_c64);
+
+    // not able to be accelarated by faer
+    let (sc, sa, sb) =
+        (c.rawvec_mut().as_raw_slice_mut(), a.rawvec().as_raw_slice(), b.rawvec().as_raw_slice());
+    return gemm_faer_dispatch(sc, lc, sa, la, sb, lb, T::one(), T::zero(), nthreads);
+}
+
+/// Contraction performed first in matrix congruence `a^T b a`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CongruenceOrder {
+    /// `t = b a`, then `c = a^T t`.
+    RightFirst,
+    /// `t = a^T b`, then `c = t a`.
+    LeftFirst,
+}
+
+/// Choose contraction order of `a^T b a` for `a` of shape `[n, m]`.
+///
+/// Each order is counted by multiplications per batch: `n n m + m n m` for
+/// `b a` first and `m n n + m n m` for `a^T b` first, i.e. `n m (n + m)` for
+/// both, since `b` is square. The cheaper order is taken; on a tie, the order
+/// whose first contraction can merge batches into one GEMM is preferred, then
+/// `b a` first.
+fn congruence_order(n: usize, m: usize, merge_rows: bool, merge_cols: bool) -> CongruenceOrder {
+    let flops_ba = n * n * m + m * n * m;
+    let flops_atb = m * n * n + m * n * m;
+    match flops_ba.cmp(&flops_atb) {
+        core::cmp::Ordering::Less => CongruenceOrder::RightFirst,
+        core::cmp::Ordering::Greater => CongruenceOrder::LeftFirst,
+        core::cmp::Ordering::Equal => match (merge_rows, merge_cols) {
+            (false, true) => CongruenceOrder::LeftFirst,
+            _ => CongruenceOrder::RightFirst,
+        },
+    }
+}
+
+impl<T> DeviceCongruenceAPI<T> for DeviceFaer
+where
+    T: Clone + Send + Sync + 'static,
+    T: Mul<T, Output = T> + Add<T, Output = T> + Zero + One,
+    Self: DeviceAPI<T, RawVec = CpuRawVec<T>>,
+{
+    fn congruence(
+        &self,
+        c: &mut Storage<T, Self>,
+        lc: &Layout<IxD>,
+        a: &Storage<T, Self>,
+        la: &Layout<Ix2>,
+        b: &Storage<T, Self>,
+        lb: &Layout<IxD>,
+        uplo: Option<TensorUpLo>,
+    ) -> Result<()> {
+        let nthreads = self.get_num_threads();
+        self.install(|| {
+            rstsr_assert!(c.device().same_device(a.device()), DeviceMismatch)?;
+            rstsr_assert!(c.device().same_device(b.device()), DeviceMismatch)?;
+
+            let (lb_rest, lb_matmul, lc_rest, lc_matmul) = layout_congruence_split(la, lb, lc)?;
+            let (n, m) = (la.shape()[0], la.shape()[1]);
+            let la_t = &la.reverse_axes();
+            let (one, zero) = (T::one(), T::zero());
+
+            let l_rest = translate_to_col_major(&[&lc_rest, &lb_rest], TensorIterOrder::K)?;
+            let (lc_rest, lb_rest) = (&l_rest[0], &l_rest[1]);
+            let ib_list = IterLayoutColMajor::new(lb_rest)?.collect_vec();
+            let ic_list = IterLayoutColMajor::new(lc_rest)?.collect_vec();
+            let nbatch = ib_list.len();
+            if nbatch == 0 || m == 0 {
+                return Ok(());
+            }
+
+            let mut lb_k = lb_matmul.clone();
+            let mut lc_k = lc_matmul.clone();
+
+            // symmetric b: symm for b a, and only lower triangle of a^T (b a)
+            if let Some(uplo) = uplo {
+                let lt = &[n, m].c();
+                let mut t = Storage::new(vec![zero.clone(); n * m].into(), self.clone());
+                for (&ib, &ic) in izip!(&ib_list, &ic_list) {
+                    unsafe { lb_k.set_offset(ib) };
+                    unsafe { lc_k.set_offset(ic) };
+                    let side = TensorSide::L;
+                    self.symm(&mut t, lt, b, &lb_k, a, la, side, uplo, one.clone(), zero.clone())?;
+                    gemm_symm_output_faer_dispatch(c, &lc_k, a, la_t, &t, lt, nthreads)?;
+                }
+                return Ok(());
+            }
+
+            let merge_rows = match nbatch > 1 {
+                true => layout_merge_batch_axis(lb, 0),
+                false => None,
+            };
+            let merge_cols = match nbatch > 1 {
+                true => layout_merge_batch_axis(lb, 1),
+                false => None,
+            };
+            let order = congruence_order(n, m, merge_rows.is_some(), merge_cols.is_some());
+
+            match (order, merge_rows, merge_cols) {
+                // batch dimensions merged into rows: t = b a as one GEMM, of shape
+                // [batch * n, m]; then c_k = a^T t_k
+                (CongruenceOrder::RightFirst, Some((lb_merged, unit)), _) => {
+                    let nrow = lb_merged.shape()[0];
+                    let lt = &[nrow, m].c();
+                    let mut t = vec![zero.clone(); nrow * m];
+                    gemm_faer_dispatch(
+                        t.as_mut_slice().into(),
+                        lt,
+                        b.rawvec().as_raw_slice(),
+                        &lb_merged,
+                        a.rawvec().as_raw_slice(),
+                        la,
+                        one.clone(),
+                        zero.clone(),
+                        nthreads,
+                    )?;
+                    let rs = if n == 1 { 1 } else { lb_matmul.stride()[0] / unit };
+                    for (&ib, &ic) in izip!(&ib_list, &ic_list) {
+                        let r0 = (ib - lb.offset()) as isize / unit;
+                        let lt_k = unsafe {
+                            Layout::new_unchecked([n, m], [rs * m as isize, 1], r0 as usize * m)
+                        };
+                        unsafe { lc_k.set_offset(ic) };
+                        gemm_faer_dispatch(
+                            c.rawvec_mut().as_raw_slice_mut(),
+                            &lc_k,
+                            a.rawvec().as_raw_slice(),
+                            la_t,
+                            t.as_slice().into(),
+                            &lt_k,
+                            one.clone(),
+                            zero.clone(),
+                            nthreads,
+                        )?;
+                    }
+                },
+                // batch dimensions merged into columns: t = a^T b as one GEMM, of
+                // shape [m, batch * n]; then c_k = t_k a
+                (CongruenceOrder::LeftFirst, _, Some((lb_merged, unit))) => {
+                    let ncol = lb_merged.shape()[1];
+                    let lt = &[m, ncol].f();
+                    let mut t = vec![zero.clone(); m * ncol];
+                    gemm_faer_dispatch(
+                        t.as_mut_slice().into(),
+                        lt,
+                        a.rawvec().as_raw_slice(),
+                        la_t,
+                        b.rawvec().as_raw_slice(),
+                        &lb_merged,
+                        one.clone(),
+                        zero.clone(),
+                        nthreads,
+                    )?;
+                    let cs = if n == 1 { 1 } else { lb_matmul.stride()[1] / unit };
+                    for (&ib, &ic) in izip!(&ib_list, &ic_list) {
+                        let c0 = (ib - lb.offset()) as isize / unit;
+                        let lt_k = unsafe {
+                            Layout::new_unchecked([m, n], [1, cs * m as isize], c0 as usize * m)
+                        };
+                        unsafe { lc_k.set_offset(ic) };
+                        gemm_faer_dispatch(
+                            c.rawvec_mut().as_raw_slice_mut(),
+                            &lc_k,
+                            t.as_slice().into(),
+                            &lt_k,
+                            a.rawvec().as_raw_slice(),
+                            la,
+                            one.clone(),
+                            zero.clone(),
+                            nthreads,
+                        )?;
+                    }
+                },
+                // not merged: t = b a and c_k = a^T t for each batch
+                _ => {
+                    let lt = &[n, m].c();
+                    let mut t = vec![zero.clone(); n * m];
+                    for (&ib, &ic) in izip!(&ib_list, &ic_list) {
+                        unsafe { lb_k.set_offset(ib) };
+                        unsafe { lc_k.set_offset(ic) };
+                        gemm_faer_dispatch(
+                            t.as_mut_slice().into(),
+                            lt,
+                            b.rawvec().as_raw_slice(),
+                            &lb_k,
+                            a.rawvec().as_raw_slice(),
+                            la,
+                            one.clone(),
+                            zero.clone(),
+                            nthreads,
+                        )?;
+                        gemm_faer_dispatch(
+                            c.rawvec_mut().as_raw_slice_mut(),
+                            &lc_k,
+                            a.rawvec().as_raw_slice(),
+                            la_t,
+                            t.as_slice().into(),
+                            lt,
+                            one.clone(),
+                            zero.clone(),
+                            nthreads,
+                        )?;
+                    }
+                },
+            }
+            return Ok(());
+        })?
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dev_utilities::allclose_f64;
+    use crate::tensor::manuplication::transpose;
+    use crate::tensor::matmul::{congruence_batched, congruence_symm_batched};
+
+    #[test]
+    fn test_congruence_order() {
+        // both orders cost n m (n + m); batches are merged when possible
+        assert_eq!(congruence_order(6, 2, false, false), CongruenceOrder::RightFirst);
+        assert_eq!(congruence_order(2, 6, false, true), CongruenceOrder::LeftFirst);
+        assert_eq!(congruence_order(2, 6, true, true), CongruenceOrder::RightFirst);
+    }
+
+    #[test]
+    fn test_congruence() {
+        let device = DeviceFaer::default();
+        let cpu = DeviceCpuSerial::default();
+        let a = Tensor::linspace(-1.0, 2.0, 24, &device).into_shape_assume_contig([6, 4]).unwrap();
+        let a_cpu = a.view().into_device(&cpu).unwrap();
+
+        let check = |b: &Tensor<f64, IxD, DeviceFaer>| {
+            let b_cpu = b.view().into_device(&cpu).unwrap();
+            let c = congruence_batched(&a, b).unwrap();
+            let c_ref = congruence_batched(&a_cpu, &b_cpu).unwrap();
+            assert!(allclose_f64(&c, &c_ref));
+            for uplo in [TensorUpLo::U, TensorUpLo::L] {
+                let c = congruence_symm_batched(&a, b, uplo).unwrap();
+                let c_ref = congruence_symm_batched(&a_cpu, &b_cpu, uplo).unwrap();
+                assert!(allclose_f64(&c, &c_ref));
+            }
+        };
+
+        // batch merged into rows
+        let b = Tensor::linspace(-3.0, 5.0, 108, &device);
+        let b = b.into_shape_assume_contig([3, 6, 6]).unwrap().into_dim::<IxD>().unwrap();
+        check(&b);
+        // batch merged into columns
+        let b = b.into_shape_assume_contig(vec![6, 6, 3]).unwrap();
+        let b = transpose(b, &[2, 0, 1]).unwrap();
+        check(&b);
+        // not able to be merged
+        let v = (0..146).map(|x| x as f64 / 10.0).collect_vec();
+        let layout = Layout::new(vec![3, 6, 6], vec![50, 8, 1], 0);
+        let b =
+            Tensor::new(DataOwned::from(Storage::new(v.into(), device.clone())), layout).unwrap();
+        check(&b);
+    }
+}
@@ -1,7 +1,6 @@
 //! Conversion to/from Faer
 
 use crate::prelude_dev::*;
-use core::mem::ManuallyDrop;
 use faer::complex_native::{c32, c64};
 use faer::{MatMut, MatRef, SimpleEntity};
 use faer_ext::{IntoFaer, IntoFaerComplex};
@@ -10,7 +9,7 @@ use num::Complex;
 impl<'a, T, B> IntoFaer for TensorView<'a, T, Ix2, B>
 where
     T: SimpleEntity,
-    B: DeviceStorageAPI<T, RawVec = Vec<T>>,
+    B: DeviceStorageAPI<T, RawVec = CpuRawVec<T>>,
 {
     type Faer = MatRef<'a, T>;
 
@@ -26,22 +25,22 @@ where
 impl<'a, T, B> IntoFaer for TensorViewMut<'a, T, Ix2, B>
 where
     T: SimpleEntity,
-    B: DeviceStorageAPI<T, RawVec = Vec<T>>,
+    B: DeviceStorageAPI<T, RawVec = CpuRawVec<T>>,
 {
     type Faer = MatMut<'a, T>;
 
     fn into_faer(mut self) -> Self::Faer {
         let [nrows, ncols] = *self.shape();
         let [row_stride, col_stride] = *self.stride();
-        let rawvec = self.data_mut().storage_mut().rawvec_mut();
-        let ptr = rawvec.as_mut_ptr();
+        let rawvec = unsafe { self.data_mut().storage_mut() }.rawvec_mut();
+        let ptr = rawvec.as_raw_slice_mut().as_mut_ptr();
         unsafe { faer::mat::from_raw_parts_mut(ptr, nrows, ncols, row_stride, col_stride) }
     }
 }
 
 impl<'a, B> IntoFaerComplex for TensorView<'a, Complex<f64>, Ix2, B>
 where
-    B: DeviceStorageAPI<Complex<f64>, RawVec = Vec<Complex<f64>>>,
+    B: DeviceStorageAPI<Complex<f64>, RawVec = CpuRawVec<Complex<f64>>>,
 {
     type Faer = MatRef<'a, c64>;
 
@@ -56,7 +55,7 @@ where
 
 impl<'a, B> IntoFaerComplex for TensorView<'a, Complex<f32>, Ix2, B>
 where
-    B: DeviceStorageAPI<Complex<f32>, RawVec = Vec<Complex<f32>>>,
+    B: DeviceStorageAPI<Complex<f32>, RawVec = CpuRawVec<Complex<f32>>>,
 {
     type Faer = MatRef<'a, c32>;
 
@@ -74,19 +73,19 @@ macro_rules! impl_into_rstsr {
         impl<'a> IntoRSTSR for MatRef<'a, $ty_faer> {
             type RSTSR = TensorView<'a, $ty, Ix2, DeviceFaer>;
 
+            /// Elements are borrowed without copy; sub-views (such as a row of
+            /// column-major matrix) keep their strides.
             fn into_rstsr(self) -> Self::RSTSR {
-                let nrows = self.nrows();
-                let ncols = self.ncols();
-                let row_stride = self.row_stride();
-                let col_stride = self.col_stride();
-                let ptr = self.as_ptr();
-
-                let layout = Layout::new([nrows, ncols], [row_stride, col_stride], 0);
-                let (_, upper_bound) = layout.bounds_index().unwrap();
-                let rawvec =
-                    unsafe { Vec::from_raw_parts(ptr as *mut $ty, upper_bound, upper_bound) };
-                let storage = ManuallyDrop::new(Storage::new(rawvec, DeviceFaer::default()));
-                let data = DataRef::from_manually_drop(storage);
+                let shape = [self.nrows(), self.ncols()];
+                let strides = [self.row_stride(), self.col_stride()];
+                let (layout, len) = Layout::from_foreign_view(shape, &strides);
+                let ptr = unsafe { self.as_ptr().sub(layout.offset()) };
+                // memory between elements of sparse view may be mutably
+                // borrowed by other views, so slice reference is not formed
+                let rawvec = unsafe { CpuRawVec::from_raw_parts(ptr as *const $ty, len, &layout) };
+                // the view does not own memory, so bypass memory accounting of `new`
+                let storage = Storage { rawvec, device: DeviceFaer::default() };
+                let data = unsafe { DataRef::from_borrowed(storage) };
                 let tensor = unsafe { TensorView::new_unchecked(data, layout) };
                 return tensor;
             }
@@ -94,19 +93,20 @@ macro_rules! impl_into_rstsr {
         impl<'a> IntoRSTSR for MatMut<'a, $ty_faer> {
             type RSTSR = TensorViewMut<'a, $ty, Ix2, DeviceFaer>;
 
+            /// Elements are borrowed without copy; sub-views (such as a row of
+            /// column-major matrix) keep their strides.
             fn into_rstsr(self) -> Self::RSTSR {
-                let nrows = self.nrows();
-                let ncols = self.ncols();
-                let row_stride = self.row_stride();
-                let col_stride = self.col_stride();
-                let ptr = self.as_ptr();
-
-                let layout = Layout::new([nrows, ncols], [row_stride, col_stride], 0);
-                let (_, upper_bound) = layout.bounds_index().unwrap();
+                let shape = [self.nrows(), self.ncols()];
+                let strides = [self.row_stride(), self.col_stride()];
+                let (layout, len) = Layout::from_foreign_view(shape, &strides);
+                let ptr = unsafe { self.as_ptr_mut().sub(layout.offset()) };
+                // memory between elements of sparse view may be mutably
+                // borrowed by other views, so slice reference is not formed
                 let rawvec =
-                    unsafe { Vec::from_raw_parts(ptr as *mut $ty, upper_bound, upper_bound) };
-                let storage = ManuallyDrop::new(Storage::new(rawvec, DeviceFaer::default()));
-                let data = DataRefMut::from_manually_drop(storage);
+                    unsafe { CpuRawVec::from_raw_parts_mut(ptr as *mut $ty, len, &layout) };
+                // the view does not own memory, so bypass memory accounting of `new`
+                let storage = Storage { rawvec, device: DeviceFaer::default() };
+                let data = unsafe { DataRefMut::from_borrowed(storage) };
                 let tensor = unsafe { TensorViewMut::new_unchecked(data, layout) };
                 return tensor;
             }
@@ -118,3 +118,38 @@ impl_into_rstsr!(f32, f32);
 impl_into_rstsr!(f64, f64);
 impl_into_rstsr!(Complex<f32>, c32);
 impl_into_rstsr!(Complex<f64>, c64);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_faer_into_rstsr() {
+        let mut buf = (0..24).map(|x| x as f64).collect_vec();
+        let mut mat = faer::mat::from_column_major_slice_mut::<f64>(&mut buf, 4, 6);
+        let (left, right) = mat.as_mut().split_at_col_mut(3);
+        let mut tsr = left.into_rstsr();
+        tsr += 1.0;
+        assert_eq!(tsr.data().storage().rawvec().len(), 12);
+        assert_eq!(right.read(1, 0), 13.0);
+        assert_eq!(mat.read(1, 2), 10.0);
+
+        // rows interleave in memory
+        let (top, bottom) = mat.as_mut().split_at_row_mut(2);
+        let mut tsr_top = top.into_rstsr();
+        tsr_top *= 10.0;
+        assert_eq!(tsr_top.stride(), &[1, 4]);
+        assert_eq!(bottom.read(0, 0), 3.0);
+        let tsr_bottom = bottom.into_rstsr();
+        assert_eq!(tsr_bottom.iter().cloned().collect_vec()[..3], [3.0, 7.0, 11.0]);
+        assert_eq!(mat.read(1, 2), 100.0);
+
+        // a row, and reversed rows
+        let tsr = mat.as_ref().row(1).as_2d().into_rstsr();
+        assert_eq!(tsr.shape(), &[1, 6]);
+        assert_eq!(tsr.iter().cloned().collect_vec(), [20.0, 60.0, 100.0, 130.0, 170.0, 210.0]);
+        let tsr = mat.as_ref().reverse_rows().into_rstsr();
+        assert_eq!(tsr.offset(), 3);
+        assert_eq!(tsr.iter().cloned().collect_vec()[..4], [4.0, 8.0, 12.0, 15.0]);
+    }
+}
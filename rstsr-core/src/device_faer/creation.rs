@@ -5,53 +5,54 @@ use num::{complex::ComplexFloat, Float, Num};
 impl<T> DeviceCreationAnyAPI<T> for DeviceFaer
 where
     T: Clone + Debug,
-    Self: DeviceRawVecAPI<T, RawVec = Vec<T>>,
+    Self: DeviceRawVecAPI<T, RawVec = CpuRawVec<T>>,
 {
     unsafe fn empty_impl(&self, len: usize) -> Result<Storage<T, Self>> {
-        let storage = DeviceCpuSerial.empty_impl(len)?;
-        Ok(Storage::new(storage.into_rawvec(), self.clone()))
+        let storage = DeviceCpuSerial::with_memory_of(self).empty_impl(len)?;
+        Ok(storage_from_serial(storage, self))
     }
 
     fn full_impl(&self, len: usize, fill: T) -> Result<Storage<T, Self>> {
-        let storage = DeviceCpuSerial.full_impl(len, fill)?;
-        Ok(Storage::new(storage.into_rawvec(), self.clone()))
+        let storage = DeviceCpuSerial::with_memory_of(self).full_impl(len, fill)?;
+        Ok(storage_from_serial(storage, self))
     }
 
     fn outof_cpu_vec(&self, vec: Vec<T>) -> Result<Storage<T, Self>> {
-        Ok(Storage::new(vec, self.clone()))
+        let storage = DeviceCpuSerial::with_memory_of(self).outof_cpu_vec(vec)?;
+        Ok(storage_from_serial(storage, self))
     }
 
     fn from_cpu_vec(&self, vec: &[T]) -> Result<Storage<T, Self>> {
-        let rawvec = vec.to_vec();
-        Ok(Storage::new(rawvec, self.clone()))
+        let storage = DeviceCpuSerial::with_memory_of(self).from_cpu_vec(vec)?;
+        Ok(storage_from_serial(storage, self))
     }
 }
 
 impl<T> DeviceCreationNumAPI<T> for DeviceFaer
 where
     T: Num + Clone + Debug,
-    Self: DeviceRawVecAPI<T, RawVec = Vec<T>>,
+    Self: DeviceRawVecAPI<T, RawVec = CpuRawVec<T>>,
 {
     fn zeros_impl(&self, len: usize) -> Result<Storage<T, Self>> {
-        let storage = DeviceCpuSerial.zeros_impl(len)?;
-        Ok(Storage::new(storage.into_rawvec(), self.clone()))
+        let storage = DeviceCpuSerial::with_memory_of(self).zeros_impl(len)?;
+        Ok(storage_from_serial(storage, self))
     }
 
     fn ones_impl(&self, len: usize) -> Result<Storage<T, Self>> {
-        let storage = DeviceCpuSerial.ones_impl(len)?;
-        Ok(Storage::new(storage.into_rawvec(), self.clone()))
+        let storage = DeviceCpuSerial::with_memory_of(self).ones_impl(len)?;
+        Ok(storage_from_serial(storage, self))
     }
 
     fn arange_int_impl(&self, len: usize) -> Result<Storage<T, Self>> {
-        let storage = DeviceCpuSerial.arange_int_impl(len)?;
-        Ok(Storage::new(storage.into_rawvec(), self.clone()))
+        let storage = DeviceCpuSerial::with_memory_of(self).arange_int_impl(len)?;
+        Ok(storage_from_serial(storage, self))
     }
 }
 
 impl<T> DeviceCreationComplexFloatAPI<T> for DeviceFaer
 where
     T: ComplexFloat + Clone + Debug + Send + Sync,
-    Self: DeviceRawVecAPI<T, RawVec = Vec<T>>,
+    Self: DeviceRawVecAPI<T, RawVec = CpuRawVec<T>>,
 {
     fn linspace_impl(
         &self,
@@ -60,19 +61,20 @@ where
         n: usize,
         endpoint: bool,
     ) -> Result<Storage<T, Self>> {
-        let storage = DeviceCpuSerial.linspace_impl(start, end, n, endpoint)?;
-        Ok(Storage::new(storage.into_rawvec(), self.clone()))
+        let storage =
+            DeviceCpuSerial::with_memory_of(self).linspace_impl(start, end, n, endpoint)?;
+        Ok(storage_from_serial(storage, self))
     }
 }
 
 impl<T> DeviceCreationFloatAPI<T> for DeviceFaer
 where
     T: Float + Clone + Debug + Send + Sync,
-    Self: DeviceRawVecAPI<T, RawVec = Vec<T>>,
+    Self: DeviceRawVecAPI<T, RawVec = CpuRawVec<T>>,
 {
     fn arange_impl(&self, start: T, end: T, step: T) -> Result<Storage<T, Self>> {
-        let storage = DeviceCpuSerial.arange_impl(start, end, step)?;
-        Ok(Storage::new(storage.into_rawvec(), self.clone()))
+        let storage = DeviceCpuSerial::with_memory_of(self).arange_impl(start, end, step)?;
+        Ok(storage_from_serial(storage, self))
     }
 }
 
@@ -22,8 +22,31 @@ impl DeviceFaer {
         self.base.get_num_threads()
     }
 
-    pub fn get_pool(&self, n: usize) -> Result<rayon::ThreadPool> {
-        self.base.get_pool(n)
+    pub fn get_pool(&self) -> Result<Option<&rayon::ThreadPool>> {
+        self.base.get_pool()
+    }
+
+    /// Device that runs on the rayon pool of caller, without creating its own
+    /// thread pool.
+    pub fn new_global_pool() -> Self {
+        DeviceFaer { base: DeviceCpuRayon::new_global_pool() }
+    }
+
+    pub fn is_global_pool(&self) -> bool {
+        self.base.is_global_pool()
+    }
+
+    /// Run closure `op` in thread pool of this device.
+    ///
+    /// # See also
+    ///
+    /// [`DeviceCpuRayon::install`]
+    pub fn install<OP, R>(&self, op: OP) -> Result<R>
+    where
+        OP: FnOnce() -> R + Send,
+        R: Send,
+    {
+        self.base.install(op)
     }
 }
 
@@ -35,7 +58,17 @@ impl Default for DeviceFaer {
 
 impl DeviceBaseAPI for DeviceFaer {
     fn same_device(&self, other: &Self) -> bool {
-        self.var_num_threads() == other.var_num_threads()
+        self.base.same_device(&other.base)
+    }
+}
+
+impl DeviceMemoryAPI for DeviceFaer {
+    fn memory(&self) -> &DeviceMemory {
+        self.base.memory()
+    }
+
+    fn memory_mut(&mut self) -> &mut DeviceMemory {
+        self.base.memory_mut()
     }
 }
 
@@ -43,7 +76,7 @@ impl<T> DeviceRawVecAPI<T> for DeviceFaer
 where
     T: Clone,
 {
-    type RawVec = Vec<T>;
+    type RawVec = CpuRawVec<T>;
 }
 
 impl<T> DeviceStorageAPI<T> for DeviceFaer
@@ -51,6 +84,7 @@ where
     T: Clone,
 {
     fn new(vector: Self::RawVec, device: Self) -> Storage<T, Self> {
+        let device = device.acquire_memory_unchecked::<T>(vector.len());
         Storage::<T, Self> { rawvec: vector, device }
     }
 
@@ -59,31 +93,31 @@ where
     }
 
     fn to_cpu_vec(storage: &Storage<T, Self>) -> Result<Vec<T>> {
-        Ok(storage.rawvec.clone())
+        Ok(storage.rawvec.to_vec())
     }
 
     fn into_cpu_vec(storage: Storage<T, Self>) -> Result<Vec<T>> {
-        Ok(storage.rawvec)
+        Ok(storage.rawvec.into_vec())
     }
 
     #[inline]
     fn get_index(storage: &Storage<T, Self>, index: usize) -> T {
-        storage.rawvec[index].clone()
+        storage.rawvec.as_raw_slice()[index].clone()
     }
 
     #[inline]
     fn get_index_ptr(storage: &Storage<T, Self>, index: usize) -> *const T {
-        &storage.rawvec[index] as *const T
+        &storage.rawvec.as_raw_slice()[index] as *const T
     }
 
     #[inline]
     fn get_index_mut_ptr(storage: &mut Storage<T, Self>, index: usize) -> *mut T {
-        &mut storage.rawvec[index] as *mut T
+        &mut storage.rawvec.as_raw_slice_mut()[index] as *mut T
     }
 
     #[inline]
     fn set_index(storage: &mut Storage<T, Self>, index: usize, value: T) {
-        storage.rawvec[index] = value;
+        storage.rawvec.as_raw_slice_mut()[index] = value;
     }
 }
 
@@ -95,7 +129,7 @@ mod test {
 
     #[test]
     fn test_device_conversion() {
-        let device_serial = DeviceCpuSerial {};
+        let device_serial = DeviceCpuSerial::default();
         let device_faer = DeviceFaer::new(0);
         let a = Tensor::linspace(1.0, 5.0, 5, &device_serial);
         let b = a.into_device(&device_faer).unwrap();
@@ -2,29 +2,31 @@
 //!
 //! This implementation does not specialize gemv. We always use gemm for matmul.
 
+use half::{bf16, f16};
+use num::complex::ComplexFloat;
 use num::{Complex, Zero};
 
 use super::matmul_impl::*;
+use crate::device_cpu_serial::matmul::{herk_naive, symm_naive, syrk_naive};
+use crate::feature_rayon::matmul::split_batched_mut;
 use crate::feature_rayon::matmul_naive::{gemm_naive_rayon, inner_dot_naive_rayon};
 use crate::prelude_dev::*;
 use core::any::TypeId;
-use core::mem::ManuallyDrop;
 use core::ops::{Add, Mul};
-use core::slice::{from_raw_parts, from_raw_parts_mut};
 use rayon::prelude::*;
 
 // code from ndarray
-fn same_type<A: 'static, B: 'static>() -> bool {
+pub(crate) fn same_type<A: 'static, B: 'static>() -> bool {
     TypeId::of::<A>() == TypeId::of::<B>()
 }
 
 #[allow(clippy::too_many_arguments)]
 pub fn gemm_faer_dispatch<TA, TB, TC>(
-    c: &mut Storage<TC, DeviceFaer>,
+    mut c: CpuRawSliceMut<'_, TC>,
     lc: &Layout<Ix2>,
-    a: &Storage<TA, DeviceFaer>,
+    a: CpuRawSlice<'_, TA>,
     la: &Layout<Ix2>,
-    b: &Storage<TB, DeviceFaer>,
+    b: CpuRawSlice<'_, TB>,
     lb: &Layout<Ix2>,
     alpha: TC,
     beta: TC,
@@ -40,8 +42,8 @@ where
     // check if syrk could be applicable
     let able_syrk = if same_type::<TA, TC>() && same_type::<TB, TC>() {
         unsafe {
-            let a_ptr = a.rawvec().as_ptr().add(la.offset()) as *const TC;
-            let b_ptr = b.rawvec().as_ptr().add(lb.offset()) as *const TC;
+            let a_ptr = a.as_ptr().add(la.offset()) as *const TC;
+            let b_ptr = b.as_ptr().add(lb.offset()) as *const TC;
             let equal_ptr = a_ptr == b_ptr;
             let equal_shape = la.shape() == lb.reverse_axes().shape();
             let equal_stride = la.stride() == lb.reverse_axes().stride();
@@ -55,10 +57,12 @@ where
     macro_rules! impl_gemm_dispatch {
         ($ty: ty, $fn_gemm_name: ident, $fn_syrk_name: ident) => {
             if (same_type::<TA, $ty>() && same_type::<TB, $ty>() && same_type::<TC, $ty>()) {
-                let a_slice = unsafe { from_raw_parts(a.rawvec().as_ptr() as *const $ty, a.len()) };
-                let b_slice = unsafe { from_raw_parts(b.rawvec().as_ptr() as *const $ty, b.len()) };
+                let a_slice =
+                    unsafe { CpuRawSlice::from_raw_parts(a.as_ptr() as *const $ty, a.len()) };
+                let b_slice =
+                    unsafe { CpuRawSlice::from_raw_parts(b.as_ptr() as *const $ty, b.len()) };
                 let c_slice =
-                    unsafe { from_raw_parts_mut(c.rawvec_mut().as_mut_ptr() as *mut $ty, c.len()) };
+                    unsafe { CpuRawSliceMut::from_raw_parts(c.as_mut_ptr() as *mut $ty, c.len()) };
                 let alpha = unsafe { *(&alpha as *const TC as *const $ty) };
                 let beta = unsafe { *(&beta as *const TC as *const $ty) };
                 if able_syrk {
@@ -76,12 +80,56 @@ where
     impl_gemm_dispatch!(Complex<f32>, gemm_faer_c32, gemm_with_syrk_faer_c32);
     impl_gemm_dispatch!(Complex<f64>, gemm_faer_c64, gemm_with_syrk_faer_c64);
 
+    // half-precision, accumulated in f32
+    macro_rules! impl_gemm_half_dispatch {
+        ($ty: ty, $fn_gemm_name: ident) => {
+            if (same_type::<TA, $ty>() && same_type::<TB, $ty>() && same_type::<TC, $ty>()) {
+                let a_slice =
+                    unsafe { CpuRawSlice::from_raw_parts(a.as_ptr() as *const $ty, a.len()) };
+                let b_slice =
+                    unsafe { CpuRawSlice::from_raw_parts(b.as_ptr() as *const $ty, b.len()) };
+                let c_slice =
+                    unsafe { CpuRawSliceMut::from_raw_parts(c.as_mut_ptr() as *mut $ty, c.len()) };
+                let alpha = unsafe { *(&alpha as *const TC as *const $ty) };
+                let beta = unsafe { *(&beta as *const TC as *const $ty) };
+                $fn_gemm_name(c_slice, lc, a_slice, la, b_slice, lb, alpha, beta, nthreads)?;
+                return Ok(());
+            }
+        };
+    }
+
+    impl_gemm_half_dispatch!(f16, gemm_faer_f16);
+    impl_gemm_half_dispatch!(bf16, gemm_faer_bf16);
+
+    // mixed types, where operands are explicitly promoted to type of c
+    macro_rules! impl_gemm_promote_dispatch {
+        ($ta: ty, $tb: ty, $tc: ty, $fn_gemm_name: ident) => {
+            if (same_type::<TA, $ta>() && same_type::<TB, $tb>() && same_type::<TC, $tc>()) {
+                let a_slice =
+                    unsafe { CpuRawSlice::from_raw_parts(a.as_ptr() as *const $ta, a.len()) };
+                let b_slice =
+                    unsafe { CpuRawSlice::from_raw_parts(b.as_ptr() as *const $tb, b.len()) };
+                let c_slice =
+                    unsafe { CpuRawSliceMut::from_raw_parts(c.as_mut_ptr() as *mut $tc, c.len()) };
+                let alpha = unsafe { *(&alpha as *const TC as *const $tc) };
+                let beta = unsafe { *(&beta as *const TC as *const $tc) };
+                let (a_p, la_p) = promote_matrix(a_slice, la, |&x| <$tc>::from(x));
+                let (b_p, lb_p) = promote_matrix(b_slice, lb, |&x| <$tc>::from(x));
+                let (a_p, b_p) = (a_p.as_slice().into(), b_p.as_slice().into());
+                $fn_gemm_name(c_slice, lc, a_p, &la_p, b_p, &lb_p, alpha, beta, nthreads)?;
+                return Ok(());
+            }
+        };
+    }
+
+    impl_gemm_promote_dispatch!(f32, Complex<f32>, Complex<f32>, gemm_faer_c32);
+    impl_gemm_promote_dispatch!(Complex<f32>, f32, Complex<f32>, gemm_faer_c32);
+    impl_gemm_promote_dispatch!(f64, Complex<f64>, Complex<f64>, gemm_faer_c64);
+    impl_gemm_promote_dispatch!(Complex<f64>, f64, Complex<f64>, gemm_faer_c64);
+
     // not able to be accelarated by faer
     // fallback to naive implementation
-    let c_slice = c.rawvec_mut();
-    let a_slice = a.rawvec();
-    let b_slice = b.rawvec();
-    return gemm_naive_rayon(c_slice, lc, a_slice, la, b_slice, lb, alpha, beta, nthreads);
+    return gemm_naive_rayon(c, lc, a, la, b, lb, alpha, beta);
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -95,9 +143,9 @@ where
     DC: DimAPI,
     TA: Mul<TB, Output = TC>,
     TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
-    Self: DeviceAPI<TA, RawVec = Vec<TA>>,
-    Self: DeviceAPI<TB, RawVec = Vec<TB>>,
-    Self: DeviceAPI<TC, RawVec = Vec<TC>>,
+    Self: DeviceAPI<TA, RawVec = CpuRawVec<TA>>,
+    Self: DeviceAPI<TB, RawVec = CpuRawVec<TB>>,
+    Self: DeviceAPI<TC, RawVec = CpuRawVec<TC>>,
 {
     fn matmul(
         &self,
@@ -111,175 +159,403 @@ where
         beta: TC,
     ) -> Result<()> {
         let nthreads = self.get_num_threads();
-        rstsr_assert!(c.device().same_device(a.device()), DeviceMismatch)?;
-        rstsr_assert!(c.device().same_device(b.device()), DeviceMismatch)?;
-        let c_slice = c.rawvec_mut().as_mut_slice();
-        let a_slice = a.rawvec().as_slice();
-        let b_slice = b.rawvec().as_slice();
-        match (la.ndim(), lb.ndim(), lc.ndim()) {
-            (1, 1, 0) => {
-                // rule 1: vector inner dot
-                let la = &la.clone().into_dim::<Ix1>().unwrap();
-                let lb = &lb.clone().into_dim::<Ix1>().unwrap();
-                let lc = &lc.clone().into_dim::<Ix0>().unwrap();
-                let c_num = &mut c_slice[lc.offset()];
-                inner_dot_naive_rayon(c_num, a_slice, la, b_slice, lb, alpha, beta, nthreads)?;
-            },
-            (2, 2, 2) => {
-                // rule 2: matrix multiplication
-                let la = &la.clone().into_dim::<Ix2>().unwrap();
-                let lb = &lb.clone().into_dim::<Ix2>().unwrap();
-                let lc = &lc.clone().into_dim::<Ix2>().unwrap();
-                gemm_faer_dispatch(c, lc, a, la, b, lb, alpha, beta, nthreads)?;
-            },
-            _ => {
-                // temporary layout
-                let la_matmul;
-                let lb_matmul;
-                let lc_matmul;
-                let la_rest;
-                let lb_rest;
-                let lc_rest;
-                match (la.ndim(), lb.ndim(), lc.ndim()) {
-                    (1, 1, 0) | (2, 2, 2) => unreachable!(),
-                    (1, 2.., _) => {
-                        // rule 3: | `        K` | `..., K, N` | `   ..., N` |
-                        rstsr_assert_eq!(lb.ndim(), lc.ndim() + 1, InvalidLayout)?;
-                        let (la_r, la_m) = la.dim_split_at(-1)?;
-                        let (lb_r, lb_m) = lb.dim_split_at(-2)?;
-                        let (lc_r, lc_m) = lc.dim_split_at(-1)?;
-                        la_rest = broadcast_layout_to_first(&lc_r, &la_r)?.1;
-                        lb_rest = lb_r;
-                        lc_rest = lc_r;
-                        la_matmul = la_m.dim_insert(0)?.into_dim::<Ix2>()?;
-                        lb_matmul = lb_m.into_dim::<Ix2>()?;
-                        lc_matmul = lc_m.dim_insert(0)?.into_dim::<Ix2>()?;
-                    },
-                    (2.., 1, _) => {
-                        // rule 4: | `..., M, K` | `        K` | `   ..., M` |
-                        rstsr_assert_eq!(la.ndim(), lc.ndim() + 1, InvalidLayout)?;
-                        let (la_r, la_m) = la.dim_split_at(-2)?;
-                        let (lb_r, lb_m) = lb.dim_split_at(-1)?;
-                        let (lc_r, lc_m) = lc.dim_split_at(-1)?;
-                        la_rest = la_r;
-                        lb_rest = broadcast_layout_to_first(&lc_r, &lb_r)?.1;
-                        lc_rest = lc_r;
-                        la_matmul = la_m.into_dim::<Ix2>()?;
-                        lb_matmul = lb_m.dim_insert(1)?.into_dim::<Ix2>()?;
-                        lc_matmul = lc_m.dim_insert(1)?.into_dim::<Ix2>()?;
-                    },
-                    (2, 3.., _) => {
-                        // rule 5: | `     M, K` | `..., K, N` | `..., M, N` |
-                        rstsr_assert_eq!(lb.ndim(), lc.ndim(), InvalidLayout)?;
-                        let (la_r, la_m) = la.dim_split_at(-2)?;
-                        let (lb_r, lb_m) = lb.dim_split_at(-2)?;
-                        let (lc_r, lc_m) = lc.dim_split_at(-2)?;
-                        la_rest = broadcast_layout_to_first(&lc_r, &la_r)?.1;
-                        lb_rest = lb_r;
-                        lc_rest = lc_r;
-                        la_matmul = la_m.into_dim::<Ix2>()?;
-                        lb_matmul = lb_m.into_dim::<Ix2>()?;
-                        lc_matmul = lc_m.into_dim::<Ix2>()?;
-                    },
-                    (3.., 2, _) => {
-                        // rule 6: | `..., M, K` | `     K, N` | `..., M, N` |
-                        rstsr_assert_eq!(la.ndim(), lc.ndim(), InvalidLayout)?;
-                        let (la_r, la_m) = la.dim_split_at(-2)?;
-                        let (lb_r, lb_m) = lb.dim_split_at(-2)?;
-                        let (lc_r, lc_m) = lc.dim_split_at(-2)?;
-                        la_rest = la_r;
-                        lb_rest = broadcast_layout_to_first(&lc_r, &lb_r)?.1;
-                        lc_rest = lc_r;
-                        la_matmul = la_m.into_dim::<Ix2>()?;
-                        lb_matmul = lb_m.into_dim::<Ix2>()?;
-                        lc_matmul = lc_m.into_dim::<Ix2>()?;
-                    },
-                    (3.., 3.., _) => {
-                        // rule 7: | `..., M, K` | `..., K, N` | `..., M, N` |
-                        rstsr_assert_eq!(la.ndim(), lc.ndim(), InvalidLayout)?;
-                        rstsr_assert_eq!(lb.ndim(), lc.ndim(), InvalidLayout)?;
-                        let (la_r, la_m) = la.dim_split_at(-2)?;
-                        let (lb_r, lb_m) = lb.dim_split_at(-2)?;
-                        let (lc_r, lc_m) = lc.dim_split_at(-2)?;
-                        la_rest = la_r;
-                        lb_rest = lb_r;
-                        lc_rest = lc_r;
-                        la_matmul = la_m.into_dim::<Ix2>()?;
-                        lb_matmul = lb_m.into_dim::<Ix2>()?;
-                        lc_matmul = lc_m.into_dim::<Ix2>()?;
-                    },
-                    _ => todo!(),
-                }
-                // now, lx_rest should have the same shape, while lx_matmul
-                // should be matmulable
-                // only parallel matmul when lx_rest is small (larger than
-                // 2*nthreads), otherwise parallel matmul anyway
-                rstsr_assert_eq!(la_rest.shape(), lb_rest.shape(), InvalidLayout)?;
-                rstsr_assert_eq!(lb_rest.shape(), lc_rest.shape(), InvalidLayout)?;
-                let n_task = la_rest.size();
-                let ita_rest = IterLayoutColMajor::new(&la_rest)?;
-                let itb_rest = IterLayoutColMajor::new(&lb_rest)?;
-                let itc_rest = IterLayoutColMajor::new(&lc_rest)?;
-                if n_task > 2 * nthreads {
-                    // parallel outer, sequential matmul
-                    let pool = self.get_pool(nthreads)?;
-                    pool.install(|| {
-                        ita_rest.into_par_iter().zip(itb_rest).zip(itc_rest).for_each(
-                            |((ia_rest, ib_rest), ic_rest)| {
+        self.install(|| {
+            rstsr_assert!(c.device().same_device(a.device()), DeviceMismatch)?;
+            rstsr_assert!(c.device().same_device(b.device()), DeviceMismatch)?;
+            let mut c_slice = c.rawvec_mut().as_raw_slice_mut();
+            let a_slice = a.rawvec().as_raw_slice();
+            let b_slice = b.rawvec().as_raw_slice();
+            match (la.ndim(), lb.ndim(), lc.ndim()) {
+                (1, 1, 0) => {
+                    // rule 1: vector inner dot
+                    let la = &la.clone().into_dim::<Ix1>().unwrap();
+                    let lb = &lb.clone().into_dim::<Ix1>().unwrap();
+                    let lc = &lc.clone().into_dim::<Ix0>().unwrap();
+                    let c_num = &mut c_slice[lc.offset()];
+                    inner_dot_naive_rayon(c_num, a_slice, la, b_slice, lb, alpha, beta)?;
+                },
+                (2, 2, 2) => {
+                    // rule 2: matrix multiplication
+                    let la = &la.clone().into_dim::<Ix2>().unwrap();
+                    let lb = &lb.clone().into_dim::<Ix2>().unwrap();
+                    let lc = &lc.clone().into_dim::<Ix2>().unwrap();
+                    gemm_faer_dispatch(
+                        c_slice.reborrow(),
+                        lc,
+                        a_slice,
+                        la,
+                        b_slice,
+                        lb,
+                        alpha,
+                        beta,
+                        nthreads,
+                    )?;
+                },
+                _ => {
+                    // temporary layout
+                    let la_matmul;
+                    let lb_matmul;
+                    let lc_matmul;
+                    let la_rest;
+                    let lb_rest;
+                    let lc_rest;
+                    match (la.ndim(), lb.ndim(), lc.ndim()) {
+                        (1, 1, 0) | (2, 2, 2) => unreachable!(),
+                        (1, 2.., _) => {
+                            // rule 3: | `        K` | `..., K, N` | `   ..., N` |
+                            rstsr_assert_eq!(lb.ndim(), lc.ndim() + 1, InvalidLayout)?;
+                            let (la_r, la_m) = la.dim_split_at(-1)?;
+                            let (lb_r, lb_m) = lb.dim_split_at(-2)?;
+                            let (lc_r, lc_m) = lc.dim_split_at(-1)?;
+                            la_rest = broadcast_layout_to_first(&lc_r, &la_r)?.1;
+                            lb_rest = lb_r;
+                            lc_rest = lc_r;
+                            la_matmul = la_m.dim_insert(0)?.into_dim::<Ix2>()?;
+                            lb_matmul = lb_m.into_dim::<Ix2>()?;
+                            lc_matmul = lc_m.dim_insert(0)?.into_dim::<Ix2>()?;
+                        },
+                        (2.., 1, _) => {
+                            // rule 4: | `..., M, K` | `        K` | `   ..., M` |
+                            rstsr_assert_eq!(la.ndim(), lc.ndim() + 1, InvalidLayout)?;
+                            let (la_r, la_m) = la.dim_split_at(-2)?;
+                            let (lb_r, lb_m) = lb.dim_split_at(-1)?;
+                            let (lc_r, lc_m) = lc.dim_split_at(-1)?;
+                            la_rest = la_r;
+                            lb_rest = broadcast_layout_to_first(&lc_r, &lb_r)?.1;
+                            lc_rest = lc_r;
+                            la_matmul = la_m.into_dim::<Ix2>()?;
+                            lb_matmul = lb_m.dim_insert(1)?.into_dim::<Ix2>()?;
+                            lc_matmul = lc_m.dim_insert(1)?.into_dim::<Ix2>()?;
+                        },
+                        (2, 3.., _) => {
+                            // rule 5: | `     M, K` | `..., K, N` | `..., M, N` |
+                            rstsr_assert_eq!(lb.ndim(), lc.ndim(), InvalidLayout)?;
+                            let (la_r, la_m) = la.dim_split_at(-2)?;
+                            let (lb_r, lb_m) = lb.dim_split_at(-2)?;
+                            let (lc_r, lc_m) = lc.dim_split_at(-2)?;
+                            la_rest = broadcast_layout_to_first(&lc_r, &la_r)?.1;
+                            lb_rest = lb_r;
+                            lc_rest = lc_r;
+                            la_matmul = la_m.into_dim::<Ix2>()?;
+                            lb_matmul = lb_m.into_dim::<Ix2>()?;
+                            lc_matmul = lc_m.into_dim::<Ix2>()?;
+                        },
+                        (3.., 2, _) => {
+                            // rule 6: | `..., M, K` | `     K, N` | `..., M, N` |
+                            rstsr_assert_eq!(la.ndim(), lc.ndim(), InvalidLayout)?;
+                            let (la_r, la_m) = la.dim_split_at(-2)?;
+                            let (lb_r, lb_m) = lb.dim_split_at(-2)?;
+                            let (lc_r, lc_m) = lc.dim_split_at(-2)?;
+                            la_rest = la_r;
+                            lb_rest = broadcast_layout_to_first(&lc_r, &lb_r)?.1;
+                            lc_rest = lc_r;
+                            la_matmul = la_m.into_dim::<Ix2>()?;
+                            lb_matmul = lb_m.into_dim::<Ix2>()?;
+                            lc_matmul = lc_m.into_dim::<Ix2>()?;
+                        },
+                        (3.., 3.., _) => {
+                            // rule 7: | `..., M, K` | `..., K, N` | `..., M, N` |
+                            rstsr_assert_eq!(la.ndim(), lc.ndim(), InvalidLayout)?;
+                            rstsr_assert_eq!(lb.ndim(), lc.ndim(), InvalidLayout)?;
+                            let (la_r, la_m) = la.dim_split_at(-2)?;
+                            let (lb_r, lb_m) = lb.dim_split_at(-2)?;
+                            let (lc_r, lc_m) = lc.dim_split_at(-2)?;
+                            la_rest = la_r;
+                            lb_rest = lb_r;
+                            lc_rest = lc_r;
+                            la_matmul = la_m.into_dim::<Ix2>()?;
+                            lb_matmul = lb_m.into_dim::<Ix2>()?;
+                            lc_matmul = lc_m.into_dim::<Ix2>()?;
+                        },
+                        _ => {
+                            return rstsr_raise!(
+                                UnImplemented,
+                                "Matmul of dimensions ({}, {}, {}) is not supported.",
+                                la.ndim(),
+                                lb.ndim(),
+                                lc.ndim()
+                            );
+                        },
+                    }
+                    // now, lx_rest should have the same shape, while lx_matmul
+                    // should be matmulable
+                    // only parallel matmul when lx_rest is small (larger than
+                    // 2*nthreads), otherwise parallel matmul anyway
+                    rstsr_assert_eq!(la_rest.shape(), lb_rest.shape(), InvalidLayout)?;
+                    rstsr_assert_eq!(lb_rest.shape(), lc_rest.shape(), InvalidLayout)?;
+                    let n_task = la_rest.size();
+                    let ita_rest = IterLayoutColMajor::new(&la_rest)?;
+                    let itb_rest = IterLayoutColMajor::new(&lb_rest)?;
+                    let c_chunks = match n_task > 2 * nthreads {
+                        true => split_batched_mut(c_slice.reborrow(), &lc_rest, &lc_matmul)?,
+                        false => None,
+                    };
+                    if let Some(c_chunks) = c_chunks {
+                        // parallel outer, sequential matmul
+                        // different tasks write to disjoint chunks of c
+                        let tasks = izip!(ita_rest, itb_rest, c_chunks).collect_vec();
+                        tasks.into_par_iter().try_for_each(
+                            |(ia_rest, ib_rest, (c_chunk, lc_m))| -> Result<()> {
                                 // prepare layout
                                 let mut la_m = la_matmul.clone();
                                 let mut lb_m = lb_matmul.clone();
-                                let mut lc_m = lc_matmul.clone();
                                 unsafe {
                                     la_m.set_offset(ia_rest);
                                     lb_m.set_offset(ib_rest);
-                                    lc_m.set_offset(ic_rest);
                                 }
-                                // move mutable reference into parallel closure
-                                let c_rawvec = unsafe {
-                                    let c_ptr = c_slice.as_ptr() as *mut TC;
-                                    let c_len = c_slice.len();
-                                    Vec::from_raw_parts(c_ptr, c_len, c_len)
-                                };
-                                let c = Storage::new(c_rawvec, a.device().clone());
-                                let mut c = ManuallyDrop::new(c);
                                 // clone alpha and beta
                                 let alpha = alpha.clone();
                                 let beta = beta.clone();
                                 gemm_faer_dispatch(
-                                    &mut c, &lc_m, a, &la_m, b, &lb_m, alpha, beta, 1,
+                                    c_chunk, &lc_m, a_slice, &la_m, b_slice, &lb_m, alpha, beta, 1,
                                 )
-                                .unwrap()
                             },
-                        );
-                    });
-                } else {
-                    // sequential outer, parallel matmul
-                    for (ia_rest, ib_rest, ic_rest) in izip!(ita_rest, itb_rest, itc_rest) {
-                        // prepare layout
-                        let mut la_m = la_matmul.clone();
-                        let mut lb_m = lb_matmul.clone();
-                        let mut lc_m = lc_matmul.clone();
-                        unsafe {
-                            la_m.set_offset(ia_rest);
-                            lb_m.set_offset(ib_rest);
-                            lc_m.set_offset(ic_rest);
+                        )?;
+                    } else {
+                        // sequential outer, parallel matmul
+                        let ita_rest = IterLayoutColMajor::new(&la_rest)?;
+                        let itb_rest = IterLayoutColMajor::new(&lb_rest)?;
+                        let itc_rest = IterLayoutColMajor::new(&lc_rest)?;
+                        for (ia_rest, ib_rest, ic_rest) in izip!(ita_rest, itb_rest, itc_rest) {
+                            // prepare layout
+                            let mut la_m = la_matmul.clone();
+                            let mut lb_m = lb_matmul.clone();
+                            let mut lc_m = lc_matmul.clone();
+                            unsafe {
+                                la_m.set_offset(ia_rest);
+                                lb_m.set_offset(ib_rest);
+                                lc_m.set_offset(ic_rest);
+                            }
+                            // clone alpha and beta
+                            let alpha = alpha.clone();
+                            let beta = beta.clone();
+                            gemm_faer_dispatch(
+                                c_slice.reborrow(),
+                                &lc_m,
+                                a_slice,
+                                &la_m,
+                                b_slice,
+                                &lb_m,
+                                alpha,
+                                beta,
+                                nthreads,
+                            )?;
                         }
-                        // clone alpha and beta
-                        let alpha = alpha.clone();
-                        let beta = beta.clone();
-                        gemm_faer_dispatch(c, &lc_m, a, &la_m, b, &lb_m, alpha, beta, nthreads)?;
                     }
-                }
-            },
-        }
-        return Ok(());
+                },
+            }
+            return Ok(());
+        })?
+    }
+}
+
+impl<TA, TB, TC> DeviceSYMMAPI<TA, TB, TC> for DeviceFaer
+where
+    TA: Clone + Send + Sync + 'static,
+    TB: Clone + Send + Sync + 'static,
+    TC: Clone + Send + Sync + 'static,
+    TA: Mul<TB, Output = TC>,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+    Self: DeviceAPI<TA, RawVec = CpuRawVec<TA>>,
+    Self: DeviceAPI<TB, RawVec = CpuRawVec<TB>>,
+    Self: DeviceAPI<TC, RawVec = CpuRawVec<TC>>,
+{
+    fn symm(
+        &self,
+        c: &mut Storage<TC, Self>,
+        lc: &Layout<Ix2>,
+        a: &Storage<TA, Self>,
+        la: &Layout<Ix2>,
+        b: &Storage<TB, Self>,
+        lb: &Layout<Ix2>,
+        side: TensorSide,
+        uplo: TensorUpLo,
+        alpha: TC,
+        beta: TC,
+    ) -> Result<()> {
+        let nthreads = self.get_num_threads();
+        self.install(|| {
+            rstsr_assert!(c.device().same_device(a.device()), DeviceMismatch)?;
+            rstsr_assert!(c.device().same_device(b.device()), DeviceMismatch)?;
+
+            // type check and dispatch
+            macro_rules! impl_symm_dispatch {
+                ($ty: ty, $fn_name: ident) => {
+                    if (same_type::<TA, $ty>() && same_type::<TB, $ty>() && same_type::<TC, $ty>())
+                    {
+                        let a_slice = unsafe {
+                            CpuRawSlice::from_raw_parts(
+                                a.rawvec().as_raw_slice().as_ptr() as *const $ty,
+                                a.len(),
+                            )
+                        };
+                        let b_slice = unsafe {
+                            CpuRawSlice::from_raw_parts(
+                                b.rawvec().as_raw_slice().as_ptr() as *const $ty,
+                                b.len(),
+                            )
+                        };
+                        let c_slice = unsafe {
+                            CpuRawSliceMut::from_raw_parts(
+                                c.rawvec_mut().as_raw_slice_mut().as_mut_ptr() as *mut $ty,
+                                c.len(),
+                            )
+                        };
+                        let alpha = unsafe { *(&alpha as *const TC as *const $ty) };
+                        let beta = unsafe { *(&beta as *const TC as *const $ty) };
+                        return $fn_name(
+                            c_slice, lc, a_slice, la, b_slice, lb, side, uplo, alpha, beta,
+                            nthreads,
+                        );
+                    }
+                };
+            }
+
+            impl_symm_dispatch!(f32, symm_faer_f32);
+            impl_symm_dispatch!(f64, symm_faer_f64);
+            impl_symm_dispatch!(Complex<f32>, symm_faer_c32);
+            impl_symm_dispatch!(Complex<f64>, symm_faer_c64);
+
+            // not able to be accelarated by faer
+            let c_slice = c.rawvec_mut().as_raw_slice_mut();
+            let a_slice = a.rawvec().as_raw_slice();
+            let b_slice = b.rawvec().as_raw_slice();
+            return symm_naive(c_slice, lc, a_slice, la, b_slice, lb, side, uplo, alpha, beta);
+        })?
+    }
+}
+
+impl<TA, TC> DeviceSYRKAPI<TA, TC> for DeviceFaer
+where
+    TA: Clone + Send + Sync + 'static,
+    TC: Clone + Send + Sync + 'static,
+    TA: Mul<TA, Output = TC>,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+    Self: DeviceAPI<TA, RawVec = CpuRawVec<TA>>,
+    Self: DeviceAPI<TC, RawVec = CpuRawVec<TC>>,
+{
+    fn syrk(
+        &self,
+        c: &mut Storage<TC, Self>,
+        lc: &Layout<Ix2>,
+        a: &Storage<TA, Self>,
+        la: &Layout<Ix2>,
+        uplo: TensorUpLo,
+        alpha: TC,
+        beta: TC,
+    ) -> Result<()> {
+        let nthreads = self.get_num_threads();
+        self.install(|| {
+            rstsr_assert!(c.device().same_device(a.device()), DeviceMismatch)?;
+
+            // type check and dispatch
+            macro_rules! impl_syrk_dispatch {
+                ($ty: ty, $fn_name: ident) => {
+                    if (same_type::<TA, $ty>() && same_type::<TC, $ty>()) {
+                        let a_slice = unsafe {
+                            CpuRawSlice::from_raw_parts(
+                                a.rawvec().as_raw_slice().as_ptr() as *const $ty,
+                                a.len(),
+                            )
+                        };
+                        let c_slice = unsafe {
+                            CpuRawSliceMut::from_raw_parts(
+                                c.rawvec_mut().as_raw_slice_mut().as_mut_ptr() as *mut $ty,
+                                c.len(),
+                            )
+                        };
+                        let alpha = unsafe { *(&alpha as *const TC as *const $ty) };
+                        let beta = unsafe { *(&beta as *const TC as *const $ty) };
+                        return $fn_name(c_slice, lc, a_slice, la, uplo, alpha, beta, nthreads);
+                    }
+                };
+            }
+
+            impl_syrk_dispatch!(f32, syrk_faer_f32);
+            impl_syrk_dispatch!(f64, syrk_faer_f64);
+            impl_syrk_dispatch!(Complex<f32>, syrk_faer_c32);
+            impl_syrk_dispatch!(Complex<f64>, syrk_faer_c64);
+
+            // not able to be accelarated by faer
+            return syrk_naive(
+                c.rawvec_mut().as_raw_slice_mut(),
+                lc,
+                a.rawvec().as_raw_slice(),
+                la,
+                uplo,
+                alpha,
+                beta,
+            );
+        })?
+    }
+}
+
+impl<T> DeviceHERKAPI<T, T> for DeviceFaer
+where
+    T: ComplexFloat + Send + Sync + 'static,
+    Self: DeviceAPI<T, RawVec = CpuRawVec<T>>,
+{
+    fn herk(
+        &self,
+        c: &mut Storage<T, Self>,
+        lc: &Layout<Ix2>,
+        a: &Storage<T, Self>,
+        la: &Layout<Ix2>,
+        uplo: TensorUpLo,
+        alpha: T,
+        beta: T,
+    ) -> Result<()> {
+        let nthreads = self.get_num_threads();
+        self.install(|| {
+            rstsr_assert!(c.device().same_device(a.device()), DeviceMismatch)?;
+
+            // type check and dispatch
+            // for real types, herk is the same to syrk
+            macro_rules! impl_herk_dispatch {
+                ($ty: ty, $fn_name: ident) => {
+                    if same_type::<T, $ty>() {
+                        let a_slice = unsafe {
+                            CpuRawSlice::from_raw_parts(
+                                a.rawvec().as_raw_slice().as_ptr() as *const $ty,
+                                a.len(),
+                            )
+                        };
+                        let c_slice = unsafe {
+                            CpuRawSliceMut::from_raw_parts(
+                                c.rawvec_mut().as_raw_slice_mut().as_mut_ptr() as *mut $ty,
+                                c.len(),
+                            )
+                        };
+                        let alpha = unsafe { *(&alpha as *const T as *const $ty) };
+                        let beta = unsafe { *(&beta as *const T as *const $ty) };
+                        return $fn_name(c_slice, lc, a_slice, la, uplo, alpha, beta, nthreads);
+                    }
+                };
+            }
+
+            impl_herk_dispatch!(f32, syrk_faer_f32);
+            impl_herk_dispatch!(f64, syrk_faer_f64);
+            impl_herk_dispatch!(Complex<f32>, herk_faer_c32);
+            impl_herk_dispatch!(Complex<f64>, herk_faer_c64);
+
+            // not able to be accelarated by faer
+            return herk_naive(
+                c.rawvec_mut().as_raw_slice_mut(),
+                lc,
+                a.rawvec().as_raw_slice(),
+                la,
+                uplo,
+                alpha,
+                beta,
+            );
+        })?
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::dev_utilities::allclose_f64;
 
     #[test]
     fn test_matmul() {
@@ -314,4 +590,122 @@ mod test {
         let b = Tensor::linspace(0.0, 14.0, 15, &device).into_shape_assume_contig([5, 3]).unwrap();
         println!("{:}", &a % &b);
     }
+
+    #[test]
+    fn test_matmul_batched_parallel() {
+        let device = DeviceFaer::new(2);
+        let cpu = DeviceCpuSerial::default();
+        // 8 tasks (parallel outer) and 2 tasks (sequential outer)
+        for nbatch in [8, 2] {
+            let a = Tensor::linspace(0.0, 1.0, nbatch * 15, &device);
+            let a = a.into_shape_assume_contig([nbatch, 3, 5]).unwrap();
+            let b = Tensor::linspace(0.0, 1.0, nbatch * 20, &device);
+            let b = b.into_shape_assume_contig([nbatch, 5, 4]).unwrap();
+            let c_ref = &a.view().into_device(&cpu).unwrap() % &b.view().into_device(&cpu).unwrap();
+            let c = (&a % &b).into_device(&cpu).unwrap();
+            assert!(allclose_f64(&c, &c_ref));
+
+            // batch axis of output has the smallest stride, spans of matrices overlap
+            let mut c = Tensor::zeros([3, 4, nbatch], &device);
+            let mut c_view = c.view_mut().into_transpose(&[2, 0, 1]).unwrap();
+            let lc = c_view.layout().clone();
+            device
+                .matmul(
+                    unsafe { c_view.data_mut().storage_mut() },
+                    &lc,
+                    a.data().storage(),
+                    a.layout(),
+                    b.data().storage(),
+                    b.layout(),
+                    1.0,
+                    0.0,
+                )
+                .unwrap();
+            let c = c.into_transpose(&[2, 0, 1]).unwrap().into_device(&cpu).unwrap();
+            assert!(allclose_f64(&c, &c_ref));
+        }
+    }
+
+    #[test]
+    fn test_symm_herk() {
+        use crate::tensor::matmul::{herk, symm};
+        use num::complex::Complex64;
+
+        let device = DeviceFaer::default();
+        let a = Tensor::linspace(0.0, 24.0, 25, &device).into_shape_assume_contig([5, 5]).unwrap();
+        let b = Tensor::linspace(0.0, 14.0, 15, &device).into_shape_assume_contig([5, 3]).unwrap();
+        let a_cpu = a.view().into_device(&DeviceCpuSerial::default()).unwrap();
+        let b_cpu = b.view().into_device(&DeviceCpuSerial::default()).unwrap();
+        // a @ a^T uses syrk internally
+        let c = &a % &a.reverse_axes();
+        let c_ref = &a_cpu % &a_cpu.reverse_axes();
+        assert!(allclose_f64(&c, &c_ref));
+        for uplo in [TensorUpLo::U, TensorUpLo::L] {
+            let c = symm(&a, &b, TensorSide::L, uplo).unwrap();
+            let c_ref = symm(&a_cpu, &b_cpu, TensorSide::L, uplo).unwrap();
+            assert!(allclose_f64(&c, &c_ref));
+            let c = symm(&a, &b.reverse_axes(), TensorSide::R, uplo).unwrap();
+            let c_ref = symm(&a_cpu, &b_cpu.reverse_axes(), TensorSide::R, uplo).unwrap();
+            assert!(allclose_f64(&c, &c_ref));
+        }
+
+        let a = Tensor::linspace(Complex64::new(1.0, 2.0), Complex64::new(-3.0, 0.5), 15, &device)
+            .into_shape_assume_contig([3, 5])
+            .unwrap();
+        let a_cpu = a.view().into_device(&DeviceCpuSerial::default()).unwrap();
+        let c = herk(&a, TensorUpLo::L).unwrap().data().storage().to_cpu_vec().unwrap();
+        let c_ref = herk(&a_cpu, TensorUpLo::L).unwrap().data().storage().to_cpu_vec().unwrap();
+        izip!(&c, &c_ref).for_each(|(x, y)| assert!((x - y).norm() < 1e-10));
+
+        // a @ a^H is computed by general gemm; herk is only used when called
+        // explicitly
+        let a_h = a_cpu.mapv(|x| x.conj()).into_reverse_axes();
+        let c_ref = (&a_cpu % &a_h).data().storage().to_cpu_vec().unwrap();
+        let c = (&a % &a_h.into_device(&device).unwrap()).data().storage().to_cpu_vec().unwrap();
+        izip!(&c, &c_ref).for_each(|(x, y)| assert!((x - y).norm() < 1e-10));
+    }
+
+    #[test]
+    fn test_matmul_half_mixed() {
+        use crate::tensor::matmul::matmul_promote;
+        use num::complex::Complex64;
+
+        fn mat<T>(v: Vec<T>, shape: [usize; 2], device: &DeviceFaer) -> Tensor<T, Ix2, DeviceFaer>
+        where
+            T: Clone + Debug,
+        {
+            let a = Tensor::<T, Ix1, DeviceFaer>::asarray((v, Some(device))).unwrap();
+            a.into_shape_assume_contig(shape).unwrap()
+        }
+
+        let device = DeviceFaer::default();
+        // inputs are exactly representable in bf16 and f16
+        let va: Vec<f64> = (0..15).map(|i| (i as f64 - 7.0) / 8.0).collect();
+        let vb: Vec<f64> = (0..20).map(|i| i as f64 / 16.0).collect();
+        let c_ref = &mat(va.clone(), [3, 5], &device) % &mat(vb.clone(), [5, 4], &device);
+        let c_ref = c_ref.data().storage().to_cpu_vec().unwrap();
+
+        // half precision, accumulated in f32
+        let a = mat(va.iter().map(|&x| f16::from_f64(x)).collect(), [3, 5], &device);
+        let b = mat(vb.iter().map(|&x| f16::from_f64(x)).collect(), [5, 4], &device);
+        let c = (&a % &b).data().storage().to_cpu_vec().unwrap();
+        izip!(&c, &c_ref).for_each(|(c, c_ref)| assert!((c.to_f64() - c_ref).abs() < 1e-2));
+        let a = mat(va.iter().map(|&x| bf16::from_f64(x)).collect(), [3, 5], &device);
+        let b = mat(vb.iter().map(|&x| bf16::from_f64(x)).collect(), [5, 4], &device);
+        let c = (&a % &b).data().storage().to_cpu_vec().unwrap();
+        izip!(&c, &c_ref).for_each(|(c, c_ref)| assert!((c.to_f64() - c_ref).abs() < 5e-2));
+
+        // real x complex, real operand promoted to complex
+        let a = mat(va.clone(), [3, 5], &device);
+        let b = mat(vb.iter().map(|&x| Complex64::new(x, 0.0)).collect(), [5, 4], &device);
+        let c = (&a % &b).data().storage().to_cpu_vec().unwrap();
+        izip!(&c, &c_ref).for_each(|(c, c_ref)| assert!((c - c_ref).norm() < 1e-12));
+
+        // f32 x f64 by explicit promotion
+        let a = mat(va.iter().map(|&x| x as f32).collect(), [3, 5], &device);
+        let b = mat(vb.clone(), [5, 4], &device);
+        let c: Tensor<f64, Ix2, _> = matmul_promote(&a, &b).unwrap();
+        let c = c.data().storage().to_cpu_vec().unwrap();
+        izip!(&c, &c_ref).for_each(|(c, c_ref)| assert!((c - c_ref).abs() < 1e-12));
+    }
 }
@@ -1,7 +1,9 @@
 //! implementation of faer matmul by basic types
 
 use crate::prelude_dev::*;
+use half::{bf16, f16};
 use num::complex::Complex;
+use num::{One, Zero};
 use rayon::prelude::*;
 
 const PARALLEL_SWITCH: usize = 256;
@@ -12,11 +14,11 @@ macro_rules! impl_gemm_faer {
     ($ty: ty, $ty_faer: ty, $fn_name: ident) => {
         #[allow(clippy::too_many_arguments)]
         pub fn $fn_name(
-            c: &mut [$ty],
+            mut c: CpuRawSliceMut<'_, $ty>,
             lc: &Layout<Ix2>,
-            a: &[$ty],
+            a: CpuRawSlice<'_, $ty>,
             la: &Layout<Ix2>,
-            b: &[$ty],
+            b: CpuRawSlice<'_, $ty>,
             lb: &Layout<Ix2>,
             alpha: $ty,
             beta: $ty,
@@ -58,11 +60,13 @@ macro_rules! impl_gemm_faer {
                     lc.stride()[1],
                 )
             };
+            // c is not referenced if beta is zero, since it may be uninitialized
+            let beta = if beta.is_zero() { None } else { Some(beta.into()) };
             faer::linalg::matmul::matmul(
                 faer_c,
                 faer_a,
                 faer_b,
-                Some(beta.into()),
+                beta,
                 alpha.into(),
                 faer::Parallelism::Rayon(nthreads),
             );
@@ -78,15 +82,90 @@ impl_gemm_faer!(Complex<f64>, faer::complex_native::c64, gemm_faer_c64);
 
 /* #endregion */
 
+/* #region gemm with promotion */
+
+/// Copy matrix `a` to a new row-major contiguous buffer, converting each
+/// element by `f`.
+pub fn promote_matrix<T, TP, F>(
+    a: CpuRawSlice<'_, T>,
+    la: &Layout<Ix2>,
+    f: F,
+) -> (Vec<TP>, Layout<Ix2>)
+where
+    T: Sync,
+    TP: Send,
+    F: Fn(&T) -> TP + Sync,
+{
+    let (m, n) = (la.shape()[0], la.shape()[1]);
+    let buf = (0..m * n)
+        .into_par_iter()
+        .map(|idx| unsafe { f(&a[la.index_uncheck(&[idx / n, idx % n]) as usize]) })
+        .collect();
+    return (buf, [m, n].c());
+}
+
+macro_rules! impl_gemm_half_faer {
+    ($ty: ty, $fn_name: ident) => {
+        /// Half-precision gemm, where multiplication and accumulation are
+        /// performed in f32.
+        #[allow(clippy::too_many_arguments)]
+        pub fn $fn_name(
+            mut c: CpuRawSliceMut<'_, $ty>,
+            lc: &Layout<Ix2>,
+            a: CpuRawSlice<'_, $ty>,
+            la: &Layout<Ix2>,
+            b: CpuRawSlice<'_, $ty>,
+            lb: &Layout<Ix2>,
+            alpha: $ty,
+            beta: $ty,
+            nthreads: usize,
+        ) -> Result<()> {
+            let (m, n) = (lc.shape()[0], lc.shape()[1]);
+            let (a_f32, la_f32) = promote_matrix(a, la, |x| x.to_f32());
+            let (b_f32, lb_f32) = promote_matrix(b, lb, |x| x.to_f32());
+            // c is not referenced if beta is zero, since it may be uninitialized
+            let (mut c_f32, lc_f32) = match beta.is_zero() {
+                true => (vec![0.0_f32; m * n], [m, n].c()),
+                false => promote_matrix(c.as_raw_slice(), lc, |x| x.to_f32()),
+            };
+            let (alpha, beta) = (alpha.to_f32(), beta.to_f32());
+            let (sc, sa, sb) = (c_f32.as_mut_slice(), a_f32.as_slice(), b_f32.as_slice());
+            gemm_faer_f32(
+                sc.into(),
+                &lc_f32,
+                sa.into(),
+                &la_f32,
+                sb.into(),
+                &lb_f32,
+                alpha,
+                beta,
+                nthreads,
+            )?;
+            for i in 0..m {
+                for j in 0..n {
+                    let idx_c = unsafe { lc.index_uncheck(&[i, j]) as usize };
+                    c[idx_c] = <$ty>::from_f32(c_f32[i * n + j]);
+                }
+            }
+            return Ok(());
+        }
+    };
+}
+
+impl_gemm_half_faer!(f16, gemm_faer_f16);
+impl_gemm_half_faer!(bf16, gemm_faer_bf16);
+
+/* #endregion */
+
 /* #region syrk */
 
 macro_rules! impl_syrk_faer {
     ($ty: ty, $ty_faer: ty, $fn_name: ident) => {
         #[allow(clippy::too_many_arguments)]
         pub fn $fn_name(
-            c: &mut [$ty],
+            mut c: CpuRawSliceMut<'_, $ty>,
             lc: &Layout<Ix2>,
-            a: &[$ty],
+            a: CpuRawSlice<'_, $ty>,
             la: &Layout<Ix2>,
             uplo: TensorUpLo,
             alpha: $ty,
@@ -127,6 +206,9 @@ macro_rules! impl_syrk_faer {
                 )
             };
 
+            // faer's triangular matmul gives zero result when given `Some(0)`, so
+            // zero beta is passed as `None`
+            let beta = if beta.is_zero() { None } else { Some(beta.into()) };
             use faer::linalg::matmul::triangular::BlockStructure;
             let block_structure = match uplo {
                 TensorUpLo::U => BlockStructure::TriangularUpper,
@@ -139,7 +221,7 @@ macro_rules! impl_syrk_faer {
                 BlockStructure::Rectangular,
                 faer_at,
                 BlockStructure::Rectangular,
-                Some(beta.into()),
+                beta,
                 alpha.into(),
                 faer::Parallelism::Rayon(nthreads),
             );
@@ -156,9 +238,9 @@ impl_syrk_faer!(Complex<f64>, faer::complex_native::c64, syrk_faer_c64);
 macro_rules! impl_gemm_with_syrk_faer {
     ($ty: ty, $fn_name: ident, $gemm_name: ident, $syrk_name: ident) => {
         pub fn $fn_name(
-            c: &mut [$ty],
+            mut c: CpuRawSliceMut<'_, $ty>,
             lc: &Layout<Ix2>,
-            a: &[$ty],
+            a: CpuRawSlice<'_, $ty>,
             la: &Layout<Ix2>,
             alpha: $ty,
             beta: $ty,
@@ -169,33 +251,11 @@ macro_rules! impl_gemm_with_syrk_faer {
             // full gemm (in order not to allocate a temporary buffer)
             // beta is usually zero, in that normal use case of tensor multiplication
             // usually do not involve output matrix c
-            if beta != <$ty>::from(0.0) {
+            if !beta.is_zero() {
                 $gemm_name(c, lc, a, la, a, &la.reverse_axes(), alpha, beta, nthreads)?;
             } else {
-                $syrk_name(c, lc, a, la, TensorUpLo::L, alpha, beta, nthreads)?;
-                // symmetrize
-                let n = lc.shape()[0];
-                if n < PARALLEL_SWITCH {
-                    for i in 0..n {
-                        for j in 0..i {
-                            let idx_ij = unsafe { lc.index_uncheck(&[i, j]) as usize };
-                            let idx_ji = unsafe { lc.index_uncheck(&[j, i]) as usize };
-                            c[idx_ji] = c[idx_ij];
-                        }
-                    }
-                } else {
-                    let pool = DeviceCpuRayon::new(nthreads).get_pool(nthreads)?;
-                    pool.install(|| {
-                        (0..n).into_par_iter().for_each(|i| {
-                            (0..i).into_par_iter().for_each(|j| unsafe {
-                                let idx_ij = lc.index_uncheck(&[i, j]) as usize;
-                                let idx_ji = lc.index_uncheck(&[j, i]) as usize;
-                                let c_ptr_ji = c.as_ptr().add(idx_ji) as *mut $ty;
-                                *c_ptr_ji = c[idx_ij];
-                            });
-                        });
-                    });
-                }
+                $syrk_name(c.reborrow(), lc, a, la, TensorUpLo::L, alpha, beta, nthreads)?;
+                symmetrize_lower(c, lc)?;
             }
             return Ok(());
         }
@@ -209,6 +269,357 @@ impl_gemm_with_syrk_faer!(Complex<f64>, gemm_with_syrk_faer_c64, gemm_faer_c64,
 
 /* #endregion */
 
+/* #region herk */
+
+macro_rules! impl_herk_faer {
+    ($ty: ty, $ty_faer: ty, $fn_name: ident) => {
+        #[allow(clippy::too_many_arguments)]
+        pub fn $fn_name(
+            mut c: CpuRawSliceMut<'_, $ty>,
+            lc: &Layout<Ix2>,
+            a: CpuRawSlice<'_, $ty>,
+            la: &Layout<Ix2>,
+            uplo: TensorUpLo,
+            alpha: $ty,
+            beta: $ty,
+            nthreads: usize,
+        ) -> Result<()> {
+            // shape check
+            let sc = lc.shape();
+            let sa = la.shape();
+            rstsr_assert_eq!(sc[0], sc[1], InvalidLayout)?;
+            rstsr_assert_eq!(sc[0], sa[0], InvalidLayout)?;
+
+            let faer_a = unsafe {
+                faer::mat::from_raw_parts::<$ty_faer>(
+                    a.as_ptr().add(la.offset()) as *const $ty_faer,
+                    la.shape()[0],
+                    la.shape()[1],
+                    la.stride()[0],
+                    la.stride()[1],
+                )
+            };
+            let faer_ah = unsafe {
+                faer::mat::from_raw_parts::<$ty_faer>(
+                    a.as_ptr().add(la.offset()) as *const $ty_faer,
+                    la.shape()[1],
+                    la.shape()[0],
+                    la.stride()[1],
+                    la.stride()[0],
+                )
+            }
+            .conjugate();
+            let faer_c = unsafe {
+                faer::mat::from_raw_parts_mut::<$ty_faer>(
+                    c.as_mut_ptr().add(lc.offset()) as *mut $ty_faer,
+                    lc.shape()[0],
+                    lc.shape()[1],
+                    lc.stride()[0],
+                    lc.stride()[1],
+                )
+            };
+
+            // faer's triangular matmul gives zero result when given `Some(0)`, so
+            // zero beta is passed as `None`
+            let beta = if beta.is_zero() { None } else { Some(beta.into()) };
+            use faer::linalg::matmul::triangular::BlockStructure;
+            let block_structure = match uplo {
+                TensorUpLo::U => BlockStructure::TriangularUpper,
+                TensorUpLo::L => BlockStructure::TriangularLower,
+            };
+            faer::linalg::matmul::triangular::matmul(
+                faer_c,
+                block_structure,
+                faer_a,
+                BlockStructure::Rectangular,
+                faer_ah,
+                BlockStructure::Rectangular,
+                beta,
+                alpha.into(),
+                faer::Parallelism::Rayon(nthreads),
+            );
+            return Ok(());
+        }
+    };
+}
+
+impl_herk_faer!(Complex<f32>, faer::complex_native::c32, herk_faer_c32);
+impl_herk_faer!(Complex<f64>, faer::complex_native::c64, herk_faer_c64);
+
+/* #endregion */
+
+/* #region symm */
+
+macro_rules! impl_symm_faer {
+    ($ty: ty, $ty_faer: ty, $fn_name: ident) => {
+        #[allow(clippy::too_many_arguments)]
+        pub fn $fn_name(
+            mut c: CpuRawSliceMut<'_, $ty>,
+            lc: &Layout<Ix2>,
+            a: CpuRawSlice<'_, $ty>,
+            la: &Layout<Ix2>,
+            b: CpuRawSlice<'_, $ty>,
+            lb: &Layout<Ix2>,
+            side: TensorSide,
+            uplo: TensorUpLo,
+            alpha: $ty,
+            beta: $ty,
+            nthreads: usize,
+        ) -> Result<()> {
+            // shape check
+            let sc = lc.shape();
+            let sa = la.shape();
+            let sb = lb.shape();
+            rstsr_assert_eq!(sa[0], sa[1], InvalidLayout, "Symmetric matrix should be square.")?;
+            rstsr_assert_eq!(sc, sb, InvalidLayout)?;
+            match side {
+                TensorSide::L => rstsr_assert_eq!(sa[1], sb[0], InvalidLayout)?,
+                TensorSide::R => rstsr_assert_eq!(sb[1], sa[0], InvalidLayout)?,
+            };
+
+            let faer_a = unsafe {
+                faer::mat::from_raw_parts::<$ty_faer>(
+                    a.as_ptr().add(la.offset()) as *const $ty_faer,
+                    la.shape()[0],
+                    la.shape()[1],
+                    la.stride()[0],
+                    la.stride()[1],
+                )
+            };
+            let faer_at = unsafe {
+                faer::mat::from_raw_parts::<$ty_faer>(
+                    a.as_ptr().add(la.offset()) as *const $ty_faer,
+                    la.shape()[1],
+                    la.shape()[0],
+                    la.stride()[1],
+                    la.stride()[0],
+                )
+            };
+            let faer_b = unsafe {
+                faer::mat::from_raw_parts::<$ty_faer>(
+                    b.as_ptr().add(lb.offset()) as *const $ty_faer,
+                    lb.shape()[0],
+                    lb.shape()[1],
+                    lb.stride()[0],
+                    lb.stride()[1],
+                )
+            };
+            let mut faer_c = unsafe {
+                faer::mat::from_raw_parts_mut::<$ty_faer>(
+                    c.as_mut_ptr().add(lc.offset()) as *mut $ty_faer,
+                    lc.shape()[0],
+                    lc.shape()[1],
+                    lc.stride()[0],
+                    lc.stride()[1],
+                )
+            };
+
+            // symmetric a is split into the stored triangle (with diagonal) of a, and the
+            // strict triangle of a^T, which is the mirror of stored triangle
+            use faer::linalg::matmul::triangular::{matmul, BlockStructure};
+            use faer::reborrow::ReborrowMut;
+            let (block_tri, block_mirror) = match uplo {
+                TensorUpLo::U => {
+                    (BlockStructure::TriangularUpper, BlockStructure::StrictTriangularLower)
+                },
+                TensorUpLo::L => {
+                    (BlockStructure::TriangularLower, BlockStructure::StrictTriangularUpper)
+                },
+            };
+            let rect = BlockStructure::Rectangular;
+            // faer's triangular matmul gives zero result when given `Some(0)`, so
+            // zero beta is passed as `None`
+            let beta = if beta.is_zero() { None } else { Some(beta.into()) };
+            let one = <$ty>::one().into();
+            let parallelism = faer::Parallelism::Rayon(nthreads);
+            match side {
+                TensorSide::L => {
+                    let c = faer_c.rb_mut();
+                    matmul(
+                        c,
+                        rect,
+                        faer_a,
+                        block_tri,
+                        faer_b,
+                        rect,
+                        beta,
+                        alpha.into(),
+                        parallelism,
+                    );
+                    let c = faer_c.rb_mut();
+                    matmul(
+                        c,
+                        rect,
+                        faer_at,
+                        block_mirror,
+                        faer_b,
+                        rect,
+                        Some(one),
+                        alpha.into(),
+                        parallelism,
+                    );
+                },
+                TensorSide::R => {
+                    let c = faer_c.rb_mut();
+                    matmul(
+                        c,
+                        rect,
+                        faer_b,
+                        rect,
+                        faer_a,
+                        block_tri,
+                        beta,
+                        alpha.into(),
+                        parallelism,
+                    );
+                    let c = faer_c.rb_mut();
+                    matmul(
+                        c,
+                        rect,
+                        faer_b,
+                        rect,
+                        faer_at,
+                        block_mirror,
+                        Some(one),
+                        alpha.into(),
+                        parallelism,
+                    );
+                },
+            }
+            return Ok(());
+        }
+    };
+}
+
+impl_symm_faer!(f32, f32, symm_faer_f32);
+impl_symm_faer!(f64, f64, symm_faer_f64);
+impl_symm_faer!(Complex<f32>, faer::complex_native::c32, symm_faer_c32);
+impl_symm_faer!(Complex<f64>, faer::complex_native::c64, symm_faer_c64);
+
+/* #endregion */
+
+/* #region gemm with triangular output */
+
+macro_rules! impl_gemm_tri_faer {
+    ($ty: ty, $ty_faer: ty, $fn_name: ident) => {
+        /// Matrix multiplication that only computes triangle `uplo` of output.
+        ///
+        /// This is useful when output is known to be symmetric.
+        #[allow(clippy::too_many_arguments)]
+        pub fn $fn_name(
+            mut c: CpuRawSliceMut<'_, $ty>,
+            lc: &Layout<Ix2>,
+            a: CpuRawSlice<'_, $ty>,
+            la: &Layout<Ix2>,
+            b: CpuRawSlice<'_, $ty>,
+            lb: &Layout<Ix2>,
+            uplo: TensorUpLo,
+            alpha: $ty,
+            beta: $ty,
+            nthreads: usize,
+        ) -> Result<()> {
+            // shape check
+            let sc = lc.shape();
+            let sa = la.shape();
+            let sb = lb.shape();
+            rstsr_assert_eq!(sc[0], sc[1], InvalidLayout)?;
+            rstsr_assert_eq!(sc[0], sa[0], InvalidLayout)?;
+            rstsr_assert_eq!(sa[1], sb[0], InvalidLayout)?;
+            rstsr_assert_eq!(sc[1], sb[1], InvalidLayout)?;
+
+            let faer_a = unsafe {
+                faer::mat::from_raw_parts::<$ty_faer>(
+                    a.as_ptr().add(la.offset()) as *const $ty_faer,
+                    la.shape()[0],
+                    la.shape()[1],
+                    la.stride()[0],
+                    la.stride()[1],
+                )
+            };
+            let faer_b = unsafe {
+                faer::mat::from_raw_parts::<$ty_faer>(
+                    b.as_ptr().add(lb.offset()) as *const $ty_faer,
+                    lb.shape()[0],
+                    lb.shape()[1],
+                    lb.stride()[0],
+                    lb.stride()[1],
+                )
+            };
+            let faer_c = unsafe {
+                faer::mat::from_raw_parts_mut::<$ty_faer>(
+                    c.as_mut_ptr().add(lc.offset()) as *mut $ty_faer,
+                    lc.shape()[0],
+                    lc.shape()[1],
+                    lc.stride()[0],
+                    lc.stride()[1],
+                )
+            };
+
+            // faer's triangular matmul gives zero result when given `Some(0)`, so
+            // zero beta is passed as `None`
+            let beta = if beta.is_zero() { None } else { Some(beta.into()) };
+            use faer::linalg::matmul::triangular::BlockStructure;
+            let block_structure = match uplo {
+                TensorUpLo::U => BlockStructure::TriangularUpper,
+                TensorUpLo::L => BlockStructure::TriangularLower,
+            };
+            faer::linalg::matmul::triangular::matmul(
+                faer_c,
+                block_structure,
+                faer_a,
+                BlockStructure::Rectangular,
+                faer_b,
+                BlockStructure::Rectangular,
+                beta,
+                alpha.into(),
+                faer::Parallelism::Rayon(nthreads),
+            );
+            return Ok(());
+        }
+    };
+}
+
+impl_gemm_tri_faer!(f32, f32, gemm_tri_faer_f32);
+impl_gemm_tri_faer!(f64, f64, gemm_tri_faer_f64);
+impl_gemm_tri_faer!(Complex<f32>, faer::complex_native::c32, gemm_tri_faer_c32);
+impl_gemm_tri_faer!(Complex<f64>, faer::complex_native::c64, gemm_tri_faer_c64);
+
+/* #endregion */
+
+/* #region symmetrize */
+
+/// Copy lower triangle of square matrix to its upper triangle.
+pub fn symmetrize_lower<T>(mut c: CpuRawSliceMut<'_, T>, lc: &Layout<Ix2>) -> Result<()>
+where
+    T: Clone + Send + Sync,
+{
+    let n = lc.shape()[0];
+    rstsr_assert_eq!(n, lc.shape()[1], InvalidLayout)?;
+    if n < PARALLEL_SWITCH {
+        for i in 0..n {
+            for j in 0..i {
+                let idx_ij = unsafe { lc.index_uncheck(&[i, j]) as usize };
+                let idx_ji = unsafe { lc.index_uncheck(&[j, i]) as usize };
+                c[idx_ji] = c[idx_ij].clone();
+            }
+        }
+    } else {
+        // lower and upper triangles do not overlap, so writes never alias reads
+        let c_ptr = c.as_mut_ptr() as usize;
+        (0..n).into_par_iter().for_each(|i| {
+            (0..i).for_each(|j| unsafe {
+                let c_ptr = c_ptr as *mut T;
+                let idx_ij = lc.index_uncheck(&[i, j]) as usize;
+                let idx_ji = lc.index_uncheck(&[j, i]) as usize;
+                *c_ptr.add(idx_ji) = (*c_ptr.add(idx_ij)).clone();
+            });
+        });
+    }
+    return Ok(());
+}
+
+/* #endregion */
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -227,13 +638,46 @@ mod test {
         let lc = [m, n].c();
 
         let start = Instant::now();
-        gemm_faer_f64(&mut c, &lc, &a, &la, &b, &lb, 1.0, 0.0, 16).unwrap();
+        gemm_faer_f64(
+            c.as_mut_slice().into(),
+            &lc,
+            a.as_slice().into(),
+            &la,
+            b.as_slice().into(),
+            &lb,
+            1.0,
+            0.0,
+            16,
+        )
+        .unwrap();
         println!("time: {:?}", start.elapsed());
         let start = Instant::now();
-        gemm_faer_f64(&mut c, &lc, &a, &la, &b, &lb, 1.0, 0.0, 16).unwrap();
+        gemm_faer_f64(
+            c.as_mut_slice().into(),
+            &lc,
+            a.as_slice().into(),
+            &la,
+            b.as_slice().into(),
+            &lb,
+            1.0,
+            0.0,
+            16,
+        )
+        .unwrap();
         println!("time: {:?}", start.elapsed());
         let start = Instant::now();
-        gemm_faer_f64(&mut c, &lc, &a, &la, &b, &lb, 1.0, 0.0, 16).unwrap();
+        gemm_faer_f64(
+            c.as_mut_slice().into(),
+            &lc,
+            a.as_slice().into(),
+            &la,
+            b.as_slice().into(),
+            &lb,
+            1.0,
+            0.0,
+            16,
+        )
+        .unwrap();
         println!("time: {:?}", start.elapsed());
     }
 
@@ -243,7 +687,17 @@ mod test {
         let mut c = vec![1.0; 4];
         let la = [2, 2].c();
         let lc = [2, 2].c();
-        syrk_faer_f64(&mut c, &lc, &a, &la, TensorUpLo::L, 2.0, 1.0, 16).unwrap();
+        syrk_faer_f64(
+            c.as_mut_slice().into(),
+            &lc,
+            a.as_slice().into(),
+            &la,
+            TensorUpLo::L,
+            2.0,
+            1.0,
+            16,
+        )
+        .unwrap();
         println!("{:?}", c);
     }
 }
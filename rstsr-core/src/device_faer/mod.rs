@@ -2,6 +2,7 @@
 //! implemented elsewhere.
 
 pub mod assignment;
+pub mod congruence;
 pub mod conversion;
 pub mod creation;
 pub mod device;
@@ -1,4 +1,5 @@
 use crate::prelude_dev::*;
+use num::Zero;
 
 /* #endregion */
 
@@ -23,16 +24,18 @@ where
         f: &mut F,
     ) -> Result<()> {
         let nthreads = self.get_num_threads();
-        op_mutc_refa_refb_func_cpu_rayon(
-            c.rawvec_mut(),
-            lc,
-            a.rawvec(),
-            la,
-            b.rawvec(),
-            lb,
-            f,
-            nthreads,
-        )
+        self.install(|| {
+            op_mutc_refa_refb_func_cpu_rayon(
+                c.rawvec_mut().as_raw_slice_mut(),
+                lc,
+                a.rawvec().as_raw_slice(),
+                la,
+                b.rawvec().as_raw_slice(),
+                lb,
+                f,
+                nthreads,
+            )
+        })?
     }
 }
 
@@ -54,7 +57,17 @@ where
         f: &mut F,
     ) -> Result<()> {
         let nthreads = self.get_num_threads();
-        op_mutc_refa_numb_func_cpu_rayon(c.rawvec_mut(), lc, a.rawvec(), la, b, f, nthreads)
+        self.install(|| {
+            op_mutc_refa_numb_func_cpu_rayon(
+                c.rawvec_mut().as_raw_slice_mut(),
+                lc,
+                a.rawvec().as_raw_slice(),
+                la,
+                b,
+                f,
+                nthreads,
+            )
+        })?
     }
 }
 
@@ -76,7 +89,17 @@ where
         f: &mut F,
     ) -> Result<()> {
         let nthreads = self.get_num_threads();
-        op_mutc_numa_refb_func_cpu_rayon(c.rawvec_mut(), lc, a, b.rawvec(), lb, f, nthreads)
+        self.install(|| {
+            op_mutc_numa_refb_func_cpu_rayon(
+                c.rawvec_mut().as_raw_slice_mut(),
+                lc,
+                a,
+                b.rawvec().as_raw_slice(),
+                lb,
+                f,
+                nthreads,
+            )
+        })?
     }
 }
 
@@ -96,7 +119,16 @@ where
         f: &mut F,
     ) -> Result<()> {
         let nthreads = self.get_num_threads();
-        op_muta_refb_func_cpu_rayon(a.rawvec_mut(), la, b.rawvec(), lb, f, nthreads)
+        self.install(|| {
+            op_muta_refb_func_cpu_rayon(
+                a.rawvec_mut().as_raw_slice_mut(),
+                la,
+                b.rawvec().as_raw_slice(),
+                lb,
+                f,
+                nthreads,
+            )
+        })?
     }
 }
 
@@ -115,7 +147,9 @@ where
         f: &mut F,
     ) -> Result<()> {
         let nthreads = self.get_num_threads();
-        op_muta_numb_func_cpu_rayon(a.rawvec_mut(), la, b, f, nthreads)
+        self.install(|| {
+            op_muta_numb_func_cpu_rayon(a.rawvec_mut().as_raw_slice_mut(), la, b, f, nthreads)
+        })?
     }
 }
 
@@ -127,8 +161,44 @@ where
 {
     fn op_muta_func(&self, a: &mut Storage<T, Self>, la: &Layout<D>, f: &mut F) -> Result<()> {
         let nthreads = self.get_num_threads();
-        op_muta_func_cpu_rayon(a.rawvec_mut(), la, f, nthreads)
+        self.install(|| op_muta_func_cpu_rayon(a.rawvec_mut().as_raw_slice_mut(), la, f, nthreads))?
     }
 }
 
 /* #endregion */
+
+impl<T, D> OpSumAPI<T, D> for DeviceFaer
+where
+    T: Zero + core::ops::Add<Output = T> + Clone,
+    D: DimAPI,
+{
+    fn sum(&self, a: &Storage<T, Self>, la: &Layout<D>) -> Result<T> {
+        sum_cpu_serial(a.rawvec().as_raw_slice(), la)
+    }
+}
+
+impl<T> OpSumAxesAPI<T> for DeviceFaer
+where
+    T: Zero + core::ops::Add<Output = T> + Clone + Send + Sync,
+{
+    fn sum_axes(
+        &self,
+        c: &mut Storage<T, Self>,
+        lc: &Layout<IxD>,
+        a: &Storage<T, Self>,
+        la: &Layout<IxD>,
+        naxes: usize,
+    ) -> Result<()> {
+        let nthreads = self.get_num_threads();
+        self.install(|| {
+            sum_axes_cpu_rayon(
+                c.rawvec_mut().as_raw_slice_mut(),
+                lc,
+                a.rawvec().as_raw_slice(),
+                la,
+                naxes,
+                nthreads,
+            )
+        })?
+    }
+}
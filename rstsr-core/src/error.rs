@@ -19,6 +19,7 @@ pub enum Error {
 
     DeviceError(String),
     RayonError(String),
+    IOError(String),
 
     Miscellaneous(String),
 }
@@ -47,6 +48,20 @@ impl From<rayon::ThreadPoolBuildError> for Error {
     }
 }
 
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IOError(format!("{:?}", e))
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl From<safetensors::SafeTensorError> for Error {
+    fn from(e: safetensors::SafeTensorError) -> Self {
+        Error::InvalidValue(format!("{:?}", e))
+    }
+}
+
 #[macro_export]
 macro_rules! rstsr_assert {
     ($cond:expr, $errtype:ident) => {
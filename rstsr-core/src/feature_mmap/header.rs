@@ -0,0 +1,277 @@
+//! Header of memory-mapped tensor files.
+
+use crate::prelude_dev::*;
+use half::{bf16, f16};
+use num::complex::Complex;
+
+/// Element type of memory-mapped tensor file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MmapDType {
+    I8 = 1,
+    I16 = 2,
+    I32 = 3,
+    I64 = 4,
+    U8 = 5,
+    U16 = 6,
+    U32 = 7,
+    U64 = 8,
+    F16 = 9,
+    BF16 = 10,
+    F32 = 11,
+    F64 = 12,
+    C32 = 13,
+    C64 = 14,
+}
+
+impl MmapDType {
+    /// Size of element in bytes.
+    pub fn size(&self) -> usize {
+        use MmapDType::*;
+        match self {
+            I8 | U8 => 1,
+            I16 | U16 | F16 | BF16 => 2,
+            I32 | U32 | F32 => 4,
+            I64 | U64 | F64 | C32 => 8,
+            C64 => 16,
+        }
+    }
+
+    fn from_code(code: u8) -> Result<Self> {
+        use MmapDType::*;
+        let dtype = match code {
+            1 => I8,
+            2 => I16,
+            3 => I32,
+            4 => I64,
+            5 => U8,
+            6 => U16,
+            7 => U32,
+            8 => U64,
+            9 => F16,
+            10 => BF16,
+            11 => F32,
+            12 => F64,
+            13 => C32,
+            14 => C64,
+            _ => rstsr_raise!(InvalidValue, "Unknown dtype code {code}.")?,
+        };
+        return Ok(dtype);
+    }
+}
+
+/// Element types that can be stored in memory-mapped tensor files.
+///
+/// # Safety
+///
+/// Implementor must be plain old data: no padding, no pointers, and any bit
+/// pattern is a valid value.
+pub unsafe trait MmapElement: Clone + 'static {
+    const DTYPE: MmapDType;
+}
+
+macro_rules! impl_mmap_element {
+    ($($t:ty => $dtype:ident),*) => {
+        $(unsafe impl MmapElement for $t {
+            const DTYPE: MmapDType = MmapDType::$dtype;
+        })*
+    };
+}
+
+impl_mmap_element!(
+    i8 => I8, i16 => I16, i32 => I32, i64 => I64,
+    u8 => U8, u16 => U16, u32 => U32, u64 => U64,
+    f16 => F16, bf16 => BF16, f32 => F32, f64 => F64,
+    Complex<f32> => C32, Complex<f64> => C64
+);
+
+/// Header of memory-mapped tensor file.
+///
+/// Binary format (all integers little-endian):
+///
+/// | bytes         | content                                              |
+/// |---------------|------------------------------------------------------|
+/// | `0..8`        | magic `b"RSTSRMAP"`                                  |
+/// | `8..10`       | format version (`u16`, currently 1)                  |
+/// | `10`          | dtype code (`u8`, see [`MmapDType`])                 |
+/// | `11`          | byte order of data (`u8`, 0 little, 1 big endian)    |
+/// | `12..16`      | number of dimensions `n` (`u32`)                     |
+/// | `16..24`      | byte position of data in file (`u64`)                |
+/// | `24..32`      | number of elements of data (`u64`)                   |
+/// | `32..40`      | offset of layout in elements (`u64`)                 |
+/// | `40..40+8n`   | shape (`u64` each)                                   |
+/// | `..40+16n`    | strides in elements (`i64` each)                     |
+///
+/// Data starts at a multiple of 64 bytes after the header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MmapHeader {
+    pub dtype: MmapDType,
+    pub layout: Layout<IxD>,
+    /// Byte position of data in file.
+    pub data_start: usize,
+    /// Number of elements of data.
+    pub data_len: usize,
+}
+
+const MAGIC: &[u8; 8] = b"RSTSRMAP";
+const VERSION: u16 = 1;
+const DATA_ALIGNMENT: usize = 64;
+const BYTE_ORDER: u8 = if cfg!(target_endian = "little") { 0 } else { 1 };
+
+impl MmapHeader {
+    /// Header for data of `dtype` viewed by `layout`.
+    pub fn new<D>(dtype: MmapDType, layout: &Layout<D>) -> Result<Self>
+    where
+        D: DimAPI,
+    {
+        let layout = layout.to_dim::<IxD>()?;
+        let (_, data_len) = layout.bounds_index()?;
+        let header_len = header_len(layout.ndim())?;
+        let data_start = header_len.div_ceil(DATA_ALIGNMENT) * DATA_ALIGNMENT;
+        let header = MmapHeader { dtype, layout, data_start, data_len };
+        header.file_len()?;
+        return Ok(header);
+    }
+
+    /// Number of bytes of file; raises error if it overflows `usize`.
+    pub fn file_len(&self) -> Result<usize> {
+        self.data_len
+            .checked_mul(self.dtype.size())
+            .and_then(|n| n.checked_add(self.data_start))
+            .ok_or(Error::ValueOutOfRange("Size of file overflows usize.".to_string()))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let ndim = self.layout.ndim();
+        let mut bytes = Vec::with_capacity(self.data_start);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.push(self.dtype as u8);
+        bytes.push(BYTE_ORDER);
+        bytes.extend_from_slice(&(ndim as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.data_start as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.data_len as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.layout.offset() as u64).to_le_bytes());
+        for &s in self.layout.shape().iter() {
+            bytes.extend_from_slice(&(s as u64).to_le_bytes());
+        }
+        for &s in self.layout.stride().iter() {
+            bytes.extend_from_slice(&(s as i64).to_le_bytes());
+        }
+        bytes.resize(self.data_start, 0);
+        return bytes;
+    }
+
+    /// Parse and validate header from beginning of file content.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let read_u64 = |pos: usize| -> Result<u64> {
+            let slc = bytes.get(pos..pos + 8);
+            let slc = slc.ok_or(Error::InvalidValue("Header is truncated.".to_string()))?;
+            Ok(u64::from_le_bytes(slc.try_into().unwrap()))
+        };
+
+        rstsr_assert!(bytes.len() >= 40, InvalidValue, "Header is truncated.")?;
+        rstsr_assert!(&bytes[0..8] == MAGIC, InvalidValue, "Not a rstsr mmap file.")?;
+        let version = u16::from_le_bytes([bytes[8], bytes[9]]);
+        rstsr_assert_eq!(version, VERSION, InvalidValue, "Unsupported format version.")?;
+        let dtype = MmapDType::from_code(bytes[10])?;
+        rstsr_assert_eq!(bytes[11], BYTE_ORDER, InvalidValue, "Byte order mismatch.")?;
+        let ndim = u32::from_le_bytes(bytes[12..16].try_into().unwrap()) as usize;
+        let header_len = header_len(ndim)?;
+        rstsr_assert!(bytes.len() >= header_len, InvalidValue, "Header is truncated.")?;
+        let data_start = read_u64(16)? as usize;
+        let data_len = read_u64(24)? as usize;
+        let offset = read_u64(32)? as usize;
+        let shape =
+            (0..ndim).map(|i| Ok(read_u64(40 + 8 * i)? as usize)).collect::<Result<Vec<_>>>()?;
+        let stride = (0..ndim)
+            .map(|i| Ok(read_u64(40 + 8 * (ndim + i))? as isize))
+            .collect::<Result<Vec<_>>>()?;
+        rstsr_assert!(data_start >= header_len, InvalidValue, "Data overlaps header.")?;
+        rstsr_assert!(
+            data_start.is_multiple_of(DATA_ALIGNMENT),
+            InvalidValue,
+            "Data is not aligned."
+        )?;
+
+        // sizes are checked before layout, since layout functions assume
+        // they do not overflow
+        let mut size = 1_usize;
+        let mut extent = offset;
+        for (&s, &t) in shape.iter().zip(stride.iter()) {
+            let s_max = s.saturating_sub(1);
+            size = size.saturating_mul(s);
+            extent = extent.saturating_add(s_max.saturating_mul(t.unsigned_abs()));
+        }
+        rstsr_assert!(
+            size < isize::MAX as usize && extent < isize::MAX as usize,
+            ValueOutOfRange,
+            "Size of layout overflows."
+        )?;
+        let layout = unsafe { Layout::new_unchecked(shape, stride, offset) };
+        layout.check_strides()?;
+        let (_, idx_max) = layout.bounds_index()?;
+        rstsr_assert!(idx_max <= data_len, ValueOutOfRange, "Layout exceeds data.")?;
+        let header = MmapHeader { dtype, layout, data_start, data_len };
+        header.file_len()?;
+        return Ok(header);
+    }
+}
+
+/// Number of bytes of header (without padding) for `ndim` dimensions.
+fn header_len(ndim: usize) -> Result<usize> {
+    ndim.checked_mul(16)
+        .and_then(|n| n.checked_add(40))
+        .ok_or(Error::ValueOutOfRange("Size of header overflows usize.".to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_header_roundtrip() {
+        let layout = Layout::new([3, 4], [1, -3], 9);
+        let header = MmapHeader::new(MmapDType::C64, &layout).unwrap();
+        assert_eq!(header.data_start, 128);
+        assert_eq!(header.data_len, 12);
+        let bytes = header.to_bytes();
+        assert_eq!(bytes.len(), 128);
+        assert_eq!(MmapHeader::from_bytes(&bytes).unwrap(), header);
+        assert!(MmapHeader::from_bytes(&bytes[..50]).is_err());
+
+        // data length that overflows file size
+        let mut bytes = bytes;
+        bytes[24..32].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(MmapHeader::from_bytes(&bytes).is_err());
+        let header = MmapHeader { data_len: usize::MAX / 2, ..header };
+        assert!(header.file_len().is_err());
+    }
+
+    #[test]
+    fn test_header_malformed() {
+        let layout = Layout::new([3, 4], [4, 1], 0);
+        let bytes = MmapHeader::new(MmapDType::F64, &layout).unwrap().to_bytes();
+        let corrupt = |pos: usize, value: u64| {
+            let mut bytes = bytes.clone();
+            bytes[pos..pos + 8].copy_from_slice(&value.to_le_bytes());
+            MmapHeader::from_bytes(&bytes)
+        };
+        assert!(MmapHeader::from_bytes(&bytes).is_ok());
+        // shape and stride start at 40 and 56
+        // overlapping strides
+        assert!(corrupt(64, 0).is_err());
+        assert!(corrupt(56, 1).is_err());
+        // negative stride pointing before data
+        assert!(corrupt(56, (-4_i64) as u64).is_err());
+        // shape or stride that overflows
+        assert!(corrupt(40, u64::MAX).is_err());
+        assert!(corrupt(56, i64::MAX as u64).is_err());
+        assert!(corrupt(56, i64::MIN as u64).is_err());
+        // offset out of data
+        assert!(corrupt(32, 12).is_err());
+        assert!(corrupt(32, u64::MAX).is_err());
+        // unaligned data start, or data start inside header
+        assert!(corrupt(16, 65).is_err());
+        assert!(corrupt(16, 0).is_err());
+    }
+}
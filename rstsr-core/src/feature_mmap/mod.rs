@@ -0,0 +1,14 @@
+//! Memory-mapped, file-backed tensors.
+//!
+//! A [`TensorMmap`] file consists of a header (see [`MmapHeader`] for its
+//! format) and raw data in native byte order. Views of the mapped data are
+//! ordinary [`TensorView`](crate::TensorView)/
+//! [`TensorViewMut`](crate::TensorViewMut) on [`DeviceCpu`](crate::DeviceCpu),
+//! so slicing and elementwise operations only touch the pages they need, and
+//! tensors larger than RAM can be processed piece by piece.
+
+pub mod header;
+pub mod tensor_mmap;
+
+pub use header::*;
+pub use tensor_mmap::*;
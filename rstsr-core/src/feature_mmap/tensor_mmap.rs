@@ -0,0 +1,263 @@
+//! Memory-mapped tensor files.
+
+extern crate std;
+
+use super::header::*;
+use crate::prelude_dev::*;
+use memmap2::{Mmap, MmapMut};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Debug)]
+enum MmapBuffer {
+    ReadOnly(Mmap),
+    ReadWrite(MmapMut),
+}
+
+impl MmapBuffer {
+    fn as_ptr(&self) -> *const u8 {
+        match self {
+            MmapBuffer::ReadOnly(mmap) => mmap.as_ptr(),
+            MmapBuffer::ReadWrite(mmap) => mmap.as_ptr(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            MmapBuffer::ReadOnly(mmap) => mmap.len(),
+            MmapBuffer::ReadWrite(mmap) => mmap.len(),
+        }
+    }
+}
+
+/// Tensor file mapped into memory.
+///
+/// Data is not read into memory on opening; pages are loaded by the operating
+/// system when they are accessed through [`view`](Self::view) or
+/// [`view_mut`](Self::view_mut).
+///
+/// ```no_run
+/// use rstsr::feature_mmap::TensorMmap;
+/// use rstsr::prelude_dev::*;
+/// use rstsr::s;
+///
+/// let mut file = TensorMmap::create::<f64, _>("a.rstsr", &[1000, 1000].c()).unwrap();
+/// let mut a = file.view_mut::<f64>().unwrap();
+/// a.slice_mut(s![0..2, ..]).unwrap().mapv_inplace(|_| 1.0);
+/// file.flush().unwrap();
+///
+/// let file = TensorMmap::open("a.rstsr").unwrap();
+/// let a = file.view::<f64>().unwrap();
+/// let b = &a.slice(s![0, 0..5]).unwrap() * 2.0;
+/// ```
+#[derive(Debug)]
+pub struct TensorMmap {
+    header: MmapHeader,
+    buffer: MmapBuffer,
+}
+
+impl TensorMmap {
+    /// Create (or truncate) file for tensor of `layout`, with data zero-filled,
+    /// and map it for reading and writing.
+    pub fn create<T, D>(path: impl AsRef<Path>, layout: &Layout<D>) -> Result<Self>
+    where
+        T: MmapElement,
+        D: DimAPI,
+    {
+        let header = MmapHeader::new(T::DTYPE, layout)?;
+        let mut file =
+            OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+        file.write_all(&header.to_bytes())?;
+        file.set_len(header.file_len()? as u64)?;
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+        return Ok(TensorMmap { header, buffer: MmapBuffer::ReadWrite(mmap) });
+    }
+
+    /// Create file with content of `tensor` (stored in c-contiguous order), and
+    /// map it for reading and writing.
+    pub fn create_from<R, T, D>(path: impl AsRef<Path>, tensor: &TensorBase<R, D>) -> Result<Self>
+    where
+        R: DataAPI<Data = Storage<T, DeviceCpu>>,
+        T: MmapElement,
+        D: DimAPI,
+        DeviceCpu: DeviceAPI<T> + OpAssignAPI<T, IxD>,
+    {
+        let layout_a = tensor.layout().to_dim::<IxD>()?;
+        let layout_c = layout_a.shape().c();
+        let mut file = Self::create::<T, _>(path, &layout_c)?;
+        let mut view = file.view_mut::<T>()?;
+        let device = tensor.device();
+        device.assign(
+            unsafe { view.data_mut().storage_mut() },
+            &layout_c,
+            tensor.storage(),
+            &layout_a,
+        )?;
+        return Ok(file);
+    }
+
+    fn open_with(path: impl AsRef<Path>, writable: bool) -> Result<Self> {
+        let file = OpenOptions::new().read(true).write(writable).open(path)?;
+        let buffer = match writable {
+            true => MmapBuffer::ReadWrite(unsafe { MmapMut::map_mut(&file)? }),
+            false => MmapBuffer::ReadOnly(unsafe { Mmap::map(&file)? }),
+        };
+        let bytes = unsafe { core::slice::from_raw_parts(buffer.as_ptr(), buffer.len()) };
+        let header = MmapHeader::from_bytes(bytes)?;
+        let file_len = header.file_len()?;
+        rstsr_assert!(
+            file_len <= buffer.len(),
+            InvalidValue,
+            "File is truncated: {} bytes expected, {} bytes found.",
+            file_len,
+            buffer.len()
+        )?;
+        return Ok(TensorMmap { header, buffer });
+    }
+
+    /// Map existing file for reading.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with(path, false)
+    }
+
+    /// Map existing file for reading and writing.
+    pub fn open_mut(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with(path, true)
+    }
+
+    pub fn header(&self) -> &MmapHeader {
+        &self.header
+    }
+
+    pub fn layout(&self) -> &Layout<IxD> {
+        &self.header.layout
+    }
+
+    pub fn dtype(&self) -> MmapDType {
+        self.header.dtype
+    }
+
+    pub fn is_writable(&self) -> bool {
+        matches!(self.buffer, MmapBuffer::ReadWrite(_))
+    }
+
+    fn check_dtype<T>(&self) -> Result<()>
+    where
+        T: MmapElement,
+    {
+        rstsr_assert_eq!(
+            T::DTYPE,
+            self.header.dtype,
+            InvalidValue,
+            "Element type does not match dtype of file."
+        )?;
+        return Ok(());
+    }
+
+    /// Pointer to data; alignment is expected from page-aligned mapping and
+    /// 64-byte aligned data start, and checked here.
+    fn data_ptr<T>(&self) -> Result<*const T> {
+        let ptr = unsafe { self.buffer.as_ptr().add(self.header.data_start) };
+        check_aligned::<T>(ptr)?;
+        Ok(ptr as *const T)
+    }
+
+    /// View of mapped data.
+    pub fn view<T>(&self) -> Result<TensorView<'_, T, IxD, DeviceCpu>>
+    where
+        T: MmapElement,
+    {
+        self.check_dtype::<T>()?;
+        let len = self.header.data_len;
+        // mapped data is valid for lifetime of `self`
+        let ptr = self.data_ptr::<T>()?;
+        let slc = unsafe { core::slice::from_raw_parts(ptr, len) };
+        let rawvec = unsafe { CpuRawVec::from_slice(slc) };
+        let storage = Storage { rawvec, device: DeviceCpu::default() };
+        let data = unsafe { DataRef::from_borrowed(storage) };
+        // layout has been checked against data length in header parsing
+        let tensor = unsafe { TensorView::new_unchecked(data, self.header.layout.clone()) };
+        return Ok(tensor);
+    }
+
+    /// Mutable view of mapped data; file must be opened for writing.
+    pub fn view_mut<T>(&mut self) -> Result<TensorViewMut<'_, T, IxD, DeviceCpu>>
+    where
+        T: MmapElement,
+    {
+        self.check_dtype::<T>()?;
+        rstsr_assert!(self.is_writable(), InvalidValue, "File is mapped read-only.")?;
+        let len = self.header.data_len;
+        let ptr = match &mut self.buffer {
+            MmapBuffer::ReadWrite(mmap) => mmap.as_mut_ptr(),
+            MmapBuffer::ReadOnly(_) => unreachable!(),
+        };
+        let ptr = unsafe { ptr.add(self.header.data_start) };
+        check_aligned::<T>(ptr)?;
+        // mapped data is valid and exclusively borrowed for lifetime of `self`
+        let slc = unsafe { core::slice::from_raw_parts_mut(ptr as *mut T, len) };
+        let rawvec = unsafe { CpuRawVec::from_slice_mut(slc) };
+        let storage = Storage { rawvec, device: DeviceCpu::default() };
+        let data = unsafe { DataRefMut::from_borrowed(storage) };
+        let tensor = unsafe { TensorViewMut::new_unchecked(data, self.header.layout.clone()) };
+        return Ok(tensor);
+    }
+
+    /// Write modified data back to file.
+    pub fn flush(&self) -> Result<()> {
+        if let MmapBuffer::ReadWrite(mmap) = &self.buffer {
+            mmap.flush()?;
+        }
+        return Ok(());
+    }
+}
+
+fn check_aligned<T>(ptr: *const u8) -> Result<()> {
+    let align = core::mem::align_of::<T>();
+    rstsr_assert!(
+        (ptr as usize).is_multiple_of(align),
+        InvalidValue,
+        "Mapped data is not aligned."
+    )?;
+    return Ok(());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::s;
+
+    #[test]
+    fn test_tensor_mmap() {
+        let path = std::env::temp_dir().join(format!("rstsr_test_mmap_{}.bin", std::process::id()));
+        let a = Tensor::<f64, _>::linspace(0.0, 23.0, 24, &DeviceCpu::default());
+        let a = a.into_shape_assume_contig([4, 6]).unwrap();
+
+        // write through mutable view of out-of-core file
+        let mut file = TensorMmap::create_from(&path, &a.reverse_axes()).unwrap();
+        assert_eq!(file.layout().shape(), &vec![6, 4]);
+        let mut b = file.view_mut::<f64>().unwrap();
+        b.slice_mut(s![1, ..]).unwrap().mapv_inplace(|x| -x);
+        file.flush().unwrap();
+        drop(file);
+
+        // reopen read-only; slicing and arithmetic work on mapped views
+        let file = TensorMmap::open(&path).unwrap();
+        assert!(!file.is_writable());
+        assert!(file.view::<f32>().is_err());
+        let b = file.view::<f64>().unwrap();
+        assert_eq!(b.storage().rawvec().as_raw_slice()[0..8], [
+            0.0, 6.0, 12.0, 18.0, -1.0, -7.0, -13.0, -19.0
+        ]);
+        let c = &b.slice(s![2..4, 1]).unwrap() * 2.0;
+        assert_eq!(c.data().storage().rawvec(), &vec![16.0, 18.0]);
+        let d = &b.slice(s![.., 3]).unwrap() + &a.slice(s![3, ..]).unwrap();
+        assert_eq!(d.data().storage().rawvec(), &vec![36.0, 0.0, 40.0, 42.0, 44.0, 46.0]);
+
+        let mut file = TensorMmap::open(&path).unwrap();
+        assert!(file.view_mut::<f64>().is_err());
+        drop(file);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,536 @@
+//! Conversion to/from nalgebra
+//!
+//! Views of nalgebra are converted without copy, keeping their strides, if
+//! their dimensions are both dynamic, both static, or of static rows (such as
+//! a row); column vector views of dynamic rows are converted to 1-D tensors.
+//! Views of dynamic rows and static columns (such as
+//! `m.fixed_columns::<2>(0)`) can be converted after re-viewing as dynamically
+//! sized, by `view.view((0, 0), view.shape())`.
+
+use crate::prelude_dev::*;
+use nalgebra::{
+    Const, DMatrix, DMatrixView, DMatrixViewMut, DVector, DVectorView, DVectorViewMut, Dim, Dyn,
+    Matrix, SMatrix, Scalar, ViewStorage, ViewStorageMut, U1,
+};
+
+/* #region nalgebra to rstsr */
+
+/// View of elements at `ptr`, by shape and strides of nalgebra view.
+///
+/// Elements are borrowed without copy, keeping strides of view; elements
+/// between them (such as of other rows of column-major matrix) are not
+/// accessed.
+///
+/// # Safety
+///
+/// `ptr`, `shape` and `strides` must be of a nalgebra view of lifetime `'a`.
+unsafe fn view_from_nalgebra<'a, T, D>(
+    ptr: *const T,
+    shape: D,
+    strides: D,
+) -> TensorView<'a, T, D, DeviceCpu>
+where
+    T: Clone,
+    D: DimAPI,
+{
+    let strides = strides.as_ref().iter().map(|&s| s as isize).collect_vec();
+    let (layout, len) = Layout::from_foreign_view(shape, &strides);
+    let rawvec = CpuRawVec::from_raw_parts(ptr, len, &layout);
+    let data = DataRef::from_borrowed(Storage { rawvec, device: DeviceCpu::default() });
+    TensorView::new_unchecked(data, layout)
+}
+
+/// Mutable view of elements at `ptr`, by shape and strides of nalgebra view.
+///
+/// # Safety
+///
+/// `ptr`, `shape` and `strides` must be of a mutable nalgebra view of lifetime
+/// `'a`.
+unsafe fn view_mut_from_nalgebra<'a, T, D>(
+    ptr: *mut T,
+    shape: D,
+    strides: D,
+) -> TensorViewMut<'a, T, D, DeviceCpu>
+where
+    T: Clone,
+    D: DimAPI,
+{
+    let strides = strides.as_ref().iter().map(|&s| s as isize).collect_vec();
+    let (layout, len) = Layout::from_foreign_view(shape, &strides);
+    let rawvec = CpuRawVec::from_raw_parts_mut(ptr, len, &layout);
+    let data = DataRefMut::from_borrowed(Storage { rawvec, device: DeviceCpu::default() });
+    TensorViewMut::new_unchecked(data, layout)
+}
+
+impl<'a, T, RS, CS> IntoRSTSR for Matrix<T, Dyn, Dyn, ViewStorage<'a, T, Dyn, Dyn, RS, CS>>
+where
+    T: Scalar,
+    RS: Dim,
+    CS: Dim,
+{
+    type RSTSR = TensorView<'a, T, Ix2, DeviceCpu>;
+
+    fn into_rstsr(self) -> Self::RSTSR {
+        let (rs, cs) = self.strides();
+        unsafe { view_from_nalgebra(self.as_ptr(), self.shape().into(), [rs, cs]) }
+    }
+}
+
+impl<'a, T, RS, CS> IntoRSTSR for Matrix<T, Dyn, Dyn, ViewStorageMut<'a, T, Dyn, Dyn, RS, CS>>
+where
+    T: Scalar,
+    RS: Dim,
+    CS: Dim,
+{
+    type RSTSR = TensorViewMut<'a, T, Ix2, DeviceCpu>;
+
+    fn into_rstsr(mut self) -> Self::RSTSR {
+        let (rs, cs) = self.strides();
+        unsafe { view_mut_from_nalgebra(self.as_mut_ptr(), self.shape().into(), [rs, cs]) }
+    }
+}
+
+/// Row view (such as `m.row(0)`) or fixed rows view.
+impl<'a, T, RS, CS, const NR: usize> IntoRSTSR
+    for Matrix<T, Const<NR>, Dyn, ViewStorage<'a, T, Const<NR>, Dyn, RS, CS>>
+where
+    T: Scalar,
+    RS: Dim,
+    CS: Dim,
+{
+    type RSTSR = TensorView<'a, T, Ix2, DeviceCpu>;
+
+    fn into_rstsr(self) -> Self::RSTSR {
+        let (rs, cs) = self.strides();
+        unsafe { view_from_nalgebra(self.as_ptr(), self.shape().into(), [rs, cs]) }
+    }
+}
+
+/// Row view (such as `m.row_mut(0)`) or fixed rows view.
+impl<'a, T, RS, CS, const NR: usize> IntoRSTSR
+    for Matrix<T, Const<NR>, Dyn, ViewStorageMut<'a, T, Const<NR>, Dyn, RS, CS>>
+where
+    T: Scalar,
+    RS: Dim,
+    CS: Dim,
+{
+    type RSTSR = TensorViewMut<'a, T, Ix2, DeviceCpu>;
+
+    fn into_rstsr(mut self) -> Self::RSTSR {
+        let (rs, cs) = self.strides();
+        unsafe { view_mut_from_nalgebra(self.as_mut_ptr(), self.shape().into(), [rs, cs]) }
+    }
+}
+
+/// Statically sized view (such as of [`SMatrix`], or `m.fixed_view::<2, 2>(0,
+/// 0)`).
+impl<'a, T, RS, CS, const NR: usize, const NC: usize> IntoRSTSR
+    for Matrix<T, Const<NR>, Const<NC>, ViewStorage<'a, T, Const<NR>, Const<NC>, RS, CS>>
+where
+    T: Scalar,
+    RS: Dim,
+    CS: Dim,
+{
+    type RSTSR = TensorView<'a, T, Ix2, DeviceCpu>;
+
+    fn into_rstsr(self) -> Self::RSTSR {
+        let (rs, cs) = self.strides();
+        unsafe { view_from_nalgebra(self.as_ptr(), [NR, NC], [rs, cs]) }
+    }
+}
+
+/// Statically sized mutable view.
+impl<'a, T, RS, CS, const NR: usize, const NC: usize> IntoRSTSR
+    for Matrix<T, Const<NR>, Const<NC>, ViewStorageMut<'a, T, Const<NR>, Const<NC>, RS, CS>>
+where
+    T: Scalar,
+    RS: Dim,
+    CS: Dim,
+{
+    type RSTSR = TensorViewMut<'a, T, Ix2, DeviceCpu>;
+
+    fn into_rstsr(mut self) -> Self::RSTSR {
+        let (rs, cs) = self.strides();
+        unsafe { view_mut_from_nalgebra(self.as_mut_ptr(), [NR, NC], [rs, cs]) }
+    }
+}
+
+impl<'a, T, RS, CS> IntoRSTSR for Matrix<T, Dyn, U1, ViewStorage<'a, T, Dyn, U1, RS, CS>>
+where
+    T: Scalar,
+    RS: Dim,
+    CS: Dim,
+{
+    type RSTSR = TensorView<'a, T, Ix1, DeviceCpu>;
+
+    fn into_rstsr(self) -> Self::RSTSR {
+        let (rs, _) = self.strides();
+        unsafe { view_from_nalgebra(self.as_ptr(), [self.nrows()], [rs]) }
+    }
+}
+
+impl<'a, T, RS, CS> IntoRSTSR for Matrix<T, Dyn, U1, ViewStorageMut<'a, T, Dyn, U1, RS, CS>>
+where
+    T: Scalar,
+    RS: Dim,
+    CS: Dim,
+{
+    type RSTSR = TensorViewMut<'a, T, Ix1, DeviceCpu>;
+
+    fn into_rstsr(mut self) -> Self::RSTSR {
+        let (rs, _) = self.strides();
+        unsafe { view_mut_from_nalgebra(self.as_mut_ptr(), [self.nrows()], [rs]) }
+    }
+}
+
+impl<T> IntoRSTSR for DMatrix<T>
+where
+    T: Scalar,
+{
+    type RSTSR = Tensor<T, Ix2, DeviceCpu>;
+
+    /// Buffer of matrix is moved into tensor without copy.
+    fn into_rstsr(self) -> Self::RSTSR {
+        let shape: Ix2 = self.shape().into();
+        let vec: Vec<T> = self.data.into();
+        Tensor::from_shape_vec(shape.f(), vec).unwrap()
+    }
+}
+
+impl<T> IntoRSTSR for DVector<T>
+where
+    T: Scalar,
+{
+    type RSTSR = Tensor<T, Ix1, DeviceCpu>;
+
+    /// Buffer of vector is moved into tensor without copy.
+    fn into_rstsr(self) -> Self::RSTSR {
+        let shape = [self.nrows()];
+        let vec: Vec<T> = self.data.into();
+        Tensor::from_shape_vec(shape, vec).unwrap()
+    }
+}
+
+impl<T, const R: usize, const C: usize> IntoRSTSR for SMatrix<T, R, C>
+where
+    T: Scalar,
+{
+    type RSTSR = Tensor<T, Ix2, DeviceCpu>;
+
+    /// Elements of statically sized matrix are copied into tensor.
+    fn into_rstsr(self) -> Self::RSTSR {
+        Tensor::from_shape_vec([R, C].f(), self.as_slice().to_vec()).unwrap()
+    }
+}
+
+/* #endregion */
+
+/* #region rstsr to nalgebra */
+
+/// Conversion of rstsr tensors to nalgebra matrices.
+///
+/// Conversion of views fails when strides are negative; conversion of mutable
+/// views also fails when strides overlap.
+pub trait IntoNalgebra {
+    type Nalgebra;
+    fn into_nalgebra(self) -> Result<Self::Nalgebra>;
+}
+
+/// Strides of layout, checked to be non-negative.
+fn nalgebra_strides<D>(layout: &Layout<D>) -> Result<D::Stride>
+where
+    D: DimAPI,
+{
+    let stride = layout.stride().clone();
+    rstsr_assert!(
+        layout.size() == 0 || stride.as_ref().iter().all(|&s| s >= 0),
+        InvalidLayout,
+        "nalgebra does not support negative strides."
+    )?;
+    return Ok(stride);
+}
+
+impl<'a, T, B> IntoNalgebra for TensorView<'a, T, Ix2, B>
+where
+    T: Scalar,
+    B: DeviceStorageAPI<T, RawVec = CpuRawVec<T>>,
+{
+    type Nalgebra = DMatrixView<'a, T, Dyn, Dyn>;
+
+    fn into_nalgebra(self) -> Result<Self::Nalgebra> {
+        let [nrows, ncols] = *self.shape();
+        let [rs, cs] = nalgebra_strides(self.layout())?;
+        let ptr = unsafe { self.data().storage().rawvec().as_ptr().add(self.offset()) };
+        let shape = (Dyn(nrows), Dyn(ncols));
+        let strides = (Dyn(rs as usize), Dyn(cs as usize));
+        let storage = unsafe { ViewStorage::from_raw_parts(ptr, shape, strides) };
+        return Ok(Matrix::from_data(storage));
+    }
+}
+
+impl<'a, T, B> IntoNalgebra for TensorViewMut<'a, T, Ix2, B>
+where
+    T: Scalar,
+    B: DeviceStorageAPI<T, RawVec = CpuRawVec<T>>,
+{
+    type Nalgebra = DMatrixViewMut<'a, T, Dyn, Dyn>;
+
+    fn into_nalgebra(mut self) -> Result<Self::Nalgebra> {
+        self.layout().check_strides()?;
+        let [nrows, ncols] = *self.shape();
+        let [rs, cs] = nalgebra_strides(self.layout())?;
+        let offset = self.offset();
+        let ptr = unsafe {
+            self.data_mut().storage_mut().rawvec_mut().as_raw_slice_mut().as_mut_ptr().add(offset)
+        };
+        let shape = (Dyn(nrows), Dyn(ncols));
+        let strides = (Dyn(rs as usize), Dyn(cs as usize));
+        let storage = unsafe { ViewStorageMut::from_raw_parts(ptr, shape, strides) };
+        return Ok(Matrix::from_data(storage));
+    }
+}
+
+impl<'a, T, B> IntoNalgebra for TensorView<'a, T, Ix1, B>
+where
+    T: Scalar,
+    B: DeviceStorageAPI<T, RawVec = CpuRawVec<T>>,
+{
+    type Nalgebra = DVectorView<'a, T, Dyn, Dyn>;
+
+    fn into_nalgebra(self) -> Result<Self::Nalgebra> {
+        let [n] = *self.shape();
+        let [s] = nalgebra_strides(self.layout())?;
+        let ptr = unsafe { self.data().storage().rawvec().as_ptr().add(self.offset()) };
+        let strides = (Dyn(s as usize), Dyn(n.max(1) * s as usize));
+        let storage = unsafe { ViewStorage::from_raw_parts(ptr, (Dyn(n), U1), strides) };
+        return Ok(Matrix::from_data(storage));
+    }
+}
+
+impl<'a, T, B> IntoNalgebra for TensorViewMut<'a, T, Ix1, B>
+where
+    T: Scalar,
+    B: DeviceStorageAPI<T, RawVec = CpuRawVec<T>>,
+{
+    type Nalgebra = DVectorViewMut<'a, T, Dyn, Dyn>;
+
+    fn into_nalgebra(mut self) -> Result<Self::Nalgebra> {
+        self.layout().check_strides()?;
+        let [n] = *self.shape();
+        let [s] = nalgebra_strides(self.layout())?;
+        let offset = self.offset();
+        let ptr = unsafe {
+            self.data_mut().storage_mut().rawvec_mut().as_raw_slice_mut().as_mut_ptr().add(offset)
+        };
+        let strides = (Dyn(s as usize), Dyn(n.max(1) * s as usize));
+        let storage = unsafe { ViewStorageMut::from_raw_parts(ptr, (Dyn(n), U1), strides) };
+        return Ok(Matrix::from_data(storage));
+    }
+}
+
+/// Vector of elements in column-major order; buffer of f-contiguous tensor
+/// starting at the beginning of its buffer is moved without copy.
+fn into_f_vec<T, D>(tensor: Tensor<T, D, DeviceCpu>) -> Vec<T>
+where
+    T: Clone,
+    D: DimAPI,
+{
+    let layout = tensor.layout();
+    if layout.f_contig() && layout.offset() == 0 {
+        let size = layout.size();
+        let mut vec = tensor.into_data().into_storage().into_rawvec().into_vec();
+        vec.truncate(size);
+        return vec;
+    }
+    tensor.iter_with_order(TensorIterOrder::F).unwrap().cloned().collect_vec()
+}
+
+impl<T> IntoNalgebra for Tensor<T, Ix2, DeviceCpu>
+where
+    T: Scalar,
+{
+    type Nalgebra = DMatrix<T>;
+
+    /// Buffer of f-contiguous tensor is moved into matrix without copy;
+    /// otherwise elements are copied.
+    fn into_nalgebra(self) -> Result<Self::Nalgebra> {
+        let [nrows, ncols] = *self.shape();
+        return Ok(DMatrix::from_vec(nrows, ncols, into_f_vec(self)));
+    }
+}
+
+impl<T> IntoNalgebra for Tensor<T, Ix1, DeviceCpu>
+where
+    T: Scalar,
+{
+    type Nalgebra = DVector<T>;
+
+    /// Buffer of contiguous tensor is moved into vector without copy;
+    /// otherwise elements are copied.
+    fn into_nalgebra(self) -> Result<Self::Nalgebra> {
+        return Ok(DVector::from_vec(into_f_vec(self)));
+    }
+}
+
+/// Statically sized matrix is copied from tensor of the same shape.
+impl<R, T, B, const NR: usize, const NC: usize> TryFrom<&TensorBase<R, Ix2>> for SMatrix<T, NR, NC>
+where
+    R: DataAPI<Data = Storage<T, B>>,
+    T: Scalar,
+    B: DeviceAPI<T>,
+    B::RawVec: CpuRawVecAPI<T>,
+{
+    type Error = Error;
+
+    fn try_from(tensor: &TensorBase<R, Ix2>) -> Result<Self> {
+        rstsr_assert_eq!(tensor.shape(), &[NR, NC], InvalidLayout)?;
+        let iter = tensor.iter_with_order(TensorIterOrder::F)?.cloned();
+        return Ok(SMatrix::from_iterator(iter));
+    }
+}
+
+/* #endregion */
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::s;
+    use nalgebra::{Matrix3, Vector3};
+
+    #[test]
+    fn test_nalgebra_view() {
+        let mat = DMatrix::from_fn(4, 6, |i, j| (10 * i + j) as f64);
+        let view = mat.columns(1, 3);
+        let tsr = view.into_rstsr();
+        assert_eq!(tsr.shape(), &[4, 3]);
+        assert_eq!(tsr.stride(), &[1, 4]);
+        assert!(tsr.data().storage().rawvec().is_borrowed());
+        assert_eq!(
+            tsr.iter_with_order(TensorIterOrder::F).unwrap().collect_vec(),
+            view.iter().collect_vec()
+        );
+
+        // back to nalgebra
+        let view1 = tsr.into_nalgebra().unwrap();
+        assert_eq!(view1.strides(), view.strides());
+        assert_eq!(view1, view);
+
+        // sparse views keep their strides
+        let view = mat.view_with_steps((1, 0), (2, 3), (0, 1));
+        let tsr = view.into_rstsr();
+        assert_eq!(tsr.stride(), &[1, 8]);
+        assert_eq!(
+            tsr.iter_with_order(TensorIterOrder::F).unwrap().collect_vec(),
+            view.iter().collect_vec()
+        );
+        let tsr = mat.rows(0, 2).into_rstsr();
+        assert_eq!(tsr.iter().copied().collect_vec()[..3], [0., 1., 2.]);
+        let tsr = mat.row(1).into_rstsr();
+        assert_eq!(tsr.shape(), &[1, 6]);
+        assert_eq!(tsr.iter().copied().collect_vec(), [10., 11., 12., 13., 14., 15.]);
+
+        // statically sized view, and view of static columns re-viewed
+        let tsr = mat.fixed_view::<2, 2>(1, 2).into_rstsr();
+        assert_eq!(tsr.iter().copied().collect_vec(), [12., 13., 22., 23.]);
+        let view = mat.fixed_columns::<2>(4);
+        let tsr = view.view((0, 0), view.shape()).into_rstsr();
+        assert_eq!(tsr.shape(), &[4, 2]);
+        let rot = Matrix3::new(0., -1., 0., 1., 0., 0., 0., 0., 1.);
+        let tsr = rot.as_view::<nalgebra::U3, nalgebra::U3, U1, nalgebra::U3>().into_rstsr();
+        assert_eq!(tsr.iter().collect_vec(), rot.transpose().iter().collect_vec());
+
+        // row-major tensor
+        let tsr = Tensor::from_shape_vec([3, 4], (0..12).collect_vec()).unwrap();
+        let view = tsr.slice(s![1.., slice!(None, None, 2)]).unwrap().into_dim::<Ix2>().unwrap();
+        let view = view.into_nalgebra().unwrap();
+        assert_eq!(view, DMatrix::from_row_slice(2, 2, &[4, 6, 8, 10]));
+        assert!(tsr
+            .slice(s![slice!(None, None, -1)])
+            .unwrap()
+            .into_dim::<Ix2>()
+            .unwrap()
+            .into_nalgebra()
+            .is_err());
+
+        // vector
+        let vec = DVector::from_vec(vec![1., 2., 3., 4., 5.]);
+        let tsr = vec.rows(1, 2).into_rstsr();
+        assert_eq!(tsr.iter().collect_vec(), [&2., &3.]);
+        let vec1 = tsr.into_nalgebra().unwrap();
+        assert_eq!(vec1, DVector::from_vec(vec![2., 3.]));
+        let tsr = vec.rows_with_step(1, 2, 1).into_rstsr();
+        assert_eq!(tsr.iter().collect_vec(), [&2., &4.]);
+    }
+
+    #[test]
+    fn test_nalgebra_view_mut() {
+        let mut mat = DMatrix::<f64>::zeros(4, 6);
+        let (left, mut right) = mat.columns_range_pair_mut(..3, 3..);
+        let mut tsr_left = left.into_rstsr();
+        tsr_left += 1.0;
+        let mut tsr_right = right.column_mut(1).into_rstsr();
+        tsr_right += 2.0;
+        assert_eq!(tsr_right.data().storage().rawvec().len(), 4);
+        assert_eq!(mat.sum(), 12.0 + 8.0);
+        assert_eq!(mat[(2, 1)], 1.0);
+        assert_eq!(mat[(1, 4)], 2.0);
+
+        // rows interleave in memory
+        let (top, bottom) = mat.rows_range_pair_mut(..2, 2..);
+        let (mut tsr_top, mut tsr_bottom) = (top.into_rstsr(), bottom.into_rstsr());
+        tsr_top *= 10.0;
+        tsr_bottom -= 1.0;
+        assert_eq!(mat.column(1).as_slice(), [10.0, 10.0, 0.0, 0.0]);
+        let mut tsr_row = mat.row_mut(3).into_rstsr();
+        tsr_row.iter_mut().for_each(|x| *x = 5.0);
+        assert_eq!(mat.row(3).iter().sum::<f64>(), 30.0);
+        let mut tsr = mat.fixed_view_mut::<2, 2>(0, 0).into_rstsr();
+        tsr.iter_mut().for_each(|x| *x = -1.0);
+        assert_eq!(mat[(1, 1)], -1.0);
+
+        let mut tsr = Tensor::from_shape_vec([4, 6], vec![0.0; 24]).unwrap();
+        let view = tsr.slice_mut(s![1..3, 2..5]).unwrap().into_dim::<Ix2>().unwrap();
+        let mut view = view.into_nalgebra().unwrap();
+        view.fill(1.0);
+        let vec = tsr.iter().cloned().collect_vec();
+        assert_eq!(vec.iter().sum::<f64>(), 6.0);
+        assert_eq!((vec[8], vec[17], vec[7]), (1.0, 0.0, 0.0));
+
+        let mut vec = Tensor::from_shape_vec([5], vec![0.0; 5]).unwrap();
+        vec.view_mut().into_nalgebra().unwrap().fill(3.0);
+        assert_eq!(vec.iter().sum::<f64>(), 15.0);
+    }
+
+    #[test]
+    fn test_nalgebra_owned() {
+        let mat = DMatrix::from_fn(3, 4, |i, j| (10 * i + j) as f64);
+        let mat0 = mat.clone();
+        let ptr = mat0.as_ptr();
+        let tsr = mat0.into_rstsr();
+        assert!(tsr.layout().f_contig());
+        assert_eq!(tsr.storage().rawvec().as_ptr(), ptr);
+        let mat1 = tsr.into_nalgebra().unwrap();
+        assert_eq!(mat1.as_ptr(), ptr);
+        assert_eq!(mat1, mat);
+
+        // row-major tensor is copied
+        let tsr = Tensor::from_shape_vec([2, 3], vec![1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(
+            tsr.into_nalgebra().unwrap(),
+            DMatrix::from_row_slice(2, 3, &[1, 2, 3, 4, 5, 6])
+        );
+
+        let vec = DVector::from_vec(vec![1, 2, 3]);
+        let tsr = vec.clone().into_rstsr();
+        assert_eq!(tsr.shape(), &[3]);
+        assert_eq!(tsr.into_nalgebra().unwrap(), vec);
+
+        // statically sized
+        let rot = Matrix3::new(0., -1., 0., 1., 0., 0., 0., 0., 1.);
+        let tsr = rot.into_rstsr();
+        assert_eq!(tsr.iter().collect_vec(), rot.transpose().iter().collect_vec());
+        let rot1 = Matrix3::try_from(&tsr).unwrap();
+        assert_eq!(rot1 * Vector3::x(), Vector3::y());
+        assert!(nalgebra::Matrix2x3::<f64>::try_from(&tsr).is_err());
+    }
+}
@@ -0,0 +1,16 @@
+//! Conversion between rstsr tensors and [`nalgebra`] matrices.
+//!
+//! - [`IntoRSTSR`](crate::prelude_dev::IntoRSTSR): `DMatrixView`/`DVectorView`
+//!   (and mutable counterparts) to `TensorView`/`TensorViewMut` of `Ix2`/`Ix1`
+//!   without copy; owned `DMatrix`/`DVector` to `Tensor` by moving its buffer;
+//!   statically sized `SMatrix` to `Tensor` by copy.
+//! - [`IntoNalgebra`]: the reverse conversions to dynamically sized matrices
+//!   and vectors; `TryFrom<&TensorBase>` for `SMatrix`.
+//!
+//! Matrices of nalgebra are column-major and can not have negative strides, so
+//! views of tensors with negative strides can not be converted; owned tensors
+//! that are not f-contiguous are copied.
+
+pub mod conversion;
+
+pub use conversion::*;
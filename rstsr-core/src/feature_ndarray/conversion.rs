@@ -0,0 +1,323 @@
+//! Conversion to/from ndarray
+
+use crate::prelude_dev::*;
+use ndarray::{Array, ArrayView, ArrayViewMut, Axis, Dimension, IxDyn, ShapeBuilder};
+
+/* #region dimension */
+
+/// Dimension of rstsr that corresponds to dimension of ndarray.
+pub trait DimNdarrayAPI: DimAPI {
+    type Ndarray: NdarrayDimAPI<RSTSR = Self>;
+    fn to_ndarray_dim(&self) -> Self::Ndarray;
+}
+
+/// Dimension of ndarray that corresponds to dimension of rstsr.
+pub trait NdarrayDimAPI: Dimension {
+    type RSTSR: DimNdarrayAPI<Ndarray = Self>;
+    fn to_rstsr_dim(&self) -> Self::RSTSR;
+}
+
+macro_rules! impl_dim_ndarray {
+    ($($n:literal),*) => {
+        $(
+            impl DimNdarrayAPI for Ix<$n> {
+                type Ndarray = ndarray::Dim<[usize; $n]>;
+                fn to_ndarray_dim(&self) -> Self::Ndarray {
+                    ndarray::Dim(*self)
+                }
+            }
+
+            impl NdarrayDimAPI for ndarray::Dim<[usize; $n]> {
+                type RSTSR = Ix<$n>;
+                fn to_rstsr_dim(&self) -> Self::RSTSR {
+                    self.slice().try_into().unwrap()
+                }
+            }
+        )*
+    };
+}
+
+impl_dim_ndarray!(0, 1, 2, 3, 4, 5, 6);
+
+impl DimNdarrayAPI for IxD {
+    type Ndarray = IxDyn;
+    fn to_ndarray_dim(&self) -> Self::Ndarray {
+        IxDyn(self)
+    }
+}
+
+impl NdarrayDimAPI for IxDyn {
+    type RSTSR = IxD;
+    fn to_rstsr_dim(&self) -> Self::RSTSR {
+        self.slice().to_vec()
+    }
+}
+
+/// Shape, non-negative strides and offset to the lowest address of layout;
+/// and axes of negative strides.
+fn layout_to_ndarray<D>(layout: &Layout<D>) -> (D::Ndarray, D::Ndarray, isize, Vec<usize>)
+where
+    D: DimNdarrayAPI,
+{
+    let shape = layout.shape().to_ndarray_dim();
+    if layout.size() == 0 {
+        let strides = shape.default_strides();
+        return (shape, strides, layout.offset() as isize, vec![]);
+    }
+    let mut strides = shape.clone();
+    let mut offset = layout.offset() as isize;
+    let mut inverted = vec![];
+    for (i, (&d, &s)) in izip!(layout.shape().as_ref(), layout.stride().as_ref()).enumerate() {
+        if s < 0 {
+            offset += (d as isize - 1) * s;
+            inverted.push(i);
+        }
+        strides[i] = s.unsigned_abs();
+    }
+    return (shape, strides, offset, inverted);
+}
+
+/* #endregion */
+
+/* #region ndarray to rstsr */
+
+impl<'a, T, ND> IntoRSTSR for ArrayView<'a, T, ND>
+where
+    T: Clone,
+    ND: NdarrayDimAPI,
+{
+    type RSTSR = TensorView<'a, T, ND::RSTSR, DeviceCpu>;
+
+    /// Elements are borrowed without copy, keeping strides of view (such as a
+    /// column of row-major array).
+    fn into_rstsr(self) -> Self::RSTSR {
+        let shape = self.raw_dim().to_rstsr_dim();
+        let (layout, len) = Layout::from_foreign_view(shape, self.strides());
+        let ptr = unsafe { self.as_ptr().sub(layout.offset()) };
+        unsafe { TensorView::from_raw_parts(ptr, len, layout) }.unwrap()
+    }
+}
+
+impl<'a, T, ND> IntoRSTSR for ArrayViewMut<'a, T, ND>
+where
+    T: Clone,
+    ND: NdarrayDimAPI,
+{
+    type RSTSR = TensorViewMut<'a, T, ND::RSTSR, DeviceCpu>;
+
+    /// Elements are borrowed without copy, keeping strides of view (such as a
+    /// column of row-major array).
+    fn into_rstsr(mut self) -> Self::RSTSR {
+        let shape = self.raw_dim().to_rstsr_dim();
+        let (layout, len) = Layout::from_foreign_view(shape, self.strides());
+        let ptr = unsafe { self.as_mut_ptr().sub(layout.offset()) };
+        unsafe { TensorViewMut::from_raw_parts_mut(ptr, len, layout) }.unwrap()
+    }
+}
+
+impl<T, ND> IntoRSTSR for Array<T, ND>
+where
+    T: Clone,
+    ND: NdarrayDimAPI,
+{
+    type RSTSR = Tensor<T, ND::RSTSR, DeviceCpu>;
+
+    /// Buffer of array is moved into tensor without copy.
+    fn into_rstsr(self) -> Self::RSTSR {
+        let shape = self.raw_dim().to_rstsr_dim();
+        let mut stride = shape.new_stride();
+        stride.as_mut().copy_from_slice(self.strides());
+        let (vec, offset) = self.into_raw_vec_and_offset();
+        let layout = unsafe { Layout::new_unchecked(shape, stride, offset.unwrap_or(0)) };
+        Tensor::from_shape_vec(layout, vec).unwrap()
+    }
+}
+
+/* #endregion */
+
+/* #region rstsr to ndarray */
+
+/// Conversion of rstsr tensors to ndarray arrays.
+///
+/// Conversion of mutable views fails when strides overlap.
+pub trait IntoNdarray {
+    type Ndarray;
+    fn into_ndarray(self) -> Result<Self::Ndarray>;
+}
+
+impl<'a, T, D, B> IntoNdarray for TensorView<'a, T, D, B>
+where
+    D: DimNdarrayAPI,
+    B: DeviceStorageAPI<T, RawVec = CpuRawVec<T>>,
+{
+    type Ndarray = ArrayView<'a, T, D::Ndarray>;
+
+    fn into_ndarray(self) -> Result<Self::Ndarray> {
+        let (shape, strides, offset, inverted) = layout_to_ndarray(self.layout());
+        let ptr = unsafe { self.data().storage().rawvec().as_ptr().offset(offset) };
+        let mut arr = unsafe { ArrayView::from_shape_ptr(shape.strides(strides), ptr) };
+        inverted.into_iter().for_each(|i| arr.invert_axis(Axis(i)));
+        return Ok(arr);
+    }
+}
+
+impl<'a, T, D, B> IntoNdarray for TensorViewMut<'a, T, D, B>
+where
+    T: Clone,
+    D: DimNdarrayAPI,
+    B: DeviceStorageAPI<T, RawVec = CpuRawVec<T>>,
+{
+    type Ndarray = ArrayViewMut<'a, T, D::Ndarray>;
+
+    /// Mutable view of overlapping strides (such as broadcasted) can not be
+    /// converted.
+    fn into_ndarray(mut self) -> Result<Self::Ndarray> {
+        self.layout().check_strides()?;
+        let (shape, strides, offset, inverted) = layout_to_ndarray(self.layout());
+        let rawvec = unsafe { self.data_mut().storage_mut() }.rawvec_mut();
+        let ptr = unsafe { rawvec.as_raw_slice_mut().as_mut_ptr().offset(offset) };
+        let mut arr = unsafe { ArrayViewMut::from_shape_ptr(shape.strides(strides), ptr) };
+        inverted.into_iter().for_each(|i| arr.invert_axis(Axis(i)));
+        return Ok(arr);
+    }
+}
+
+impl<T, D> IntoNdarray for Tensor<T, D, DeviceCpu>
+where
+    T: Clone,
+    D: DimNdarrayAPI,
+{
+    type Ndarray = Array<T, D::Ndarray>;
+
+    /// Buffer of tensor is moved into array without copy, if the tensor
+    /// starts at the beginning of its buffer and does not overlap; otherwise
+    /// elements are copied in row-major order.
+    fn into_ndarray(self) -> Result<Self::Ndarray> {
+        let (shape, strides, offset, inverted) = layout_to_ndarray(self.layout());
+        if offset == 0 && self.layout().check_strides().is_ok() {
+            let vec = self.into_data().into_storage().into_rawvec().into_vec();
+            let mut arr = Array::from_shape_vec(shape.strides(strides), vec).unwrap();
+            inverted.into_iter().for_each(|i| arr.invert_axis(Axis(i)));
+            return Ok(arr);
+        }
+        let vec = self.iter_with_order(TensorIterOrder::C)?.cloned().collect_vec();
+        Ok(Array::from_shape_vec(shape, vec).unwrap())
+    }
+}
+
+/* #endregion */
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::s;
+    use ndarray::{s as nd_s, Array2, Array3};
+
+    #[test]
+    fn test_ndarray_view() {
+        let arr = Array3::from_shape_fn((3, 4, 5), |(i, j, k)| (100 * i + 10 * j + k) as f64);
+        let view = arr.slice(nd_s![1.., ..;-1, ..;-1]);
+        let tsr = view.into_rstsr();
+        assert_eq!(tsr.shape(), &[2, 4, 5]);
+        assert_eq!(tsr.stride(), view.strides());
+        assert!(tsr.data().storage().rawvec().is_borrowed());
+        assert_eq!(tsr.iter().collect_vec(), view.iter().collect_vec());
+
+        // back to ndarray
+        let view1 = tsr.into_ndarray().unwrap();
+        assert_eq!(view1.strides(), view.strides());
+        assert_eq!(view1.as_ptr(), view.as_ptr());
+        assert_eq!(view1, view);
+
+        // sparse view keeps its strides
+        let view = arr.slice(nd_s![1.., ..;-2, ..3;-1]);
+        let tsr = view.into_rstsr();
+        assert_eq!(tsr.shape(), &[2, 2, 3]);
+        assert_eq!(tsr.stride(), view.strides());
+        assert_eq!(tsr.iter().collect_vec(), view.iter().collect_vec());
+        let tsr = arr.slice(nd_s![.., 0, 0]).into_rstsr();
+        assert_eq!(tsr.stride(), &[20_isize]);
+        assert_eq!(tsr.iter().copied().collect_vec(), [0.0, 100.0, 200.0]);
+
+        // dynamic dimension
+        let view = arr.view().into_dyn();
+        let tsr = view.clone().into_rstsr();
+        assert_eq!(tsr.shape(), &vec![3, 4, 5]);
+        let tsr = tsr.slice(s![.., slice!(None, None, -1), 2]).unwrap();
+        let view1 = tsr.into_ndarray().unwrap();
+        assert_eq!(view1, arr.slice(nd_s![.., ..;-1, 2]).into_dyn());
+
+        // empty
+        let view = arr.slice(nd_s![.., 2..2, ..;-1]);
+        let tsr = view.into_rstsr();
+        assert_eq!(tsr.shape(), &[3, 0, 5]);
+        assert_eq!(tsr.into_ndarray().unwrap().shape(), &[3, 0, 5]);
+    }
+
+    #[test]
+    fn test_ndarray_view_mut() {
+        let mut arr = Array2::<f64>::zeros((4, 6));
+        let (mut upper, mut lower) = arr.view_mut().split_at(ndarray::Axis(0), 1);
+        let mut tsr_upper = upper.slice_mut(nd_s![.., ..;-1]).into_rstsr();
+        tsr_upper += 1.0;
+        let mut tsr_lower = lower.view_mut().into_rstsr();
+        tsr_lower += 2.0;
+        assert_eq!(tsr_lower.data().storage().rawvec().len(), 18);
+        assert_eq!(arr.slice(nd_s![..1, ..]).sum(), 6.0);
+        assert_eq!(arr.slice(nd_s![1.., ..]).sum(), 36.0);
+
+        // columns interleave in memory
+        let (left, right) = arr.view_mut().split_at(ndarray::Axis(1), 3);
+        let (mut tsr_left, mut tsr_right) = (left.into_rstsr(), right.into_rstsr());
+        assert_eq!(tsr_left.stride(), &[6, 1]);
+        tsr_left *= 10.0;
+        tsr_right -= 1.0;
+        assert_eq!(arr.row(1).to_vec(), [20.0, 20.0, 20.0, 1.0, 1.0, 1.0]);
+
+        let mut tsr = Tensor::from_shape_vec([4, 6], vec![0.0; 24]).unwrap();
+        let mut view =
+            tsr.slice_mut(s![slice!(None, None, -2), 1..3]).unwrap().into_ndarray().unwrap();
+        view.fill(1.0);
+        assert_eq!(view.shape(), &[2, 2]);
+        assert_eq!(tsr.iter().filter(|&&x| x == 1.0).count(), 4);
+        let vec = tsr.iter().cloned().collect_vec();
+        assert_eq!((vec[19], vec[13]), (1.0, 0.0));
+
+        // overlapping mutable view is refused
+        let layout = unsafe { Layout::new_unchecked([2, 3], [0, 1], 0) };
+        let view = unsafe { TensorViewMut::new_unchecked(tsr.data_mut().as_ref_mut(), layout) };
+        assert!(view.into_ndarray().is_err());
+    }
+
+    #[test]
+    fn test_ndarray_owned() {
+        let arr = Array2::from_shape_fn((3, 4), |(i, j)| (10 * i + j) as f64);
+        let arr0 = arr.clone();
+        let ptr = arr0.as_ptr();
+        let tsr = arr0.into_rstsr();
+        assert_eq!(tsr.iter().collect_vec(), arr.iter().collect_vec());
+
+        // negative strides and offset are preserved, without copy
+        let mut arr1 = arr.clone();
+        arr1.invert_axis(ndarray::Axis(0));
+        let arr0 = arr1.clone();
+        let ptr1 = arr0.as_ptr();
+        let tsr1 = arr0.into_rstsr();
+        assert_eq!(tsr1.stride(), &[-4, 1]);
+        assert_eq!(tsr1.offset(), 8);
+        assert_eq!(tsr1.iter().collect_vec(), arr1.iter().collect_vec());
+        let arr2 = tsr1.into_ndarray().unwrap();
+        assert_eq!(arr2, arr1);
+        assert_eq!(arr2.strides(), &[-4, 1]);
+        assert_eq!(arr2.as_ptr(), ptr1);
+
+        let arr2 = tsr.into_ndarray().unwrap();
+        assert_eq!(arr2.as_ptr(), ptr);
+        assert_eq!(arr2, arr);
+
+        // tensor not starting at its buffer is copied
+        let tsr = Tensor::from_shape_vec(Layout::new([2, 2], [1, 3], 4), (0..12).collect_vec());
+        let arr = tsr.unwrap().into_ndarray().unwrap();
+        assert_eq!(arr, ndarray::arr2(&[[4, 7], [5, 8]]));
+    }
+}
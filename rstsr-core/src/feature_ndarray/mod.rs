@@ -0,0 +1,15 @@
+//! Conversion between rstsr tensors and [`ndarray`] arrays.
+//!
+//! - [`IntoRSTSR`](crate::prelude_dev::IntoRSTSR): `ArrayView`/`ArrayViewMut`
+//!   to `TensorView`/`TensorViewMut` without copy, and owned `Array` to
+//!   `Tensor` by moving its buffer;
+//! - [`IntoNdarray`]: the reverse conversions.
+//!
+//! Strides (including negative ones) and offsets are preserved. Tensors of
+//! ndarray are on device [`DeviceCpu`](crate::DeviceCpu); dimension types are
+//! mapped by [`DimNdarrayAPI`] and [`NdarrayDimAPI`] (`Ix0`-`Ix6` and dynamic
+//! dimension).
+
+pub mod conversion;
+
+pub use conversion::*;
@@ -12,9 +12,9 @@ const PARALLEL_SWITCH: usize = 256;
 // (non-contiguous part).
 
 pub fn assign_arbitary_cpu_rayon<T, DC, DA>(
-    c: &mut [T],
+    mut c: CpuRawSliceMut<'_, T>,
     lc: &Layout<DC>,
-    a: &[T],
+    a: CpuRawSlice<'_, T>,
     la: &Layout<DA>,
     nthreads: usize,
 ) -> Result<()>
@@ -30,7 +30,6 @@ where
     }
 
     // actual parallel iteration
-    let pool = DeviceCpuRayon::new(nthreads).get_pool(nthreads)?;
     if lc.c_contig() && la.c_contig() || lc.f_contig() && la.f_contig() {
         // contiguous case
         // we do not perform parallel for this case
@@ -58,20 +57,18 @@ where
         let iter_c = IterLayoutColMajor::new(&lc)?;
         let iter_a = IterLayoutColMajor::new(&la)?;
         // iterate and assign
-        pool.install(|| {
-            (iter_c, iter_a).into_par_iter().for_each(|(idx_c, idx_a)| unsafe {
-                let c_ptr = c.as_ptr() as *mut T;
-                *c_ptr.add(idx_c) = a[idx_a].clone();
-            });
+        (iter_c, iter_a).into_par_iter().for_each(|(idx_c, idx_a)| unsafe {
+            let c_ptr = c.as_ptr() as *mut T;
+            *c_ptr.add(idx_c) = a[idx_a].clone();
         });
     }
     return Ok(());
 }
 
 pub fn assign_cpu_rayon<T, D>(
-    c: &mut [T],
+    c: CpuRawSliceMut<'_, T>,
     lc: &Layout<D>,
-    a: &[T],
+    a: CpuRawSlice<'_, T>,
     la: &Layout<D>,
     nthreads: usize,
 ) -> Result<()>
@@ -91,34 +88,34 @@ where
     let (layouts_contig, size_contig) = translate_to_col_major_with_contig(&layouts_full_ref);
 
     // actual parallel iteration
-    let pool = DeviceCpuRayon::new(nthreads).get_pool(nthreads)?;
     if size_contig < CONTIG_SWITCH {
         // not possible for contiguous assign
         let iter_c = IterLayoutColMajor::new(&layouts_full[0])?;
         let iter_a = IterLayoutColMajor::new(&layouts_full[1])?;
-        pool.install(|| {
-            (iter_c, iter_a).into_par_iter().for_each(|(idx_c, idx_a)| unsafe {
-                let c_ptr = c.as_ptr() as *mut T;
-                *c_ptr.add(idx_c) = a[idx_a].clone();
-            });
+        (iter_c, iter_a).into_par_iter().for_each(|(idx_c, idx_a)| unsafe {
+            let c_ptr = c.as_ptr() as *mut T;
+            *c_ptr.add(idx_c) = a[idx_a].clone();
         });
     } else {
         // parallel for outer iteration
         let iter_c = IterLayoutColMajor::new(&layouts_contig[0])?;
         let iter_a = IterLayoutColMajor::new(&layouts_contig[1])?;
-        pool.install(|| {
-            (iter_c, iter_a).into_par_iter().for_each(|(idx_c, idx_a)| unsafe {
-                let c_ptr = c.as_ptr().add(idx_c) as *mut T;
-                (0..size_contig).for_each(|idx| {
-                    *c_ptr.add(idx) = a[idx_a + idx].clone();
-                })
-            });
+        (iter_c, iter_a).into_par_iter().for_each(|(idx_c, idx_a)| unsafe {
+            let c_ptr = c.as_ptr().add(idx_c) as *mut T;
+            (0..size_contig).for_each(|idx| {
+                *c_ptr.add(idx) = a[idx_a + idx].clone();
+            })
         });
     }
     return Ok(());
 }
 
-pub fn fill_cpu_rayon<T, D>(c: &mut [T], lc: &Layout<D>, fill: T, nthreads: usize) -> Result<()>
+pub fn fill_cpu_rayon<T, D>(
+    c: CpuRawSliceMut<'_, T>,
+    lc: &Layout<D>,
+    fill: T,
+    nthreads: usize,
+) -> Result<()>
 where
     T: Clone + Send + Sync,
     D: DimAPI,
@@ -135,27 +132,71 @@ where
     let (layouts_contig, size_contig) = translate_to_col_major_with_contig(&layouts_full_ref);
 
     // actual parallel iteration
-    let pool = DeviceCpuRayon::new(nthreads).get_pool(nthreads)?;
     if size_contig < CONTIG_SWITCH {
         // not possible for contiguous fill
         let iter_c = IterLayoutColMajor::new(&layouts_full[0])?;
-        pool.install(|| {
-            (iter_c).into_par_iter().for_each(|idx_c| unsafe {
-                let c_ptr = c.as_ptr() as *mut T;
-                *c_ptr.add(idx_c) = fill.clone();
-            });
+        (iter_c).into_par_iter().for_each(|idx_c| unsafe {
+            let c_ptr = c.as_ptr() as *mut T;
+            *c_ptr.add(idx_c) = fill.clone();
         });
     } else {
         // parallel for outer iteration
         let iter_c = IterLayoutColMajor::new(&layouts_contig[0])?;
-        pool.install(|| {
-            (iter_c).into_par_iter().for_each(|idx_c| unsafe {
-                let c_ptr = c.as_ptr().add(idx_c) as *mut T;
-                (0..size_contig).for_each(|idx| {
-                    *c_ptr.add(idx) = fill.clone();
-                })
-            });
+        (iter_c).into_par_iter().for_each(|idx_c| unsafe {
+            let c_ptr = c.as_ptr().add(idx_c) as *mut T;
+            (0..size_contig).for_each(|idx| {
+                *c_ptr.add(idx) = fill.clone();
+            })
         });
     }
     return Ok(());
 }
+
+/* #region impl assignment for DeviceCpuRayon */
+
+impl<T, DC, DA> OpAssignArbitaryAPI<T, DC, DA> for DeviceCpuRayon
+where
+    T: Clone + Send + Sync,
+    DC: DimAPI,
+    DA: DimAPI,
+{
+    fn assign_arbitary(
+        &self,
+        c: &mut Storage<T, Self>,
+        lc: &Layout<DC>,
+        a: &Storage<T, Self>,
+        la: &Layout<DA>,
+    ) -> Result<()> {
+        let c = c.rawvec_mut().as_raw_slice_mut();
+        let a = a.rawvec().as_raw_slice();
+        let nthreads = self.get_num_threads();
+        self.install(|| assign_arbitary_cpu_rayon(c, lc, a, la, nthreads))?
+    }
+}
+
+impl<T, D> OpAssignAPI<T, D> for DeviceCpuRayon
+where
+    T: Clone + Send + Sync,
+    D: DimAPI,
+{
+    fn assign(
+        &self,
+        c: &mut Storage<T, Self>,
+        lc: &Layout<D>,
+        a: &Storage<T, Self>,
+        la: &Layout<D>,
+    ) -> Result<()> {
+        let c = c.rawvec_mut().as_raw_slice_mut();
+        let a = a.rawvec().as_raw_slice();
+        let nthreads = self.get_num_threads();
+        self.install(|| assign_cpu_rayon(c, lc, a, la, nthreads))?
+    }
+
+    fn fill(&self, c: &mut Storage<T, Self>, lc: &Layout<D>, fill: T) -> Result<()> {
+        let c = c.rawvec_mut().as_raw_slice_mut();
+        let nthreads = self.get_num_threads();
+        self.install(|| fill_cpu_rayon(c, lc, fill, nthreads))?
+    }
+}
+
+/* #endregion */
@@ -0,0 +1,91 @@
+use crate::prelude_dev::*;
+use num::{complex::ComplexFloat, Float, Num};
+
+// for creation, we use most of the functions from DeviceCpuSerial
+impl<T> DeviceCreationAnyAPI<T> for DeviceCpuRayon
+where
+    T: Clone + Debug,
+    Self: DeviceRawVecAPI<T, RawVec = CpuRawVec<T>>,
+{
+    unsafe fn empty_impl(&self, len: usize) -> Result<Storage<T, Self>> {
+        let storage = DeviceCpuSerial::with_memory_of(self).empty_impl(len)?;
+        Ok(storage_from_serial(storage, self))
+    }
+
+    fn full_impl(&self, len: usize, fill: T) -> Result<Storage<T, Self>> {
+        let storage = DeviceCpuSerial::with_memory_of(self).full_impl(len, fill)?;
+        Ok(storage_from_serial(storage, self))
+    }
+
+    fn outof_cpu_vec(&self, vec: Vec<T>) -> Result<Storage<T, Self>> {
+        let storage = DeviceCpuSerial::with_memory_of(self).outof_cpu_vec(vec)?;
+        Ok(storage_from_serial(storage, self))
+    }
+
+    fn from_cpu_vec(&self, vec: &[T]) -> Result<Storage<T, Self>> {
+        let storage = DeviceCpuSerial::with_memory_of(self).from_cpu_vec(vec)?;
+        Ok(storage_from_serial(storage, self))
+    }
+}
+
+impl<T> DeviceCreationNumAPI<T> for DeviceCpuRayon
+where
+    T: Num + Clone + Debug,
+    Self: DeviceRawVecAPI<T, RawVec = CpuRawVec<T>>,
+{
+    fn zeros_impl(&self, len: usize) -> Result<Storage<T, Self>> {
+        let storage = DeviceCpuSerial::with_memory_of(self).zeros_impl(len)?;
+        Ok(storage_from_serial(storage, self))
+    }
+
+    fn ones_impl(&self, len: usize) -> Result<Storage<T, Self>> {
+        let storage = DeviceCpuSerial::with_memory_of(self).ones_impl(len)?;
+        Ok(storage_from_serial(storage, self))
+    }
+
+    fn arange_int_impl(&self, len: usize) -> Result<Storage<T, Self>> {
+        let storage = DeviceCpuSerial::with_memory_of(self).arange_int_impl(len)?;
+        Ok(storage_from_serial(storage, self))
+    }
+}
+
+impl<T> DeviceCreationComplexFloatAPI<T> for DeviceCpuRayon
+where
+    T: ComplexFloat + Clone + Debug + Send + Sync,
+    Self: DeviceRawVecAPI<T, RawVec = CpuRawVec<T>>,
+{
+    fn linspace_impl(
+        &self,
+        start: T,
+        end: T,
+        n: usize,
+        endpoint: bool,
+    ) -> Result<Storage<T, Self>> {
+        let storage =
+            DeviceCpuSerial::with_memory_of(self).linspace_impl(start, end, n, endpoint)?;
+        Ok(storage_from_serial(storage, self))
+    }
+}
+
+impl<T> DeviceCreationFloatAPI<T> for DeviceCpuRayon
+where
+    T: Float + Clone + Debug + Send + Sync,
+    Self: DeviceRawVecAPI<T, RawVec = CpuRawVec<T>>,
+{
+    fn arange_impl(&self, start: T, end: T, step: T) -> Result<Storage<T, Self>> {
+        let storage = DeviceCpuSerial::with_memory_of(self).arange_impl(start, end, step)?;
+        Ok(storage_from_serial(storage, self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_linspace() {
+        let device = DeviceCpuRayon::default();
+        let a = Tensor::linspace(1.0, 5.0, 5, &device);
+        assert_eq!(a.data().storage().rawvec(), &vec![1., 2., 3., 4., 5.]);
+    }
+}
@@ -1,40 +1,98 @@
+extern crate std;
+
 use crate::prelude_dev::*;
+use std::sync::{Arc, OnceLock};
 
-/// This is base device for Parallel CPU device.
+/// Parallel CPU device.
 ///
-/// This device is not intended to be used directly, but to be used as a base.
-/// Possible inherited devices could be Faer or Blas.
+/// This device works for any element type `T: Clone + Send + Sync`, with
+/// naive (but parallel) matmul. It is also used as the base of other parallel
+/// devices, such as Faer.
 ///
-/// This device is intended not to implement `DeviceAPI<T>`.
+/// The thread pool is created lazily when first used, and is shared by all
+/// clones of the device. Alternatively, the device can run on the rayon pool
+/// of caller (by default the global pool), see
+/// [`DeviceCpuRayon::new_global_pool`].
 #[derive(Clone, Debug)]
 pub struct DeviceCpuRayon {
     num_threads: usize,
+    /// `None` for running on pool of caller.
+    pool: Option<Arc<OnceLock<rayon::ThreadPool>>>,
+    memory: DeviceMemory,
 }
 
 impl DeviceCpuRayon {
+    /// Device with its own thread pool of `num_threads` threads (`0` for
+    /// number of threads of the global rayon pool).
     pub fn new(num_threads: usize) -> Self {
-        DeviceCpuRayon { num_threads }
+        let pool = Some(Arc::new(OnceLock::new()));
+        DeviceCpuRayon { num_threads, pool, memory: DeviceMemory::default() }
+    }
+
+    /// Device that runs on the rayon pool of caller, without creating its own
+    /// thread pool.
+    pub fn new_global_pool() -> Self {
+        DeviceCpuRayon { num_threads: 0, pool: None, memory: DeviceMemory::default() }
     }
 
     pub fn var_num_threads(&self) -> usize {
         self.num_threads
     }
 
+    /// Set number of threads.
+    ///
+    /// This device will then use a new thread pool (not shared with its
+    /// previous clones), which is created when first used.
     pub fn set_num_threads(&mut self, num_threads: usize) {
         self.num_threads = num_threads;
+        self.pool = Some(Arc::new(OnceLock::new()));
     }
 
     pub fn get_num_threads(&self) -> usize {
-        match self.num_threads {
-            0 => rayon::current_num_threads(),
-            _ => rayon::current_num_threads().max(self.num_threads),
+        match (&self.pool, self.num_threads) {
+            (None, _) => rayon::current_num_threads(),
+            (Some(pool), 0) => {
+                pool.get().map_or_else(rayon::current_num_threads, |p| p.current_num_threads())
+            },
+            (Some(_), n) => n,
+        }
+    }
+
+    /// Whether this device runs on the rayon pool of caller.
+    pub fn is_global_pool(&self) -> bool {
+        self.pool.is_none()
+    }
+
+    /// Thread pool of this device, created if not yet.
+    ///
+    /// Returns `None` if this device runs on the rayon pool of caller.
+    pub fn get_pool(&self) -> Result<Option<&rayon::ThreadPool>> {
+        let Some(pool) = &self.pool else {
+            return Ok(None);
+        };
+        if pool.get().is_none() {
+            let nthreads = self.get_num_threads();
+            let new_pool = rayon::ThreadPoolBuilder::new().num_threads(nthreads).build()?;
+            // another clone may have initialized the pool at the same time; then the
+            // newly built pool is simply dropped
+            let _ = pool.set(new_pool);
         }
+        return Ok(pool.get());
     }
 
-    pub fn get_pool(&self, n: usize) -> Result<rayon::ThreadPool> {
-        rstsr_pattern!(n, 0..=self.get_num_threads(), RayonError, "Specified too much threads.")?;
-        let nthreads = if n == 0 { self.get_num_threads() } else { n };
-        rayon::ThreadPoolBuilder::new().num_threads(nthreads).build().map_err(Error::from)
+    /// Run closure `op` in thread pool of this device.
+    ///
+    /// Parallel iterators and `rayon::join` inside `op` are executed by this
+    /// device's threads.
+    pub fn install<OP, R>(&self, op: OP) -> Result<R>
+    where
+        OP: FnOnce() -> R + Send,
+        R: Send,
+    {
+        match self.get_pool()? {
+            Some(pool) => Ok(pool.install(op)),
+            None => Ok(op()),
+        }
     }
 }
 
@@ -46,6 +104,93 @@ impl Default for DeviceCpuRayon {
 
 impl DeviceBaseAPI for DeviceCpuRayon {
     fn same_device(&self, other: &Self) -> bool {
-        self.num_threads == other.num_threads
+        self.num_threads == other.num_threads && self.is_global_pool() == other.is_global_pool()
+    }
+}
+
+impl DeviceMemoryAPI for DeviceCpuRayon {
+    fn memory(&self) -> &DeviceMemory {
+        &self.memory
+    }
+
+    fn memory_mut(&mut self) -> &mut DeviceMemory {
+        &mut self.memory
+    }
+}
+
+impl<T> DeviceRawVecAPI<T> for DeviceCpuRayon
+where
+    T: Clone,
+{
+    type RawVec = CpuRawVec<T>;
+}
+
+impl<T> DeviceStorageAPI<T> for DeviceCpuRayon
+where
+    T: Clone,
+{
+    fn new(vector: Self::RawVec, device: Self) -> Storage<T, Self> {
+        let device = device.acquire_memory_unchecked::<T>(vector.len());
+        Storage::<T, Self> { rawvec: vector, device }
+    }
+
+    fn len(storage: &Storage<T, Self>) -> usize {
+        storage.rawvec.len()
+    }
+
+    fn to_cpu_vec(storage: &Storage<T, Self>) -> Result<Vec<T>> {
+        Ok(storage.rawvec.to_vec())
+    }
+
+    fn into_cpu_vec(storage: Storage<T, Self>) -> Result<Vec<T>> {
+        Ok(storage.rawvec.into_vec())
+    }
+
+    #[inline]
+    fn get_index(storage: &Storage<T, Self>, index: usize) -> T {
+        storage.rawvec.as_raw_slice()[index].clone()
+    }
+
+    #[inline]
+    fn get_index_ptr(storage: &Storage<T, Self>, index: usize) -> *const T {
+        &storage.rawvec.as_raw_slice()[index] as *const T
+    }
+
+    #[inline]
+    fn get_index_mut_ptr(storage: &mut Storage<T, Self>, index: usize) -> *mut T {
+        &mut storage.rawvec.as_raw_slice_mut()[index] as *mut T
+    }
+
+    #[inline]
+    fn set_index(storage: &mut Storage<T, Self>, index: usize, value: T) {
+        storage.rawvec.as_raw_slice_mut()[index] = value;
+    }
+}
+
+impl<T> DeviceAPI<T> for DeviceCpuRayon where T: Clone {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pool_shared() {
+        let device = DeviceCpuRayon::new(3);
+        let cloned = device.clone();
+        let pool = device.get_pool().unwrap().unwrap() as *const rayon::ThreadPool;
+        let pool_cloned = cloned.get_pool().unwrap().unwrap() as *const rayon::ThreadPool;
+        assert_eq!(pool, pool_cloned);
+        assert_eq!(device.install(rayon::current_num_threads).unwrap(), 3);
+
+        let mut device = cloned;
+        device.set_num_threads(2);
+        let pool_new = device.get_pool().unwrap().unwrap() as *const rayon::ThreadPool;
+        assert_ne!(pool, pool_new);
+        assert_eq!(device.install(rayon::current_num_threads).unwrap(), 2);
+
+        let device = DeviceCpuRayon::new_global_pool();
+        assert!(device.get_pool().unwrap().is_none());
+        let n = device.install(rayon::current_num_threads).unwrap();
+        assert_eq!(n, rayon::current_num_threads());
     }
 }
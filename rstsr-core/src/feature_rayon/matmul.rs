@@ -0,0 +1,398 @@
+//! Matrix multiplication for parallel CPU device.
+//!
+//! **This implementation is not optimized!**
+
+use crate::device_cpu_serial::matmul::{herk_naive, symm_naive, syrk_naive};
+use crate::feature_rayon::matmul_naive::{gemm_naive_rayon, inner_dot_naive_rayon};
+use crate::prelude_dev::*;
+use core::ops::{Add, Mul};
+use num::complex::ComplexFloat;
+use num::Zero;
+use rayon::prelude::*;
+
+impl<TA, TB, TC, DA, DB, DC> DeviceMatMulAPI<TA, TB, TC, DA, DB, DC> for DeviceCpuRayon
+where
+    TA: Clone + Send + Sync,
+    TB: Clone + Send + Sync,
+    TC: Clone + Send + Sync,
+    DA: DimAPI,
+    DB: DimAPI,
+    DC: DimAPI,
+    TA: Mul<TB, Output = TC>,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+{
+    fn matmul(
+        &self,
+        c: &mut Storage<TC, Self>,
+        lc: &Layout<DC>,
+        a: &Storage<TA, Self>,
+        la: &Layout<DA>,
+        b: &Storage<TB, Self>,
+        lb: &Layout<DB>,
+        alpha: TC,
+        beta: TC,
+    ) -> Result<()> {
+        let nthreads = self.get_num_threads();
+        self.install(|| {
+            rstsr_assert!(c.device().same_device(a.device()), DeviceMismatch)?;
+            rstsr_assert!(c.device().same_device(b.device()), DeviceMismatch)?;
+            let mut c_slice = c.rawvec_mut().as_raw_slice_mut();
+            let a_slice = a.rawvec().as_raw_slice();
+            let b_slice = b.rawvec().as_raw_slice();
+            match (la.ndim(), lb.ndim(), lc.ndim()) {
+                (1, 1, 0) => {
+                    // rule 1: vector inner dot
+                    let la = &la.clone().into_dim::<Ix1>().unwrap();
+                    let lb = &lb.clone().into_dim::<Ix1>().unwrap();
+                    let lc = &lc.clone().into_dim::<Ix0>().unwrap();
+                    let c_num = &mut c_slice[lc.offset()];
+                    inner_dot_naive_rayon(c_num, a_slice, la, b_slice, lb, alpha, beta)?;
+                },
+                (2, 2, 2) => {
+                    // rule 2: matrix multiplication
+                    let la = &la.clone().into_dim::<Ix2>().unwrap();
+                    let lb = &lb.clone().into_dim::<Ix2>().unwrap();
+                    let lc = &lc.clone().into_dim::<Ix2>().unwrap();
+                    gemm_naive_rayon(c_slice, lc, a_slice, la, b_slice, lb, alpha, beta)?;
+                },
+                _ => {
+                    // temporary layout
+                    let la_matmul;
+                    let lb_matmul;
+                    let lc_matmul;
+                    let la_rest;
+                    let lb_rest;
+                    let lc_rest;
+                    match (la.ndim(), lb.ndim(), lc.ndim()) {
+                        (1, 1, 0) | (2, 2, 2) => unreachable!(),
+                        (1, 2.., _) => {
+                            // rule 3: | `        K` | `..., K, N` | `   ..., N` |
+                            rstsr_assert_eq!(lb.ndim(), lc.ndim() + 1, InvalidLayout)?;
+                            let (la_r, la_m) = la.dim_split_at(-1)?;
+                            let (lb_r, lb_m) = lb.dim_split_at(-2)?;
+                            let (lc_r, lc_m) = lc.dim_split_at(-1)?;
+                            la_rest = broadcast_layout_to_first(&lc_r, &la_r)?.1;
+                            lb_rest = lb_r;
+                            lc_rest = lc_r;
+                            la_matmul = la_m.dim_insert(0)?.into_dim::<Ix2>()?;
+                            lb_matmul = lb_m.into_dim::<Ix2>()?;
+                            lc_matmul = lc_m.dim_insert(0)?.into_dim::<Ix2>()?;
+                        },
+                        (2.., 1, _) => {
+                            // rule 4: | `..., M, K` | `        K` | `   ..., M` |
+                            rstsr_assert_eq!(la.ndim(), lc.ndim() + 1, InvalidLayout)?;
+                            let (la_r, la_m) = la.dim_split_at(-2)?;
+                            let (lb_r, lb_m) = lb.dim_split_at(-1)?;
+                            let (lc_r, lc_m) = lc.dim_split_at(-1)?;
+                            la_rest = la_r;
+                            lb_rest = broadcast_layout_to_first(&lc_r, &lb_r)?.1;
+                            lc_rest = lc_r;
+                            la_matmul = la_m.into_dim::<Ix2>()?;
+                            lb_matmul = lb_m.dim_insert(1)?.into_dim::<Ix2>()?;
+                            lc_matmul = lc_m.dim_insert(1)?.into_dim::<Ix2>()?;
+                        },
+                        (2, 3.., _) => {
+                            // rule 5: | `     M, K` | `..., K, N` | `..., M, N` |
+                            rstsr_assert_eq!(lb.ndim(), lc.ndim(), InvalidLayout)?;
+                            let (la_r, la_m) = la.dim_split_at(-2)?;
+                            let (lb_r, lb_m) = lb.dim_split_at(-2)?;
+                            let (lc_r, lc_m) = lc.dim_split_at(-2)?;
+                            la_rest = broadcast_layout_to_first(&lc_r, &la_r)?.1;
+                            lb_rest = lb_r;
+                            lc_rest = lc_r;
+                            la_matmul = la_m.into_dim::<Ix2>()?;
+                            lb_matmul = lb_m.into_dim::<Ix2>()?;
+                            lc_matmul = lc_m.into_dim::<Ix2>()?;
+                        },
+                        (3.., 2, _) => {
+                            // rule 6: | `..., M, K` | `     K, N` | `..., M, N` |
+                            rstsr_assert_eq!(la.ndim(), lc.ndim(), InvalidLayout)?;
+                            let (la_r, la_m) = la.dim_split_at(-2)?;
+                            let (lb_r, lb_m) = lb.dim_split_at(-2)?;
+                            let (lc_r, lc_m) = lc.dim_split_at(-2)?;
+                            la_rest = la_r;
+                            lb_rest = broadcast_layout_to_first(&lc_r, &lb_r)?.1;
+                            lc_rest = lc_r;
+                            la_matmul = la_m.into_dim::<Ix2>()?;
+                            lb_matmul = lb_m.into_dim::<Ix2>()?;
+                            lc_matmul = lc_m.into_dim::<Ix2>()?;
+                        },
+                        (3.., 3.., _) => {
+                            // rule 7: | `..., M, K` | `..., K, N` | `..., M, N` |
+                            rstsr_assert_eq!(la.ndim(), lc.ndim(), InvalidLayout)?;
+                            rstsr_assert_eq!(lb.ndim(), lc.ndim(), InvalidLayout)?;
+                            let (la_r, la_m) = la.dim_split_at(-2)?;
+                            let (lb_r, lb_m) = lb.dim_split_at(-2)?;
+                            let (lc_r, lc_m) = lc.dim_split_at(-2)?;
+                            la_rest = la_r;
+                            lb_rest = lb_r;
+                            lc_rest = lc_r;
+                            la_matmul = la_m.into_dim::<Ix2>()?;
+                            lb_matmul = lb_m.into_dim::<Ix2>()?;
+                            lc_matmul = lc_m.into_dim::<Ix2>()?;
+                        },
+                        _ => {
+                            return rstsr_raise!(
+                                UnImplemented,
+                                "Matmul of dimensions ({}, {}, {}) is not supported.",
+                                la.ndim(),
+                                lb.ndim(),
+                                lc.ndim()
+                            );
+                        },
+                    }
+                    // now, lx_rest should have the same shape, while lx_matmul
+                    // should be matmulable
+                    // only parallel matmul when lx_rest is small (larger than
+                    // 2*nthreads), otherwise parallel matmul anyway
+                    rstsr_assert_eq!(la_rest.shape(), lb_rest.shape(), InvalidLayout)?;
+                    rstsr_assert_eq!(lb_rest.shape(), lc_rest.shape(), InvalidLayout)?;
+                    let n_task = la_rest.size();
+                    let ita_rest = IterLayoutColMajor::new(&la_rest)?;
+                    let itb_rest = IterLayoutColMajor::new(&lb_rest)?;
+                    let c_chunks = match n_task > 2 * nthreads {
+                        true => split_batched_mut(c_slice.reborrow(), &lc_rest, &lc_matmul)?,
+                        false => None,
+                    };
+                    if let Some(c_chunks) = c_chunks {
+                        // parallel outer, sequential matmul
+                        // different tasks write to disjoint chunks of c
+                        let tasks = izip!(ita_rest, itb_rest, c_chunks).collect_vec();
+                        tasks.into_par_iter().try_for_each(
+                            |(ia_rest, ib_rest, (c_chunk, lc_m))| -> Result<()> {
+                                // prepare layout
+                                let mut la_m = la_matmul.clone();
+                                let mut lb_m = lb_matmul.clone();
+                                unsafe {
+                                    la_m.set_offset(ia_rest);
+                                    lb_m.set_offset(ib_rest);
+                                }
+                                // clone alpha and beta
+                                let alpha = alpha.clone();
+                                let beta = beta.clone();
+                                gemm_naive_rayon(
+                                    c_chunk, &lc_m, a_slice, &la_m, b_slice, &lb_m, alpha, beta,
+                                )
+                            },
+                        )?;
+                    } else {
+                        // sequential outer, parallel matmul
+                        let ita_rest = IterLayoutColMajor::new(&la_rest)?;
+                        let itb_rest = IterLayoutColMajor::new(&lb_rest)?;
+                        let itc_rest = IterLayoutColMajor::new(&lc_rest)?;
+                        for (ia_rest, ib_rest, ic_rest) in izip!(ita_rest, itb_rest, itc_rest) {
+                            // prepare layout
+                            let mut la_m = la_matmul.clone();
+                            let mut lb_m = lb_matmul.clone();
+                            let mut lc_m = lc_matmul.clone();
+                            unsafe {
+                                la_m.set_offset(ia_rest);
+                                lb_m.set_offset(ib_rest);
+                                lc_m.set_offset(ic_rest);
+                            }
+                            // clone alpha and beta
+                            let alpha = alpha.clone();
+                            let beta = beta.clone();
+                            gemm_naive_rayon(
+                                c_slice.reborrow(),
+                                &lc_m,
+                                a_slice,
+                                &la_m,
+                                b_slice,
+                                &lb_m,
+                                alpha,
+                                beta,
+                            )?;
+                        }
+                    }
+                },
+            }
+            return Ok(());
+        })?
+    }
+}
+
+/// Chunks of `c` and their matrix layouts, as given by [`split_batched_mut`].
+type BatchedChunks<'a, T> = Vec<(CpuRawSliceMut<'a, T>, Layout<Ix2>)>;
+
+/// Split `c` into disjoint chunks, one for each matrix of batched matmul.
+///
+/// Matrices are ordered as col-major iteration of `lc_rest`, and each is
+/// returned with its chunk and layout of `lc_matmul` rebased to that chunk.
+/// Returns `None` if the spans of matrices in `c` overlap (e.g. when batch
+/// axes have smaller strides than matrix axes), where chunks are not possible.
+pub(crate) fn split_batched_mut<'a, T>(
+    mut c: CpuRawSliceMut<'a, T>,
+    lc_rest: &Layout<IxD>,
+    lc_matmul: &Layout<Ix2>,
+) -> Result<Option<BatchedChunks<'a, T>>> {
+    // span of each matrix in c, sorted by start
+    let mut spans = vec![];
+    for (k, ic_rest) in IterLayoutColMajor::new(lc_rest)?.enumerate() {
+        let lc_m =
+            unsafe { Layout::new_unchecked(*lc_matmul.shape(), *lc_matmul.stride(), ic_rest) };
+        let (start, end) = lc_m.bounds_index()?;
+        rstsr_assert!(end <= c.len(), ValueOutOfRange)?;
+        spans.push((start, end, ic_rest, k));
+    }
+    spans.sort_unstable();
+    if spans.windows(2).any(|w| w[0].1 > w[1].0) {
+        return Ok(None);
+    }
+
+    let mut chunks = Vec::with_capacity(spans.len());
+    let ptr = c.as_mut_ptr();
+    for (start, end, ic_rest, k) in spans {
+        // spans are disjoint and in bounds of `c`, which is borrowed for `'a`
+        let chunk = unsafe { CpuRawSliceMut::from_raw_parts(ptr.add(start), end - start) };
+        let lc_m = unsafe {
+            Layout::new_unchecked(*lc_matmul.shape(), *lc_matmul.stride(), ic_rest - start)
+        };
+        chunks.push((k, chunk, lc_m));
+    }
+    chunks.sort_unstable_by_key(|(k, _, _)| *k);
+    return Ok(Some(chunks.into_iter().map(|(_, chunk, lc_m)| (chunk, lc_m)).collect()));
+}
+
+/* #region symm, syrk, herk */
+
+// These routines are not parallelized, since they are usually not the
+// bottleneck for types that faer or BLAS does not support.
+
+impl<TA, TB, TC> DeviceSYMMAPI<TA, TB, TC> for DeviceCpuRayon
+where
+    TA: Clone,
+    TB: Clone,
+    TC: Clone,
+    TA: Mul<TB, Output = TC>,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+{
+    fn symm(
+        &self,
+        c: &mut Storage<TC, Self>,
+        lc: &Layout<Ix2>,
+        a: &Storage<TA, Self>,
+        la: &Layout<Ix2>,
+        b: &Storage<TB, Self>,
+        lb: &Layout<Ix2>,
+        side: TensorSide,
+        uplo: TensorUpLo,
+        alpha: TC,
+        beta: TC,
+    ) -> Result<()> {
+        let vc = c.rawvec_mut().as_raw_slice_mut();
+        let va = a.rawvec().as_raw_slice();
+        let vb = b.rawvec().as_raw_slice();
+        symm_naive(vc, lc, va, la, vb, lb, side, uplo, alpha, beta)
+    }
+}
+
+impl<TA, TC> DeviceSYRKAPI<TA, TC> for DeviceCpuRayon
+where
+    TA: Clone,
+    TC: Clone,
+    TA: Mul<TA, Output = TC>,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero,
+{
+    fn syrk(
+        &self,
+        c: &mut Storage<TC, Self>,
+        lc: &Layout<Ix2>,
+        a: &Storage<TA, Self>,
+        la: &Layout<Ix2>,
+        uplo: TensorUpLo,
+        alpha: TC,
+        beta: TC,
+    ) -> Result<()> {
+        syrk_naive(
+            c.rawvec_mut().as_raw_slice_mut(),
+            lc,
+            a.rawvec().as_raw_slice(),
+            la,
+            uplo,
+            alpha,
+            beta,
+        )
+    }
+}
+
+impl<T> DeviceHERKAPI<T, T> for DeviceCpuRayon
+where
+    T: ComplexFloat,
+{
+    fn herk(
+        &self,
+        c: &mut Storage<T, Self>,
+        lc: &Layout<Ix2>,
+        a: &Storage<T, Self>,
+        la: &Layout<Ix2>,
+        uplo: TensorUpLo,
+        alpha: T,
+        beta: T,
+    ) -> Result<()> {
+        herk_naive(
+            c.rawvec_mut().as_raw_slice_mut(),
+            lc,
+            a.rawvec().as_raw_slice(),
+            la,
+            uplo,
+            alpha,
+            beta,
+        )
+    }
+}
+
+/* #endregion */
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_matmul() {
+        let device = DeviceCpuRayon::new(2);
+        // rule 7 with parallel outer (8 tasks) and sequential outer (2 tasks)
+        for nbatch in [8, 2] {
+            let a = Tensor::<i64, _, _>::arange_int(nbatch * 15, &device);
+            let a = a.into_shape_assume_contig([nbatch, 3, 5]).unwrap().into_dim::<IxD>().unwrap();
+            let b = Tensor::<i64, _, _>::arange_int(nbatch * 20, &device);
+            let b = b.into_shape_assume_contig([nbatch, 5, 4]).unwrap().into_dim::<IxD>().unwrap();
+            let a_cpu = a.view().into_device(&DeviceCpuSerial::default()).unwrap();
+            let b_cpu = b.view().into_device(&DeviceCpuSerial::default()).unwrap();
+            let c = &a % &b;
+            let c_ref = &a_cpu % &b_cpu;
+            let c = c.into_device(&DeviceCpuSerial::default()).unwrap();
+            assert_eq!(c.data().storage().rawvec(), c_ref.data().storage().rawvec());
+        }
+        // rule 1: vector inner dot
+        let a = Tensor::<i64, _, _>::arange_int(5, &device);
+        let c = &a % &a;
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), vec![30]);
+    }
+
+    #[test]
+    fn test_split_batched_mut() {
+        // batch axis of largest stride: disjoint chunks
+        let mut c = (0..24).collect_vec();
+        let lc_rest = Layout::new(vec![4], vec![6], 0);
+        let lc_matmul = Layout::new([2, 3], [3, 1], 0);
+        let chunks =
+            split_batched_mut(c.as_mut_slice().into(), &lc_rest, &lc_matmul).unwrap().unwrap();
+        assert_eq!(chunks.len(), 4);
+        for (k, (chunk, lc_m)) in chunks.iter().enumerate() {
+            assert_eq!(chunk[0], 6 * k);
+            assert_eq!(lc_m.offset(), 0);
+        }
+        // batch axis in reversed order
+        let lc_rest = Layout::new(vec![4], vec![-6], 18);
+        let chunks =
+            split_batched_mut(c.as_mut_slice().into(), &lc_rest, &lc_matmul).unwrap().unwrap();
+        assert_eq!(chunks[0].0[0], 18);
+        // batch axis of smallest stride: spans overlap
+        let lc_rest = Layout::new(vec![4], vec![1], 0);
+        let lc_matmul = Layout::new([2, 3], [12, 4], 0);
+        assert!(split_batched_mut(c.as_mut_slice().into(), &lc_rest, &lc_matmul)
+            .unwrap()
+            .is_none());
+    }
+}
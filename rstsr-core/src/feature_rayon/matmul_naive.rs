@@ -11,15 +11,14 @@ use rayon::prelude::*;
 
 #[allow(clippy::too_many_arguments)]
 pub fn gemm_naive_rayon<TA, TB, TC>(
-    c: &mut [TC],
+    c: CpuRawSliceMut<'_, TC>,
     lc: &Layout<Ix2>,
-    a: &[TA],
+    a: CpuRawSlice<'_, TA>,
     la: &Layout<Ix2>,
-    b: &[TB],
+    b: CpuRawSlice<'_, TB>,
     lb: &Layout<Ix2>,
     alpha: TC,
     beta: TC,
-    nthreads: usize,
 ) -> Result<()>
 where
     TA: Clone + Send + Sync + Mul<TB, Output = TC>,
@@ -35,33 +34,30 @@ where
     rstsr_assert_eq!(sc[1], sb[1], InvalidLayout)?;
     let (m, n, k) = (sc[0], sc[1], sa[1]);
 
-    let pool = DeviceCpuRayon::new(nthreads).get_pool(nthreads)?;
-    pool.install(|| {
-        (0..n).into_par_iter().for_each(|j| {
-            (0..m).into_par_iter().for_each(|i| unsafe {
-                let ptr_c = c.as_ptr().offset(lc.index_uncheck(&[i, j])) as *mut TC;
-                *ptr_c = (*ptr_c).clone() * beta.clone()
-                    + (0..k).fold(TC::zero(), |acc, p| {
-                        let val_a = a[la.index_uncheck(&[i, p]) as usize].clone();
-                        let val_b = b[lb.index_uncheck(&[p, j]) as usize].clone();
-                        acc + val_a * val_b
-                    }) * alpha.clone();
-            });
+    (0..n).into_par_iter().for_each(|j| {
+        (0..m).into_par_iter().for_each(|i| unsafe {
+            let ptr_c = c.as_ptr().offset(lc.index_uncheck(&[i, j])) as *mut TC;
+            // c is not referenced if beta is zero, since it may be uninitialized
+            let c_beta = if beta.is_zero() { TC::zero() } else { (*ptr_c).clone() * beta.clone() };
+            *ptr_c = c_beta
+                + (0..k).fold(TC::zero(), |acc, p| {
+                    let val_a = a[la.index_uncheck(&[i, p]) as usize].clone();
+                    let val_b = b[lb.index_uncheck(&[p, j]) as usize].clone();
+                    acc + val_a * val_b
+                }) * alpha.clone();
         });
     });
     return Ok(());
 }
 
-#[allow(clippy::too_many_arguments)]
 pub fn inner_dot_naive_rayon<TA, TB, TC>(
     c: &mut TC,
-    a: &[TA],
+    a: CpuRawSlice<'_, TA>,
     la: &Layout<Ix1>,
-    b: &[TB],
+    b: CpuRawSlice<'_, TB>,
     lb: &Layout<Ix1>,
     alpha: TC,
     beta: TC,
-    nthreads: usize,
 ) -> Result<()>
 where
     TA: Clone + Send + Sync + Mul<TB, Output = TC>,
@@ -74,21 +70,18 @@ where
     rstsr_assert_eq!(sa[0], sb[0], InvalidLayout)?;
     let n = sa[0];
 
-    let pool = DeviceCpuRayon::new(nthreads).get_pool(nthreads)?;
-
-    let c_innerdot = pool.install(|| {
-        (0..n)
-            .into_par_iter()
-            .fold(
-                || TC::zero(),
-                |acc, i| unsafe {
-                    acc + a[la.index_uncheck(&[i]) as usize].clone()
-                        * b[lb.index_uncheck(&[i]) as usize].clone()
-                },
-            )
-            .reduce_with(|a, b| a + b)
-            .unwrap_or(TC::zero())
-    });
-    *c = c_innerdot * alpha + c.clone() * beta;
+    let c_innerdot = (0..n)
+        .into_par_iter()
+        .fold(
+            || TC::zero(),
+            |acc, i| unsafe {
+                acc + a[la.index_uncheck(&[i]) as usize].clone()
+                    * b[lb.index_uncheck(&[i]) as usize].clone()
+            },
+        )
+        .reduce_with(|a, b| a + b)
+        .unwrap_or(TC::zero());
+    let c_beta = if beta.is_zero() { TC::zero() } else { c.clone() * beta };
+    *c = c_innerdot * alpha + c_beta;
     return Ok(());
 }
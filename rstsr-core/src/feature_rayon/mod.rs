@@ -2,14 +2,24 @@
 //!
 //! - Layout parallel iterator
 //! - Tensor parallel iterator
+//! - Parallel CPU device [`DeviceCpuRayon`]
+//!
+//! Parallel functions in this module run on the current rayon thread pool;
+//! devices call them inside their own pool by `install`.
 
 pub mod assignment;
+pub mod creation;
 pub mod device;
 pub mod layout_par_iter;
+pub mod matmul;
 pub mod matmul_naive;
+pub mod op_binary;
+pub mod op_ternary;
 pub mod op_with_func;
+pub mod tensor_par_iter;
 
 pub use assignment::*;
 pub use device::*;
 pub use layout_par_iter::*;
 pub use op_with_func::*;
+pub use tensor_par_iter::*;
@@ -0,0 +1,3 @@
+use crate::device_cpu_serial::operators::op_binary::impl_op_binary_cpu;
+
+impl_op_binary_cpu!(DeviceCpuRayon, Send, Sync);
@@ -0,0 +1,27 @@
+use crate::device_cpu_serial::operators::op_ternary::impl_op_ternary_cpu;
+
+impl_op_ternary_cpu!(DeviceCpuRayon, Send, Sync);
+
+#[cfg(test)]
+mod test {
+    use crate::prelude_dev::*;
+
+    #[test]
+    fn test_add() {
+        let device_serial = DeviceCpuSerial::default();
+        let device_rayon = DeviceCpuRayon::default();
+        let a1 = Tensor::<i64, _, _>::arange_int(1024 * 1024, &device_serial);
+        let a1 = a1.into_shape_assume_contig([1024, 1024]).unwrap();
+        let b1 = a1.reverse_axes();
+        let a2 = Tensor::<i64, _, _>::arange_int(1024 * 1024, &device_rayon);
+        let a2 = a2.into_shape_assume_contig([1024, 1024]).unwrap();
+        let b2 = a2.reverse_axes();
+
+        let c1 = (&a1 + &b1) ^ 3;
+        let c2 = (&a2 + &b2) ^ 3;
+        assert_eq!(
+            c1.data().storage().to_cpu_vec().unwrap(),
+            c2.data().storage().to_cpu_vec().unwrap()
+        );
+    }
+}
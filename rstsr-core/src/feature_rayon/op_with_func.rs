@@ -14,11 +14,11 @@ const PARALLEL_SWITCH: usize = 256;
 
 #[allow(clippy::too_many_arguments)]
 pub fn op_mutc_refa_refb_func_cpu_rayon<TA, TB, TC, D, F>(
-    c: &mut [TC],
+    c: CpuRawSliceMut<'_, TC>,
     lc: &Layout<D>,
-    a: &[TA],
+    a: CpuRawSlice<'_, TA>,
     la: &Layout<D>,
-    b: &[TB],
+    b: CpuRawSlice<'_, TB>,
     lb: &Layout<D>,
     f: &mut F,
     nthreads: usize,
@@ -42,18 +42,15 @@ where
     let (layouts_outer, size_contig) = translate_to_col_major_with_contig(&layouts_full_ref);
 
     // actual parallel iteration
-    let pool = DeviceCpuRayon::new(nthreads).get_pool(nthreads)?;
     if size_contig >= CONTIG_SWITCH {
         // parallel for outer iteration
         let iter_c = IterLayoutColMajor::new(&layouts_outer[0])?;
         let iter_a = IterLayoutColMajor::new(&layouts_outer[1])?;
         let iter_b = IterLayoutColMajor::new(&layouts_outer[2])?;
-        pool.install(|| {
-            (iter_c, iter_a, iter_b).into_par_iter().for_each(|(idx_c, idx_a, idx_b)| unsafe {
-                let c_ptr = c.as_ptr().add(idx_c) as *mut TC;
-                (0..size_contig).for_each(|idx| {
-                    f(&mut *c_ptr.add(idx), &a[idx_a + idx], &b[idx_b + idx]);
-                });
+        (iter_c, iter_a, iter_b).into_par_iter().for_each(|(idx_c, idx_a, idx_b)| unsafe {
+            let c_ptr = c.as_ptr().add(idx_c) as *mut TC;
+            (0..size_contig).for_each(|idx| {
+                f(&mut *c_ptr.add(idx), &a[idx_a + idx], &b[idx_b + idx]);
             });
         });
     } else {
@@ -61,20 +58,18 @@ where
         let iter_c = IterLayoutColMajor::new(&layouts_full[0])?;
         let iter_a = IterLayoutColMajor::new(&layouts_full[1])?;
         let iter_b = IterLayoutColMajor::new(&layouts_full[2])?;
-        pool.install(|| {
-            (iter_c, iter_a, iter_b).into_par_iter().for_each(|(idx_c, idx_a, idx_b)| unsafe {
-                let c_ptr = c.as_ptr() as *mut TC;
-                f(&mut *c_ptr.add(idx_c), &a[idx_a], &b[idx_b]);
-            });
+        (iter_c, iter_a, iter_b).into_par_iter().for_each(|(idx_c, idx_a, idx_b)| unsafe {
+            let c_ptr = c.as_ptr() as *mut TC;
+            f(&mut *c_ptr.add(idx_c), &a[idx_a], &b[idx_b]);
         });
     }
     return Ok(());
 }
 
 pub fn op_mutc_refa_numb_func_cpu_rayon<TA, TB, TC, D, F>(
-    c: &mut [TC],
+    c: CpuRawSliceMut<'_, TC>,
     lc: &Layout<D>,
-    a: &[TA],
+    a: CpuRawSlice<'_, TA>,
     la: &Layout<D>,
     b: TB,
     f: &mut F,
@@ -99,38 +94,33 @@ where
     let (layouts_outer, size_contig) = translate_to_col_major_with_contig(&layouts_full_ref);
 
     // actual parallel iteration
-    let pool = DeviceCpuRayon::new(nthreads).get_pool(nthreads)?;
     if size_contig >= CONTIG_SWITCH {
         // parallel for outer iteration
         let iter_c = IterLayoutColMajor::new(&layouts_outer[0])?;
         let iter_a = IterLayoutColMajor::new(&layouts_outer[1])?;
-        pool.install(|| {
-            (iter_c, iter_a).into_par_iter().for_each(|(idx_c, idx_a)| unsafe {
-                let c_ptr = c.as_ptr().add(idx_c) as *mut TC;
-                (0..size_contig).for_each(|idx| {
-                    f(&mut *c_ptr.add(idx), &a[idx_a + idx], &b);
-                });
+        (iter_c, iter_a).into_par_iter().for_each(|(idx_c, idx_a)| unsafe {
+            let c_ptr = c.as_ptr().add(idx_c) as *mut TC;
+            (0..size_contig).for_each(|idx| {
+                f(&mut *c_ptr.add(idx), &a[idx_a + idx], &b);
             });
         });
     } else {
         // not possible for contiguous assign
         let iter_c = IterLayoutColMajor::new(&layouts_full[0])?;
         let iter_a = IterLayoutColMajor::new(&layouts_full[1])?;
-        pool.install(|| {
-            (iter_c, iter_a).into_par_iter().for_each(|(idx_c, idx_a)| unsafe {
-                let c_ptr = c.as_ptr() as *mut TC;
-                f(&mut *c_ptr.add(idx_c), &a[idx_a], &b);
-            });
+        (iter_c, iter_a).into_par_iter().for_each(|(idx_c, idx_a)| unsafe {
+            let c_ptr = c.as_ptr() as *mut TC;
+            f(&mut *c_ptr.add(idx_c), &a[idx_a], &b);
         });
     }
     return Ok(());
 }
 
 pub fn op_mutc_numa_refb_func_cpu_rayon<TA, TB, TC, D, F>(
-    c: &mut [TC],
+    c: CpuRawSliceMut<'_, TC>,
     lc: &Layout<D>,
     a: TA,
-    b: &[TB],
+    b: CpuRawSlice<'_, TB>,
     lb: &Layout<D>,
     f: &mut F,
     nthreads: usize,
@@ -154,37 +144,32 @@ where
     let (layouts_outer, size_contig) = translate_to_col_major_with_contig(&layouts_full_ref);
 
     // actual parallel iteration
-    let pool = DeviceCpuRayon::new(nthreads).get_pool(nthreads)?;
     if size_contig >= CONTIG_SWITCH {
         // parallel for outer iteration
         let iter_c = IterLayoutColMajor::new(&layouts_outer[0])?;
         let iter_b = IterLayoutColMajor::new(&layouts_outer[1])?;
-        pool.install(|| {
-            (iter_c, iter_b).into_par_iter().for_each(|(idx_c, idx_b)| unsafe {
-                let c_ptr = c.as_ptr().add(idx_c) as *mut TC;
-                (0..size_contig).for_each(|idx| {
-                    f(&mut *c_ptr.add(idx), &a, &b[idx_b + idx]);
-                });
+        (iter_c, iter_b).into_par_iter().for_each(|(idx_c, idx_b)| unsafe {
+            let c_ptr = c.as_ptr().add(idx_c) as *mut TC;
+            (0..size_contig).for_each(|idx| {
+                f(&mut *c_ptr.add(idx), &a, &b[idx_b + idx]);
             });
         });
     } else {
         // not possible for contiguous assign
         let iter_c = IterLayoutColMajor::new(&layouts_full[0])?;
         let iter_b = IterLayoutColMajor::new(&layouts_full[1])?;
-        pool.install(|| {
-            (iter_c, iter_b).into_par_iter().for_each(|(idx_c, idx_b)| unsafe {
-                let c_ptr = c.as_ptr() as *mut TC;
-                f(&mut *c_ptr.add(idx_c), &a, &b[idx_b]);
-            });
+        (iter_c, iter_b).into_par_iter().for_each(|(idx_c, idx_b)| unsafe {
+            let c_ptr = c.as_ptr() as *mut TC;
+            f(&mut *c_ptr.add(idx_c), &a, &b[idx_b]);
         });
     }
     return Ok(());
 }
 
 pub fn op_muta_refb_func_cpu_rayon<TA, TB, D, F>(
-    a: &mut [TA],
+    a: CpuRawSliceMut<'_, TA>,
     la: &Layout<D>,
-    b: &[TB],
+    b: CpuRawSlice<'_, TB>,
     lb: &Layout<D>,
     f: &mut F,
     nthreads: usize,
@@ -207,35 +192,30 @@ where
     let (layouts_outer, size_contig) = translate_to_col_major_with_contig(&layouts_full_ref);
 
     // actual parallel iteration
-    let pool = DeviceCpuRayon::new(nthreads).get_pool(nthreads)?;
     if size_contig >= CONTIG_SWITCH {
         // parallel for outer iteration
         let iter_a = IterLayoutColMajor::new(&layouts_outer[0])?;
         let iter_b = IterLayoutColMajor::new(&layouts_outer[1])?;
-        pool.install(|| {
-            (iter_a, iter_b).into_par_iter().for_each(|(idx_a, idx_b)| unsafe {
-                let a_ptr = a.as_ptr().add(idx_a) as *mut TA;
-                (0..size_contig).for_each(|idx| {
-                    f(&mut *a_ptr.add(idx), &b[idx_b + idx]);
-                });
+        (iter_a, iter_b).into_par_iter().for_each(|(idx_a, idx_b)| unsafe {
+            let a_ptr = a.as_ptr().add(idx_a) as *mut TA;
+            (0..size_contig).for_each(|idx| {
+                f(&mut *a_ptr.add(idx), &b[idx_b + idx]);
             });
         });
     } else {
         // not possible for contiguous assign
         let iter_a = IterLayoutColMajor::new(&layouts_full[0])?;
         let iter_b = IterLayoutColMajor::new(&layouts_full[1])?;
-        pool.install(|| {
-            (iter_a, iter_b).into_par_iter().for_each(|(idx_a, idx_b)| unsafe {
-                let a_ptr = a.as_ptr() as *mut TA;
-                f(&mut *a_ptr.add(idx_a), &b[idx_b]);
-            });
+        (iter_a, iter_b).into_par_iter().for_each(|(idx_a, idx_b)| unsafe {
+            let a_ptr = a.as_ptr() as *mut TA;
+            f(&mut *a_ptr.add(idx_a), &b[idx_b]);
         });
     }
     return Ok(());
 }
 
 pub fn op_muta_numb_func_cpu_rayon<TA, TB, D, F>(
-    a: &mut [TA],
+    a: CpuRawSliceMut<'_, TA>,
     la: &Layout<D>,
     b: TB,
     f: &mut F,
@@ -258,33 +238,28 @@ where
     let (layout_contig, size_contig) = translate_to_col_major_with_contig(&[&layout]);
 
     // actual parallel iteration
-    let pool = DeviceCpuRayon::new(nthreads).get_pool(nthreads)?;
     if size_contig >= CONTIG_SWITCH {
         // parallel for outer iteration
         let iter_a = IterLayoutColMajor::new(&layout_contig[0])?;
-        pool.install(|| {
-            iter_a.into_par_iter().for_each(|idx_a| unsafe {
-                let a_ptr = a.as_ptr().add(idx_a) as *mut TA;
-                (0..size_contig).for_each(|idx| {
-                    f(&mut *a_ptr.add(idx), &b);
-                });
+        iter_a.into_par_iter().for_each(|idx_a| unsafe {
+            let a_ptr = a.as_ptr().add(idx_a) as *mut TA;
+            (0..size_contig).for_each(|idx| {
+                f(&mut *a_ptr.add(idx), &b);
             });
         });
     } else {
         // not possible for contiguous assign
         let iter_a = IterLayoutColMajor::new(&layout)?;
-        pool.install(|| {
-            iter_a.into_par_iter().for_each(|idx_a| unsafe {
-                let a_ptr = a.as_ptr() as *mut TA;
-                f(&mut *a_ptr.add(idx_a), &b);
-            });
+        iter_a.into_par_iter().for_each(|idx_a| unsafe {
+            let a_ptr = a.as_ptr() as *mut TA;
+            f(&mut *a_ptr.add(idx_a), &b);
         });
     }
     return Ok(());
 }
 
 pub fn op_muta_func_cpu_rayon<T, D, F>(
-    a: &mut [T],
+    a: CpuRawSliceMut<'_, T>,
     la: &Layout<D>,
     f: &mut F,
     nthreads: usize,
@@ -305,25 +280,258 @@ where
     let (layout_contig, size_contig) = translate_to_col_major_with_contig(&[&layout]);
 
     // actual parallel iteration
-    let pool = DeviceCpuRayon::new(nthreads).get_pool(nthreads)?;
     if size_contig >= CONTIG_SWITCH {
         let iter_a = IterLayoutColMajor::new(&layout_contig[0])?;
-        pool.install(|| {
-            iter_a.into_par_iter().for_each(|idx_a| unsafe {
-                let a_ptr = a.as_ptr().add(idx_a) as *mut T;
-                (0..size_contig).for_each(|idx| {
-                    f(&mut *a_ptr.add(idx));
-                });
+        iter_a.into_par_iter().for_each(|idx_a| unsafe {
+            let a_ptr = a.as_ptr().add(idx_a) as *mut T;
+            (0..size_contig).for_each(|idx| {
+                f(&mut *a_ptr.add(idx));
             });
         });
     } else {
         let iter_a = IterLayoutColMajor::new(&layout)?;
-        pool.install(|| {
-            iter_a.into_par_iter().for_each(|idx_a| unsafe {
-                let a_ptr = a.as_ptr() as *mut T;
-                f(&mut *a_ptr.add(idx_a));
-            });
+        iter_a.into_par_iter().for_each(|idx_a| unsafe {
+            let a_ptr = a.as_ptr() as *mut T;
+            f(&mut *a_ptr.add(idx_a));
         });
     }
     return Ok(());
 }
+
+pub fn sum_axes_cpu_rayon<T>(
+    mut c: CpuRawSliceMut<'_, T>,
+    lc: &Layout<IxD>,
+    a: CpuRawSlice<'_, T>,
+    la: &Layout<IxD>,
+    naxes: usize,
+    nthreads: usize,
+) -> Result<()>
+where
+    T: num::Zero + core::ops::Add<Output = T> + Clone + Send + Sync,
+{
+    // determine whether to use parallel iteration
+    let size = la.size();
+    if size < PARALLEL_SWITCH * nthreads || lc.size() < 2 {
+        return sum_axes_cpu_serial(c, lc, a, la, naxes);
+    }
+
+    rstsr_assert!(naxes <= la.ndim(), InvalidLayout)?;
+    let (la_outer, la_inner) = la.dim_split_at((la.ndim() - naxes) as isize)?;
+    rstsr_assert_eq!(lc.shape(), la_outer.shape(), InvalidLayout)?;
+
+    // parallel for outer iteration; each task writes to its own element of c
+    let c_ptr = c.as_mut_ptr() as usize;
+    let iter_c = IterLayoutColMajor::new(lc)?;
+    let iter_a = IterLayoutColMajor::new(&la_outer)?;
+    (iter_c, iter_a).into_par_iter().try_for_each(|(idx_c, idx_a)| -> Result<()> {
+        let la_sum = unsafe {
+            Layout::new_unchecked(la_inner.shape().clone(), la_inner.stride().clone(), idx_a)
+        };
+        let sum = sum_cpu_serial(a, &la_sum)?;
+        let c_ptr = c_ptr as *mut T;
+        unsafe { *c_ptr.add(idx_c) = sum };
+        return Ok(());
+    })
+}
+
+/* #endregion */
+
+/* #region impl op_func for DeviceCpuRayon */
+
+impl<TA, TB, TC, D, F> DeviceOp_MutC_RefA_RefB_API<TA, TB, TC, D, F> for DeviceCpuRayon
+where
+    TA: Clone + Send + Sync,
+    TB: Clone + Send + Sync,
+    TC: Clone + Send + Sync,
+    D: DimAPI,
+    F: Fn(&mut TC, &TA, &TB) + ?Sized + Send + Sync,
+{
+    fn op_mutc_refa_refb_func(
+        &self,
+        c: &mut Storage<TC, Self>,
+        lc: &Layout<D>,
+        a: &Storage<TA, Self>,
+        la: &Layout<D>,
+        b: &Storage<TB, Self>,
+        lb: &Layout<D>,
+        f: &mut F,
+    ) -> Result<()> {
+        let nthreads = self.get_num_threads();
+        self.install(|| {
+            op_mutc_refa_refb_func_cpu_rayon(
+                c.rawvec_mut().as_raw_slice_mut(),
+                lc,
+                a.rawvec().as_raw_slice(),
+                la,
+                b.rawvec().as_raw_slice(),
+                lb,
+                f,
+                nthreads,
+            )
+        })?
+    }
+}
+
+impl<TA, TB, TC, D, F> DeviceOp_MutC_RefA_NumB_API<TA, TB, TC, D, F> for DeviceCpuRayon
+where
+    TA: Clone + Send + Sync,
+    TB: Clone + Send + Sync,
+    TC: Clone + Send + Sync,
+    D: DimAPI,
+    F: Fn(&mut TC, &TA, &TB) + ?Sized + Send + Sync,
+{
+    fn op_mutc_refa_numb_func(
+        &self,
+        c: &mut Storage<TC, Self>,
+        lc: &Layout<D>,
+        a: &Storage<TA, Self>,
+        la: &Layout<D>,
+        b: TB,
+        f: &mut F,
+    ) -> Result<()> {
+        let nthreads = self.get_num_threads();
+        self.install(|| {
+            op_mutc_refa_numb_func_cpu_rayon(
+                c.rawvec_mut().as_raw_slice_mut(),
+                lc,
+                a.rawvec().as_raw_slice(),
+                la,
+                b,
+                f,
+                nthreads,
+            )
+        })?
+    }
+}
+
+impl<TA, TB, TC, D, F> DeviceOp_MutC_NumA_RefB_API<TA, TB, TC, D, F> for DeviceCpuRayon
+where
+    TA: Clone + Send + Sync,
+    TB: Clone + Send + Sync,
+    TC: Clone + Send + Sync,
+    D: DimAPI,
+    F: Fn(&mut TC, &TA, &TB) + ?Sized + Send + Sync,
+{
+    fn op_mutc_numa_refb_func(
+        &self,
+        c: &mut Storage<TC, Self>,
+        lc: &Layout<D>,
+        a: TA,
+        b: &Storage<TB, Self>,
+        lb: &Layout<D>,
+        f: &mut F,
+    ) -> Result<()> {
+        let nthreads = self.get_num_threads();
+        self.install(|| {
+            op_mutc_numa_refb_func_cpu_rayon(
+                c.rawvec_mut().as_raw_slice_mut(),
+                lc,
+                a,
+                b.rawvec().as_raw_slice(),
+                lb,
+                f,
+                nthreads,
+            )
+        })?
+    }
+}
+
+impl<TA, TB, D, F> DeviceOp_MutA_RefB_API<TA, TB, D, F> for DeviceCpuRayon
+where
+    TA: Clone + Send + Sync,
+    TB: Clone + Send + Sync,
+    D: DimAPI,
+    F: Fn(&mut TA, &TB) + ?Sized + Send + Sync,
+{
+    fn op_muta_refb_func(
+        &self,
+        a: &mut Storage<TA, Self>,
+        la: &Layout<D>,
+        b: &Storage<TB, Self>,
+        lb: &Layout<D>,
+        f: &mut F,
+    ) -> Result<()> {
+        let nthreads = self.get_num_threads();
+        self.install(|| {
+            op_muta_refb_func_cpu_rayon(
+                a.rawvec_mut().as_raw_slice_mut(),
+                la,
+                b.rawvec().as_raw_slice(),
+                lb,
+                f,
+                nthreads,
+            )
+        })?
+    }
+}
+
+impl<TA, TB, D, F> DeviceOp_MutA_NumB_API<TA, TB, D, F> for DeviceCpuRayon
+where
+    TA: Clone + Send + Sync,
+    TB: Clone + Send + Sync,
+    D: DimAPI,
+    F: Fn(&mut TA, &TB) + ?Sized + Send + Sync,
+{
+    fn op_muta_numb_func(
+        &self,
+        a: &mut Storage<TA, Self>,
+        la: &Layout<D>,
+        b: TB,
+        f: &mut F,
+    ) -> Result<()> {
+        let nthreads = self.get_num_threads();
+        self.install(|| {
+            op_muta_numb_func_cpu_rayon(a.rawvec_mut().as_raw_slice_mut(), la, b, f, nthreads)
+        })?
+    }
+}
+
+impl<T, D, F> DeviceOp_MutA_API<T, D, F> for DeviceCpuRayon
+where
+    T: Clone + Send + Sync,
+    D: DimAPI,
+    F: Fn(&mut T) + ?Sized + Send + Sync,
+{
+    fn op_muta_func(&self, a: &mut Storage<T, Self>, la: &Layout<D>, f: &mut F) -> Result<()> {
+        let nthreads = self.get_num_threads();
+        self.install(|| op_muta_func_cpu_rayon(a.rawvec_mut().as_raw_slice_mut(), la, f, nthreads))?
+    }
+}
+
+/* #endregion */
+
+impl<T, D> OpSumAPI<T, D> for DeviceCpuRayon
+where
+    T: num::Zero + core::ops::Add<Output = T> + Clone,
+    D: DimAPI,
+{
+    fn sum(&self, a: &Storage<T, Self>, la: &Layout<D>) -> Result<T> {
+        sum_cpu_serial(a.rawvec().as_raw_slice(), la)
+    }
+}
+
+impl<T> OpSumAxesAPI<T> for DeviceCpuRayon
+where
+    T: num::Zero + core::ops::Add<Output = T> + Clone + Send + Sync,
+{
+    fn sum_axes(
+        &self,
+        c: &mut Storage<T, Self>,
+        lc: &Layout<IxD>,
+        a: &Storage<T, Self>,
+        la: &Layout<IxD>,
+        naxes: usize,
+    ) -> Result<()> {
+        let nthreads = self.get_num_threads();
+        self.install(|| {
+            sum_axes_cpu_rayon(
+                c.rawvec_mut().as_raw_slice_mut(),
+                lc,
+                a.rawvec().as_raw_slice(),
+                la,
+                naxes,
+                nthreads,
+            )
+        })?
+    }
+}
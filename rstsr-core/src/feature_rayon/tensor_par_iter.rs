@@ -0,0 +1,262 @@
+//! Tensor parallel iterator
+
+use crate::prelude_dev::*;
+use rayon::{
+    iter::plumbing::{bridge, Consumer, Producer, ProducerCallback, UnindexedConsumer},
+    prelude::*,
+};
+
+/* #region parallel iterator */
+
+/// Parallel iterator of tensor, which splits serial iterator `I` by
+/// [`IterSplitAtAPI::split_at`].
+pub struct ParIterTensor<I> {
+    pub iter: I,
+}
+
+impl<I> Producer for ParIterTensor<I>
+where
+    I: IterSplitAtAPI + Send,
+    I::Item: Send,
+{
+    type Item = I::Item;
+    type IntoIter = I;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let (lhs, rhs) = self.iter.split_at(index);
+        return (ParIterTensor { iter: lhs }, ParIterTensor { iter: rhs });
+    }
+}
+
+impl<I> ParallelIterator for ParIterTensor<I>
+where
+    I: IterSplitAtAPI + Send,
+    I::Item: Send,
+{
+    type Item = I::Item;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+impl<I> IndexedParallelIterator for ParIterTensor<I>
+where
+    I: IterSplitAtAPI + Send,
+    I::Item: Send,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+        callback.callback(self)
+    }
+}
+
+/* #endregion */
+
+/* #region tensor methods */
+
+impl<R, T, D, B> TensorBase<R, D>
+where
+    R: DataAPI<Data = Storage<T, B>>,
+    D: DimAPI,
+    B: DeviceAPI<T>,
+{
+    /// Parallel iterator of element references in `order`.
+    ///
+    /// # See also
+    ///
+    /// [`TensorBase::iter_with_order`]
+    pub fn par_iter_with_order<'a>(
+        &'a self,
+        order: TensorIterOrder,
+    ) -> Result<ParIterTensor<IterTensor<'a, T, D>>>
+    where
+        B: 'a,
+        B::RawVec: CpuRawVecAPI<T>,
+    {
+        self.iter_with_order(order).map(|iter| ParIterTensor { iter })
+    }
+
+    /// Parallel iterator of element references.
+    ///
+    /// # See also
+    ///
+    /// [`TensorBase::iter`]
+    pub fn par_iter<'a>(&'a self) -> ParIterTensor<IterTensor<'a, T, D>>
+    where
+        B: 'a,
+        B::RawVec: CpuRawVecAPI<T>,
+    {
+        ParIterTensor { iter: self.iter() }
+    }
+
+    /// Parallel iterator of multi-indices and element references in `order`.
+    ///
+    /// # See also
+    ///
+    /// [`TensorBase::indexed_iter_with_order`]
+    pub fn par_indexed_iter_with_order<'a>(
+        &'a self,
+        order: TensorIterOrder,
+    ) -> Result<ParIterTensor<IndexedIterTensor<'a, T, D>>>
+    where
+        B: 'a,
+        B::RawVec: CpuRawVecAPI<T>,
+    {
+        self.indexed_iter_with_order(order).map(|iter| ParIterTensor { iter })
+    }
+
+    /// Parallel iterator of multi-indices and element references.
+    ///
+    /// # See also
+    ///
+    /// [`TensorBase::indexed_iter`]
+    pub fn par_indexed_iter<'a>(&'a self) -> ParIterTensor<IndexedIterTensor<'a, T, D>>
+    where
+        B: 'a,
+        B::RawVec: CpuRawVecAPI<T>,
+    {
+        ParIterTensor { iter: self.indexed_iter() }
+    }
+
+    /// Parallel iterator of sub-views by selecting each index of `axis`.
+    ///
+    /// # See also
+    ///
+    /// [`TensorBase::axis_iter`]
+    pub fn par_axis_iter<I>(
+        &self,
+        axis: I,
+    ) -> Result<ParIterTensor<IterAxisView<'_, T, D::SmallerOne, B>>>
+    where
+        D: DimSmallerOneAPI,
+        D::SmallerOne: DimAPI,
+        IxD: DimConvertAPI<D::SmallerOne>,
+        I: TryInto<isize>,
+    {
+        self.axis_iter(axis).map(|iter| ParIterTensor { iter })
+    }
+}
+
+impl<R, T, D, B> TensorBase<R, D>
+where
+    R: DataMutAPI<Data = Storage<T, B>>,
+    D: DimAPI,
+    B: DeviceAPI<T>,
+{
+    /// Parallel iterator of mutable element references in `order`.
+    ///
+    /// # See also
+    ///
+    /// [`TensorBase::iter_mut_with_order`]
+    pub fn par_iter_mut_with_order<'a>(
+        &'a mut self,
+        order: TensorIterOrder,
+    ) -> Result<ParIterTensor<IterMutTensor<'a, T, D>>>
+    where
+        B: 'a,
+        B::RawVec: CpuRawVecAPI<T>,
+    {
+        self.iter_mut_with_order(order).map(|iter| ParIterTensor { iter })
+    }
+
+    /// Parallel iterator of mutable element references.
+    ///
+    /// # See also
+    ///
+    /// [`TensorBase::iter_mut`]
+    ///
+    /// # Panics
+    ///
+    /// - Elements of tensor overlap in memory (such as broadcasted tensor).
+    pub fn par_iter_mut<'a>(&'a mut self) -> ParIterTensor<IterMutTensor<'a, T, D>>
+    where
+        B: 'a,
+        B::RawVec: CpuRawVecAPI<T>,
+    {
+        ParIterTensor { iter: self.iter_mut() }
+    }
+
+    /// Parallel iterator of mutable sub-views by selecting each index of
+    /// `axis`.
+    ///
+    /// Sub-views borrow disjoint (but possibly interleaved) elements of
+    /// buffer, so they can be written from different threads.
+    ///
+    /// # See also
+    ///
+    /// [`TensorBase::axis_iter_mut`]
+    pub fn par_axis_iter_mut<I>(
+        &mut self,
+        axis: I,
+    ) -> Result<ParIterTensor<IterAxisViewMut<'_, T, D::SmallerOne, B>>>
+    where
+        T: Clone,
+        B: DeviceAPI<T, RawVec = CpuRawVec<T>>,
+        D: DimSmallerOneAPI,
+        D::SmallerOne: DimAPI,
+        IxD: DimConvertAPI<D::SmallerOne>,
+        I: TryInto<isize>,
+    {
+        self.axis_iter_mut(axis).map(|iter| ParIterTensor { iter })
+    }
+}
+
+/* #endregion */
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::s;
+
+    #[test]
+    fn test_par_iter() {
+        let a = Tensor::from_shape_vec([64, 48], (0..3072).collect::<Vec<usize>>()).unwrap();
+        let b = a.slice(s![slice!(None, None, -2), 3..]).unwrap();
+        for order in [TensorIterOrder::C, TensorIterOrder::F, TensorIterOrder::K] {
+            let ser = b.iter_with_order(order).unwrap().copied().collect_vec();
+            let par = b.par_iter_with_order(order).unwrap().copied().collect::<Vec<_>>();
+            assert_eq!(ser, par);
+            let ser = b.indexed_iter_with_order(order).unwrap().collect_vec();
+            let par = b.par_indexed_iter_with_order(order).unwrap().collect::<Vec<_>>();
+            assert_eq!(ser, par);
+        }
+
+        let mut c = Tensor::from_shape_vec([64, 48], vec![0; 3072]).unwrap();
+        c.par_iter_mut().enumerate().for_each(|(i, x)| *x = 2 * i);
+        assert!(c.iter().zip(a.iter()).all(|(&x, &y)| x == 2 * y));
+
+        let sums = a.par_axis_iter(1).unwrap().map(|v| v.iter().sum::<usize>()).collect::<Vec<_>>();
+        assert_eq!(sums, a.axis_iter(1).unwrap().map(|v| v.iter().sum::<usize>()).collect_vec());
+
+        // fill independent slabs of output
+        let mut d = Tensor::from_shape_vec([8, 5, 6], vec![0; 240]).unwrap();
+        d.par_axis_iter_mut(0).unwrap().enumerate().for_each(|(i, mut v)| {
+            v.iter_mut().for_each(|x| *x = i);
+        });
+        assert!(d.indexed_iter().all(|(idx, &x)| x == idx[0]));
+        // sub-views interleave in memory
+        d.par_axis_iter_mut(2).unwrap().enumerate().for_each(|(i, mut v)| {
+            v.iter_mut().for_each(|x| *x += 10 * i);
+        });
+        assert!(d.indexed_iter().all(|(idx, &x)| x == idx[0] + 10 * idx[2]));
+    }
+}
@@ -0,0 +1,104 @@
+//! Serialization of [`Layout`].
+
+use crate::prelude_dev::*;
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize)]
+#[serde(rename = "Layout")]
+struct LayoutRef<'a> {
+    shape: &'a [usize],
+    stride: &'a [isize],
+    offset: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Layout")]
+struct LayoutRepr {
+    shape: Vec<usize>,
+    stride: Vec<isize>,
+    offset: usize,
+}
+
+impl LayoutRepr {
+    fn into_layout<D>(self) -> Result<Layout<D>>
+    where
+        D: DimAPI,
+    {
+        let LayoutRepr { shape, stride, offset } = self;
+        rstsr_assert_eq!(shape.len(), stride.len(), InvalidLayout)?;
+        let ndim = shape.len();
+        let (Ok(shape), Ok(stride)) = (D::try_from(shape), D::Stride::try_from(stride)) else {
+            return rstsr_raise!(InvalidLayout, "Number of dimensions {ndim} does not match.");
+        };
+        let layout = unsafe { Layout::new_unchecked(shape, stride, offset) };
+        layout.check_strides()?;
+        layout.bounds_index()?;
+        return Ok(layout);
+    }
+}
+
+impl<D> Serialize for Layout<D>
+where
+    D: DimAPI,
+{
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let repr = LayoutRef {
+            shape: self.shape().as_ref(),
+            stride: self.stride().as_ref(),
+            offset: self.offset(),
+        };
+        repr.serialize(serializer)
+    }
+}
+
+impl<'de, D> Deserialize<'de> for Layout<D>
+where
+    D: DimAPI,
+{
+    fn deserialize<De>(deserializer: De) -> core::result::Result<Self, De::Error>
+    where
+        De: Deserializer<'de>,
+    {
+        let repr = LayoutRepr::deserialize(deserializer)?;
+        repr.into_layout().map_err(De::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_serde_layout() {
+        let layout = Layout::new([3, 4, 5], [-20, 1, 4], 40);
+        let json = serde_json::to_string(&layout).unwrap();
+        assert_eq!(json, r#"{"shape":[3,4,5],"stride":[-20,1,4],"offset":40}"#);
+        let layout1: Layout<Ix3> = serde_json::from_str(&json).unwrap();
+        assert_eq!(layout1.shape(), layout.shape());
+        assert_eq!(layout1.stride(), layout.stride());
+        assert_eq!(layout1.offset(), layout.offset());
+        assert_eq!(layout1.size(), 60);
+
+        // dynamic dimension
+        let layout1: Layout<IxD> = serde_json::from_str(&json).unwrap();
+        assert_eq!(layout1.shape(), &vec![3, 4, 5]);
+        let bytes = bincode::serialize(&layout1).unwrap();
+        let layout2: Layout<IxD> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(layout2.stride(), &vec![-20, 1, 4]);
+
+        // mismatched number of dimensions
+        assert!(serde_json::from_str::<Layout<Ix2>>(&json).is_err());
+        let json = r#"{"shape":[3,4],"stride":[1],"offset":0}"#;
+        assert!(serde_json::from_str::<Layout<IxD>>(json).is_err());
+        // overlapping strides
+        let json = r#"{"shape":[3,4],"stride":[1,1],"offset":0}"#;
+        assert!(serde_json::from_str::<Layout<Ix2>>(json).is_err());
+        // negative index
+        let json = r#"{"shape":[3,4],"stride":[-4,1],"offset":0}"#;
+        assert!(serde_json::from_str::<Layout<Ix2>>(json).is_err());
+    }
+}
@@ -0,0 +1,16 @@
+//! Serialization of layouts and tensors by [`serde`].
+//!
+//! - [`Layout<D>`](crate::Layout) is serialized as shape, stride and offset;
+//!   deserialized layout is validated by
+//!   [`Layout::check_strides`](crate::Layout::check_strides) and
+//!   [`Layout::bounds_index`](crate::Layout::bounds_index).
+//! - [`TensorBase`](crate::TensorBase) is serialized as shape, order and flat
+//!   data in that order; it is deserialized into an owned [`Tensor<T, D,
+//!   B>`](crate::Tensor), whose device is re-created by [`Default`].
+//!
+//! [`TensorOrder`](crate::flags::TensorOrder),
+//! [`Indexer`](crate::layout::Indexer) and [`Slice`](crate::layout::Slice)
+//! derive serde traits directly.
+
+pub mod layout;
+pub mod tensor;
@@ -0,0 +1,144 @@
+//! Serialization of tensors.
+
+extern crate alloc;
+
+use crate::prelude_dev::*;
+use alloc::borrow::Cow;
+use serde::de::Error as DeError;
+use serde::ser::Error as SerError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize)]
+#[serde(rename = "Tensor")]
+struct TensorRef<'a, T>
+where
+    T: Clone,
+{
+    shape: &'a [usize],
+    order: TensorOrder,
+    data: Cow<'a, [T]>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Tensor")]
+struct TensorRepr<T> {
+    shape: Vec<usize>,
+    order: TensorOrder,
+    data: Vec<T>,
+}
+
+impl<T> TensorRepr<T> {
+    fn into_tensor<D, B>(self) -> Result<Tensor<T, D, B>>
+    where
+        D: DimAPI,
+        B: DeviceAPI<T> + DeviceCreationAnyAPI<T> + Default,
+    {
+        let TensorRepr { shape, order, data } = self;
+        let ndim = shape.len();
+        let Ok(shape) = D::try_from(shape) else {
+            return rstsr_raise!(InvalidLayout, "Number of dimensions {ndim} does not match.");
+        };
+        let layout = match order {
+            TensorOrder::C => shape.c(),
+            TensorOrder::F => shape.f(),
+        };
+        rstsr_assert_eq!(data.len(), layout.size(), InvalidLayout)?;
+        let storage = B::default().outof_cpu_vec(data)?;
+        return Tensor::new(storage.into(), layout);
+    }
+}
+
+/// Tensor is serialized as shape, order and flat data.
+///
+/// Order is [`TensorOrder::F`] for f-contiguous (but not c-contiguous)
+/// tensors, and [`TensorOrder::C`] otherwise. Data of contiguous tensors is
+/// not copied.
+impl<R, T, D, B> Serialize for TensorBase<R, D>
+where
+    R: DataAPI<Data = Storage<T, B>>,
+    T: Clone + Serialize,
+    D: DimAPI,
+    B: DeviceAPI<T>,
+    B::RawVec: CpuRawVecAPI<T>,
+{
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let layout = self.layout();
+        let (offset, size) = (layout.offset(), layout.size());
+        let order = match layout.f_contig() && !layout.c_contig() {
+            true => TensorOrder::F,
+            false => TensorOrder::C,
+        };
+        let data = match layout.c_contig() || layout.f_contig() {
+            true => Cow::Borrowed(
+                self.storage().rawvec().as_raw_slice().get_range(offset..offset + size),
+            ),
+            false => {
+                let iter = self.iter_with_order(TensorIterOrder::C).map_err(S::Error::custom)?;
+                Cow::Owned(iter.cloned().collect_vec())
+            },
+        };
+        let repr = TensorRef { shape: layout.shape().as_ref(), order, data };
+        repr.serialize(serializer)
+    }
+}
+
+/// Tensor is deserialized from shape, order and flat data; device is created
+/// by [`Default`].
+impl<'de, T, D, B> Deserialize<'de> for Tensor<T, D, B>
+where
+    T: Deserialize<'de>,
+    D: DimAPI,
+    B: DeviceAPI<T> + DeviceCreationAnyAPI<T> + Default,
+{
+    fn deserialize<De>(deserializer: De) -> core::result::Result<Self, De::Error>
+    where
+        De: Deserializer<'de>,
+    {
+        let repr = TensorRepr::<T>::deserialize(deserializer)?;
+        repr.into_tensor().map_err(De::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::s;
+
+    #[test]
+    fn test_serde_tensor() {
+        let a = Tensor::from_shape_vec([2, 3], vec![0., 1., 2., 3., 4., 5.]).unwrap();
+        let json = serde_json::to_string(&a).unwrap();
+        assert_eq!(json, r#"{"shape":[2,3],"order":"C","data":[0.0,1.0,2.0,3.0,4.0,5.0]}"#);
+        let a1: Tensor<f64, Ix2> = serde_json::from_str(&json).unwrap();
+        assert_eq!(a1.shape(), &[2, 3]);
+        assert_eq!(a1.iter().collect_vec(), a.iter().collect_vec());
+
+        // f-contiguous view keeps its order
+        let at = a.reverse_axes();
+        let json = serde_json::to_string(&at).unwrap();
+        assert_eq!(json, r#"{"shape":[3,2],"order":"F","data":[0.0,1.0,2.0,3.0,4.0,5.0]}"#);
+        let at1: Tensor<f64, IxD> = serde_json::from_str(&json).unwrap();
+        assert!(at1.layout().f_contig());
+        assert_eq!(at1.iter().collect_vec(), at.iter().collect_vec());
+
+        // non-contiguous view is flattened in row-major
+        let b = a.slice(s![.., slice!(None, None, -1)]).unwrap();
+        let bytes = bincode::serialize(&b).unwrap();
+        let b1: Tensor<f64, Ix2> = bincode::deserialize(&bytes).unwrap();
+        assert!(b1.layout().c_contig());
+        assert_eq!(b1.iter().collect_vec(), b.iter().collect_vec());
+
+        // other devices
+        let c: Tensor<f64, Ix2, DeviceCpuCounting> = serde_json::from_str(&json).unwrap();
+        assert_eq!(c.shape(), &[3, 2]);
+
+        // invalid data
+        let json = r#"{"shape":[3,2],"order":"C","data":[0.0,1.0,2.0]}"#;
+        assert!(serde_json::from_str::<Tensor<f64, Ix2>>(json).is_err());
+        let json = r#"{"shape":[3,2],"order":"C","data":[0.0,1.0,2.0,3.0,4.0,5.0]}"#;
+        assert!(serde_json::from_str::<Tensor<f64, Ix3>>(json).is_err());
+    }
+}
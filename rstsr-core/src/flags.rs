@@ -50,6 +50,7 @@ macro_rules! impl_changeable_default {
 ///
 /// You may change default value by [`TensorOrder::change_default`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TensorOrder {
     /// row-major order.
     C,
@@ -137,6 +138,7 @@ pub mod TensorCopyPolicy {
 
 /* #region blas-flags */
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TensorTranspose {
     /// No transpose
     N,
@@ -146,6 +148,7 @@ pub enum TensorTranspose {
     C,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TensorSide {
     /// Left side
     L,
@@ -153,6 +156,7 @@ pub enum TensorSide {
     R,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TensorUpLo {
     /// Upper triangle
     U,
@@ -160,6 +164,7 @@ pub enum TensorUpLo {
     L,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TensorDiag {
     /// Non-unit diagonal
     N,
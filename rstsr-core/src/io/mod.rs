@@ -0,0 +1,22 @@
+//! Reading and writing tensors in NumPy formats.
+//!
+//! - `.npy` files of single array: [`read_npy`], [`write_npy`], and their file
+//!   path counterparts [`load_npy`], [`save_npy`];
+//! - `.npz` archives of named arrays: [`NpzReader`], [`NpzWriter`];
+//! - safetensors files of named tensors (feature `safetensors`):
+//!   `SafeTensorsFile`, `SafeTensorsWriter`.
+//!
+//! Arrays are read into any device implementing
+//! [`DeviceCreationAnyAPI`](crate::prelude_dev::DeviceCreationAnyAPI), and
+//! written from tensors on CPU devices. Element types are given by
+//! [`NpyElement`].
+
+pub mod npy;
+pub mod npz;
+#[cfg(feature = "safetensors")]
+pub mod safetensors;
+
+#[cfg(feature = "safetensors")]
+pub use self::safetensors::*;
+pub use npy::*;
+pub use npz::*;
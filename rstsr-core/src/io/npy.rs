@@ -0,0 +1,443 @@
+//! Reading and writing NumPy `.npy` files.
+
+extern crate std;
+
+use crate::prelude_dev::*;
+use half::f16;
+use num::complex::Complex;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write as IoWrite};
+use std::path::Path;
+
+/* #region element types */
+
+/// Element types that can be stored in `.npy` files.
+///
+/// # Safety
+///
+/// Implementor must be plain old data: no padding, no pointers, and any bit
+/// pattern is a valid value. Its memory representation must be the one
+/// described by [`DESCR`](Self::DESCR) in native byte order.
+pub unsafe trait NpyElement: Clone + 'static {
+    /// NumPy type string without byte order character (such as `"f8"`).
+    const DESCR: &'static str;
+
+    /// Size of each number (component of complex) in bytes, as unit of byte
+    /// swapping.
+    const UNIT_SIZE: usize;
+}
+
+macro_rules! impl_npy_element {
+    ($($t:ty => $descr:literal, $unit:literal),*) => {
+        $(unsafe impl NpyElement for $t {
+            const DESCR: &'static str = $descr;
+            const UNIT_SIZE: usize = $unit;
+        })*
+    };
+}
+
+impl_npy_element!(
+    i8 => "i1", 1, i16 => "i2", 2, i32 => "i4", 4, i64 => "i8", 8,
+    u8 => "u1", 1, u16 => "u2", 2, u32 => "u4", 4, u64 => "u8", 8,
+    f16 => "f2", 2, f32 => "f4", 4, f64 => "f8", 8,
+    Complex<f32> => "c8", 4, Complex<f64> => "c16", 8
+);
+
+const NATIVE_ORDER: char = if cfg!(target_endian = "little") { '<' } else { '>' };
+
+/* #endregion */
+
+/* #region header */
+
+/// Header of `.npy` file.
+///
+/// Only simple (non-structured) dtypes are supported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NpyHeader {
+    /// NumPy type string, with byte order character (such as `"<f8"`).
+    pub descr: String,
+    pub fortran_order: bool,
+    pub shape: Vec<usize>,
+}
+
+const MAGIC: &[u8; 6] = b"\x93NUMPY";
+const HEADER_ALIGNMENT: usize = 64;
+
+impl NpyHeader {
+    /// Header for elements of `T` in native byte order.
+    pub fn new<T>(shape: Vec<usize>, fortran_order: bool) -> Self
+    where
+        T: NpyElement,
+    {
+        let order = if T::UNIT_SIZE == 1 { '|' } else { NATIVE_ORDER };
+        let descr = format!("{order}{}", T::DESCR);
+        NpyHeader { descr, fortran_order, shape }
+    }
+
+    /// Layout of data (c- or f-contiguous).
+    pub fn layout(&self) -> Layout<IxD> {
+        match self.fortran_order {
+            true => self.shape.f(),
+            false => self.shape.c(),
+        }
+    }
+
+    /// Whether data is of element type `T`, and whether bytes of each number
+    /// should be swapped to get native byte order.
+    ///
+    /// Error is given when element type does not match.
+    pub fn check_dtype<T>(&self) -> Result<bool>
+    where
+        T: NpyElement,
+    {
+        let (order, descr) = self.descr.split_at(1.min(self.descr.len()));
+        rstsr_assert_eq!(
+            descr,
+            T::DESCR,
+            InvalidValue,
+            "Element type does not match dtype {} of file.",
+            self.descr
+        )?;
+        let swap = match (order, T::UNIT_SIZE) {
+            (_, 1) | ("|", _) | ("=", _) => false,
+            ("<", _) | (">", _) => !order.starts_with(NATIVE_ORDER),
+            _ => rstsr_raise!(InvalidValue, "Unknown byte order of dtype {}.", self.descr)?,
+        };
+        return Ok(swap);
+    }
+
+    /// Header bytes (including magic and version), padded so that data is
+    /// aligned to 64 bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let shape = match self.shape.len() {
+            1 => format!("({},)", self.shape[0]),
+            _ => format!("({})", self.shape.iter().join(", ")),
+        };
+        let fortran_order = if self.fortran_order { "True" } else { "False" };
+        let mut dict = format!(
+            "{{'descr': '{}', 'fortran_order': {fortran_order}, 'shape': {shape}, }}",
+            self.descr
+        );
+
+        // version 1.0 stores header length by u16, version 2.0 by u32
+        let prefix_len = if dict.len() + 11 + HEADER_ALIGNMENT < 65536 { 10 } else { 12 };
+        let total_len = (prefix_len + dict.len() + 1).div_ceil(HEADER_ALIGNMENT) * HEADER_ALIGNMENT;
+        dict.extend(core::iter::repeat_n(' ', total_len - prefix_len - dict.len() - 1));
+        dict.push('\n');
+
+        let mut bytes = Vec::with_capacity(total_len);
+        bytes.extend_from_slice(MAGIC);
+        if prefix_len == 10 {
+            bytes.extend_from_slice(&[1, 0]);
+            bytes.extend_from_slice(&(dict.len() as u16).to_le_bytes());
+        } else {
+            bytes.extend_from_slice(&[2, 0]);
+            bytes.extend_from_slice(&(dict.len() as u32).to_le_bytes());
+        }
+        bytes.extend_from_slice(dict.as_bytes());
+        return bytes;
+    }
+
+    /// Read and parse header from beginning of `.npy` content.
+    pub fn read(reader: &mut impl Read) -> Result<Self> {
+        let mut prefix = [0u8; 8];
+        reader.read_exact(&mut prefix)?;
+        rstsr_assert!(&prefix[0..6] == MAGIC, InvalidValue, "Not a npy file.")?;
+        let header_len = match prefix[6] {
+            1 => {
+                let mut len = [0u8; 2];
+                reader.read_exact(&mut len)?;
+                u16::from_le_bytes(len) as usize
+            },
+            2 | 3 => {
+                let mut len = [0u8; 4];
+                reader.read_exact(&mut len)?;
+                u32::from_le_bytes(len) as usize
+            },
+            v => rstsr_raise!(InvalidValue, "Unsupported npy format version {v}.")?,
+        };
+        let mut dict = vec![0u8; header_len];
+        reader.read_exact(&mut dict)?;
+        let dict = core::str::from_utf8(&dict)
+            .map_err(|_| Error::InvalidValue("Header is not valid text.".to_string()))?;
+        return Self::parse_dict(dict);
+    }
+
+    /// Parse python dict literal of header, such as
+    /// `{'descr': '<f8', 'fortran_order': False, 'shape': (3, 4), }`.
+    fn parse_dict(dict: &str) -> Result<Self> {
+        let invalid = || Error::InvalidValue(format!("Invalid npy header: {dict}"));
+        let body = dict.trim().strip_prefix('{').and_then(|s| s.strip_suffix('}'));
+        let mut rest = body.ok_or_else(invalid)?.trim();
+
+        let (mut descr, mut fortran_order, mut shape) = (None, None, None);
+        while !rest.is_empty() {
+            // key
+            let quote = rest.chars().next().unwrap();
+            rstsr_assert!(
+                quote == '\'' || quote == '"',
+                InvalidValue,
+                "Invalid npy header: {dict}"
+            )?;
+            let (key, after) = rest[1..].split_once(quote).ok_or_else(invalid)?;
+            let after = after.trim_start().strip_prefix(':').ok_or_else(invalid)?.trim_start();
+            // value: string, tuple or bool
+            let (value, after) = match after.chars().next() {
+                Some(q @ ('\'' | '"')) => {
+                    let (value, after) = after[1..].split_once(q).ok_or_else(invalid)?;
+                    (value, after)
+                },
+                Some('(') => {
+                    let (value, after) = after[1..].split_once(')').ok_or_else(invalid)?;
+                    (value, after)
+                },
+                _ => after.split_at(after.find(',').unwrap_or(after.len())),
+            };
+            match key {
+                "descr" => descr = Some(value.to_string()),
+                "fortran_order" => match value.trim() {
+                    "True" => fortran_order = Some(true),
+                    "False" => fortran_order = Some(false),
+                    _ => return Err(invalid()),
+                },
+                "shape" => {
+                    let dims = value.split(',').map(|s| s.trim()).filter(|s| !s.is_empty());
+                    let dims = dims.map(|s| s.parse::<usize>().map_err(|_| invalid()));
+                    shape = Some(dims.collect::<Result<Vec<usize>>>()?);
+                },
+                _ => (),
+            }
+            let after = after.trim_start();
+            rest = after.strip_prefix(',').unwrap_or(after).trim_start();
+        }
+
+        match (descr, fortran_order, shape) {
+            (Some(descr), Some(fortran_order), Some(shape)) => {
+                Ok(NpyHeader { descr, fortran_order, shape })
+            },
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/* #endregion */
+
+/* #region read and write */
+
+/// Bytes of data read at a time by [`read_npy`].
+const NPY_READ_CHUNK_BYTES: usize = 1 << 20;
+
+/// Read tensor from `.npy` content into device `device`.
+///
+/// Data of big-endian (or little-endian) files are converted to native byte
+/// order; the layout of tensor is c- or f-contiguous by `fortran_order` of
+/// file.
+pub fn read_npy<T, B>(reader: &mut impl Read, device: &B) -> Result<Tensor<T, IxD, B>>
+where
+    T: NpyElement,
+    B: DeviceAPI<T> + DeviceCreationAnyAPI<T>,
+{
+    let header = NpyHeader::read(reader)?;
+    let swap = header.check_dtype::<T>()?;
+    let size = header.shape.iter().try_fold(1_usize, |acc, &n| acc.checked_mul(n));
+    let nbytes = size.and_then(|size| size.checked_mul(core::mem::size_of::<T>()));
+    let size = match nbytes {
+        Some(nbytes) if nbytes <= isize::MAX as usize => size.unwrap(),
+        _ => rstsr_raise!(InvalidValue, "Size of array of shape {:?} overflows.", header.shape)?,
+    };
+    let layout = header.layout();
+
+    // data is read chunk by chunk, so that shape of truncated (or forged) file
+    // does not allocate more memory than content actually given
+    let chunk = (NPY_READ_CHUNK_BYTES / core::mem::size_of::<T>()).max(1);
+    let mut vec: Vec<T> = Vec::new();
+    while vec.len() < size {
+        let (start, n) = (vec.len(), chunk.min(size - vec.len()));
+        vec.reserve(n);
+        // SAFETY: T is plain old data, zero bytes are valid values
+        let bytes = unsafe {
+            core::ptr::write_bytes(vec.as_mut_ptr().add(start), 0, n);
+            vec.set_len(start + n);
+            let ptr = vec.as_mut_ptr().add(start) as *mut u8;
+            core::slice::from_raw_parts_mut(ptr, n * core::mem::size_of::<T>())
+        };
+        reader.read_exact(bytes)?;
+        if swap {
+            bytes.chunks_exact_mut(T::UNIT_SIZE).for_each(|unit| unit.reverse());
+        }
+    }
+
+    let storage = device.outof_cpu_vec(vec)?;
+    return Tensor::new(storage.into(), layout);
+}
+
+/// Bytes of `.npy` content of tensor, passed to `f` chunk by chunk.
+///
+/// Contiguous data is passed as a whole without copy; otherwise elements are
+/// passed one by one.
+pub(crate) fn for_each_npy_chunk<R, T, D, B>(
+    tensor: &TensorBase<R, D>,
+    f: &mut dyn FnMut(&[u8]) -> Result<()>,
+) -> Result<()>
+where
+    R: DataAPI<Data = Storage<T, B>>,
+    T: NpyElement,
+    D: DimAPI,
+    B: DeviceAPI<T>,
+    B::RawVec: CpuRawVecAPI<T>,
+{
+    let as_bytes = |slc: &[T]| unsafe {
+        core::slice::from_raw_parts(slc.as_ptr() as *const u8, core::mem::size_of_val(slc))
+    };
+
+    let layout = tensor.layout();
+    let shape = layout.shape().as_ref().to_vec();
+    let (fortran_order, contig) = match (layout.c_contig(), layout.f_contig()) {
+        (true, _) => (false, true),
+        (false, true) => (true, true),
+        (false, false) => (!layout.c_prefer() && layout.f_prefer(), false),
+    };
+    f(&NpyHeader::new::<T>(shape, fortran_order).to_bytes())?;
+
+    if contig {
+        let offset = layout.offset();
+        let slc =
+            tensor.storage().rawvec().as_raw_slice().get_range(offset..offset + layout.size());
+        f(as_bytes(slc))?;
+    } else {
+        let order = if fortran_order { TensorIterOrder::F } else { TensorIterOrder::C };
+        for x in tensor.iter_with_order(order)? {
+            f(as_bytes(core::slice::from_ref(x)))?;
+        }
+    }
+    return Ok(());
+}
+
+/// Write tensor as `.npy` content.
+///
+/// Data is written in C order (or F order if layout is f-contiguous, or
+/// prefers F order), in native byte order. Contiguous data is written without
+/// copy.
+pub fn write_npy<R, T, D, B>(writer: &mut impl IoWrite, tensor: &TensorBase<R, D>) -> Result<()>
+where
+    R: DataAPI<Data = Storage<T, B>>,
+    T: NpyElement,
+    D: DimAPI,
+    B: DeviceAPI<T>,
+    B::RawVec: CpuRawVecAPI<T>,
+{
+    for_each_npy_chunk(tensor, &mut |bytes| Ok(writer.write_all(bytes)?))
+}
+
+/// Read tensor from `.npy` file at `path` into device `device`.
+///
+/// # See also
+///
+/// [`read_npy`]
+pub fn load_npy<T, B>(path: impl AsRef<Path>, device: &B) -> Result<Tensor<T, IxD, B>>
+where
+    T: NpyElement,
+    B: DeviceAPI<T> + DeviceCreationAnyAPI<T>,
+{
+    let mut reader = BufReader::new(File::open(path)?);
+    read_npy(&mut reader, device)
+}
+
+/// Write tensor to `.npy` file at `path`.
+///
+/// # See also
+///
+/// [`write_npy`]
+pub fn save_npy<R, T, D, B>(path: impl AsRef<Path>, tensor: &TensorBase<R, D>) -> Result<()>
+where
+    R: DataAPI<Data = Storage<T, B>>,
+    T: NpyElement,
+    D: DimAPI,
+    B: DeviceAPI<T>,
+    B::RawVec: CpuRawVecAPI<T>,
+{
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_npy(&mut writer, tensor)?;
+    writer.flush()?;
+    return Ok(());
+}
+
+/* #endregion */
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::s;
+
+    #[test]
+    fn test_npy_header() {
+        let dict = "{'descr': '>c16', 'fortran_order': True, 'shape': (3, 4), }   \n";
+        let header = NpyHeader::parse_dict(dict).unwrap();
+        assert_eq!(header.descr, ">c16");
+        assert!(header.fortran_order);
+        assert_eq!(header.shape, [3, 4]);
+        assert_eq!(header.check_dtype::<Complex<f64>>().unwrap(), cfg!(target_endian = "little"));
+        assert!(header.check_dtype::<f64>().is_err());
+
+        let header = NpyHeader::parse_dict("{'shape': (), 'descr': '|u1', 'fortran_order': False}");
+        assert_eq!(header.unwrap().shape, Vec::<usize>::new());
+        assert!(NpyHeader::parse_dict("{'descr': '<f8', 'shape': (3,)}").is_err());
+
+        for shape in [vec![], vec![5], vec![2, 3, 4]] {
+            let header = NpyHeader::new::<f32>(shape, false);
+            let bytes = header.to_bytes();
+            assert_eq!(bytes.len() % 64, 0);
+            assert_eq!(NpyHeader::read(&mut bytes.as_slice()).unwrap(), header);
+        }
+    }
+
+    #[test]
+    fn test_npy_read_write() {
+        // header as written by numpy: `np.arange(6, dtype='>i4').reshape(2, 3)`
+        let mut bytes = b"\x93NUMPY\x01\x00\x76\x00".to_vec();
+        let dict = "{'descr': '>i4', 'fortran_order': False, 'shape': (2, 3), }";
+        bytes.extend_from_slice(format!("{dict:<117}\n").as_bytes());
+        (0..6).for_each(|x: i32| bytes.extend_from_slice(&x.to_be_bytes()));
+        let a = read_npy::<i32, _>(&mut bytes.as_slice(), &DeviceCpu::default()).unwrap();
+        assert_eq!(a.shape(), &[2, 3]);
+        assert_eq!(a.iter().copied().collect_vec(), [0, 1, 2, 3, 4, 5]);
+        assert!(read_npy::<i64, _>(&mut bytes.as_slice(), &DeviceCpu::default()).is_err());
+
+        // truncated data, and shape of forged header, are refused before allocation
+        assert!(read_npy::<i32, _>(&mut &bytes[..bytes.len() - 1], &DeviceCpu::default()).is_err());
+        for shape in ["(1099511627776, 1099511627776)", "(1152921504606846976,)"] {
+            let dict = format!("{{'descr': '>i4', 'fortran_order': False, 'shape': {shape}, }}");
+            let mut forged = b"\x93NUMPY\x01\x00\x76\x00".to_vec();
+            forged.extend_from_slice(format!("{dict:<117}\n").as_bytes());
+            forged.extend_from_slice(&[0; 24]);
+            assert!(read_npy::<i32, _>(&mut forged.as_slice(), &DeviceCpu::default()).is_err());
+        }
+
+        // contiguous (c and f) and strided tensors
+        let a = Tensor::from_shape_vec([3, 4], (0..12).map(|x| x as f64).collect_vec()).unwrap();
+        for b in [
+            a.view().into_dim::<IxD>().unwrap(),
+            a.reverse_axes().into_dim().unwrap(),
+            a.slice(s![.., 1..3]).unwrap(),
+        ] {
+            let mut bytes = vec![];
+            write_npy(&mut bytes, &b).unwrap();
+            let header = NpyHeader::read(&mut bytes.as_slice()).unwrap();
+            assert_eq!(header.fortran_order, b.layout().f_contig() && !b.layout().c_contig());
+            let c = read_npy::<f64, _>(&mut bytes.as_slice(), &DeviceCpu::default()).unwrap();
+            assert_eq!(c.shape(), b.shape());
+            assert_eq!(c.iter().collect_vec(), b.iter().collect_vec());
+        }
+
+        let path = std::env::temp_dir().join(format!("rstsr_test_npy_{}.npy", std::process::id()));
+        let b =
+            Tensor::from_shape_vec([2], vec![Complex::new(1.0_f32, 2.0), Complex::new(3.0, 4.0)]);
+        save_npy(&path, &b.unwrap()).unwrap();
+        let c = load_npy::<Complex<f32>, _>(&path, &DeviceCpu::default()).unwrap();
+        assert_eq!(c.iter().copied().collect_vec(), [
+            Complex::new(1.0, 2.0),
+            Complex::new(3.0, 4.0)
+        ]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,460 @@
+//! Reading and writing NumPy `.npz` archives.
+//!
+//! Archives are zip files of `.npy` members. Only stored (uncompressed)
+//! members are supported, as written by `numpy.savez`; archives written by
+//! `numpy.savez_compressed` can not be read. Zip64 archives (members or
+//! archives larger than 4 GiB) are not supported either.
+
+extern crate std;
+
+use super::npy::*;
+use crate::prelude_dev::*;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write as IoWrite};
+use std::path::Path;
+
+/* #region zip format */
+
+const LOCAL_HEADER_SIG: u32 = 0x04034b50;
+const CENTRAL_HEADER_SIG: u32 = 0x02014b50;
+const END_OF_CENTRAL_SIG: u32 = 0x06054b50;
+/// Modification date 1980-01-01 00:00 in MS-DOS format.
+const DOS_DATE: u16 = (1 << 5) | 1;
+const ZIP_VERSION: u16 = 20;
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 { 0xedb88320 ^ (c >> 1) } else { c >> 1 };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+}
+
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+/// Incremental CRC-32 (as used by zip) of bytes.
+#[derive(Clone, Copy, Debug)]
+struct Crc32(u32);
+
+impl Crc32 {
+    fn new() -> Self {
+        Crc32(0xffffffff)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = CRC32_TABLE[((self.0 ^ b as u32) & 0xff) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    fn finish(self) -> u32 {
+        !self.0
+    }
+}
+
+/// Reader that computes CRC-32 of bytes read.
+struct Crc32Reader<R> {
+    reader: R,
+    crc: Crc32,
+}
+
+impl<R> Read for Crc32Reader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        self.crc.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Entry of zip central directory.
+#[derive(Clone, Debug)]
+struct ZipEntry {
+    name: String,
+    crc: u32,
+    size: u32,
+    local_offset: u32,
+}
+
+/* #endregion */
+
+/* #region writer */
+
+/// Writer of `.npz` archive.
+///
+/// Arrays are written into the archive on [`add`](Self::add); the archive is
+/// completed by [`finish`](Self::finish).
+///
+/// ```no_run
+/// use rstsr::io::NpzWriter;
+/// use rstsr::prelude_dev::*;
+///
+/// let a = Tensor::<f64, _>::linspace(0.0, 1.0, 11, &DeviceCpu::default());
+/// let b = Tensor::from_shape_vec([2, 2], vec![1_i32, 2, 3, 4]).unwrap();
+/// let mut npz = NpzWriter::create("ab.npz").unwrap();
+/// npz.add("a", &a).unwrap();
+/// npz.add("b", &b.reverse_axes()).unwrap();
+/// npz.finish().unwrap();
+/// ```
+pub struct NpzWriter<W>
+where
+    W: IoWrite,
+{
+    writer: W,
+    entries: Vec<ZipEntry>,
+    position: u64,
+}
+
+impl NpzWriter<BufWriter<File>> {
+    /// Create (or truncate) `.npz` file at `path`.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W> NpzWriter<W>
+where
+    W: IoWrite,
+{
+    pub fn new(writer: W) -> Self {
+        NpzWriter { writer, entries: vec![], position: 0 }
+    }
+
+    /// Add tensor as array `name` (member `name.npy`) of archive.
+    ///
+    /// Data is passed twice (to compute checksum before writing), but not
+    /// copied if contiguous.
+    pub fn add<R, T, D, B>(&mut self, name: &str, tensor: &TensorBase<R, D>) -> Result<()>
+    where
+        R: DataAPI<Data = Storage<T, B>>,
+        T: NpyElement,
+        D: DimAPI,
+        B: DeviceAPI<T>,
+        B::RawVec: CpuRawVecAPI<T>,
+    {
+        let name = format!("{name}.npy");
+        rstsr_assert!(
+            self.entries.iter().all(|e| e.name != name),
+            InvalidValue,
+            "Array {name} is already in archive."
+        )?;
+        // counts and lengths of zip (without zip64) are 16-bit
+        rstsr_assert!(
+            name.len() <= u16::MAX as usize,
+            InvalidValue,
+            "Name of array is longer than {} bytes.",
+            u16::MAX
+        )?;
+        rstsr_assert!(
+            self.entries.len() < u16::MAX as usize,
+            UnImplemented,
+            "Archive with more than {} arrays (zip64) is not supported.",
+            u16::MAX
+        )?;
+
+        let mut crc = Crc32::new();
+        let mut size = 0_usize;
+        for_each_npy_chunk(tensor, &mut |bytes| {
+            crc.update(bytes);
+            size += bytes.len();
+            Ok(())
+        })?;
+        let local_offset = self.position;
+        let (size, local_offset) = match (u32::try_from(size), u32::try_from(local_offset)) {
+            (Ok(size), Ok(offset)) => (size, offset),
+            _ => {
+                rstsr_raise!(UnImplemented, "Archive larger than 4 GiB (zip64) is not supported.")?
+            },
+        };
+        let entry = ZipEntry { name, crc: crc.finish(), size, local_offset };
+
+        let mut header = Vec::with_capacity(30 + entry.name.len());
+        header.extend_from_slice(&LOCAL_HEADER_SIG.to_le_bytes());
+        Self::extend_entry_info(&mut header, &entry);
+        header.extend_from_slice(&0_u16.to_le_bytes()); // extra field length
+        header.extend_from_slice(entry.name.as_bytes());
+        self.writer.write_all(&header)?;
+        for_each_npy_chunk(tensor, &mut |bytes| Ok(self.writer.write_all(bytes)?))?;
+
+        self.position += (header.len() + size as usize) as u64;
+        self.entries.push(entry);
+        return Ok(());
+    }
+
+    /// Fields from "version needed to extract" to "file name length", shared
+    /// by local and central headers.
+    fn extend_entry_info(bytes: &mut Vec<u8>, entry: &ZipEntry) {
+        bytes.extend_from_slice(&ZIP_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&0_u16.to_le_bytes()); // flags
+        bytes.extend_from_slice(&0_u16.to_le_bytes()); // method: stored
+        bytes.extend_from_slice(&0_u16.to_le_bytes()); // time
+        bytes.extend_from_slice(&DOS_DATE.to_le_bytes());
+        bytes.extend_from_slice(&entry.crc.to_le_bytes());
+        bytes.extend_from_slice(&entry.size.to_le_bytes()); // compressed size
+        bytes.extend_from_slice(&entry.size.to_le_bytes()); // uncompressed size
+        bytes.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+    }
+
+    /// Write central directory, and give back the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        let mut bytes = vec![];
+        for entry in self.entries.iter() {
+            bytes.extend_from_slice(&CENTRAL_HEADER_SIG.to_le_bytes());
+            bytes.extend_from_slice(&ZIP_VERSION.to_le_bytes()); // version made by
+            Self::extend_entry_info(&mut bytes, entry);
+            bytes.extend_from_slice(&[0; 12]); // extra, comment, disk, attributes
+            bytes.extend_from_slice(&entry.local_offset.to_le_bytes());
+            bytes.extend_from_slice(entry.name.as_bytes());
+        }
+        let central_offset = u32::try_from(self.position);
+        let central_offset = central_offset.map_err(|_| {
+            Error::UnImplemented("Archive larger than 4 GiB (zip64) is not supported.".to_string())
+        })?;
+        // number of entries and length of names are checked in `add`
+        let nentries = self.entries.len() as u16;
+        let central_len = u32::try_from(bytes.len()).map_err(|_| {
+            Error::UnImplemented("Archive larger than 4 GiB (zip64) is not supported.".to_string())
+        })?;
+        bytes.extend_from_slice(&END_OF_CENTRAL_SIG.to_le_bytes());
+        bytes.extend_from_slice(&[0; 4]); // disk numbers
+        bytes.extend_from_slice(&nentries.to_le_bytes());
+        bytes.extend_from_slice(&nentries.to_le_bytes());
+        bytes.extend_from_slice(&central_len.to_le_bytes());
+        bytes.extend_from_slice(&central_offset.to_le_bytes());
+        bytes.extend_from_slice(&0_u16.to_le_bytes()); // comment length
+        self.writer.write_all(&bytes)?;
+        self.writer.flush()?;
+        return Ok(self.writer);
+    }
+}
+
+/* #endregion */
+
+/* #region reader */
+
+/// Reader of `.npz` archive.
+///
+/// ```no_run
+/// use rstsr::io::NpzReader;
+/// use rstsr::prelude_dev::*;
+///
+/// let mut npz = NpzReader::open("ab.npz").unwrap();
+/// println!("{:?}", npz.names());
+/// let a = npz.read::<f64, _>("a", &DeviceCpu::default()).unwrap();
+/// ```
+pub struct NpzReader<R>
+where
+    R: Read + Seek,
+{
+    reader: R,
+    entries: Vec<ZipEntry>,
+}
+
+impl NpzReader<BufReader<File>> {
+    /// Open `.npz` file at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R> NpzReader<R>
+where
+    R: Read + Seek,
+{
+    /// Read central directory of archive.
+    pub fn new(mut reader: R) -> Result<Self> {
+        // end of central directory record is at the end, followed by comment of at
+        // most 65535 bytes
+        let file_len = reader.seek(SeekFrom::End(0))?;
+        let tail_len = file_len.min(22 + 65535);
+        reader.seek(SeekFrom::Start(file_len - tail_len))?;
+        let mut tail = vec![0u8; tail_len as usize];
+        reader.read_exact(&mut tail)?;
+        let sig = END_OF_CENTRAL_SIG.to_le_bytes();
+        let pos = (0..tail.len().saturating_sub(21)).rev().find(|&i| tail[i..i + 4] == sig);
+        let eocd = match pos {
+            Some(pos) => &tail[pos..],
+            None => rstsr_raise!(InvalidValue, "Not a npz (zip) file.")?,
+        };
+        let nentries = u16::from_le_bytes([eocd[10], eocd[11]]) as usize;
+        let central_len = u32::from_le_bytes(eocd[12..16].try_into().unwrap()) as usize;
+        let central_offset = u32::from_le_bytes(eocd[16..20].try_into().unwrap()) as u64;
+        rstsr_assert!(
+            nentries != 0xffff && central_offset != 0xffffffff,
+            UnImplemented,
+            "Zip64 archive is not supported."
+        )?;
+
+        reader.seek(SeekFrom::Start(central_offset))?;
+        let mut central = vec![0u8; central_len];
+        reader.read_exact(&mut central)?;
+        let truncated = || Error::InvalidValue("Central directory is truncated.".to_string());
+        let read_u16 = |pos: usize| -> Result<u16> {
+            let slc = central.get(pos..pos + 2).ok_or_else(truncated)?;
+            Ok(u16::from_le_bytes(slc.try_into().unwrap()))
+        };
+        let read_u32 = |pos: usize| -> Result<u32> {
+            let slc = central.get(pos..pos + 4).ok_or_else(truncated)?;
+            Ok(u32::from_le_bytes(slc.try_into().unwrap()))
+        };
+
+        let mut entries = Vec::with_capacity(nentries);
+        let mut pos = 0;
+        for _ in 0..nentries {
+            rstsr_assert_eq!(
+                read_u32(pos)?,
+                CENTRAL_HEADER_SIG,
+                InvalidValue,
+                "Invalid zip entry."
+            )?;
+            let method = read_u16(pos + 10)?;
+            let crc = read_u32(pos + 16)?;
+            let size = read_u32(pos + 24)?;
+            let name_len = read_u16(pos + 28)? as usize;
+            let extra_len = read_u16(pos + 30)? as usize;
+            let comment_len = read_u16(pos + 32)? as usize;
+            let local_offset = read_u32(pos + 42)?;
+            let name = central.get(pos + 46..pos + 46 + name_len).ok_or_else(truncated)?;
+            let name = String::from_utf8_lossy(name).to_string();
+            rstsr_assert!(
+                method == 0,
+                UnImplemented,
+                "Member {name} is compressed; only stored (uncompressed) npz is supported."
+            )?;
+            entries.push(ZipEntry { name, crc, size, local_offset });
+            pos += 46 + name_len + extra_len + comment_len;
+        }
+        return Ok(NpzReader { reader, entries });
+    }
+
+    /// Names of arrays in archive (without `.npy` suffix).
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.iter().map(|e| e.name.strip_suffix(".npy").unwrap_or(&e.name)).collect()
+    }
+
+    /// Read array `name` into device `device`.
+    ///
+    /// Error is given if CRC-32 of member does not match the one recorded in
+    /// archive.
+    ///
+    /// # See also
+    ///
+    /// [`read_npy`]
+    pub fn read<T, B>(&mut self, name: &str, device: &B) -> Result<Tensor<T, IxD, B>>
+    where
+        T: NpyElement,
+        B: DeviceAPI<T> + DeviceCreationAnyAPI<T>,
+    {
+        let entry = self.entries.iter().find(|e| e.name == name || e.name == format!("{name}.npy"));
+        let entry = match entry {
+            Some(entry) => entry,
+            None => rstsr_raise!(InvalidValue, "Array {name} is not in archive.")?,
+        };
+
+        // name and extra field of local header may differ from central directory
+        self.reader.seek(SeekFrom::Start(entry.local_offset as u64))?;
+        let mut local = [0u8; 30];
+        self.reader.read_exact(&mut local)?;
+        rstsr_assert_eq!(
+            u32::from_le_bytes(local[0..4].try_into().unwrap()),
+            LOCAL_HEADER_SIG,
+            InvalidValue,
+            "Invalid zip entry."
+        )?;
+        let name_len = u16::from_le_bytes([local[26], local[27]]) as i64;
+        let extra_len = u16::from_le_bytes([local[28], local[29]]) as i64;
+        self.reader.seek(SeekFrom::Current(name_len + extra_len))?;
+
+        let member = (&mut self.reader).take(entry.size as u64);
+        let mut member = Crc32Reader { reader: member, crc: Crc32::new() };
+        let tensor = read_npy(&mut member, device)?;
+        // checksum covers the whole member, including bytes after data
+        std::io::copy(&mut member, &mut std::io::sink())?;
+        rstsr_assert_eq!(
+            member.crc.finish(),
+            entry.crc,
+            InvalidValue,
+            "CRC-32 of member {} does not match; archive is corrupted.",
+            entry.name
+        )?;
+        return Ok(tensor);
+    }
+}
+
+/* #endregion */
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use num::complex::Complex;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_crc32() {
+        let mut crc = Crc32::new();
+        crc.update(b"123456789");
+        assert_eq!(crc.finish(), 0xcbf43926);
+    }
+
+    #[test]
+    fn test_npz() {
+        let device = DeviceCpu::default();
+        let a = Tensor::from_shape_vec([3, 4], (0..12).map(|x| x as f64).collect_vec()).unwrap();
+        let b = Tensor::from_shape_vec([2], vec![Complex::new(1.0_f32, -1.0); 2]).unwrap();
+
+        let mut npz = NpzWriter::new(Cursor::new(vec![]));
+        npz.add("a", &a).unwrap();
+        npz.add("at", &a.reverse_axes()).unwrap();
+        npz.add("b", &b).unwrap();
+        assert!(npz.add("b", &b).is_err());
+        let bytes = npz.finish().unwrap().into_inner();
+
+        let mut npz = NpzReader::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(npz.names(), ["a", "at", "b"]);
+        let at = npz.read::<f64, _>("at", &device).unwrap();
+        assert!(at.layout().f_contig());
+        assert_eq!(at.iter().collect_vec(), a.reverse_axes().iter().collect_vec());
+        let b1 = npz.read::<Complex<f32>, _>("b.npy", &device).unwrap();
+        assert_eq!(b1.iter().collect_vec(), b.iter().collect_vec());
+        let a1 = npz.read::<f64, _>("a", &device).unwrap();
+        assert_eq!(a1.iter().collect_vec(), a.iter().collect_vec());
+        assert!(npz.read::<f64, _>("c", &device).is_err());
+        assert!(npz.read::<f32, _>("a", &device).is_err());
+
+        // corrupted data is detected by checksum
+        let mut bytes = npz.reader.into_inner();
+        let pos = bytes.windows(8).position(|w| w == 11.0_f64.to_ne_bytes()).unwrap();
+        bytes[pos + 7] ^= 1;
+        let mut npz = NpzReader::new(Cursor::new(bytes)).unwrap();
+        assert!(npz.read::<f64, _>("at", &device).is_ok());
+        assert!(npz.read::<f64, _>("a", &device).is_err());
+    }
+
+    #[test]
+    fn test_npz_limits() {
+        let a = Tensor::from_shape_vec([1], vec![1.0_f64]).unwrap();
+        let mut npz = NpzWriter::new(std::io::sink());
+        // names (with suffix `.npy`) longer than 65535 bytes
+        assert!(npz.add(&"c".repeat(65532), &a).is_err());
+        npz.add(&"c".repeat(65531), &a).unwrap();
+
+        // more than 65535 arrays
+        let entry = ZipEntry { name: String::new(), crc: 0, size: 0, local_offset: 0 };
+        npz.entries = (0..u16::MAX as usize - 1)
+            .map(|i| ZipEntry { name: format!("x{i}.npy"), ..entry.clone() })
+            .collect();
+        npz.add("a", &a).unwrap();
+        assert!(npz.add("b", &a).is_err());
+        assert!(npz.finish().is_ok());
+    }
+}
@@ -0,0 +1,402 @@
+//! Reading and writing tensors in safetensors format.
+//!
+//! A safetensors file consists of a JSON header (dtype, shape and byte offsets
+//! of each named tensor, and optional string metadata) followed by raw
+//! little-endian data in row-major (C) order. Parsing and validation of the
+//! header is done by the [`safetensors`](::safetensors) crate.
+
+extern crate std;
+
+use crate::prelude_dev::*;
+use ::safetensors::tensor::{Metadata, TensorInfo};
+use ::safetensors::{Dtype, SafeTensors, View};
+use half::{bf16, f16};
+use memmap2::Mmap;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+
+/* #region element types */
+
+/// Element types that can be stored in safetensors files.
+///
+/// # Safety
+///
+/// Implementor must be plain old data without padding, whose memory
+/// representation in little-endian is the one described by
+/// [`DTYPE`](Self::DTYPE). Bit patterns that are not valid values must be
+/// refused by [`check_bytes`](Self::check_bytes).
+pub unsafe trait SafeTensorsElement: Clone + 'static {
+    const DTYPE: Dtype;
+
+    /// Check that data bytes are valid values of this type.
+    fn check_bytes(_bytes: &[u8]) -> Result<()> {
+        Ok(())
+    }
+}
+
+macro_rules! impl_safetensors_element {
+    ($($t:ty => $dtype:ident),*) => {
+        $(unsafe impl SafeTensorsElement for $t {
+            const DTYPE: Dtype = Dtype::$dtype;
+        })*
+    };
+}
+
+impl_safetensors_element!(
+    i8 => I8, i16 => I16, i32 => I32, i64 => I64,
+    u8 => U8, u16 => U16, u32 => U32, u64 => U64,
+    f16 => F16, bf16 => BF16, f32 => F32, f64 => F64
+);
+
+unsafe impl SafeTensorsElement for bool {
+    const DTYPE: Dtype = Dtype::BOOL;
+
+    fn check_bytes(bytes: &[u8]) -> Result<()> {
+        rstsr_assert!(bytes.iter().all(|&b| b <= 1), InvalidValue, "Invalid bool value in data.")
+    }
+}
+
+/// Bytes of elements in little-endian, borrowed if possible.
+fn elements_to_le_bytes<T>(slc: &[T]) -> Cow<'_, [u8]>
+where
+    T: SafeTensorsElement,
+{
+    let size = core::mem::size_of::<T>();
+    let bytes = unsafe {
+        core::slice::from_raw_parts(slc.as_ptr() as *const u8, core::mem::size_of_val(slc))
+    };
+    match cfg!(target_endian = "little") || size == 1 {
+        true => Cow::Borrowed(bytes),
+        false => {
+            let mut bytes = bytes.to_vec();
+            bytes.chunks_exact_mut(size).for_each(|x| x.reverse());
+            Cow::Owned(bytes)
+        },
+    }
+}
+
+/* #endregion */
+
+/* #region writer */
+
+/// Tensor data prepared for writing: dtype, shape and data bytes.
+#[derive(Debug, Clone)]
+struct SafeTensorsEntry<'a> {
+    dtype: Dtype,
+    shape: Vec<usize>,
+    data: Cow<'a, [u8]>,
+}
+
+impl View for &SafeTensorsEntry<'_> {
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    fn data(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self.data)
+    }
+
+    fn data_len(&self) -> usize {
+        self.data.len()
+    }
+}
+
+/// Writer of safetensors file.
+///
+/// Tensors are borrowed by the writer until it is serialized; data of
+/// c-contiguous tensors is not copied (on little-endian platforms).
+///
+/// ```no_run
+/// use rstsr::io::{SafeTensorsFile, SafeTensorsWriter};
+/// use rstsr::prelude_dev::*;
+///
+/// let a = Tensor::<f64, _>::linspace(0.0, 1.0, 11, &DeviceCpu::default());
+/// let mut st = SafeTensorsWriter::new();
+/// st.add("a", &a).unwrap();
+/// st.add_metadata("iteration", "12");
+/// st.save("checkpoint.safetensors").unwrap();
+///
+/// let file = SafeTensorsFile::open("checkpoint.safetensors").unwrap();
+/// let a = file.view::<f64>("a").unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SafeTensorsWriter<'a> {
+    entries: BTreeMap<String, SafeTensorsEntry<'a>>,
+    metadata: HashMap<String, String>,
+}
+
+impl<'a> SafeTensorsWriter<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add tensor of name `name`.
+    ///
+    /// Tensor is stored in row-major order; non-c-contiguous tensor is copied.
+    pub fn add<R, T, D, B>(&mut self, name: &str, tensor: &'a TensorBase<R, D>) -> Result<()>
+    where
+        R: DataAPI<Data = Storage<T, B>>,
+        T: SafeTensorsElement,
+        D: DimAPI,
+        B: DeviceAPI<T> + 'a,
+        B::RawVec: CpuRawVecAPI<T>,
+    {
+        rstsr_assert!(
+            !self.entries.contains_key(name),
+            InvalidValue,
+            "Tensor {name} is already added."
+        )?;
+        let layout = tensor.layout();
+        let data = match layout.c_contig() {
+            true => {
+                let offset = layout.offset();
+                let slc = tensor
+                    .storage()
+                    .rawvec()
+                    .as_raw_slice()
+                    .get_range(offset..offset + layout.size());
+                elements_to_le_bytes(slc)
+            },
+            false => {
+                let vec = tensor.iter_with_order(TensorIterOrder::C)?.cloned().collect_vec();
+                Cow::Owned(elements_to_le_bytes(&vec).into_owned())
+            },
+        };
+        let shape = layout.shape().as_ref().to_vec();
+        let entry = SafeTensorsEntry { dtype: T::DTYPE, shape, data };
+        self.entries.insert(name.to_string(), entry);
+        return Ok(());
+    }
+
+    /// Add string metadata of file (such as iteration count or parameters).
+    pub fn add_metadata(&mut self, key: &str, value: &str) {
+        self.metadata.insert(key.to_string(), value.to_string());
+    }
+
+    fn metadata(&self) -> Option<HashMap<String, String>> {
+        (!self.metadata.is_empty()).then(|| self.metadata.clone())
+    }
+
+    /// Serialize into bytes of safetensors file.
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        let bytes = ::safetensors::serialize(self.entries.iter(), &self.metadata())?;
+        return Ok(bytes);
+    }
+
+    /// Write safetensors file at `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        ::safetensors::serialize_to_file(self.entries.iter(), &self.metadata(), path.as_ref())?;
+        return Ok(());
+    }
+}
+
+/* #endregion */
+
+/* #region reader */
+
+#[derive(Debug)]
+enum SafeTensorsBuffer {
+    Mmap(Mmap),
+    Owned(Vec<u8>),
+}
+
+impl SafeTensorsBuffer {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            SafeTensorsBuffer::Mmap(mmap) => mmap,
+            SafeTensorsBuffer::Owned(vec) => vec,
+        }
+    }
+}
+
+/// Safetensors file, memory-mapped or read into memory.
+///
+/// Header is parsed and validated on opening. Tensors can be viewed without
+/// copy ([`view`](Self::view)) or loaded into any device
+/// ([`load`](Self::load)).
+#[derive(Debug)]
+pub struct SafeTensorsFile {
+    metadata: Metadata,
+    data_start: usize,
+    buffer: SafeTensorsBuffer,
+}
+
+impl SafeTensorsFile {
+    fn new(buffer: SafeTensorsBuffer) -> Result<Self> {
+        let (header_len, metadata) = SafeTensors::read_metadata(buffer.as_slice())?;
+        return Ok(SafeTensorsFile { metadata, data_start: 8 + header_len, buffer });
+    }
+
+    /// Map file at `path` into memory (read-only).
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::open(path)?;
+        let mmap = unsafe { Mmap::map(&file)? };
+        Self::new(SafeTensorsBuffer::Mmap(mmap))
+    }
+
+    /// Take content of safetensors file from bytes.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        Self::new(SafeTensorsBuffer::Owned(bytes))
+    }
+
+    /// Names of tensors, sorted.
+    pub fn names(&self) -> Vec<String> {
+        self.metadata.tensors().into_keys().sorted().collect()
+    }
+
+    /// Information (dtype, shape, byte offsets) of tensor `name`.
+    pub fn info(&self, name: &str) -> Result<&TensorInfo> {
+        match self.metadata.info(name) {
+            Some(info) => Ok(info),
+            None => rstsr_raise!(InvalidValue, "Tensor {name} is not in file."),
+        }
+    }
+
+    /// String metadata of file.
+    pub fn metadata(&self) -> Option<&HashMap<String, String>> {
+        self.metadata.metadata().as_ref()
+    }
+
+    /// Layout (c-contiguous) and data bytes of tensor `name`, with dtype
+    /// checked.
+    fn tensor_bytes<T>(&self, name: &str) -> Result<(Layout<IxD>, &[u8])>
+    where
+        T: SafeTensorsElement,
+    {
+        let info = self.info(name)?;
+        rstsr_assert_eq!(
+            info.dtype,
+            T::DTYPE,
+            InvalidValue,
+            "Element type does not match dtype of tensor {name}."
+        )?;
+        let (start, end) = info.data_offsets;
+        let bytes = &self.buffer.as_slice()[self.data_start + start..self.data_start + end];
+        T::check_bytes(bytes)?;
+        return Ok((info.shape.c(), bytes));
+    }
+
+    /// View of tensor `name` without copy.
+    ///
+    /// Data must be aligned for `T` (which holds for memory-mapped files
+    /// written by this crate or by python `safetensors`), and platform must be
+    /// little-endian; otherwise use [`load`](Self::load).
+    pub fn view<T>(&self, name: &str) -> Result<TensorView<'_, T, IxD, DeviceCpu>>
+    where
+        T: SafeTensorsElement,
+    {
+        let (layout, bytes) = self.tensor_bytes::<T>(name)?;
+        rstsr_assert!(
+            cfg!(target_endian = "little") || core::mem::size_of::<T>() == 1,
+            UnImplemented,
+            "Zero-copy view of safetensors requires little-endian platform."
+        )?;
+        rstsr_assert!(
+            (bytes.as_ptr() as usize).is_multiple_of(core::mem::align_of::<T>()),
+            InvalidLayout,
+            "Data of tensor {name} is not aligned."
+        )?;
+        let len = layout.size();
+        let slc = unsafe { core::slice::from_raw_parts(bytes.as_ptr() as *const T, len) };
+        let rawvec = unsafe { CpuRawVec::from_slice(slc) };
+        // the view does not own memory, so bypass memory accounting of `new`
+        let storage = Storage { rawvec, device: DeviceCpu::default() };
+        let data = unsafe { DataRef::from_borrowed(storage) };
+        // layout has been checked against data length in header parsing
+        let tensor = unsafe { TensorView::new_unchecked(data, layout) };
+        return Ok(tensor);
+    }
+
+    /// Copy tensor `name` into device `device`.
+    pub fn load<T, B>(&self, name: &str, device: &B) -> Result<Tensor<T, IxD, B>>
+    where
+        T: SafeTensorsElement,
+        B: DeviceAPI<T> + DeviceCreationAnyAPI<T>,
+    {
+        let (layout, bytes) = self.tensor_bytes::<T>(name)?;
+        let size = core::mem::size_of::<T>();
+        let vec = bytes
+            .chunks_exact(size)
+            .map(|x| {
+                let mut elem = core::mem::MaybeUninit::<T>::uninit();
+                unsafe {
+                    let ptr = elem.as_mut_ptr() as *mut u8;
+                    core::ptr::copy_nonoverlapping(x.as_ptr(), ptr, size);
+                    if cfg!(target_endian = "big") {
+                        core::slice::from_raw_parts_mut(ptr, size).reverse();
+                    }
+                    elem.assume_init()
+                }
+            })
+            .collect_vec();
+        let storage = device.outof_cpu_vec(vec)?;
+        return Tensor::new(storage.into(), layout);
+    }
+}
+
+/* #endregion */
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_safetensors() {
+        let device = DeviceCpu::default();
+        let a = Tensor::from_shape_vec([3, 4], (0..12).map(|x| x as f64).collect_vec()).unwrap();
+        let b = Tensor::from_shape_vec([3], vec![true, false, true]).unwrap();
+        let c = Tensor::from_shape_vec([2, 2], vec![bf16::ONE, bf16::ZERO, bf16::MAX, bf16::MIN]);
+        let c = c.unwrap();
+
+        let mut st = SafeTensorsWriter::new();
+        st.add("a", &a).unwrap();
+        let at = a.reverse_axes();
+        st.add("at", &at).unwrap();
+        st.add("b", &b).unwrap();
+        st.add("c", &c).unwrap();
+        assert!(st.add("c", &c).is_err());
+        st.add_metadata("iteration", "12");
+        let bytes = st.serialize().unwrap();
+
+        // header is readable by safetensors crate itself
+        let tensors = SafeTensors::deserialize(&bytes).unwrap();
+        assert_eq!(tensors.tensor("at").unwrap().shape(), &[4, 3]);
+
+        let file = SafeTensorsFile::from_bytes(bytes.clone()).unwrap();
+        assert_eq!(file.names(), ["a", "at", "b", "c"]);
+        assert_eq!(file.metadata().unwrap()["iteration"], "12");
+        let at1 = file.load::<f64, _>("at", &device).unwrap();
+        assert!(at1.layout().c_contig());
+        assert_eq!(at1.iter().collect_vec(), at.iter().collect_vec());
+        let b1 = file.load::<bool, _>("b", &device).unwrap();
+        assert_eq!(b1.iter().collect_vec(), b.iter().collect_vec());
+        assert!(file.load::<f32, _>("a", &device).is_err());
+        assert!(file.load::<f64, _>("d", &device).is_err());
+
+        // invalid bool value
+        let mut bytes_invalid = bytes.clone();
+        let pos = bytes.len() - 3;
+        bytes_invalid[pos] = 2;
+        let file_invalid = SafeTensorsFile::from_bytes(bytes_invalid).unwrap();
+        assert!(file_invalid.load::<bool, _>("b", &device).is_err());
+
+        // zero-copy views of memory-mapped file
+        let path = std::env::temp_dir()
+            .join(format!("rstsr_test_safetensors_{}.safetensors", std::process::id()));
+        st.save(&path).unwrap();
+        let file = SafeTensorsFile::open(&path).unwrap();
+        let a1 = file.view::<f64>("a").unwrap();
+        assert!(a1.data().storage().rawvec().is_borrowed());
+        assert_eq!(a1.iter().collect_vec(), a.iter().collect_vec());
+        let c1 = file.view::<bf16>("c").unwrap();
+        assert_eq!(c1.iter().collect_vec(), c.iter().collect_vec());
+        drop(file);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -2,6 +2,7 @@ use crate::prelude_dev::*;
 
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Indexer {
     /// Slice the tensor by a range, denoted by slice instead of
     /// std::ops::Range.
@@ -108,7 +109,8 @@ where
             let offset = (self.offset() as isize + stride[axis] * start) as usize;
             shape[axis] = ((stop - start + step - 1) / step).max(0) as usize;
             stride[axis] *= step;
-            return Ok(Self::new(shape, stride, offset));
+            // narrowing valid layout (possibly broadcasted) gives valid layout
+            return Ok(unsafe { Self::new_unchecked(shape, stride, offset) });
         } else {
             // step < 0
             // default start = len_prev - 1 and stop = -1
@@ -133,9 +135,10 @@ where
             }
 
             let offset = (self.offset() as isize + stride[axis] * start) as usize;
-            shape[axis] = ((stop - start - step - 1) / step).max(0) as usize;
+            shape[axis] = ((stop - start + step + 1) / step).max(0) as usize;
             stride[axis] *= step;
-            return Ok(Self::new(shape, stride, offset));
+            // narrowing valid layout (possibly broadcasted) gives valid layout
+            return Ok(unsafe { Self::new_unchecked(shape, stride, offset) });
         }
     }
 }
@@ -189,8 +192,9 @@ where
             }
         }
 
+        // selection of valid layout (possibly broadcasted) gives valid layout
         let offset = offset as usize;
-        return Ok(Layout::<IxD>::new(shape_new, stride_new, offset));
+        return Ok(unsafe { Layout::<IxD>::new_unchecked(shape_new, stride_new, offset) });
     }
 
     fn dim_insert(&self, axis: isize) -> Result<Layout<IxD>> {
@@ -228,7 +232,7 @@ where
         // transform any layout to dynamic layout
         let shape = self.shape().as_ref().to_vec();
         let stride = self.stride().as_ref().to_vec();
-        let mut layout = Layout::new(shape, stride, self.offset);
+        let mut layout = unsafe { Layout::new_unchecked(shape, stride, self.offset) };
 
         // clone indexers to vec to make it changeable
         let mut indexers = indexers.to_vec();
@@ -259,8 +263,16 @@ where
                 indexers.remove(idx);
             }
         } else {
-            let idx_ellipsis = idx_ellipsis.unwrap_or(indexers.len());
-            indexers[idx_ellipsis] = SliceI { start: None, stop: None, step: None }.into();
+            let idx_ellipsis = match idx_ellipsis {
+                Some(idx) => {
+                    indexers[idx] = SliceI { start: None, stop: None, step: None }.into();
+                    idx
+                },
+                None => {
+                    indexers.push(SliceI { start: None, stop: None, step: None }.into());
+                    indexers.len() - 1
+                },
+            };
             if n_ellipsis > 1 {
                 for _ in 1..n_ellipsis {
                     indexers.insert(
@@ -392,5 +404,14 @@ mod tests {
         let l4 = l.dim_slice(s![Indexer::Ellipsis, 1..3, None, 2]).unwrap();
         let l4 = l4.into_dim::<Ix3>().unwrap();
         println!("{:?}", l4);
+
+        // negative step
+        let l = Layout::<Ix1>::new([4], [1], 0);
+        let l5 = l.dim_narrow(0, slice!(None, None, -2)).unwrap();
+        assert_eq!((l5.shape(), l5.stride(), l5.offset()), (&[2], &[-2], 3));
+        let l6 = l.dim_narrow(0, slice!(None, 0, -3)).unwrap();
+        assert_eq!(l6.shape(), &[1]);
+        let l7 = l.dim_narrow(0, slice!(2, 2, -1)).unwrap();
+        assert_eq!(l7.shape(), &[0]);
     }
 }
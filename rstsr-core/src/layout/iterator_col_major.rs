@@ -93,6 +93,19 @@ impl<D> IterLayoutColMajor<D>
 where
     D: DimDevAPI,
 {
+    /// Multi-index of the element to be given by next call of `next`.
+    #[inline]
+    pub fn index_start(&self) -> &D {
+        &self.index_start
+    }
+
+    /// Multi-index of the element given by last call of `next_back` (or one
+    /// past the last element if `next_back` is not called).
+    #[inline]
+    pub fn index_end(&self) -> &D {
+        &self.index_end
+    }
+
     #[inline]
     fn next_iter_index(&mut self) {
         let layout = &self.layout;
@@ -270,6 +283,11 @@ where
         self.next_iter_index();
         return Some(offset.try_into().unwrap());
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.iter_end - self.iter_start;
+        (len, Some(len))
+    }
 }
 
 impl<D> DoubleEndedIterator for IterLayoutColMajor<D>
@@ -300,7 +300,7 @@ where
         let shape_sorted = indices.iter().map(|&k| shape[k]).collect::<Vec<_>>();
         let stride_sorted = indices.iter().map(|&k| stride[k].unsigned_abs()).collect::<Vec<_>>();
 
-        for i in 0..indices.len() - 1 {
+        for i in 0..indices.len().saturating_sub(1) {
             // following function also checks that stride could not be zero
             rstsr_pattern!(
                 shape_sorted[i] * stride_sorted[i],
@@ -408,6 +408,30 @@ where
         Layout { shape, stride, offset, size }
     }
 
+    /// Layout of a foreign view (such as of ndarray or faer) by its shape and
+    /// strides, which may be negative; offset is measured from the lowest
+    /// address of the view.
+    ///
+    /// Number of elements spanned from the lowest address is also returned.
+    #[cfg(any(feature = "faer", feature = "ndarray", feature = "nalgebra"))]
+    pub(crate) fn from_foreign_view(shape: D, strides: &[isize]) -> (Self, usize)
+    where
+        D: DimShapeAPI + DimStrideAPI,
+    {
+        let mut stride = shape.new_stride();
+        stride.as_mut().copy_from_slice(strides);
+        if shape.as_ref().contains(&0) {
+            return (unsafe { Layout::new_unchecked(shape, stride, 0) }, 0);
+        }
+        let offset = izip!(shape.as_ref(), strides)
+            .filter(|(_, &s)| s < 0)
+            .map(|(&d, &s)| (d - 1) * s.unsigned_abs())
+            .sum();
+        let layout = unsafe { Layout::new_unchecked(shape, stride, offset) };
+        let (_, len) = layout.bounds_index().unwrap();
+        return (layout, len);
+    }
+
     /// New zero shape, which number of dimensions are the same to current
     /// layout.
     #[inline]
@@ -1018,8 +1042,8 @@ mod test {
         // zero-dim
         let layout = Layout::new([], [], 782);
         let trans = layout.reverse_axes();
-        assert_eq!(trans.shape(), &[]);
-        assert_eq!(trans.stride(), &[]);
+        assert_eq!(trans.shape(), &[] as &[usize]);
+        assert_eq!(trans.stride(), &[] as &[isize]);
     }
 
     #[test]
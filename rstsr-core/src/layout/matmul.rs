@@ -519,6 +519,154 @@ impl_fixed!(IxD, Ix7, IxD);
 impl_fixed!(IxD, Ix8, IxD);
 impl_fixed!(IxD, Ix9, IxD);
 
+/* #region congruence */
+
+/// Batch and matrix layouts of `b` and `c` in matrix congruence.
+type CongruenceSplit = (Layout<IxD>, Layout<Ix2>, Layout<IxD>, Layout<Ix2>);
+
+/// Split layouts of matrix congruence `c = a^T b a` into batch and matrix
+/// parts.
+///
+/// | A | B | C |
+/// |---|---|---|
+/// | `N, M` | `..., N, N` | `..., M, M` |
+///
+/// Returns `(lb_rest, lb_matmul, lc_rest, lc_matmul)`.
+pub fn layout_congruence_split(
+    la: &Layout<Ix2>,
+    lb: &Layout<IxD>,
+    lc: &Layout<IxD>,
+) -> Result<CongruenceSplit> {
+    rstsr_assert!(lb.ndim() >= 2, InvalidLayout, "Matrix b should be at least 2-D.")?;
+    rstsr_assert_eq!(lb.ndim(), lc.ndim(), InvalidLayout)?;
+    let (n, m) = (la.shape()[0], la.shape()[1]);
+    let (lb_rest, lb_matmul) = lb.dim_split_at(-2)?;
+    let (lc_rest, lc_matmul) = lc.dim_split_at(-2)?;
+    let lb_matmul = lb_matmul.into_dim::<Ix2>()?;
+    let lc_matmul = lc_matmul.into_dim::<Ix2>()?;
+    rstsr_assert_eq!(lb_matmul.shape(), &[n, n], InvalidLayout)?;
+    rstsr_assert_eq!(lc_matmul.shape(), &[m, m], InvalidLayout)?;
+    rstsr_assert_eq!(lb_rest.shape(), lc_rest.shape(), InvalidLayout)?;
+    return Ok((lb_rest, lb_matmul, lc_rest, lc_matmul));
+}
+
+/// Merge batch dimensions (all dimensions except the last two) of a batched
+/// matrix layout into one of the matrix axes.
+///
+/// - `axis = 0`: batch dimensions are merged into rows, giving shape `[batch *
+///   nrow, ncol]`;
+/// - `axis = 1`: batch dimensions are merged into columns, giving shape `[nrow,
+///   batch * ncol]`.
+///
+/// This is possible when the batch dimensions and the axis to be merged form
+/// a single strided axis in memory (in any order). Returns the merged layout
+/// and the unit stride of the merged axis; `None` if not mergeable, or any
+/// stride involved is not positive.
+pub fn layout_merge_batch_axis(l: &Layout<IxD>, axis: usize) -> Option<(Layout<Ix2>, isize)> {
+    let nd = l.ndim();
+    if nd < 2 || axis > 1 {
+        return None;
+    }
+    let shape = l.shape();
+    let stride = l.stride();
+    let axis_full = nd - 2 + axis;
+    let other_full = nd - 1 - axis;
+
+    // dimensions to be merged; dimensions of size 1 do not matter
+    let mut dims = (0..nd - 2).chain([axis_full]).filter(|&k| shape[k] != 1).collect_vec();
+    if dims.iter().any(|&k| stride[k] <= 0) {
+        return None;
+    }
+    dims.sort_by_key(|&k| stride[k]);
+    for w in dims.windows(2) {
+        if stride[w[1]] != stride[w[0]] * shape[w[0]] as isize {
+            return None;
+        }
+    }
+    let unit = dims.first().map(|&k| stride[k]).unwrap_or(1);
+    let merged = (0..nd - 2).chain([axis_full]).map(|k| shape[k]).product::<usize>();
+    let (shape_2d, stride_2d) = match axis {
+        0 => ([merged, shape[other_full]], [unit, stride[other_full]]),
+        _ => ([shape[other_full], merged], [stride[other_full], unit]),
+    };
+    let layout = unsafe { Layout::new_unchecked(shape_2d, stride_2d, l.offset()) };
+    return Some((layout, unit));
+}
+
+/* #endregion */
+
+/* #region tensordot */
+
+/// Merge axes into one strided axis, in row-major (C) or column-major (F)
+/// order of axes.
+///
+/// Returns merged size and stride; `None` if axes could not be merged without
+/// data copy.
+pub(crate) fn merge_axes(
+    shape: &[usize],
+    stride: &[isize],
+    order: TensorOrder,
+) -> Option<(usize, isize)> {
+    let size = shape.iter().product::<usize>();
+    // axes of size 1 do not affect memory access
+    let mut axes = (0..shape.len()).filter(|&k| shape[k] != 1).collect_vec();
+    if order == TensorOrder::C {
+        axes.reverse();
+    }
+    // now axes are ordered from fastest to slowest
+    for w in axes.windows(2) {
+        if stride[w[1]] != stride[w[0]] * shape[w[0]] as isize {
+            return None;
+        }
+    }
+    let stride = axes.first().map(|&k| stride[k]).unwrap_or(1);
+    return Some((size, stride));
+}
+
+/// View layout as a matrix, with the first `nrow` axes merged as rows and the
+/// remaining axes merged as columns.
+///
+/// Axes are merged in order `order`, i.e., for `TensorOrder::C`, the last
+/// axis of each group is the fastest one. Returns `None` if this matrix view
+/// does not exist without data copy.
+pub fn layout_as_matrix(
+    layout: &Layout<IxD>,
+    nrow: usize,
+    order: TensorOrder,
+) -> Option<Layout<Ix2>> {
+    layout_as_batched_matrix(layout, 0, nrow, order)?.into_dim::<Ix2>().ok()
+}
+
+/// View layout as batched matrices `[..., m, n]`.
+///
+/// The first `nbatch` axes are kept as batch axes; the next `nrow` axes are
+/// merged as rows and the remaining axes are merged as columns.
+///
+/// # See also
+///
+/// [`layout_as_matrix`]
+pub fn layout_as_batched_matrix(
+    layout: &Layout<IxD>,
+    nbatch: usize,
+    nrow: usize,
+    order: TensorOrder,
+) -> Option<Layout<IxD>> {
+    if nbatch + nrow > layout.ndim() {
+        return None;
+    }
+    let (shape_batch, shape_mat) = layout.shape().split_at(nbatch);
+    let (stride_batch, stride_mat) = layout.stride().split_at(nbatch);
+    let (shape_row, shape_col) = shape_mat.split_at(nrow);
+    let (stride_row, stride_col) = stride_mat.split_at(nrow);
+    let (m, sm) = merge_axes(shape_row, stride_row, order)?;
+    let (n, sn) = merge_axes(shape_col, stride_col, order)?;
+    let shape = [shape_batch, &[m, n]].concat();
+    let stride = [stride_batch, &[sm, sn]].concat();
+    return Some(unsafe { Layout::new_unchecked(shape, stride, layout.offset()) });
+}
+
+/* #endregion */
+
 #[cfg(test)]
 mod test_fixed {
     use super::*;
@@ -529,7 +677,7 @@ mod test_fixed {
         let lb = [4].c();
         let config = LayoutMatMulConfig::layout_matmul(&la, &lb, Order::C).unwrap();
         assert_eq!(config.matmul_type, MatMulType::InnerDot);
-        assert_eq!(config.lc.shape(), &[]);
+        assert_eq!(config.lc.shape(), &[] as &[usize]);
         assert_eq!(config.la_matmul.shape(), &[4]);
         assert_eq!(config.lb_matmul.shape(), &[4]);
 
@@ -564,3 +712,59 @@ mod test_fixed {
         assert_eq!(config.lc, Layout::new([2, 3, 4, 5, 7], [12, 4, 1, 24, 120], 0));
     }
 }
+
+#[cfg(test)]
+mod test_congruence {
+    use super::*;
+
+    #[test]
+    fn test_layout_merge_batch_axis() {
+        // c-contiguous: batch merges into rows
+        let l = [3, 4, 4].c().into_dim::<IxD>().unwrap();
+        let (l2, unit) = layout_merge_batch_axis(&l, 0).unwrap();
+        assert_eq!(l2, Layout::new([12, 4], [4, 1], 0));
+        assert_eq!(unit, 4);
+        assert!(layout_merge_batch_axis(&l, 1).is_none());
+
+        // f-contiguous: batch also merges into rows, with batch index fastest
+        let l = [3, 4, 4].f().into_dim::<IxD>().unwrap();
+        let (l2, unit) = layout_merge_batch_axis(&l, 0).unwrap();
+        assert_eq!(l2, Layout::new([12, 4], [1, 12], 0));
+        assert_eq!(unit, 1);
+
+        // batch stacked along columns, with padded rows
+        let l = Layout::new(vec![3, 4, 4], vec![4, 24, 1], 0);
+        assert!(layout_merge_batch_axis(&l, 0).is_none());
+        let (l2, unit) = layout_merge_batch_axis(&l, 1).unwrap();
+        assert_eq!(l2, Layout::new([4, 12], [24, 1], 0));
+        assert_eq!(unit, 1);
+    }
+}
+
+#[cfg(test)]
+mod test_tensordot {
+    use super::*;
+
+    #[test]
+    fn test_layout_as_matrix() {
+        let l = [2, 3, 4, 5].c().into_dim::<IxD>().unwrap();
+        let l2 = layout_as_matrix(&l, 2, TensorOrder::C).unwrap();
+        assert_eq!(l2, Layout::new([6, 20], [20, 1], 0));
+        assert!(layout_as_matrix(&l, 2, TensorOrder::F).is_none());
+        let l2 = layout_as_matrix(&l, 0, TensorOrder::C).unwrap();
+        assert_eq!(l2.shape(), &[1, 120]);
+
+        // reversed axes in each group are mergeable in F order
+        let l = l.permute_dims(&[1, 0, 3, 2]).unwrap();
+        assert!(layout_as_matrix(&l, 2, TensorOrder::C).is_none());
+        let l2 = layout_as_matrix(&l, 2, TensorOrder::F).unwrap();
+        assert_eq!(l2, Layout::new([6, 20], [20, 1], 0));
+
+        // batched
+        let l = [2, 3, 4, 5].c().into_dim::<IxD>().unwrap();
+        let l = l.permute_dims(&[1, 0, 2, 3]).unwrap();
+        let l2 = layout_as_batched_matrix(&l, 1, 1, TensorOrder::C).unwrap();
+        assert_eq!(l2, Layout::new(vec![3, 2, 20], vec![20, 60, 1], 0));
+        assert!(layout_as_batched_matrix(&l, 0, 2, TensorOrder::C).is_none());
+    }
+}
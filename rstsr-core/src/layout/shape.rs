@@ -283,9 +283,9 @@ mod test {
         assert_eq!(stride, [1, 2, 6]);
         // empty dimension test
         let stride = [].stride_f_contig();
-        assert_eq!(stride, []);
+        assert_eq!(stride, [] as [isize; 0]);
         let stride = vec![].stride_f_contig();
-        assert_eq!(stride, vec![]);
+        assert_eq!(stride, Vec::<isize>::new());
         // zero element test
         let stride = [1, 2, 0, 4].stride_f_contig();
         println!("{stride:?}");
@@ -298,9 +298,9 @@ mod test {
         assert_eq!(stride, [15, 5, 1]);
         // empty dimension test
         let stride = [].stride_c_contig();
-        assert_eq!(stride, []);
+        assert_eq!(stride, [] as [isize; 0]);
         let stride = vec![].stride_c_contig();
-        assert_eq!(stride, vec![]);
+        assert_eq!(stride, Vec::<isize>::new());
         // zero element test
         let stride = [1, 2, 0, 4].stride_c_contig();
         println!("{stride:?}");
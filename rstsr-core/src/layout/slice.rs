@@ -2,6 +2,7 @@ use num::Integer;
 
 /// Slicing for python (numpy) convention; somehow similar to Rust's range.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Slice<T>
 where
     T: Integer + Clone,
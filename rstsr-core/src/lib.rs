@@ -16,7 +16,7 @@ pub mod storage;
 
 pub mod tensor;
 pub mod tensorbase;
-pub use tensorbase::{Tensor, TensorBase, TensorCow, TensorView, TensorViewMut};
+pub use tensorbase::{Tensor, TensorArc, TensorBase, TensorCow, TensorView, TensorViewMut};
 
 pub mod format;
 
@@ -28,6 +28,27 @@ pub mod device_faer;
 
 pub mod device_cpu_serial;
 
+#[cfg(any(test, feature = "counting"))]
+pub mod device_cpu_counting;
+
+#[cfg(feature = "std")]
+pub mod device_cpu_pooled;
+
+#[cfg(feature = "mmap")]
+pub mod feature_mmap;
+
+#[cfg(feature = "std")]
+pub mod io;
+
+#[cfg(feature = "serde")]
+pub mod feature_serde;
+
+#[cfg(feature = "ndarray")]
+pub mod feature_ndarray;
+
+#[cfg(feature = "nalgebra")]
+pub mod feature_nalgebra;
+
 mod dev_utilities;
 
 pub type DeviceCpu = device_cpu_serial::DeviceCpuSerial;
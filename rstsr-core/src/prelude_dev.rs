@@ -1,4 +1,5 @@
 extern crate alloc;
+pub use alloc::collections::{BTreeMap, BTreeSet};
 pub use alloc::format;
 pub use alloc::string::{String, ToString};
 pub use alloc::vec;
@@ -15,9 +16,11 @@ pub use crate::flags::*;
 pub use crate::layout::*;
 
 pub use crate::storage::assignment::*;
+pub use crate::storage::cpu_rawvec::*;
 pub use crate::storage::creation::*;
 pub use crate::storage::device::*;
 pub use crate::storage::matmul::*;
+pub use crate::storage::memory::*;
 pub use crate::storage::operators::*;
 
 pub use crate::device_cpu_serial::assignment::*;
@@ -25,6 +28,11 @@ pub use crate::device_cpu_serial::device::*;
 pub use crate::device_cpu_serial::op_with_func::*;
 pub use crate::DeviceCpu;
 
+#[cfg(any(test, feature = "counting"))]
+pub use crate::device_cpu_counting::device::*;
+#[cfg(feature = "std")]
+pub use crate::device_cpu_pooled::{aligned_vec::*, device::*};
+
 #[allow(unused_imports)]
 pub(crate) use crate::dev_utilities::*;
 
@@ -33,8 +41,11 @@ pub use crate::tensor::creation::*;
 pub use crate::tensor::data::*;
 pub use crate::tensor::device_conversion::*;
 pub use crate::tensor::ext_conversion::*;
+pub use crate::tensor::iterator::*;
+pub use crate::tensor::iterator_axes::*;
 pub use crate::tensor::manuplication::*;
 pub use crate::tensor::ownership_conversion::*;
+pub use crate::tensor::zip::*;
 
 #[cfg(feature = "rayon")]
 pub use crate::feature_rayon::assignment::*;
@@ -42,11 +53,19 @@ pub use crate::feature_rayon::assignment::*;
 pub use crate::feature_rayon::device::*;
 #[cfg(feature = "rayon")]
 pub use crate::feature_rayon::op_with_func::*;
+#[cfg(feature = "rayon")]
+pub use crate::feature_rayon::tensor_par_iter::*;
 
 #[cfg(feature = "faer")]
 pub use crate::device_faer::device::*;
 
-pub use crate::{Tensor, TensorBase, TensorCow, TensorView, TensorViewMut};
+#[cfg(feature = "ndarray")]
+pub use crate::feature_ndarray::conversion::*;
+
+#[cfg(feature = "nalgebra")]
+pub use crate::feature_nalgebra::conversion::*;
+
+pub use crate::{Tensor, TensorArc, TensorBase, TensorCow, TensorView, TensorViewMut};
 
 pub use crate::slice;
 pub use crate::{rstsr_assert, rstsr_assert_eq, rstsr_invalid, rstsr_pattern, rstsr_raise};
@@ -0,0 +1,610 @@
+//! Raw vector of CPU devices, which either owns or borrows its buffer.
+//!
+//! Views of data that is not owned by a tensor (such as memory-mapped files,
+//! or sub-views given by mutable axis iteration) are storages whose raw vector
+//! borrows the buffer. Borrowed buffers are never freed, reallocated or (for
+//! read-only borrows) written through; cloning always gives an owned copy, so
+//! borrowed buffers can not escape from the lifetime-bounded view that holds
+//! them.
+//!
+//! A borrow whose elements do not fill its buffer (such as a row of
+//! column-major matrix) records layout of the borrowed elements. Other
+//! elements may be mutably borrowed by others; copies of the raw vector
+//! (clone, [`CpuRawVec::to_vec`], comparison and formatting) never read them.
+//!
+//! Computation kernels access raw vectors by [`CpuRawSlice`] and
+//! [`CpuRawSliceMut`], which are pointer and length of buffer. Elements are
+//! accessed by index (or by contiguous sub-range); a slice reference over the
+//! whole buffer is never formed, since mutable sub-views may borrow interleaved
+//! elements of the same buffer (such as columns of a row-major matrix).
+
+use crate::prelude_dev::*;
+use core::marker::PhantomData;
+use core::ops::{Index, IndexMut, Range};
+use core::ptr::NonNull;
+
+/* #region raw slice */
+
+/// Read-only buffer of CPU raw vector, as pointer and length.
+///
+/// This behaves as `&'a [T]` for the elements that are accessed, but does
+/// not borrow the whole buffer.
+pub struct CpuRawSlice<'a, T> {
+    ptr: NonNull<T>,
+    len: usize,
+    _phantom: PhantomData<&'a [T]>,
+}
+
+/// Mutable buffer of CPU raw vector, as pointer and length.
+///
+/// This behaves as `&'a mut [T]` for the elements that are accessed, but does
+/// not borrow the whole buffer.
+pub struct CpuRawSliceMut<'a, T> {
+    ptr: NonNull<T>,
+    len: usize,
+    _phantom: PhantomData<&'a mut [T]>,
+}
+
+// SAFETY: behaves as `&[T]` and `&mut [T]`
+unsafe impl<T: Sync> Send for CpuRawSlice<'_, T> {}
+unsafe impl<T: Sync> Sync for CpuRawSlice<'_, T> {}
+unsafe impl<T: Send> Send for CpuRawSliceMut<'_, T> {}
+unsafe impl<T: Sync> Sync for CpuRawSliceMut<'_, T> {}
+
+impl<T> Clone for CpuRawSlice<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for CpuRawSlice<'_, T> {}
+
+impl<'a, T> CpuRawSlice<'a, T> {
+    /// # Safety
+    ///
+    /// `ptr` must be non-null, and valid for reading the elements that are
+    /// accessed (among `len` elements) for lifetime `'a`.
+    pub unsafe fn from_raw_parts(ptr: *const T, len: usize) -> Self {
+        let ptr = NonNull::new_unchecked(ptr as *mut T);
+        CpuRawSlice { ptr, len, _phantom: PhantomData }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr.as_ptr()
+    }
+
+    /// Reference to element at `index`; `None` if out of bounds.
+    pub fn get(self, index: usize) -> Option<&'a T> {
+        (index < self.len).then(|| unsafe { self.get_unchecked(index) })
+    }
+
+    /// Reference to element at `index`, which lives as long as the buffer
+    /// (instead of `self`).
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    pub fn at(self, index: usize) -> &'a T {
+        self.get(index).unwrap_or_else(|| index_out_of_bounds(index, self.len))
+    }
+
+    /// # Safety
+    ///
+    /// `index` must be in bounds.
+    pub unsafe fn get_unchecked(self, index: usize) -> &'a T {
+        debug_assert!(index < self.len);
+        &*self.ptr.as_ptr().add(index)
+    }
+
+    /// Slice of contiguous elements in `range`.
+    pub fn get_range(self, range: Range<usize>) -> &'a [T] {
+        check_range(&range, self.len);
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr().add(range.start), range.len()) }
+    }
+
+    /// Iterator of all elements, one by one.
+    pub fn iter(self) -> impl DoubleEndedIterator<Item = &'a T> + ExactSizeIterator {
+        (0..self.len).map(move |i| unsafe { &*self.ptr.as_ptr().add(i) })
+    }
+
+    /// Copy of all elements into vector, one by one.
+    pub fn to_vec(self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<'a, T> CpuRawSliceMut<'a, T> {
+    /// # Safety
+    ///
+    /// `ptr` must be non-null, and valid for reading and writing the elements
+    /// that are accessed (among `len` elements) for lifetime `'a`; these
+    /// elements must not be accessed by others meanwhile.
+    pub unsafe fn from_raw_parts(ptr: *mut T, len: usize) -> Self {
+        let ptr = NonNull::new_unchecked(ptr);
+        CpuRawSliceMut { ptr, len, _phantom: PhantomData }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr.as_ptr()
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    /// Shorter-lived mutable buffer, for passing to other functions.
+    pub fn reborrow(&mut self) -> CpuRawSliceMut<'_, T> {
+        CpuRawSliceMut { ptr: self.ptr, len: self.len, _phantom: PhantomData }
+    }
+
+    /// Read-only buffer of the same elements.
+    pub fn as_raw_slice(&self) -> CpuRawSlice<'_, T> {
+        CpuRawSlice { ptr: self.ptr, len: self.len, _phantom: PhantomData }
+    }
+
+    /// Mutable reference to element at `index`; `None` if out of bounds.
+    pub fn get_mut(self, index: usize) -> Option<&'a mut T> {
+        (index < self.len).then(|| unsafe { &mut *self.ptr.as_ptr().add(index) })
+    }
+
+    /// Mutable slice of contiguous elements in `range`.
+    pub fn get_range_mut(self, range: Range<usize>) -> &'a mut [T] {
+        check_range(&range, self.len);
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr().add(range.start), range.len()) }
+    }
+}
+
+#[cold]
+fn index_out_of_bounds(index: usize, len: usize) -> ! {
+    panic!("index {index} out of bounds of buffer of length {len}")
+}
+
+#[inline]
+fn check_range(range: &Range<usize>, len: usize) {
+    if range.start > range.end || range.end > len {
+        panic!("range {range:?} out of bounds of buffer of length {len}");
+    }
+}
+
+impl<T> Index<usize> for CpuRawSlice<'_, T> {
+    type Output = T;
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        self.at(index)
+    }
+}
+
+impl<T> Index<Range<usize>> for CpuRawSlice<'_, T> {
+    type Output = [T];
+    #[inline]
+    fn index(&self, range: Range<usize>) -> &[T] {
+        self.get_range(range)
+    }
+}
+
+impl<T> Index<usize> for CpuRawSliceMut<'_, T> {
+    type Output = T;
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        self.as_raw_slice().at(index)
+    }
+}
+
+impl<T> IndexMut<usize> for CpuRawSliceMut<'_, T> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        let len = self.len;
+        self.reborrow().get_mut(index).unwrap_or_else(|| index_out_of_bounds(index, len))
+    }
+}
+
+impl<T> Index<Range<usize>> for CpuRawSliceMut<'_, T> {
+    type Output = [T];
+    #[inline]
+    fn index(&self, range: Range<usize>) -> &[T] {
+        self.as_raw_slice().get_range(range)
+    }
+}
+
+impl<T> IndexMut<Range<usize>> for CpuRawSliceMut<'_, T> {
+    #[inline]
+    fn index_mut(&mut self, range: Range<usize>) -> &mut [T] {
+        self.reborrow().get_range_mut(range)
+    }
+}
+
+impl<'a, T> From<&'a [T]> for CpuRawSlice<'a, T> {
+    fn from(slc: &'a [T]) -> Self {
+        unsafe { CpuRawSlice::from_raw_parts(slc.as_ptr(), slc.len()) }
+    }
+}
+
+impl<'a, T> From<&'a mut [T]> for CpuRawSliceMut<'a, T> {
+    fn from(slc: &'a mut [T]) -> Self {
+        unsafe { CpuRawSliceMut::from_raw_parts(slc.as_mut_ptr(), slc.len()) }
+    }
+}
+
+impl<'a, T> From<CpuRawSliceMut<'a, T>> for CpuRawSlice<'a, T> {
+    fn from(slc: CpuRawSliceMut<'a, T>) -> Self {
+        CpuRawSlice { ptr: slc.ptr, len: slc.len, _phantom: PhantomData }
+    }
+}
+
+impl<T: Debug> Debug for CpuRawSlice<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Debug> Debug for CpuRawSliceMut<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&self.as_raw_slice(), f)
+    }
+}
+
+/// Raw vectors of CPU devices, accessed by computation kernels.
+pub trait CpuRawVecAPI<T> {
+    fn as_raw_slice(&self) -> CpuRawSlice<'_, T>;
+    fn as_raw_slice_mut(&mut self) -> CpuRawSliceMut<'_, T>;
+}
+
+/* #endregion */
+
+/* #region raw vector */
+
+enum CpuRawVecRepr<T> {
+    Owned(Vec<T>),
+    Borrowed(CpuRawBorrow<T>),
+    BorrowedMut(CpuRawBorrow<T>),
+}
+
+/// Buffer at pointer of length; `sparse` is layout of the borrowed elements
+/// if they do not fill the buffer.
+struct CpuRawBorrow<T> {
+    ptr: NonNull<T>,
+    len: usize,
+    sparse: Option<Layout<IxD>>,
+}
+
+impl<T> CpuRawBorrow<T> {
+    fn new(ptr: NonNull<T>, len: usize) -> Self {
+        CpuRawBorrow { ptr, len, sparse: None }
+    }
+
+    fn with_layout<D>(ptr: NonNull<T>, len: usize, layout: &Layout<D>) -> Self
+    where
+        D: DimAPI,
+    {
+        if layout.size() == 0 {
+            // no elements are borrowed
+            return CpuRawBorrow::new(ptr, 0);
+        }
+        if layout.size() == len && layout.check_strides().is_ok() {
+            return CpuRawBorrow::new(ptr, len);
+        }
+        let sparse = Some(layout.to_dim::<IxD>().unwrap());
+        CpuRawBorrow { ptr, len, sparse }
+    }
+}
+
+/// Raw vector of CPU devices: an owned [`Vec`] or a borrowed buffer.
+pub struct CpuRawVec<T> {
+    repr: CpuRawVecRepr<T>,
+}
+
+// SAFETY: owned variant is Vec; borrowed variants behave as `&[T]` or
+// `&mut [T]`
+unsafe impl<T: Send + Sync> Send for CpuRawVec<T> {}
+unsafe impl<T: Sync> Sync for CpuRawVec<T> {}
+
+impl<T> CpuRawVec<T> {
+    /// Raw vector that borrows `slc` for reading.
+    ///
+    /// # Safety
+    ///
+    /// The returned value (and anything holding it) must not be used after the
+    /// borrow of `slc` ends. This is usually ensured by putting it into a
+    /// [`DataRef`] of the same lifetime.
+    pub unsafe fn from_slice(slc: &[T]) -> Self {
+        let ptr = NonNull::from(slc).cast();
+        CpuRawVec { repr: CpuRawVecRepr::Borrowed(CpuRawBorrow::new(ptr, slc.len())) }
+    }
+
+    /// Raw vector that borrows `slc` for reading and writing.
+    ///
+    /// # Safety
+    ///
+    /// The returned value (and anything holding it) must not be used after the
+    /// borrow of `slc` ends. This is usually ensured by putting it into a
+    /// [`DataRefMut`] of the same lifetime.
+    pub unsafe fn from_slice_mut(slc: &mut [T]) -> Self {
+        let ptr = NonNull::from(&mut *slc).cast();
+        CpuRawVec { repr: CpuRawVecRepr::BorrowedMut(CpuRawBorrow::new(ptr, slc.len())) }
+    }
+
+    /// Raw vector that borrows elements indexed by `layout` for reading, from
+    /// buffer at `ptr` of length `len`, without forming a slice reference.
+    ///
+    /// This is used when the borrowed elements may be interleaved with
+    /// elements that are mutably borrowed by others, such as a row of
+    /// column-major matrix; these elements are never accessed.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null and aligned, and valid for reading the elements
+    /// indexed by `layout` (which must be among `len` elements) while the
+    /// returned value is used.
+    pub(crate) unsafe fn from_raw_parts<D>(ptr: *const T, len: usize, layout: &Layout<D>) -> Self
+    where
+        D: DimAPI,
+    {
+        let ptr = NonNull::new_unchecked(ptr as *mut T);
+        CpuRawVec { repr: CpuRawVecRepr::Borrowed(CpuRawBorrow::with_layout(ptr, len, layout)) }
+    }
+
+    /// Raw vector that borrows elements indexed by `layout` for reading and
+    /// writing, from buffer at `ptr` of length `len`, without forming a slice
+    /// reference.
+    ///
+    /// This is used when several raw vectors borrow interleaved (but not
+    /// overlapping) elements of the same buffer.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null and aligned, and valid for reading and writing
+    /// the elements indexed by `layout` (which must be among `len` elements)
+    /// while the returned value is used; these elements must not be accessed
+    /// by others meanwhile.
+    pub(crate) unsafe fn from_raw_parts_mut<D>(ptr: *mut T, len: usize, layout: &Layout<D>) -> Self
+    where
+        D: DimAPI,
+    {
+        let ptr = NonNull::new_unchecked(ptr);
+        CpuRawVec { repr: CpuRawVecRepr::BorrowedMut(CpuRawBorrow::with_layout(ptr, len, layout)) }
+    }
+
+    /// Whether the buffer is borrowed (instead of owned).
+    pub fn is_borrowed(&self) -> bool {
+        !matches!(self.repr, CpuRawVecRepr::Owned(_))
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.repr {
+            CpuRawVecRepr::Owned(vec) => vec.len(),
+            CpuRawVecRepr::Borrowed(b) | CpuRawVecRepr::BorrowedMut(b) => b.len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        self.raw_slice().as_ptr()
+    }
+
+    fn raw_slice(&self) -> CpuRawSlice<'_, T> {
+        match &self.repr {
+            CpuRawVecRepr::Owned(vec) => CpuRawSlice::from(vec.as_slice()),
+            CpuRawVecRepr::Borrowed(b) | CpuRawVecRepr::BorrowedMut(b) => unsafe {
+                CpuRawSlice::from_raw_parts(b.ptr.as_ptr(), b.len)
+            },
+        }
+    }
+
+    /// Elements of buffer to be copied, compared or formatted.
+    ///
+    /// Elements not borrowed by a sparse borrow are given as the first
+    /// borrowed element, so that they are never read.
+    fn elements(&self) -> impl Iterator<Item = &T> + '_ {
+        let slc = self.raw_slice();
+        let indices = match &self.repr {
+            CpuRawVecRepr::Borrowed(b) | CpuRawVecRepr::BorrowedMut(b) => {
+                b.sparse.as_ref().map(|layout| {
+                    let mut iter = IterLayoutColMajor::new(layout).unwrap();
+                    let first = iter.next().unwrap();
+                    let mut indices = vec![first; b.len];
+                    iter.for_each(|i| indices[i] = i);
+                    indices
+                })
+            },
+            CpuRawVecRepr::Owned(_) => None,
+        };
+        (0..slc.len()).map(move |i| slc.at(indices.as_ref().map_or(i, |indices| indices[i])))
+    }
+
+    /// Convert to vector; borrowed buffer is copied element by element.
+    pub fn into_vec(self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        match self.repr {
+            CpuRawVecRepr::Owned(vec) => vec,
+            _ => self.to_vec(),
+        }
+    }
+
+    /// Copy to vector.
+    ///
+    /// For a borrow whose elements do not fill its buffer, the other elements
+    /// are filled by the first borrowed element.
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.elements().cloned().collect()
+    }
+}
+
+impl<T> CpuRawVecAPI<T> for CpuRawVec<T>
+where
+    T: Clone,
+{
+    fn as_raw_slice(&self) -> CpuRawSlice<'_, T> {
+        self.raw_slice()
+    }
+
+    /// Buffer borrowed for reading only is copied into an owned vector first.
+    fn as_raw_slice_mut(&mut self) -> CpuRawSliceMut<'_, T> {
+        if let CpuRawVecRepr::Borrowed(..) = self.repr {
+            self.repr = CpuRawVecRepr::Owned(self.to_vec());
+        }
+        match &mut self.repr {
+            CpuRawVecRepr::Owned(vec) => CpuRawSliceMut::from(vec.as_mut_slice()),
+            CpuRawVecRepr::BorrowedMut(b) => unsafe {
+                CpuRawSliceMut::from_raw_parts(b.ptr.as_ptr(), b.len)
+            },
+            CpuRawVecRepr::Borrowed(..) => unreachable!(),
+        }
+    }
+}
+
+impl<T> Clone for CpuRawVec<T>
+where
+    T: Clone,
+{
+    /// Clone is always owned, even if the buffer is borrowed; borrowed
+    /// buffer is copied element by element.
+    fn clone(&self) -> Self {
+        CpuRawVec::from(self.to_vec())
+    }
+}
+
+impl<T> Default for CpuRawVec<T> {
+    fn default() -> Self {
+        CpuRawVec::from(Vec::new())
+    }
+}
+
+impl<T: Debug> Debug for CpuRawVec<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.elements()).finish()
+    }
+}
+
+impl<T> From<Vec<T>> for CpuRawVec<T> {
+    fn from(vec: Vec<T>) -> Self {
+        CpuRawVec { repr: CpuRawVecRepr::Owned(vec) }
+    }
+}
+
+impl<T> From<CpuRawVec<T>> for Vec<T>
+where
+    T: Clone,
+{
+    fn from(rawvec: CpuRawVec<T>) -> Self {
+        rawvec.into_vec()
+    }
+}
+
+impl<T, U> PartialEq<CpuRawVec<U>> for CpuRawVec<T>
+where
+    T: PartialEq<U>,
+{
+    fn eq(&self, other: &CpuRawVec<U>) -> bool {
+        self.len() == other.len() && self.elements().zip(other.elements()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T, U> PartialEq<Vec<U>> for CpuRawVec<T>
+where
+    T: PartialEq<U>,
+{
+    fn eq(&self, other: &Vec<U>) -> bool {
+        self.len() == other.len() && self.elements().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T, U> PartialEq<CpuRawVec<U>> for Vec<T>
+where
+    T: PartialEq<U>,
+{
+    fn eq(&self, other: &CpuRawVec<U>) -> bool {
+        self.len() == other.len() && self.iter().zip(other.elements()).all(|(a, b)| a == b)
+    }
+}
+
+/* #endregion */
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cpu_rawvec_borrowed() {
+        let vec = vec![1, 2, 3];
+        let a = unsafe { CpuRawVec::from_slice(&vec) };
+        assert!(a.is_borrowed());
+        assert_eq!(a.as_ptr(), vec.as_ptr());
+        let b = a.clone();
+        assert!(!b.is_borrowed());
+        assert_ne!(b.as_ptr(), vec.as_ptr());
+
+        // read-only borrow is copied on write
+        let mut a = a;
+        a.as_raw_slice_mut()[0] = 10;
+        assert!(!a.is_borrowed());
+        assert_eq!(vec, vec![1, 2, 3]);
+        assert_eq!(a, vec![10, 2, 3]);
+
+        let mut vec = vec;
+        let mut a = unsafe { CpuRawVec::from_slice_mut(&mut vec) };
+        a.as_raw_slice_mut()[0] = 10;
+        assert!(a.is_borrowed());
+        assert_eq!(a.into_vec(), vec![10, 2, 3]);
+        assert_eq!(vec, vec![10, 2, 3]);
+    }
+
+    #[test]
+    fn test_cpu_raw_slice() {
+        let mut vec = vec![1, 2, 3, 4, 5, 6];
+        let ptr = vec.as_mut_ptr();
+        // two raw vectors of interleaved elements
+        let layout = Layout::new([3], [2], 0);
+        let mut a = unsafe { CpuRawVec::from_raw_parts_mut(ptr, 5, &layout) };
+        let mut b = unsafe { CpuRawVec::from_raw_parts_mut(ptr.add(1), 5, &layout) };
+        let (mut sa, mut sb) = (a.as_raw_slice_mut(), b.as_raw_slice_mut());
+        for i in [0, 2, 4] {
+            sa[i] *= 10;
+            sb[i] = -sb[i];
+        }
+        sa[2..3].copy_from_slice(&[0]);
+        assert_eq!(sa.as_raw_slice().get(4), Some(&50));
+        assert_eq!(sa.as_raw_slice().get(5), None);
+        // elements of the other raw vector are not read by copies
+        assert_eq!(a.to_vec(), vec![10, 10, 0, 10, 50]);
+        assert_eq!(format!("{b:?}"), "[-2, -2, -4, -2, -6]");
+        assert_eq!(b.clone(), vec![-2, -2, -4, -2, -6]);
+        assert_eq!(vec, vec![10, -2, 0, -4, 50, -6]);
+
+        // no elements are borrowed from empty layout
+        let a = unsafe { CpuRawVec::from_raw_parts(ptr, 6, &Layout::new([0, 3], [1, 2], 0)) };
+        assert!(a.is_empty());
+
+        let s = CpuRawSlice::from(vec.as_slice());
+        assert_eq!(&s[1..3], &[-2, 0]);
+        assert_eq!(s.iter().next_back(), Some(&-6));
+        assert_eq!(s.to_vec(), vec);
+        assert!(std::panic::catch_unwind(|| s[6]).is_err());
+    }
+}
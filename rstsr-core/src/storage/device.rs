@@ -20,15 +20,28 @@ where
 
 impl<T, B> Clone for Storage<T, B>
 where
-    B: DeviceRawVecAPI<T>,
+    B: DeviceStorageAPI<T>,
 {
     fn clone(&self) -> Self {
-        Self { rawvec: self.rawvec.clone(), device: self.device.clone() }
+        // call device API, so that devices are aware of the copy
+        B::clone_storage(self)
     }
 }
 
 pub trait DeviceStorageAPI<T>: DeviceRawVecAPI<T> {
     fn new(vector: Self::RawVec, device: Self) -> Storage<T, Self>;
+    /// Copy of storage, used by [`Clone`] of [`Storage`].
+    fn clone_storage(storage: &Storage<T, Self>) -> Storage<T, Self> {
+        Self::new(storage.rawvec.clone(), storage.device.clone())
+    }
+    /// Copy of storage, failing if its bytes exceed memory limit of device.
+    fn try_clone_storage(storage: &Storage<T, Self>) -> Result<Storage<T, Self>>
+    where
+        Self: DeviceMemoryAPI,
+    {
+        let device = storage.device.acquire_memory::<T>(storage.len())?;
+        Ok(Storage { rawvec: storage.rawvec.clone(), device })
+    }
     fn len(storage: &Storage<T, Self>) -> usize;
     fn is_empty(storage: &Storage<T, Self>) -> bool {
         storage.len() == 0
@@ -66,7 +79,18 @@ where
     }
 
     pub fn new(vector: B::RawVec, device: B) -> Self {
-        Self { rawvec: vector, device }
+        B::new(vector, device)
+    }
+
+    /// Copy of storage, checked against memory limit of device.
+    ///
+    /// [`Clone`] of storage is always accounted and never fails; use this
+    /// function when the limit should be respected.
+    pub fn try_clone(&self) -> Result<Self>
+    where
+        B: DeviceMemoryAPI,
+    {
+        B::try_clone_storage(self)
     }
 
     pub fn len(&self) -> usize {
@@ -153,3 +153,28 @@ where
         beta: TC,
     ) -> Result<()>;
 }
+
+/// Matrix congruence `c = a^T b a`.
+///
+/// Matrix `b` could be batched with shape `[..., n, n]`, and `c` is of shape
+/// `[..., m, m]` with the same leading dimensions, while `a` is of shape
+/// `[n, m]`.
+///
+/// If `uplo` is given, `b` is treated as symmetric and only its triangle
+/// `uplo` is referenced; the output `c` is then also symmetric.
+pub trait DeviceCongruenceAPI<T>
+where
+    T: Mul<T, Output = T> + Add<T, Output = T>,
+    Self: DeviceAPI<T>,
+{
+    fn congruence(
+        &self,
+        c: &mut Storage<T, Self>,
+        lc: &Layout<IxD>,
+        a: &Storage<T, Self>,
+        la: &Layout<Ix2>,
+        b: &Storage<T, Self>,
+        lb: &Layout<IxD>,
+        uplo: Option<TensorUpLo>,
+    ) -> Result<()>;
+}
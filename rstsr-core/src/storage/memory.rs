@@ -0,0 +1,331 @@
+//! Memory accounting of devices.
+//!
+//! A [`MemoryTracker`] counts live and peak bytes of storages owned by
+//! devices attached to it, and refuses allocations over its limit with
+//! [`Error::DeviceError`].
+//!
+//! Creation functions (`empty_impl`, `zeros_impl`, etc.) reserve bytes before
+//! allocating, and fail if the limit would be exceeded; device conversion
+//! goes through `outof_cpu_vec` of the target device, and is checked the
+//! same way. [`Storage::try_clone`] and
+//! [`TensorBase::try_to_owned_keep_layout`] are the checked counterparts of
+//! copying storage.
+//!
+//! The following paths can not fail, and bypass the limit (bytes are still
+//! accounted, so they may push live bytes over the limit, and following
+//! allocations fail):
+//!
+//! - [`DeviceStorageAPI::new`] and [`Storage::new`], which hand an existing
+//!   vector to device;
+//! - [`Clone`] of storage, and of owned tensors;
+//! - [`DataAPI::into_owned`] of views (and tensor functions using it, such as
+//!   `into_owned_keep_layout` and `into_shared`);
+//! - [`TensorBase::make_mut`] and other mutable access of shared tensors, which
+//!   clone storage by [`Arc::make_mut`] if it is shared.
+//!
+//! Bytes of a storage are released when the storage (more precisely, the
+//! device handle owned by the storage) is dropped. Cloning a device handle
+//! does not clone the bytes it accounts.
+
+extern crate alloc;
+
+use crate::prelude_dev::*;
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Live and peak bytes of storages, with optional hard limit.
+#[derive(Debug)]
+pub struct MemoryTracker {
+    live: AtomicUsize,
+    peak: AtomicUsize,
+    /// `usize::MAX` for no limit.
+    limit: AtomicUsize,
+}
+
+impl Default for MemoryTracker {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl MemoryTracker {
+    pub fn new(limit: Option<usize>) -> Self {
+        let limit = AtomicUsize::new(limit.unwrap_or(usize::MAX));
+        MemoryTracker { live: AtomicUsize::new(0), peak: AtomicUsize::new(0), limit }
+    }
+
+    pub fn live_bytes(&self) -> usize {
+        self.live.load(Ordering::Relaxed)
+    }
+
+    pub fn peak_bytes(&self) -> usize {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    pub fn limit(&self) -> Option<usize> {
+        match self.limit.load(Ordering::Relaxed) {
+            usize::MAX => None,
+            limit => Some(limit),
+        }
+    }
+
+    /// Set limit of live bytes.
+    ///
+    /// Storages already allocated are not affected, even if live bytes
+    /// exceeds the new limit.
+    pub fn set_limit(&self, limit: Option<usize>) {
+        self.limit.store(limit.unwrap_or(usize::MAX), Ordering::Relaxed);
+    }
+
+    /// Reset peak bytes to current live bytes.
+    pub fn reset_peak(&self) {
+        self.peak.store(self.live_bytes(), Ordering::Relaxed);
+    }
+
+    /// Account `bytes`, or raise error if this exceeds the limit.
+    fn acquire(self: &Arc<Self>, bytes: usize) -> Result<MemoryGuard> {
+        let limit = self.limit.load(Ordering::Relaxed);
+        let mut live = self.live.load(Ordering::Relaxed);
+        loop {
+            let new_live = live.checked_add(bytes).filter(|&n| n <= limit);
+            let Some(new_live) = new_live else {
+                rstsr_raise!(
+                    DeviceError,
+                    "out of memory budget: requested {bytes} bytes, {live} bytes live, limit {limit} bytes"
+                )?
+            };
+            match self.live.compare_exchange_weak(
+                live,
+                new_live,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    self.peak.fetch_max(new_live, Ordering::Relaxed);
+                    return Ok(MemoryGuard { tracker: self.clone(), bytes });
+                },
+                Err(current) => live = current,
+            }
+        }
+    }
+
+    /// Account `bytes` regardless of the limit.
+    fn acquire_unchecked(self: &Arc<Self>, bytes: usize) -> MemoryGuard {
+        let new_live = self.live.fetch_add(bytes, Ordering::Relaxed).saturating_add(bytes);
+        self.peak.fetch_max(new_live, Ordering::Relaxed);
+        MemoryGuard { tracker: self.clone(), bytes }
+    }
+}
+
+/// Bytes of one storage accounted in [`MemoryTracker`], released on drop.
+#[derive(Debug)]
+pub struct MemoryGuard {
+    tracker: Arc<MemoryTracker>,
+    bytes: usize,
+}
+
+impl MemoryGuard {
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+impl Drop for MemoryGuard {
+    fn drop(&mut self) {
+        self.tracker.live.fetch_sub(self.bytes, Ordering::Relaxed);
+    }
+}
+
+/// Memory accounting state of a device handle.
+///
+/// The tracker is shared by clones of device. The guard is owned by the
+/// device handle inside a storage, and is not cloned with the handle; copies
+/// of storage are accounted by [`DeviceStorageAPI::new`] instead.
+#[derive(Debug, Default)]
+pub struct DeviceMemory {
+    tracker: Option<Arc<MemoryTracker>>,
+    guard: Option<MemoryGuard>,
+}
+
+impl Clone for DeviceMemory {
+    fn clone(&self) -> Self {
+        DeviceMemory { tracker: self.tracker.clone(), guard: None }
+    }
+}
+
+impl DeviceMemory {
+    pub fn tracker(&self) -> Option<&Arc<MemoryTracker>> {
+        self.tracker.as_ref()
+    }
+
+    pub fn guard(&self) -> Option<&MemoryGuard> {
+        self.guard.as_ref()
+    }
+
+    /// Memory state for device handle of a new storage of `bytes`.
+    pub fn acquire(&self, bytes: usize) -> Result<DeviceMemory> {
+        let tracker = self.tracker.clone();
+        let guard = match &tracker {
+            Some(tracker) => Some(tracker.acquire(bytes)?),
+            None => None,
+        };
+        return Ok(DeviceMemory { tracker, guard });
+    }
+
+    /// Memory state for device handle of an existing storage of `bytes`,
+    /// accounted regardless of the limit.
+    pub fn acquire_unchecked(&self, bytes: usize) -> DeviceMemory {
+        let tracker = self.tracker.clone();
+        let guard = tracker.as_ref().map(|tracker| tracker.acquire_unchecked(bytes));
+        return DeviceMemory { tracker, guard };
+    }
+}
+
+/// Memory accounting of devices.
+pub trait DeviceMemoryAPI: DeviceBaseAPI {
+    fn memory(&self) -> &DeviceMemory;
+    fn memory_mut(&mut self) -> &mut DeviceMemory;
+
+    /// Memory tracker of this device; `None` if memory is not tracked.
+    fn memory_tracker(&self) -> Option<&Arc<MemoryTracker>> {
+        self.memory().tracker()
+    }
+
+    /// Attach memory tracker to this device (and its clones made after this
+    /// call).
+    ///
+    /// A tracker can be shared by multiple devices, for accounting them
+    /// together.
+    fn set_memory_tracker(&mut self, tracker: Option<Arc<MemoryTracker>>) {
+        *self.memory_mut() = DeviceMemory { tracker, guard: None };
+    }
+
+    /// Attach a new memory tracker with limit of live bytes.
+    fn set_memory_limit(&mut self, limit: Option<usize>) {
+        self.set_memory_tracker(Some(Arc::new(MemoryTracker::new(limit))));
+    }
+
+    /// Device handle for a new storage of `len` elements of `T`, which
+    /// accounts the storage's bytes in memory tracker.
+    ///
+    /// This should be called before allocation, so that allocation over the
+    /// limit fails without touching the allocator.
+    fn acquire_memory<T>(&self, len: usize) -> Result<Self> {
+        let bytes = len.saturating_mul(core::mem::size_of::<T>());
+        let mut device = self.clone();
+        *device.memory_mut() = self.memory().acquire(bytes)?;
+        return Ok(device);
+    }
+
+    /// Device handle for an existing storage of `len` elements of `T`, which
+    /// accounts the storage's bytes regardless of the limit.
+    fn acquire_memory_unchecked<T>(&self, len: usize) -> Self {
+        let bytes = len.saturating_mul(core::mem::size_of::<T>());
+        let mut device = self.clone();
+        *device.memory_mut() = self.memory().acquire_unchecked(bytes);
+        return device;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_memory_limit() {
+        let mut device = DeviceCpuSerial::default();
+        device.set_memory_limit(Some(1000));
+        let tracker = device.memory_tracker().unwrap().clone();
+
+        let a = Tensor::<f64, _, _>::zeros([10, 5], &device);
+        let b = Tensor::<f64, _, _>::ones([10, 5], &device);
+        assert_eq!(tracker.live_bytes(), 800);
+
+        // output of 400 bytes exceeds limit
+        let err = crate::tensor::operators::add(&a, &b).unwrap_err();
+        assert!(matches!(&err, Error::DeviceError(s) if s.contains("out of memory budget")));
+        assert_eq!(tracker.live_bytes(), 800);
+
+        // storage is released on drop, but clones of device are still tracked
+        drop(a);
+        assert_eq!(tracker.live_bytes(), 400);
+        let c = &b + &b;
+        assert_eq!(tracker.live_bytes(), 800);
+        assert_eq!(tracker.peak_bytes(), 800);
+        drop((b, c));
+        assert_eq!(tracker.live_bytes(), 0);
+        assert_eq!(tracker.peak_bytes(), 800);
+        let err = unsafe { DeviceCreationAnyAPI::<f64>::empty_impl(&device, 126) }.unwrap_err();
+        assert!(matches!(err, Error::DeviceError(_)));
+
+        // arange fails before allocation
+        let err = DeviceCreationFloatAPI::<f64>::arange_impl(&device, 0.0, 1e12, 1.0).unwrap_err();
+        assert!(matches!(err, Error::DeviceError(_)));
+        assert_eq!(tracker.live_bytes(), 0);
+    }
+
+    #[test]
+    fn test_memory_copies() {
+        let mut device = DeviceCpuSerial::default();
+        device.set_memory_limit(Some(1000));
+        let tracker = device.memory_tracker().unwrap().clone();
+
+        // storage moved in by `new`
+        let a =
+            Tensor::new(Storage::new(vec![1.0_f64; 10].into(), device.clone()).into(), [10].c());
+        let a = a.unwrap();
+        assert_eq!(tracker.live_bytes(), 80);
+
+        // clone of storage and device handle
+        let b = a.clone();
+        assert_eq!(tracker.live_bytes(), 160);
+        let device_handle = a.device().clone();
+        assert_eq!(tracker.live_bytes(), 160);
+        drop((a, b));
+        assert_eq!(tracker.live_bytes(), 0);
+
+        // device conversion
+        let a = Tensor::<f64, _, _>::zeros([10], &DeviceCpuSerial::default());
+        let a = a.into_device(&device_handle).unwrap();
+        assert_eq!(tracker.live_bytes(), 80);
+        let a = a.into_device(&DeviceCpuSerial::default()).unwrap();
+        assert_eq!(tracker.live_bytes(), 0);
+
+        // view on another device does not own memory
+        let _a_view = a.view().into_device(&device_handle).unwrap();
+        assert_eq!(tracker.live_bytes(), 0);
+
+        // checked copies fail over the limit, unchecked clone does not
+        let a = Tensor::<f64, _, _>::zeros([100], &device_handle);
+        assert_eq!(tracker.live_bytes(), 800);
+        let err = a.data().storage().try_clone().unwrap_err();
+        assert!(matches!(&err, Error::DeviceError(s) if s.contains("out of memory budget")));
+        assert!(a.view().try_to_owned_keep_layout().is_err());
+        assert_eq!(tracker.live_bytes(), 800);
+        let b = a.clone();
+        assert_eq!(tracker.live_bytes(), 1600);
+        drop(b);
+        let a_small = Tensor::<f64, _, _>::zeros([10], &device_handle);
+        assert_eq!(a_small.data().storage().try_clone().unwrap().len(), 10);
+        assert_eq!(tracker.live_bytes(), 880);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_memory_shared_tracker() {
+        let tracker = Arc::new(MemoryTracker::new(None));
+        let mut device_rayon = DeviceCpuRayon::default();
+        device_rayon.set_memory_tracker(Some(tracker.clone()));
+        let mut device_serial = DeviceCpuSerial::default();
+        device_serial.set_memory_tracker(Some(tracker.clone()));
+
+        let a = Tensor::<f32, _, _>::zeros([16], &device_rayon);
+        let b = Tensor::<f32, _, _>::linspace(0.0, 1.0, 16, &device_serial);
+        assert_eq!(tracker.live_bytes(), 128);
+        drop(a);
+        tracker.reset_peak();
+        assert_eq!(tracker.peak_bytes(), 64);
+        drop(b);
+        assert_eq!(tracker.live_bytes(), 0);
+    }
+}
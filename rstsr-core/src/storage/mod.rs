@@ -1,10 +1,14 @@
 pub mod assignment;
+pub mod cpu_rawvec;
 pub mod creation;
 pub mod device;
 pub mod matmul;
+pub mod memory;
 pub mod operators;
 
 pub use assignment::*;
+pub use cpu_rawvec::*;
 pub use creation::*;
 pub use device::*;
 pub use matmul::*;
+pub use memory::*;
@@ -112,3 +112,21 @@ where
 {
     fn sum(&self, a: &Storage<T, Self>, la: &Layout<D>) -> Result<T>;
 }
+
+pub trait OpSumAxesAPI<T>
+where
+    T: Zero + core::ops::Add<Output = T>,
+    Self: DeviceAPI<T>,
+{
+    /// Sum `a` over its last `naxes` axes into `c`.
+    ///
+    /// Layout `lc` should have the same shape as the leading axes of `la`.
+    fn sum_axes(
+        &self,
+        c: &mut Storage<T, Self>,
+        lc: &Layout<IxD>,
+        a: &Storage<T, Self>,
+        la: &Layout<IxD>,
+        naxes: usize,
+    ) -> Result<()>;
+}
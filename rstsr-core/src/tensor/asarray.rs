@@ -1,7 +1,5 @@
 //! Implementation of function `asarray`.
 
-use core::mem::ManuallyDrop;
-
 use crate::prelude_dev::*;
 
 pub trait AsArrayAPI<Param>: Sized {
@@ -90,8 +88,8 @@ where
 {
     fn asarray(input: Vec<T>) -> Result<Self> {
         let layout = [input.len()].c();
-        let device = DeviceCpu {};
-        let storage = Storage::new(input, device);
+        let device = DeviceCpu::default();
+        let storage = Storage::new(input.into(), device);
         let data = DataOwned::from(storage);
         let tensor = unsafe { Tensor::new_unchecked(data, layout) };
         return Ok(tensor);
@@ -120,8 +118,8 @@ where
 {
     fn asarray(input: [T; N]) -> Result<Self> {
         let layout = [input.len()].c();
-        let device = DeviceCpu {};
-        let storage = Storage::new(input.into(), device);
+        let device = DeviceCpu::default();
+        let storage = Storage::new(Vec::from(input).into(), device);
         let data = DataOwned::from(storage);
         let tensor = unsafe { Tensor::new_unchecked(data, layout) };
         return Ok(tensor);
@@ -133,19 +131,7 @@ where
     T: Clone,
 {
     fn asarray(input: &'a [T]) -> Result<Self> {
-        let layout = [input.len()].c();
-        let device = DeviceCpu {};
-
-        let ptr = input.as_ptr();
-        let len = input.len();
-        let rawvec: Vec<T> = unsafe {
-            let ptr = ptr as *mut T;
-            Vec::from_raw_parts(ptr, len, len)
-        };
-        let storage = ManuallyDrop::new(Storage::new(rawvec, device));
-        let data = DataRef::from_manually_drop(storage);
-        let tensor = unsafe { TensorView::new_unchecked(data, layout) };
-        return Ok(tensor);
+        TensorView::from_slice_with_layout(input, [input.len()])
     }
 }
 
@@ -158,14 +144,119 @@ where
 {
     fn from(data: Vec<T>) -> Self {
         let size = data.len();
-        let device = DeviceCpu {};
-        let storage = Storage { rawvec: data, device };
+        let device = DeviceCpu::default();
+        let storage = Storage { rawvec: data.into(), device };
         let data = DataOwned { storage };
         let layout = [size].into();
         Tensor::new(data, layout).unwrap()
     }
 }
 
+/// Shape vector can be casted to tensor in CPU, if layout fits in vector.
+impl<T, D> Tensor<T, D, DeviceCpu>
+where
+    T: Clone,
+    D: DimAPI,
+{
+    /// Tensor of data `vec` with `layout`, without copying.
+    ///
+    /// `layout` can be shape (c-contiguous) or general layout; its strides
+    /// must not overlap, and its index bounds must be within `vec`.
+    pub fn from_shape_vec(layout: impl Into<Layout<D>>, vec: Vec<T>) -> Result<Self> {
+        let storage = Storage::new(vec.into(), DeviceCpu::default());
+        Tensor::new(DataOwned::from(storage), layout.into())
+    }
+}
+
+/* #endregion */
+
+/* #region slice casting to tensor view */
+
+/// Check that indices of `layout` are within data of length `len`.
+fn check_layout_bounds<D>(layout: &Layout<D>, len: usize) -> Result<()>
+where
+    D: DimAPI,
+{
+    let (_, idx_max) = layout.bounds_index()?;
+    rstsr_pattern!(idx_max, ..=len, ValueOutOfRange)?;
+    return Ok(());
+}
+
+impl<'a, T, D> TensorView<'a, T, D, DeviceCpu>
+where
+    T: Clone,
+    D: DimAPI,
+{
+    /// View of slice with `layout`, without copying.
+    ///
+    /// `layout` can be shape (c-contiguous) or general layout (strides may
+    /// overlap, such as broadcasted); its index bounds must be within `slc`.
+    pub fn from_slice_with_layout(slc: &'a [T], layout: impl Into<Layout<D>>) -> Result<Self> {
+        unsafe { Self::from_raw_parts(slc.as_ptr(), slc.len(), layout) }
+    }
+
+    /// View of `len` elements at `ptr` with `layout`.
+    ///
+    /// Index bounds of `layout` are checked against `len`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null and aligned, and valid for reading the elements
+    /// indexed by `layout` (among `len` elements) for lifetime `'a`. Other
+    /// elements are not accessed, so they may be mutably borrowed by others.
+    pub unsafe fn from_raw_parts(
+        ptr: *const T,
+        len: usize,
+        layout: impl Into<Layout<D>>,
+    ) -> Result<Self> {
+        let layout = layout.into();
+        check_layout_bounds(&layout, len)?;
+        let rawvec = CpuRawVec::from_raw_parts(ptr, len, &layout);
+        let data = DataRef::from_borrowed(Storage { rawvec, device: DeviceCpu::default() });
+        return Ok(TensorView::new_unchecked(data, layout));
+    }
+}
+
+impl<'a, T, D> TensorViewMut<'a, T, D, DeviceCpu>
+where
+    T: Clone,
+    D: DimAPI,
+{
+    /// Mutable view of slice with `layout`, without copying.
+    ///
+    /// `layout` can be shape (c-contiguous) or general layout; its strides
+    /// must not overlap, and its index bounds must be within `slc`.
+    pub fn from_slice_mut_with_layout(
+        slc: &'a mut [T],
+        layout: impl Into<Layout<D>>,
+    ) -> Result<Self> {
+        unsafe { Self::from_raw_parts_mut(slc.as_mut_ptr(), slc.len(), layout) }
+    }
+
+    /// Mutable view of `len` elements at `ptr` with `layout`.
+    ///
+    /// Strides and index bounds of `layout` are checked.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null and aligned, and valid for reading and writing
+    /// the elements indexed by `layout` (among `len` elements) for lifetime
+    /// `'a`; these elements must not be accessed by others meanwhile. Other
+    /// elements are not accessed, so they may be borrowed by others.
+    pub unsafe fn from_raw_parts_mut(
+        ptr: *mut T,
+        len: usize,
+        layout: impl Into<Layout<D>>,
+    ) -> Result<Self> {
+        let layout = layout.into();
+        layout.check_strides()?;
+        check_layout_bounds(&layout, len)?;
+        let rawvec = CpuRawVec::from_raw_parts_mut(ptr, len, &layout);
+        let data = DataRefMut::from_borrowed(Storage { rawvec, device: DeviceCpu::default() });
+        return Ok(TensorViewMut::new_unchecked(data, layout));
+    }
+}
+
 /* #endregion */
 
 #[cfg(test)]
@@ -198,9 +289,52 @@ mod tests {
     fn vec_cast_to_tensor() {
         use crate::layout::*;
         let a = Tensor::<f64, Ix<2>> {
-            data: Storage { rawvec: vec![1.12345, 2.0], device: DeviceCpu {} }.into(),
+            data: Storage { rawvec: vec![1.12345, 2.0].into(), device: DeviceCpu::default() }
+                .into(),
             layout: [1, 2].new_c_contig(None),
         };
         println!("{a:6.3?}");
     }
+
+    #[test]
+    fn test_from_slice_with_layout() {
+        let vec = (0..12).collect::<Vec<i32>>();
+        let a = TensorView::from_slice_with_layout(&vec, [3, 4]).unwrap();
+        assert_eq!(a.storage().rawvec().as_ptr(), vec.as_ptr());
+        assert_eq!(a.shape(), &[3, 4]);
+
+        // strided and broadcasted layouts
+        let a = TensorView::from_slice_with_layout(&vec, Layout::new([3, 2], [4, 1], 1)).unwrap();
+        assert_eq!(a.mapv(|x| x).storage().rawvec(), &vec![1, 2, 5, 6, 9, 10]);
+        let a = TensorView::from_slice_with_layout(&vec, unsafe {
+            Layout::new_unchecked([2, 3], [0, -1], 2)
+        })
+        .unwrap();
+        let a = Tensor::asarray((&a, TensorIterOrder::C)).unwrap();
+        assert_eq!(a.storage().rawvec(), &vec![2, 1, 0, 2, 1, 0]);
+        assert!(TensorView::from_slice_with_layout(&vec, [3, 5]).is_err());
+        assert!(TensorView::from_slice_with_layout(&vec, unsafe {
+            Layout::new_unchecked([3], [-1], 1)
+        })
+        .is_err());
+
+        let mut vec = vec;
+        let mut a =
+            TensorViewMut::from_slice_mut_with_layout(&mut vec, Layout::new([2, 2], [3, 1], 5))
+                .unwrap();
+        a.mapv_inplace(|x| -x);
+        assert_eq!(vec[4..11], [4, -5, -6, 7, -8, -9, 10]);
+        assert!(TensorViewMut::from_slice_mut_with_layout(&mut vec, unsafe {
+            Layout::new_unchecked([2, 3], [0, 1], 0)
+        })
+        .is_err());
+        let a = unsafe { TensorViewMut::from_raw_parts_mut(vec.as_mut_ptr(), 4, [4]) }.unwrap();
+        assert_eq!(a.size(), 4);
+
+        let a = Tensor::from_shape_vec([2, 3], vec![1.0; 6]).unwrap();
+        assert_eq!(a.stride(), &[3, 1]);
+        let a = Tensor::from_shape_vec(Layout::new([2], [2], 1), vec![1.0; 4]).unwrap();
+        assert_eq!(a.offset(), 1);
+        assert!(Tensor::from_shape_vec([2, 3], vec![1.0; 5]).is_err());
+    }
 }
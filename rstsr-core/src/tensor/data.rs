@@ -1,4 +1,6 @@
-use core::mem::ManuallyDrop;
+extern crate alloc;
+
+use alloc::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct DataOwned<S>
@@ -11,13 +13,85 @@ where
 #[derive(Debug, Clone)]
 pub enum DataRef<'a, S> {
     TrueRef(&'a S),
-    ManuallyDropOwned(ManuallyDrop<S>),
+    /// Storage that borrows its buffer for lifetime `'a`, such as a storage
+    /// with raw vector
+    /// [`CpuRawVec::from_slice`](crate::storage::CpuRawVec::from_slice).
+    ///
+    /// Cloning shares the borrowed buffer, same to [`DataRef::TrueRef`].
+    Borrowed(DataBorrowed<S>),
 }
 
 #[derive(Debug)]
 pub enum DataRefMut<'a, S> {
     TrueRef(&'a mut S),
-    ManuallyDropOwned(ManuallyDrop<S>),
+    /// Storage that mutably borrows its buffer for lifetime `'a`, such as a
+    /// storage with raw vector
+    /// [`CpuRawVec::from_slice_mut`](crate::storage::CpuRawVec::from_slice_mut).
+    Borrowed(DataBorrowedMut<'a, S>),
+}
+
+/// Storage that borrows its buffer.
+///
+/// The storage is kept private, so that it can not be moved out of the
+/// lifetime of its buffer. It is shared by [`Arc`], so that clones of the view
+/// do not copy the buffer.
+#[derive(Debug)]
+pub struct DataBorrowed<S> {
+    storage: Arc<S>,
+}
+
+impl<S> Clone for DataBorrowed<S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        DataBorrowed { storage: self.storage.clone() }
+    }
+}
+
+/// Storage that mutably borrows its buffer, or a reborrow of it.
+///
+/// The storage is kept private, so that it can not be moved out of the
+/// lifetime of its buffer.
+#[derive(Debug)]
+pub struct DataBorrowedMut<'a, S> {
+    inner: BorrowedMutInner<'a, S>,
+}
+
+#[derive(Debug)]
+enum BorrowedMutInner<'a, S> {
+    Storage(S),
+    Reborrow(&'a mut S),
+}
+
+impl<S> DataBorrowedMut<'_, S> {
+    #[inline]
+    fn storage(&self) -> &S {
+        match &self.inner {
+            BorrowedMutInner::Storage(storage) => storage,
+            BorrowedMutInner::Reborrow(storage) => storage,
+        }
+    }
+
+    #[inline]
+    fn storage_mut(&mut self) -> &mut S {
+        match &mut self.inner {
+            BorrowedMutInner::Storage(storage) => storage,
+            BorrowedMutInner::Reborrow(storage) => storage,
+        }
+    }
+
+    #[inline]
+    fn reborrow(&mut self) -> DataBorrowedMut<'_, S> {
+        DataBorrowedMut { inner: BorrowedMutInner::Reborrow(self.storage_mut()) }
+    }
+}
+
+/// Reference-counted data, shared by clones.
+///
+/// Mutation through [`DataMutAPI`] is copy-on-write: storage is cloned only if
+/// it is shared by other clones.
+#[derive(Debug, Clone)]
+pub struct DataArc<S> {
+    pub(crate) storage: Arc<S>,
 }
 
 #[derive(Debug)]
@@ -53,6 +127,33 @@ impl<S> DataOwned<S> {
     }
 }
 
+impl<S> From<S> for DataArc<S> {
+    #[inline]
+    fn from(data: S) -> Self {
+        Self { storage: Arc::new(data) }
+    }
+}
+
+impl<S> From<DataOwned<S>> for DataArc<S> {
+    #[inline]
+    fn from(data: DataOwned<S>) -> Self {
+        Self { storage: Arc::new(data.storage) }
+    }
+}
+
+impl<S> DataArc<S> {
+    /// Number of clones sharing the storage.
+    #[inline]
+    pub fn strong_count(&self) -> usize {
+        Arc::strong_count(&self.storage)
+    }
+
+    #[inline]
+    pub fn into_arc(self) -> Arc<S> {
+        self.storage
+    }
+}
+
 impl<'a, S> From<&'a S> for DataRef<'a, S> {
     #[inline]
     fn from(data: &'a S) -> Self {
@@ -61,29 +162,47 @@ impl<'a, S> From<&'a S> for DataRef<'a, S> {
 }
 
 impl<'a, S> DataRef<'a, S> {
+    /// Data of storage that borrows its buffer.
+    ///
+    /// # Safety
+    ///
+    /// Buffer of `storage` must be valid for reading for lifetime `'a`.
     #[inline]
-    pub fn from_manually_drop(data: ManuallyDrop<S>) -> Self {
-        DataRef::ManuallyDropOwned(data)
+    pub unsafe fn from_borrowed(storage: S) -> Self {
+        DataRef::Borrowed(DataBorrowed { storage: Arc::new(storage) })
     }
 }
 
 impl<'a, S> DataRefMut<'a, S> {
+    /// Data of storage that mutably borrows its buffer.
+    ///
+    /// # Safety
+    ///
+    /// Buffer of `storage` must be valid for reading and writing, and not
+    /// accessed by others, for lifetime `'a`.
     #[inline]
-    pub fn from_manually_drop(data: ManuallyDrop<S>) -> Self {
-        DataRefMut::ManuallyDropOwned(data)
+    pub unsafe fn from_borrowed(storage: S) -> Self {
+        DataRefMut::Borrowed(DataBorrowedMut { inner: BorrowedMutInner::Storage(storage) })
     }
 }
 
 pub trait DataAPI {
     type Data: Clone;
     fn storage(&self) -> &Self::Data;
-    fn as_ref(&self) -> DataRef<Self::Data>;
+    fn as_ref(&self) -> DataRef<'_, Self::Data>;
     fn into_owned(self) -> DataOwned<Self::Data>;
 }
 
 pub trait DataMutAPI: DataAPI {
-    fn storage_mut(&mut self) -> &mut Self::Data;
-    fn as_ref_mut(&mut self) -> DataRefMut<Self::Data>;
+    /// Mutable reference to storage.
+    ///
+    /// # Safety
+    ///
+    /// Storage must not be moved out of `self`, e.g. by [`core::mem::swap`]:
+    /// storage of [`DataRefMut::Borrowed`] does not own its buffer, and must
+    /// not outlive the borrow.
+    unsafe fn storage_mut(&mut self) -> &mut Self::Data;
+    fn as_ref_mut(&mut self) -> DataRefMut<'_, Self::Data>;
 }
 
 pub trait DataOwnedAPI: DataMutAPI {}
@@ -102,7 +221,7 @@ where
     }
 
     #[inline]
-    fn as_ref(&self) -> DataRef<Self::Data> {
+    fn as_ref(&self) -> DataRef<'_, Self::Data> {
         DataRef::from(&self.storage)
     }
 
@@ -122,15 +241,15 @@ where
     fn storage(&self) -> &Self::Data {
         match self {
             DataRef::TrueRef(storage) => storage,
-            DataRef::ManuallyDropOwned(storage) => storage,
+            DataRef::Borrowed(data) => data.storage.as_ref(),
         }
     }
 
     #[inline]
-    fn as_ref(&self) -> DataRef<Self::Data> {
+    fn as_ref(&self) -> DataRef<'_, Self::Data> {
         match self {
             DataRef::TrueRef(storage) => DataRef::TrueRef(storage),
-            DataRef::ManuallyDropOwned(storage) => DataRef::TrueRef(storage),
+            DataRef::Borrowed(data) => DataRef::TrueRef(data.storage.as_ref()),
         }
     }
 
@@ -138,10 +257,8 @@ where
     fn into_owned(self) -> DataOwned<Self::Data> {
         match self {
             DataRef::TrueRef(storage) => DataOwned::from(storage.clone()),
-            DataRef::ManuallyDropOwned(storage) => {
-                let v = ManuallyDrop::into_inner(storage);
-                DataOwned::from(v.clone())
-            },
+            // clone of borrowed storage owns its buffer
+            DataRef::Borrowed(data) => DataOwned::from(data.storage.as_ref().clone()),
         }
     }
 }
@@ -156,7 +273,7 @@ where
     fn storage(&self) -> &Self::Data {
         match self {
             DataRefMut::TrueRef(storage) => storage,
-            DataRefMut::ManuallyDropOwned(storage) => storage,
+            DataRefMut::Borrowed(data) => data.storage(),
         }
     }
 
@@ -171,6 +288,30 @@ where
     }
 }
 
+impl<S> DataAPI for DataArc<S>
+where
+    S: Clone,
+{
+    type Data = S;
+
+    #[inline]
+    fn storage(&self) -> &Self::Data {
+        &self.storage
+    }
+
+    #[inline]
+    fn as_ref(&self) -> DataRef<'_, Self::Data> {
+        DataRef::from(self.storage.as_ref())
+    }
+
+    /// Storage is moved if not shared, otherwise cloned.
+    #[inline]
+    fn into_owned(self) -> DataOwned<Self::Data> {
+        let storage = Arc::try_unwrap(self.storage).unwrap_or_else(|arc| (*arc).clone());
+        DataOwned::from(storage)
+    }
+}
+
 impl<'a, S> DataAPI for DataCow<'a, S>
 where
     S: Clone,
@@ -186,7 +327,7 @@ where
     }
 
     #[inline]
-    fn as_ref(&self) -> DataRef<Self::Data> {
+    fn as_ref(&self) -> DataRef<'_, Self::Data> {
         match self {
             DataCow::Owned(data) => data.as_ref(),
             DataCow::Ref(data) => data.as_ref(),
@@ -211,12 +352,12 @@ where
     S: Clone,
 {
     #[inline]
-    fn storage_mut(&mut self) -> &mut Self::Data {
+    unsafe fn storage_mut(&mut self) -> &mut Self::Data {
         &mut self.storage
     }
 
     #[inline]
-    fn as_ref_mut(&mut self) -> DataRefMut<Self::Data> {
+    fn as_ref_mut(&mut self) -> DataRefMut<'_, Self::Data> {
         DataRefMut::TrueRef(&mut self.storage)
     }
 }
@@ -226,10 +367,10 @@ where
     S: Clone,
 {
     #[inline]
-    fn storage_mut(&mut self) -> &mut Self::Data {
+    unsafe fn storage_mut(&mut self) -> &mut Self::Data {
         match self {
             DataRefMut::TrueRef(storage) => storage,
-            DataRefMut::ManuallyDropOwned(storage) => storage,
+            DataRefMut::Borrowed(data) => data.storage_mut(),
         }
     }
 
@@ -237,11 +378,28 @@ where
     fn as_ref_mut(&mut self) -> DataRefMut<'_, Self::Data> {
         match self {
             DataRefMut::TrueRef(storage) => DataRefMut::TrueRef(storage),
-            DataRefMut::ManuallyDropOwned(storage) => DataRefMut::TrueRef(storage),
+            // reborrow keeps the storage private
+            DataRefMut::Borrowed(data) => DataRefMut::Borrowed(data.reborrow()),
         }
     }
 }
 
+impl<S> DataMutAPI for DataArc<S>
+where
+    S: Clone,
+{
+    /// Storage is cloned first if it is shared.
+    #[inline]
+    unsafe fn storage_mut(&mut self) -> &mut Self::Data {
+        Arc::make_mut(&mut self.storage)
+    }
+
+    #[inline]
+    fn as_ref_mut(&mut self) -> DataRefMut<'_, Self::Data> {
+        DataRefMut::TrueRef(Arc::make_mut(&mut self.storage))
+    }
+}
+
 /* #endregion */
 
 /* #region DataOwnedAPI */
@@ -269,6 +427,7 @@ impl<'a, S> From<DataRef<'a, S>> for DataCow<'a, S> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::storage::{CpuRawVec, CpuRawVecAPI};
 
     #[test]
     fn test_trait_data() {
@@ -284,12 +443,45 @@ mod test {
         println!("{:?}", data_ref2.storage().as_ptr());
 
         println!("===");
-        let data_ref = DataRef::from_manually_drop(ManuallyDrop::new(vec.clone()));
+        let data_ref = unsafe { DataRef::from_borrowed(CpuRawVec::from_slice(&vec)) };
         let data_ref_ref = data_ref.as_ref();
-        println!("{:?}", data_ref.storage().as_ptr());
-        println!("{:?}", data_ref_ref.storage().as_ptr());
+        assert_eq!(data_ref.storage().as_ptr(), vec.as_ptr());
+        assert_eq!(data_ref_ref.storage().as_ptr(), vec.as_ptr());
+        assert_eq!(data_ref.clone().storage().as_ptr(), vec.as_ptr());
         let mut data_ref2 = data_ref.into_owned();
-        println!("{:?}", data_ref2.storage().as_ptr());
-        data_ref2.storage_mut()[1] = 10;
+        assert_ne!(data_ref2.storage().as_ptr(), vec.as_ptr());
+        unsafe { data_ref2.storage_mut().as_raw_slice_mut()[1] = 10 };
+        assert_eq!(vec, [10, 20, 30]);
+
+        println!("===");
+        let mut vec = vec;
+        let mut data_mut =
+            unsafe { DataRefMut::from_borrowed(CpuRawVec::from_slice_mut(&mut vec)) };
+        let mut data_mut_ref = data_mut.as_ref_mut();
+        assert!(matches!(data_mut_ref, DataRefMut::Borrowed(_)));
+        unsafe { data_mut_ref.storage_mut().as_raw_slice_mut()[1] = 0 };
+        assert_eq!(data_mut.storage().as_ptr(), vec.as_ptr());
+        assert_eq!(vec, [10, 0, 30]);
+    }
+
+    #[test]
+    fn test_data_arc() {
+        let data = DataArc::from(vec![10, 20, 30]);
+        let ptr = data.storage().as_ptr();
+        let mut data2 = data.clone();
+        assert_eq!(data2.strong_count(), 2);
+        assert_eq!(data2.storage().as_ptr(), ptr);
+
+        // shared storage is copied on write
+        unsafe { data2.storage_mut()[0] = 0 };
+        assert_ne!(data2.storage().as_ptr(), ptr);
+        assert_eq!(data.storage(), &vec![10, 20, 30]);
+
+        // unique storage is mutated in place, and moved into owned
+        let mut data = data;
+        assert_eq!(data.strong_count(), 1);
+        unsafe { data.storage_mut()[0] = 0 };
+        assert_eq!(data.storage().as_ptr(), ptr);
+        assert_eq!(data.into_owned().storage().as_ptr(), ptr);
     }
 }
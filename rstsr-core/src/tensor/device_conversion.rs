@@ -1,5 +1,3 @@
-use core::mem::ManuallyDrop;
-
 use crate::prelude_dev::*;
 
 pub trait TensorToDeviceAPI<B> {
@@ -27,24 +25,47 @@ where
 impl<'a, T, D, B1, B2> TensorToDeviceAPI<B2> for TensorView<'a, T, D, B1>
 where
     D: DimAPI,
-    B1: DeviceAPI<T, RawVec = Vec<T>>,
-    B2: DeviceAPI<T, RawVec = Vec<T>> + 'a,
+    B1: DeviceAPI<T, RawVec = CpuRawVec<T>>,
+    B2: DeviceAPI<T, RawVec = CpuRawVec<T>> + 'a,
 {
     type DataRepr = DataRef<'a, Storage<T, B2>>;
     type Dim = D;
     fn into_device(self, device: &B2) -> Result<TensorView<'a, T, D, B2>> {
         let layout = self.layout().clone();
         let oldvec = self.storage().rawvec();
-
-        let ptr = oldvec.as_ptr();
-        let len = oldvec.len();
-        let rawvec: Vec<T> = unsafe {
-            let ptr = ptr as *mut T;
-            Vec::from_raw_parts(ptr, len, len)
-        };
-        let storage = ManuallyDrop::new(Storage::new(rawvec, device.clone()));
-        let data = DataRef::from_manually_drop(storage);
+        // buffer of `self` is valid for `'a`
+        let slc: &'a [T] = unsafe { core::slice::from_raw_parts(oldvec.as_ptr(), oldvec.len()) };
+        let rawvec = unsafe { CpuRawVec::from_slice(slc) };
+        // the view does not own memory, so bypass memory accounting of `new`
+        let storage = Storage { rawvec, device: device.clone() };
+        let data = unsafe { DataRef::from_borrowed(storage) };
         let tensor_view = unsafe { TensorView::new_unchecked(data, layout) };
         Ok(tensor_view)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_view_into_device() {
+        let a = Tensor::<f64, _>::linspace(0.0, 5.0, 6, &DeviceCpu::default());
+        let ptr = a.storage().rawvec().as_ptr();
+
+        // re-tag view to another CPU device without copy, and back
+        let device = DeviceCpuCounting::new();
+        let b = a.view().into_device(&device).unwrap();
+        assert_eq!(b.storage().rawvec().as_ptr(), ptr);
+        let c = b.view().into_device(&DeviceCpu::default()).unwrap();
+        assert_eq!(c.storage().rawvec().as_ptr(), ptr);
+
+        // owned copy of borrowed view does not alias
+        let d = (&b * 2.0).into_device(&DeviceCpu::default()).unwrap();
+        let e = c.into_owned_keep_layout();
+        assert_ne!(e.storage().rawvec().as_ptr(), ptr);
+        assert_eq!(d.storage().rawvec().as_raw_slice()[5], 10.0);
+        assert_eq!(e.storage().rawvec(), a.storage().rawvec());
+        assert!(!e.storage().rawvec().is_borrowed());
+    }
+}
@@ -0,0 +1,683 @@
+//! einops-style tensor rearrangement by patterns.
+//!
+//! Patterns are of format such as `"b (h w) c -> b h w c"`. Axes of each side
+//! are separated by whitespace; parenthesized axes are composed into one
+//! dimension in row-major order; `...` stands for any number of axes; a number
+//! stands for an anonymous axis of that size.
+//!
+//! All operations are performed on an elementary layout, where each
+//! composite dimension of input is split into its axes. Splitting is always a
+//! view; composing axes of output is a view when strides allow, and data is
+//! copied to a row-major buffer otherwise.
+//!
+//! # See also
+//!
+//! [einops](https://einops.rocks/)
+
+use crate::prelude_dev::*;
+use crate::tensor::manuplication::broadcast_to;
+use core::ops::{Add, Div};
+use num::{FromPrimitive, Zero};
+
+/* #region parse */
+
+/// Axis in einops pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum EinopsAxis {
+    Named(String),
+    Anonymous(usize),
+    Ellipsis,
+}
+
+/// One side of einops pattern; each entry is one dimension, composed of
+/// (possibly multiple) axes.
+type EinopsTerm = Vec<Vec<EinopsAxis>>;
+
+/// Parse one side of einops pattern; each dimension is also marked whether it
+/// is parenthesized.
+fn einops_parse_term(term: &str) -> Result<Vec<(Vec<EinopsAxis>, bool)>> {
+    let spaced = term.replace('(', " ( ").replace(')', " ) ");
+    let mut dims = vec![];
+    let mut group: Option<Vec<EinopsAxis>> = None;
+    let mut names = BTreeSet::new();
+    let mut n_ellipsis = 0;
+    for token in spaced.split_whitespace() {
+        let axis = match token {
+            "(" => {
+                rstsr_assert!(
+                    group.is_none(),
+                    InvalidValue,
+                    "Nested parenthesis in einops pattern: {term}"
+                )?;
+                group = Some(vec![]);
+                continue;
+            },
+            ")" => {
+                let Some(g) = group.take() else {
+                    rstsr_raise!(InvalidValue, "Unmatched parenthesis in einops pattern: {term}")?
+                };
+                dims.push((g, true));
+                continue;
+            },
+            "..." => {
+                n_ellipsis += 1;
+                EinopsAxis::Ellipsis
+            },
+            t if t.chars().all(|c| c.is_ascii_digit()) => match t.parse::<usize>() {
+                Ok(n) => EinopsAxis::Anonymous(n),
+                Err(_) => rstsr_raise!(InvalidValue, "Invalid axis size '{t}' in einops pattern.")?,
+            },
+            t if t.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                && t.chars().all(|c| c.is_alphanumeric() || c == '_') =>
+            {
+                rstsr_assert!(
+                    names.insert(t.to_string()),
+                    InvalidValue,
+                    "Axis '{t}' appears more than once in einops pattern: {term}"
+                )?;
+                EinopsAxis::Named(t.to_string())
+            },
+            t => rstsr_raise!(InvalidValue, "Invalid axis '{t}' in einops pattern: {term}")?,
+        };
+        match group.as_mut() {
+            Some(g) => g.push(axis),
+            None => dims.push((vec![axis], false)),
+        }
+    }
+    rstsr_assert!(
+        group.is_none(),
+        InvalidValue,
+        "Unmatched parenthesis in einops pattern: {term}"
+    )?;
+    rstsr_assert!(n_ellipsis <= 1, InvalidValue, "Only one ellipsis is allowed: {term}")?;
+    return Ok(dims);
+}
+
+/// Axis of input elementary layout; anonymous axes are not named.
+type EinopsInput = Vec<(Option<String>, usize)>;
+
+/// Parsed einops pattern, with sizes of all axes resolved.
+#[derive(Debug, Clone)]
+struct EinopsPattern {
+    /// Axes of input elementary layout (each composite dimension split).
+    input: EinopsInput,
+    /// Number of axes in each dimension of input.
+    groups: Vec<usize>,
+    /// Dimensions of output; ellipsis is expanded to named axes.
+    output: EinopsTerm,
+    /// Size of each named axis.
+    sizes: BTreeMap<String, usize>,
+}
+
+/// Name of the `k`-th axis represented by ellipsis; not a valid identifier,
+/// so it never conflicts with user-named axes.
+fn einops_ellipsis_name(k: usize) -> String {
+    format!("...{k}")
+}
+
+fn einops_parse(
+    pattern: &str,
+    shape: &[usize],
+    axes_lengths: &[(&str, usize)],
+) -> Result<EinopsPattern> {
+    let Some((left, right)) = pattern.split_once("->") else {
+        rstsr_raise!(InvalidValue, "Einops pattern must contain '->': {pattern}")?
+    };
+    let left = einops_parse_term(left)?;
+    let right = einops_parse_term(right)?;
+
+    let is_ellipsis = |dim: &Vec<EinopsAxis>| dim.contains(&EinopsAxis::Ellipsis);
+    let left_has_ellipsis = left.iter().any(|(dim, _)| is_ellipsis(dim));
+    let right_has_ellipsis = right.iter().any(|(dim, _)| is_ellipsis(dim));
+    rstsr_assert!(
+        left_has_ellipsis || !right_has_ellipsis,
+        InvalidValue,
+        "Ellipsis appears on right side of einops pattern, but not on left side: {pattern}"
+    )?;
+    rstsr_assert!(
+        left.iter().all(|(dim, parenthesized)| !(is_ellipsis(dim) && *parenthesized)),
+        InvalidValue,
+        "Ellipsis in parenthesis is not allowed on left side of einops pattern: {pattern}"
+    )?;
+
+    // expand ellipsis
+    let n_ellipsis = match left_has_ellipsis {
+        true => {
+            rstsr_assert!(
+                shape.len() + 1 >= left.len(),
+                InvalidValue,
+                "Einops pattern {pattern} expects at least {} dimensions, but tensor has shape {:?}.",
+                left.len() - 1,
+                shape
+            )?;
+            shape.len() + 1 - left.len()
+        },
+        false => {
+            rstsr_assert_eq!(
+                shape.len(),
+                left.len(),
+                InvalidValue,
+                "Einops pattern {pattern} does not match tensor of shape {shape:?}."
+            )?;
+            0
+        },
+    };
+    let expand = |term: Vec<(Vec<EinopsAxis>, bool)>| -> EinopsTerm {
+        let ellipsis = (0..n_ellipsis).map(|k| EinopsAxis::Named(einops_ellipsis_name(k)));
+        let mut result = vec![];
+        for (dim, parenthesized) in term {
+            match (is_ellipsis(&dim), parenthesized) {
+                (true, false) => result.extend(ellipsis.clone().map(|axis| vec![axis])),
+                (true, true) => result.push(
+                    dim.into_iter()
+                        .flat_map(|axis| match axis {
+                            EinopsAxis::Ellipsis => ellipsis.clone().collect_vec(),
+                            axis => vec![axis],
+                        })
+                        .collect(),
+                ),
+                (false, _) => result.push(dim),
+            }
+        }
+        result
+    };
+    let left = expand(left);
+    let output = expand(right);
+    let groups = left.iter().map(|dim| dim.len()).collect_vec();
+
+    // user-given sizes
+    let mut sizes = BTreeMap::new();
+    for &(name, size) in axes_lengths {
+        let named = EinopsAxis::Named(name.to_string());
+        rstsr_assert!(
+            left.iter().chain(&output).any(|dim| dim.contains(&named)),
+            InvalidValue,
+            "Axis '{name}' is given a length, but does not appear in einops pattern: {pattern}"
+        )?;
+        sizes.insert(name.to_string(), size);
+    }
+
+    // sizes of input axes, inferring at most one unknown axis per dimension
+    let mut input = vec![];
+    for (dim, &n) in izip!(&left, shape) {
+        let mut known = 1;
+        let mut unknown = vec![];
+        for axis in dim {
+            match axis {
+                EinopsAxis::Named(name) => match sizes.get(name) {
+                    Some(&s) => known *= s,
+                    None => unknown.push(name.clone()),
+                },
+                EinopsAxis::Anonymous(s) => known *= s,
+                EinopsAxis::Ellipsis => unreachable!(),
+            }
+        }
+        match unknown.len() {
+            0 => rstsr_assert_eq!(
+                known,
+                n,
+                InvalidValue,
+                "Dimension of size {n} can not be composed of axes {dim:?} in einops pattern."
+            )?,
+            1 => {
+                rstsr_assert!(
+                    known != 0 && n % known == 0,
+                    InvalidValue,
+                    "Dimension of size {n} is not divisible by known axes {dim:?} in einops pattern."
+                )?;
+                sizes.insert(unknown.pop().unwrap(), n / known);
+            },
+            _ => rstsr_raise!(
+                InvalidValue,
+                "Could not infer sizes of axes {unknown:?} from dimension of size {n} in einops pattern."
+            )?,
+        }
+        for axis in dim {
+            match axis {
+                EinopsAxis::Named(name) => input.push((Some(name.clone()), sizes[name])),
+                EinopsAxis::Anonymous(s) => input.push((None, *s)),
+                EinopsAxis::Ellipsis => unreachable!(),
+            }
+        }
+    }
+    return Ok(EinopsPattern { input, groups, output, sizes });
+}
+
+/// Named axes of output, checked to be distinct.
+fn einops_output_names(output: &EinopsTerm) -> Vec<&String> {
+    output
+        .iter()
+        .flatten()
+        .filter_map(|axis| match axis {
+            EinopsAxis::Named(name) => Some(name),
+            _ => None,
+        })
+        .collect_vec()
+}
+
+/* #endregion */
+
+/* #region layout */
+
+/// Split each dimension of layout into its axes (row-major composition).
+fn einops_split_layout(layout: &Layout<IxD>, left: &[usize], input: &EinopsInput) -> Layout<IxD> {
+    let mut shape = vec![];
+    let mut stride = vec![];
+    let mut axes = input.iter();
+    for (&n_axes, &s) in izip!(left, layout.stride()) {
+        let dim = axes.by_ref().take(n_axes).map(|(_, n)| *n).collect_vec();
+        let mut st = s;
+        let mut dim_stride = vec![0; dim.len()];
+        for (k, &n) in dim.iter().enumerate().rev() {
+            dim_stride[k] = st;
+            st *= n as isize;
+        }
+        shape.extend(dim);
+        stride.extend(dim_stride);
+    }
+    unsafe { Layout::new_unchecked(shape, stride, layout.offset()) }
+}
+
+/// Permute elementary layout (with axes named by `names`) to order of output
+/// axes; axes not in `names` (anonymous, or new axes) are inserted with size 1.
+///
+/// Returns the elementary output layout, and number of axes in each output
+/// dimension.
+fn einops_permute_layout(
+    layout: &Layout<IxD>,
+    names: &[Option<String>],
+    output: &EinopsTerm,
+) -> (Layout<IxD>, Vec<usize>) {
+    let mut shape = vec![];
+    let mut stride = vec![];
+    for axis in output.iter().flatten() {
+        let position = match axis {
+            EinopsAxis::Named(name) => names.iter().position(|x| x.as_ref() == Some(name)),
+            _ => None,
+        };
+        let (n, s) = match position {
+            Some(k) => (layout.shape()[k], layout.stride()[k]),
+            None => (1, 0),
+        };
+        shape.push(n);
+        stride.push(s);
+    }
+    let groups = output.iter().map(|dim| dim.len()).collect_vec();
+    let layout = unsafe { Layout::new_unchecked(shape, stride, layout.offset()) };
+    return (layout, groups);
+}
+
+/// Compose groups of axes into dimensions (row-major); `None` if this is not
+/// possible without data copy.
+fn einops_merge_layout(layout: &Layout<IxD>, groups: &[usize]) -> Option<Layout<IxD>> {
+    let mut shape = vec![];
+    let mut stride = vec![];
+    let mut start = 0;
+    for &n in groups {
+        let range = start..start + n;
+        let (size, st) =
+            merge_axes(&layout.shape()[range.clone()], &layout.stride()[range], TensorOrder::C)?;
+        shape.push(size);
+        stride.push(st);
+        start += n;
+    }
+    return Some(unsafe { Layout::new_unchecked(shape, stride, layout.offset()) });
+}
+
+/// Compose groups of axes of elementary output into dimensions, copying data
+/// if a view is not possible.
+fn einops_finalize<'a, T, B>(
+    data: DataCow<'a, Storage<T, B>>,
+    layout: &Layout<IxD>,
+    groups: &[usize],
+) -> Result<TensorBase<DataCow<'a, Storage<T, B>>, IxD>>
+where
+    T: Clone,
+    B: DeviceAPI<T> + DeviceCreationAnyAPI<T> + OpAssignAPI<T, IxD>,
+{
+    if let Some(layout) = einops_merge_layout(layout, groups) {
+        return Ok(unsafe { TensorBase::new_unchecked(data, layout) });
+    }
+    let device = data.storage().device();
+    let layout_copy = layout.shape().c();
+    let mut storage = unsafe { device.empty_impl(layout_copy.size())? };
+    device.assign(&mut storage, &layout_copy, data.storage(), layout)?;
+    let layout = einops_merge_layout(&layout_copy, groups).unwrap();
+    return Ok(unsafe { TensorBase::new_unchecked(DataCow::Owned(storage.into()), layout) });
+}
+
+/* #endregion */
+
+/* #region einops */
+
+/// Methods for einops-style tensor rearrangement.
+impl<R, T, D, B> TensorBase<R, D>
+where
+    R: DataAPI<Data = Storage<T, B>>,
+    D: DimAPI,
+    T: Clone,
+    B: DeviceAPI<T> + DeviceCreationAnyAPI<T> + OpAssignAPI<T, IxD>,
+{
+    /// Rearrange axes of tensor by einops pattern.
+    ///
+    /// Pattern is of format such as `"b (h w) c -> b h w c"`; sizes of axes
+    /// that can not be inferred from tensor shape are given by `axes_lengths`,
+    /// such as `&[("h", 8)]`. Both sides must contain the same named axes;
+    /// anonymous axes must be of size 1.
+    ///
+    /// Result is a view when the layout allows, and data is copied otherwise.
+    ///
+    /// # See also
+    ///
+    /// [`einops.rearrange`](https://einops.rocks/api/rearrange/)
+    pub fn rearrange(
+        &self,
+        pattern: &str,
+        axes_lengths: &[(&str, usize)],
+    ) -> Result<TensorBase<DataCow<'_, R::Data>, IxD>> {
+        let layout = self.layout().to_dim::<IxD>()?;
+        let EinopsPattern { input, groups, output, .. } =
+            einops_parse(pattern, layout.shape(), axes_lengths)?;
+
+        let input_names =
+            input.iter().filter_map(|(name, _)| name.as_ref()).collect::<BTreeSet<_>>();
+        let output_names = einops_output_names(&output);
+        rstsr_assert!(
+            input_names == output_names.iter().cloned().collect(),
+            InvalidValue,
+            "Axes of both sides of einops pattern must be the same for rearrange: {pattern}"
+        )?;
+        for (_, n) in input.iter().filter(|(name, _)| name.is_none()) {
+            rstsr_assert!(
+                *n == 1,
+                InvalidValue,
+                "Anonymous axis of size {n} in input of rearrange."
+            )?;
+        }
+        for axis in output.iter().flatten() {
+            rstsr_assert!(
+                !matches!(axis, EinopsAxis::Anonymous(n) if *n != 1),
+                InvalidValue,
+                "Anonymous axis of size other than 1 in output of rearrange: {pattern}"
+            )?;
+        }
+
+        let layout = einops_split_layout(&layout, &groups, &input);
+        let names = input.into_iter().map(|(name, _)| name).collect_vec();
+        let (layout, groups) = einops_permute_layout(&layout, &names, &output);
+        let data = DataCow::Ref(self.data().as_ref());
+        return einops_finalize(data, &layout, &groups);
+    }
+
+    /// Repeat tensor along new axes by einops pattern.
+    ///
+    /// Axes of output that are not in input (named axes with sizes given by
+    /// `axes_lengths`, or anonymous axes) are broadcasted.
+    ///
+    /// # See also
+    ///
+    /// [`einops.repeat`](https://einops.rocks/api/repeat/)
+    pub fn repeat(
+        &self,
+        pattern: &str,
+        axes_lengths: &[(&str, usize)],
+    ) -> Result<TensorBase<DataCow<'_, R::Data>, IxD>> {
+        let layout = self.layout().to_dim::<IxD>()?;
+        let EinopsPattern { input, groups, output, sizes } =
+            einops_parse(pattern, layout.shape(), axes_lengths)?;
+
+        let output_names = einops_output_names(&output);
+        for (name, n) in &input {
+            match name {
+                Some(name) => rstsr_assert!(
+                    output_names.contains(&name),
+                    InvalidValue,
+                    "Axis '{name}' of input does not appear in output of repeat: {pattern}"
+                )?,
+                None => rstsr_assert!(
+                    *n == 1,
+                    InvalidValue,
+                    "Anonymous axis of size {n} in input of repeat."
+                )?,
+            }
+        }
+        let shape = output
+            .iter()
+            .flatten()
+            .map(|axis| match axis {
+                EinopsAxis::Named(name) => match sizes.get(name) {
+                    Some(&n) => Ok(n),
+                    None => rstsr_raise!(
+                        InvalidValue,
+                        "Size of new axis '{name}' is not given for repeat."
+                    ),
+                },
+                EinopsAxis::Anonymous(n) => Ok(*n),
+                EinopsAxis::Ellipsis => unreachable!(),
+            })
+            .collect::<Result<Vec<usize>>>()?;
+
+        // new axes are inserted with size 1, then broadcasted
+        let layout = einops_split_layout(&layout, &groups, &input);
+        let names = input.into_iter().map(|(name, _)| name).collect_vec();
+        let (layout, groups) = einops_permute_layout(&layout, &names, &output);
+        let view = unsafe { TensorBase::new_unchecked(self.data().as_ref(), layout) };
+        let view = broadcast_to(view, &shape)?;
+        let layout = view.layout().clone();
+        return einops_finalize(view.data.into(), &layout, &groups);
+    }
+
+    /// Reduce axes of tensor by einops pattern.
+    ///
+    /// Axes of input that are not in output are reduced by `reduction`, which
+    /// is either `"sum"` or `"mean"`. Other reductions of einops (`"max"`,
+    /// `"min"`, `"prod"`) are not supported.
+    ///
+    /// # See also
+    ///
+    /// [`einops.reduce`](https://einops.rocks/api/reduce/)
+    pub fn reduce(
+        &self,
+        pattern: &str,
+        reduction: &str,
+        axes_lengths: &[(&str, usize)],
+    ) -> Result<Tensor<T, IxD, B>>
+    where
+        T: Zero + Add<Output = T> + Div<Output = T> + FromPrimitive + Send + Sync,
+        B: OpSumAxesAPI<T>,
+        B: for<'f> DeviceOp_MutA_API<T, IxD, dyn Fn(&mut T) + Send + Sync + 'f>,
+    {
+        let is_mean = match reduction {
+            "sum" => false,
+            "mean" => true,
+            _ => rstsr_raise!(
+                InvalidValue,
+                "Reduction '{reduction}' in einops reduce is not supported; use 'sum' or 'mean'."
+            )?,
+        };
+        let layout = self.layout().to_dim::<IxD>()?;
+        let EinopsPattern { input, groups, output, .. } =
+            einops_parse(pattern, layout.shape(), axes_lengths)?;
+
+        let output_names = einops_output_names(&output);
+        let input_names = input.iter().filter_map(|(name, _)| name.as_ref()).collect_vec();
+        for name in &output_names {
+            rstsr_assert!(
+                input_names.contains(name),
+                InvalidValue,
+                "Axis '{name}' of output does not appear in input of reduce: {pattern}"
+            )?;
+        }
+        for axis in output.iter().flatten() {
+            rstsr_assert!(
+                !matches!(axis, EinopsAxis::Anonymous(n) if *n != 1),
+                InvalidValue,
+                "Anonymous axis of size other than 1 in output of reduce: {pattern}"
+            )?;
+        }
+
+        // split elementary layout into kept and reduced axes
+        let layout = einops_split_layout(&layout, &groups, &input);
+        let is_kept =
+            |name: &Option<String>| name.as_ref().is_some_and(|name| output_names.contains(&name));
+        let select = |kept: bool| {
+            let axes = (0..input.len()).filter(|&k| is_kept(&input[k].0) == kept).collect_vec();
+            let shape = axes.iter().map(|&k| layout.shape()[k]).collect_vec();
+            let stride = axes.iter().map(|&k| layout.stride()[k]).collect_vec();
+            let layout = unsafe { Layout::new_unchecked(shape, stride, layout.offset()) };
+            (axes, layout)
+        };
+        let (axes_kept, layout_kept) = select(true);
+        let (axes_reduced, layout_reduced) = select(false);
+
+        // reduced axes permuted to the end, and summed by one device call
+        let perm = axes_kept.iter().chain(&axes_reduced).map(|&k| k as isize).collect_vec();
+        let layout_sum = layout.transpose(&perm)?;
+        let layout = layout_kept.shape().c();
+        let device = self.device();
+        let mut storage = unsafe { device.empty_impl(layout.size())? };
+        device.sum_axes(
+            &mut storage,
+            &layout,
+            self.data().storage(),
+            &layout_sum,
+            axes_reduced.len(),
+        )?;
+        if is_mean {
+            let Some(count) = T::from_usize(layout_reduced.size()) else {
+                rstsr_raise!(InvalidValue, "Number of reduced elements overflows.")?
+            };
+            let mut f = move |x: &mut T| *x = x.clone() / count.clone();
+            device.op_muta_func(&mut storage, &layout, &mut f)?;
+        }
+
+        let names = axes_kept.iter().map(|&k| input[k].0.clone()).collect_vec();
+        let (layout, groups) = einops_permute_layout(&layout, &names, &output);
+        let data = DataCow::Owned(storage.into());
+        let result = einops_finalize(data, &layout, &groups)?;
+        let layout = result.layout().clone();
+        return Ok(unsafe { Tensor::new_unchecked(result.data.into_owned(), layout) });
+    }
+}
+
+/* #endregion */
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn arange(shape: &[usize]) -> (Vec<f64>, Tensor<f64, IxD>) {
+        let n = shape.iter().product::<usize>();
+        let v = (0..n).map(|x| x as f64).collect_vec();
+        let t =
+            Tensor::asarray(v.clone()).unwrap().into_shape_assume_contig(shape.to_vec()).unwrap();
+        return (v, t);
+    }
+
+    fn to_vec<R>(t: &TensorBase<R, IxD>) -> Vec<f64>
+    where
+        R: DataAPI<Data = Storage<f64, DeviceCpu>>,
+    {
+        let layout = t.layout().shape().c();
+        let mut s = unsafe { DeviceCpu::default().empty_impl(layout.size()).unwrap() };
+        DeviceCpu::default().assign(&mut s, &layout, t.data().storage(), t.layout()).unwrap();
+        s.into_cpu_vec().unwrap()
+    }
+
+    #[test]
+    fn test_rearrange() {
+        let (v, a) = arange(&[2, 6, 3]);
+
+        // split is a view
+        let b = a.rearrange("b (h w) c -> b h w c", &[("h", 2)]).unwrap();
+        assert_eq!(b.shape(), &[2, 2, 3, 3]);
+        assert!(matches!(b.data(), DataCow::Ref(_)));
+        assert_eq!(to_vec(&b), v);
+
+        // transpose and compose requires copy
+        let c = b.rearrange("b h w c -> (b c) h w", &[]).unwrap();
+        assert_eq!(c.shape(), &[6, 2, 3]);
+        assert!(matches!(c.data(), DataCow::Owned(_)));
+        let mut c_ref = vec![];
+        for (b, c, h, w) in itertools::iproduct!(0..2, 0..3, 0..2, 0..3) {
+            c_ref.push(v[b * 18 + (h * 3 + w) * 3 + c]);
+        }
+        assert_eq!(to_vec(&c), c_ref);
+
+        // compose of contiguous axes is a view; anonymous axes of size 1
+        let d = a.rearrange("b n c -> 1 (b n) c 1", &[]).unwrap();
+        assert_eq!(d.shape(), &[1, 12, 3, 1]);
+        assert!(matches!(d.data(), DataCow::Ref(_)));
+        let e = d.rearrange("1 n c 1 -> c n", &[]).unwrap();
+        assert_eq!(e.shape(), &[3, 12]);
+
+        // ellipsis
+        let f = a.rearrange("b ... -> ... b", &[]).unwrap();
+        assert_eq!(f.shape(), &[6, 3, 2]);
+        let g = a.rearrange("b ... -> b (...)", &[]).unwrap();
+        assert_eq!(g.shape(), &[2, 18]);
+        assert_eq!(to_vec(&g), v);
+
+        // errors
+        assert!(a.rearrange("b n c -> b n", &[]).is_err());
+        assert!(a.rearrange("b n -> b n", &[]).is_err());
+        assert!(a.rearrange("b (h w) c -> b h w c", &[]).is_err());
+        assert!(a.rearrange("b (h w) c -> b h w c", &[("h", 4)]).is_err());
+        assert!(a.rearrange("b n c -> b n c", &[("h", 4)]).is_err());
+        assert!(a.rearrange("b n n -> b n", &[]).is_err());
+        assert!(a.rearrange("b (n c -> b n c", &[]).is_err());
+        assert!(a.rearrange("b n c", &[]).is_err());
+    }
+
+    #[test]
+    fn test_reduce() {
+        let (v, a) = arange(&[2, 3, 4]);
+
+        let b = a.reduce("i j k -> i", "sum", &[]).unwrap();
+        let b_ref = (0..2).map(|i| v[i * 12..(i + 1) * 12].iter().sum::<f64>()).collect_vec();
+        assert_eq!(to_vec(&b), b_ref);
+
+        let c = a.reduce("i j k -> k i", "mean", &[]).unwrap();
+        assert_eq!(c.shape(), &[4, 2]);
+        let mut c_ref = vec![];
+        for (k, i) in itertools::iproduct!(0..4, 0..2) {
+            c_ref.push((0..3).map(|j| v[i * 12 + j * 4 + k]).sum::<f64>() / 3.0);
+        }
+        assert_eq!(to_vec(&c), c_ref);
+
+        // pooling by composed axes
+        let d = a.reduce("i j (k2 k) -> i j k", "sum", &[("k2", 2)]).unwrap();
+        assert_eq!(d.shape(), &[2, 3, 2]);
+        let d_ref =
+            (0..12).map(|x| v[(x / 2) * 4 + x % 2] + v[(x / 2) * 4 + x % 2 + 2]).collect_vec();
+        assert_eq!(to_vec(&d), d_ref);
+
+        let e = a.reduce("... -> ", "sum", &[]).unwrap();
+        assert_eq!(e.shape(), &[] as &[usize]);
+        assert_eq!(to_vec(&e), vec![v.iter().sum::<f64>()]);
+
+        assert!(a.reduce("i j k -> l", "sum", &[]).is_err());
+        assert!(a.reduce("i j k -> i", "median", &[]).is_err());
+        assert!(a.reduce("i j k -> i", "max", &[]).is_err());
+    }
+
+    #[test]
+    fn test_repeat() {
+        let (v, a) = arange(&[2, 3]);
+
+        let b = a.repeat("h w -> h w c", &[("c", 2)]).unwrap();
+        assert_eq!(b.shape(), &[2, 3, 2]);
+        assert!(matches!(b.data(), DataCow::Ref(_)));
+        assert_eq!(to_vec(&b), v.iter().flat_map(|&x| [x, x]).collect_vec());
+
+        let c = a.repeat("h w -> (2 h) w", &[]).unwrap();
+        assert_eq!(c.shape(), &[4, 3]);
+        assert_eq!(to_vec(&c), [v.clone(), v.clone()].concat());
+
+        let d = a.repeat("h w -> h (w r)", &[("r", 2)]).unwrap();
+        assert_eq!(to_vec(&d), v.iter().flat_map(|&x| [x, x]).collect_vec());
+
+        assert!(a.repeat("h w -> h w c", &[]).is_err());
+        assert!(a.repeat("h w -> h", &[]).is_err());
+    }
+}
@@ -0,0 +1,769 @@
+//! Implementation of Einstein summation `einsum`.
+//!
+//! Each operand is first simplified: broadcasted axes of size 1 are dropped,
+//! repeated labels (diagonals) become strided views, and labels that appear in
+//! only one operand and not in output (traces and partial sums) are summed by
+//! the axis reduction of device. The remaining operands are contracted
+//! pairwise by (batched) GEMM, in an order searched to minimize FLOPs.
+
+use crate::prelude_dev::*;
+use crate::tensor::product::storage_as_batched_matrix;
+use core::ops::{Add, Mul};
+use num::Num;
+
+/// Label id of the first axis represented by ellipsis.
+///
+/// Explicit labels are represented by their ASCII code.
+const ELLIPSIS_LABEL: usize = 128;
+
+/// Strategy of pairwise contraction order search in [`einsum`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EinsumOptimize {
+    /// Contract operands from left to right.
+    None,
+    /// At each step, contract the pair of operands whose result is the smallest
+    /// compared to the operands it replaces (ties broken by FLOPs), as the
+    /// greedy path of opt_einsum.
+    Greedy,
+    /// Exhaustive search of the contraction order with the fewest FLOPs.
+    Optimal,
+    /// `Optimal` for a few operands, otherwise `Greedy`.
+    #[default]
+    Auto,
+}
+
+/// Number of operands below (or equal to) which `EinsumOptimize::Auto` uses
+/// exhaustive search.
+const EINSUM_OPTIMAL_LIMIT: usize = 6;
+
+/* #region parse */
+
+/// Subscripts of einsum, with labels represented by integers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EinsumSubscripts {
+    inputs: Vec<Vec<usize>>,
+    output: Vec<usize>,
+    /// Size of each label.
+    sizes: BTreeMap<usize, usize>,
+}
+
+/// Parse one term of subscripts into labels, with `None` for ellipsis.
+fn einsum_parse_term(term: &str) -> Result<Vec<Option<usize>>> {
+    let mut labels = vec![];
+    let mut chars = term.chars().filter(|c| !c.is_whitespace()).peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                rstsr_assert!(
+                    chars.next() == Some('.') && chars.next() == Some('.'),
+                    InvalidValue,
+                    "Invalid ellipsis in einsum subscripts: {term}"
+                )?;
+                labels.push(None);
+            },
+            c if c.is_ascii_alphabetic() => labels.push(Some(c as usize)),
+            _ => rstsr_raise!(InvalidValue, "Invalid label '{c}' in einsum subscripts.")?,
+        }
+    }
+    rstsr_assert!(
+        labels.iter().filter(|l| l.is_none()).count() <= 1,
+        InvalidValue,
+        "Only one ellipsis is allowed in each term: {term}"
+    )?;
+    return Ok(labels);
+}
+
+/// Parse subscripts of einsum, given shapes of operands.
+fn einsum_parse(subscripts: &str, shapes: &[&[usize]]) -> Result<EinsumSubscripts> {
+    let (inputs, output) = match subscripts.split_once("->") {
+        Some((inputs, output)) => (inputs, Some(output)),
+        None => (subscripts, None),
+    };
+    let terms = inputs.split(',').map(einsum_parse_term).collect::<Result<Vec<_>>>()?;
+    rstsr_assert_eq!(
+        terms.len(),
+        shapes.len(),
+        InvalidValue,
+        "Number of terms in einsum subscripts does not match number of operands."
+    )?;
+
+    // expand ellipsis; ellipsis axes are aligned to the right
+    let mut n_ellipsis = vec![];
+    for (term, shape) in izip!(&terms, shapes) {
+        let n_explicit = term.iter().filter(|l| l.is_some()).count();
+        let has_ellipsis = term.len() != n_explicit;
+        rstsr_assert!(
+            shape.len() == n_explicit || (has_ellipsis && shape.len() > n_explicit),
+            InvalidLayout,
+            "Number of labels does not match number of dimensions of operand ({:?}).",
+            shape
+        )?;
+        n_ellipsis.push(shape.len() - n_explicit);
+    }
+    let max_ellipsis = n_ellipsis.iter().max().cloned().unwrap_or(0);
+    let expand = |term: &[Option<usize>], n: usize| {
+        term.iter()
+            .flat_map(|l| match l {
+                Some(l) => vec![*l],
+                None => (max_ellipsis - n..max_ellipsis).map(|k| ELLIPSIS_LABEL + k).collect(),
+            })
+            .collect_vec()
+    };
+    let inputs = izip!(&terms, &n_ellipsis).map(|(term, &n)| expand(term, n)).collect_vec();
+
+    // label sizes; axes of size 1 are broadcastable
+    let mut sizes = BTreeMap::new();
+    for (labels, shape) in izip!(&inputs, shapes) {
+        for (&l, &s) in izip!(labels, shape.iter()) {
+            let size = sizes.entry(l).or_insert(s);
+            if *size == 1 {
+                *size = s;
+            }
+            rstsr_assert!(
+                s == 1 || s == *size,
+                InvalidLayout,
+                "Size of label '{}' mismatch: {} and {}.",
+                einsum_label_name(l),
+                s,
+                *size
+            )?;
+        }
+    }
+
+    let output = match output {
+        Some(output) => {
+            let output = expand(&einsum_parse_term(output)?, max_ellipsis);
+            for (i, l) in output.iter().enumerate() {
+                rstsr_assert!(
+                    sizes.contains_key(l),
+                    InvalidValue,
+                    "Output label '{}' does not appear in inputs.",
+                    einsum_label_name(*l)
+                )?;
+                rstsr_assert!(
+                    !output[..i].contains(l),
+                    InvalidValue,
+                    "Output label '{}' is repeated.",
+                    einsum_label_name(*l)
+                )?;
+            }
+            output
+        },
+        None => {
+            // ellipsis axes first, then labels appearing exactly once in alphabetical order
+            let mut count = BTreeMap::new();
+            inputs.iter().flatten().for_each(|&l| *count.entry(l).or_insert(0) += 1);
+            let ellipsis = (0..max_ellipsis).map(|k| ELLIPSIS_LABEL + k);
+            let explicit = count.iter().filter(|&(&l, &c)| l < ELLIPSIS_LABEL && c == 1);
+            ellipsis.chain(explicit.map(|(&l, _)| l)).collect_vec()
+        },
+    };
+    return Ok(EinsumSubscripts { inputs, output, sizes });
+}
+
+fn einsum_label_name(label: usize) -> String {
+    match label {
+        l if l < ELLIPSIS_LABEL => format!("{}", l as u8 as char),
+        l => format!("...{}", l - ELLIPSIS_LABEL),
+    }
+}
+
+/// Labels of operands after simplification.
+///
+/// Returns labels after dropping broadcasted axes and merging repeated labels,
+/// and labels (among them) to be summed before pairwise contraction.
+fn einsum_simplified_labels(
+    subscripts: &EinsumSubscripts,
+    shapes: &[&[usize]],
+) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+    let EinsumSubscripts { inputs, output, sizes } = subscripts;
+    let labels = izip!(inputs, shapes)
+        .map(|(labels, shape)| {
+            let mut unique = vec![];
+            for (&l, &s) in izip!(labels, shape.iter()) {
+                let broadcasted = s == 1 && sizes[&l] != 1;
+                if !broadcasted && !unique.contains(&l) {
+                    unique.push(l);
+                }
+            }
+            unique
+        })
+        .collect_vec();
+    let summed = labels
+        .iter()
+        .enumerate()
+        .map(|(i, li)| {
+            li.iter()
+                .filter(|l| !output.contains(l))
+                .filter(|l| !labels.iter().enumerate().any(|(j, lj)| j != i && lj.contains(l)))
+                .cloned()
+                .collect_vec()
+        })
+        .collect_vec();
+    return (labels, summed);
+}
+
+/* #endregion */
+
+/* #region path */
+
+fn einsum_flops(labels: &BTreeSet<usize>, sizes: &BTreeMap<usize, usize>) -> f64 {
+    labels.iter().map(|l| sizes[l] as f64).product()
+}
+
+/// Search order of pairwise contractions.
+///
+/// Returns list of pairs `(i, j)` with `i < j`; at each step, operands at
+/// positions `i` and `j` are removed, and their contraction is appended to the
+/// end of the operand list.
+fn einsum_search_path(
+    inputs: &[Vec<usize>],
+    output: &[usize],
+    sizes: &BTreeMap<usize, usize>,
+    optimize: EinsumOptimize,
+) -> Vec<(usize, usize)> {
+    let n = inputs.len();
+    if n < 2 {
+        return vec![];
+    }
+    match optimize {
+        EinsumOptimize::None => {
+            let mut path = vec![(0, 1)];
+            path.extend((2..n).map(|k| (0, n - k)));
+            path
+        },
+        EinsumOptimize::Greedy => einsum_path_greedy(inputs, output, sizes),
+        EinsumOptimize::Optimal => einsum_path_optimal(inputs, output, sizes),
+        EinsumOptimize::Auto => match n <= EINSUM_OPTIMAL_LIMIT {
+            true => einsum_path_optimal(inputs, output, sizes),
+            false => einsum_path_greedy(inputs, output, sizes),
+        },
+    }
+}
+
+fn einsum_path_greedy(
+    inputs: &[Vec<usize>],
+    output: &[usize],
+    sizes: &BTreeMap<usize, usize>,
+) -> Vec<(usize, usize)> {
+    let mut operands: Vec<BTreeSet<usize>> =
+        inputs.iter().map(|l| l.iter().cloned().collect()).collect();
+    let output: BTreeSet<usize> = output.iter().cloned().collect();
+    let mut path = vec![];
+    while operands.len() > 1 {
+        // (size of result minus size of operands, flops, i, j, labels of result)
+        let mut best: Option<(f64, f64, usize, usize, BTreeSet<usize>)> = None;
+        for i in 0..operands.len() {
+            for j in i + 1..operands.len() {
+                let all = operands[i].union(&operands[j]).cloned().collect::<BTreeSet<_>>();
+                let kept = all
+                    .iter()
+                    .filter(|l| {
+                        output.contains(l)
+                            || operands
+                                .iter()
+                                .enumerate()
+                                .any(|(k, o)| k != i && k != j && o.contains(l))
+                    })
+                    .cloned()
+                    .collect::<BTreeSet<_>>();
+                let removed = einsum_flops(&kept, sizes)
+                    - einsum_flops(&operands[i], sizes)
+                    - einsum_flops(&operands[j], sizes);
+                let flops = einsum_flops(&all, sizes);
+                if best.as_ref().is_none_or(|b| (removed, flops) < (b.0, b.1)) {
+                    best = Some((removed, flops, i, j, kept));
+                }
+            }
+        }
+        let (_, _, i, j, kept) = best.unwrap();
+        operands.remove(j);
+        operands.remove(i);
+        operands.push(kept);
+        path.push((i, j));
+    }
+    return path;
+}
+
+fn einsum_path_optimal(
+    inputs: &[Vec<usize>],
+    output: &[usize],
+    sizes: &BTreeMap<usize, usize>,
+) -> Vec<(usize, usize)> {
+    let n = inputs.len();
+    let full = (1_usize << n) - 1;
+    let union = |mask: usize| {
+        (0..n)
+            .filter(|k| mask & (1 << k) != 0)
+            .flat_map(|k| inputs[k].iter().cloned())
+            .collect::<BTreeSet<_>>()
+    };
+
+    // labels of intermediate by contracting all operands in subset
+    let labels = (0..=full)
+        .map(|mask| {
+            let outside = union(full ^ mask);
+            union(mask).into_iter().filter(|l| outside.contains(l) || output.contains(l)).collect()
+        })
+        .collect::<Vec<BTreeSet<usize>>>();
+
+    // dynamic programming over subsets; submasks are always smaller than mask
+    let mut cost = vec![f64::INFINITY; full + 1];
+    let mut split = vec![0; full + 1];
+    for mask in 1..=full {
+        if mask.count_ones() == 1 {
+            cost[mask] = 0.0;
+            continue;
+        }
+        let lowbit = mask & mask.wrapping_neg();
+        let mut sub = (mask - 1) & mask;
+        while sub > 0 {
+            if sub & lowbit != 0 {
+                let other = mask ^ sub;
+                let all = labels[sub].union(&labels[other]).cloned().collect();
+                let c = cost[sub] + cost[other] + einsum_flops(&all, sizes);
+                if c < cost[mask] {
+                    cost[mask] = c;
+                    split[mask] = sub;
+                }
+            }
+            sub = (sub - 1) & mask;
+        }
+    }
+
+    // contraction tree to list of pairs
+    fn post_order(mask: usize, split: &[usize], pairs: &mut Vec<(usize, usize)>) {
+        if mask.count_ones() == 1 {
+            return;
+        }
+        let (sub, other) = (split[mask], mask ^ split[mask]);
+        post_order(sub, split, pairs);
+        post_order(other, split, pairs);
+        pairs.push((sub, other));
+    }
+    let mut pairs = vec![];
+    post_order(full, &split, &mut pairs);
+
+    let mut operands = (0..n).map(|k| 1 << k).collect_vec();
+    let mut path = vec![];
+    for (m1, m2) in pairs {
+        let p1 = operands.iter().position(|&m| m == m1).unwrap();
+        let p2 = operands.iter().position(|&m| m == m2).unwrap();
+        let (i, j) = (p1.min(p2), p1.max(p2));
+        operands.remove(j);
+        operands.remove(i);
+        operands.push(m1 | m2);
+        path.push((i, j));
+    }
+    return path;
+}
+
+/// Order of pairwise contractions that [`einsum`] would perform.
+///
+/// Returns list of pairs `(i, j)` with `i < j`; at each step, operands at
+/// positions `i` and `j` are removed, and their contraction is appended to the
+/// end of the operand list. Operands are counted after labels that appear in
+/// only one operand (and not in output) are summed.
+pub fn einsum_path(
+    subscripts: &str,
+    shapes: &[&[usize]],
+    optimize: EinsumOptimize,
+) -> Result<Vec<(usize, usize)>> {
+    let subscripts = einsum_parse(subscripts, shapes)?;
+    let (labels, summed) = einsum_simplified_labels(&subscripts, shapes);
+    let labels = izip!(labels, summed)
+        .map(|(l, s)| l.into_iter().filter(|x| !s.contains(x)).collect_vec())
+        .collect_vec();
+    return Ok(einsum_search_path(&labels, &subscripts.output, &subscripts.sizes, optimize));
+}
+
+/* #endregion */
+
+/* #region contraction */
+
+/// Operand of [`einsum`].
+///
+/// This trait allows operands of different ownership and dimensionality to be
+/// passed to [`einsum`] together, e.g. `&[&a, &b.view()]`.
+pub trait EinsumOperandAPI<T, B>
+where
+    B: DeviceAPI<T>,
+{
+    /// Dynamic-dimensional view of operand.
+    fn einsum_view(&self) -> Result<TensorView<'_, T, IxD, B>>;
+}
+
+impl<R, T, D, B> EinsumOperandAPI<T, B> for TensorBase<R, D>
+where
+    R: DataAPI<Data = Storage<T, B>>,
+    D: DimAPI,
+    B: DeviceAPI<T>,
+{
+    fn einsum_view(&self) -> Result<TensorView<'_, T, IxD, B>> {
+        self.view().into_dim::<IxD>()
+    }
+}
+
+/// Sum over labels `summed` of operand.
+///
+/// Labels of the result are the remaining labels of `a`, in the same order.
+fn einsum_sum_labels<'a, T, B>(
+    a: &TensorCow<'_, T, IxD, B>,
+    labels_a: &[usize],
+    summed: &[usize],
+) -> Result<(TensorCow<'a, T, IxD, B>, Vec<usize>)>
+where
+    T: Clone + Num,
+    B: DeviceAPI<T> + DeviceCreationAnyAPI<T> + OpSumAxesAPI<T>,
+{
+    let kept = labels_a.iter().filter(|l| !summed.contains(l)).cloned().collect_vec();
+    let position = |l: &usize| labels_a.iter().position(|x| x == l).unwrap() as isize;
+    let perm = kept.iter().chain(summed).map(position).collect_vec();
+    let la = a.layout().transpose(&perm)?;
+
+    let lc = la.shape()[..kept.len()].to_vec().new_contig(None);
+    let device = a.device();
+    let mut sc = unsafe { device.empty_impl(lc.size())? };
+    device.sum_axes(&mut sc, &lc, a.data().storage(), &la, summed.len())?;
+
+    let c = unsafe { TensorBase::new_unchecked(DataCow::Owned(sc.into()), lc) };
+    return Ok((c, kept));
+}
+
+/// Contract two operands by (batched) GEMM.
+///
+/// Labels in both operands are batch axes if they are in `kept`, otherwise
+/// contracted. Labels of the result are batch labels, then remaining labels of
+/// `a`, then remaining labels of `b`.
+fn einsum_contract_pair<'a, T, B>(
+    a: &TensorCow<'_, T, IxD, B>,
+    labels_a: &[usize],
+    b: &TensorCow<'_, T, IxD, B>,
+    labels_b: &[usize],
+    kept: &BTreeSet<usize>,
+) -> Result<(TensorCow<'a, T, IxD, B>, Vec<usize>)>
+where
+    T: Clone + Mul<T, Output = T> + Add<T, Output = T> + Num,
+    B: DeviceAPI<T> + DeviceCreationAnyAPI<T> + DeviceCreationNumAPI<T>,
+    B: OpAssignAPI<T, IxD> + DeviceMatMulAPI<T, T, T, IxD, IxD, IxD>,
+{
+    let common = labels_a.iter().filter(|l| labels_b.contains(l)).cloned().collect_vec();
+    let batch = common.iter().filter(|l| kept.contains(l)).cloned().collect_vec();
+    let contracted = common.iter().filter(|l| !kept.contains(l)).cloned().collect_vec();
+    let free_a = labels_a.iter().filter(|l| !common.contains(l)).cloned().collect_vec();
+    let free_b = labels_b.iter().filter(|l| !common.contains(l)).cloned().collect_vec();
+
+    let position = |labels: &[usize], l: &usize| labels.iter().position(|x| x == l).unwrap();
+    let perm_a = [&batch[..], &free_a, &contracted].concat();
+    let perm_a = perm_a.iter().map(|l| position(labels_a, l) as isize).collect_vec();
+    let perm_b = [&batch[..], &contracted, &free_b].concat();
+    let perm_b = perm_b.iter().map(|l| position(labels_b, l) as isize).collect_vec();
+    let la = a.layout().transpose(&perm_a)?;
+    let lb = b.layout().transpose(&perm_b)?;
+    let nbatch = batch.len();
+    let a_mat = storage_as_batched_matrix(a.data().storage(), &la, nbatch, free_a.len())?;
+    let b_mat = storage_as_batched_matrix(b.data().storage(), &lb, nbatch, contracted.len())?;
+
+    let shape_c = la.shape()[..nbatch + free_a.len()]
+        .iter()
+        .chain(&lb.shape()[nbatch + contracted.len()..])
+        .cloned()
+        .collect_vec();
+    let lc = shape_c.new_contig(None);
+    let order = TensorOrder::default();
+    let lc_mat = layout_as_batched_matrix(&lc, nbatch, free_a.len(), order).unwrap();
+    let device = a.device();
+    let mut sc = device.zeros_impl(lc.size())?;
+    let (sa, sb) = (a_mat.data().storage(), b_mat.data().storage());
+    let (la_mat, lb_mat) = (a_mat.layout(), b_mat.layout());
+    device.matmul(&mut sc, &lc_mat, sa, la_mat, sb, lb_mat, T::one(), T::zero())?;
+
+    let c = unsafe { TensorBase::new_unchecked(DataCow::Owned(sc.into()), lc) };
+    let labels_c = [batch, free_a, free_b].concat();
+    return Ok((c, labels_c));
+}
+
+/// Einstein summation over operands, with specified strategy of contraction
+/// order search.
+///
+/// # See also
+///
+/// [`einsum`]
+pub fn einsum_with_optimize<T, B>(
+    subscripts: &str,
+    operands: &[&dyn EinsumOperandAPI<T, B>],
+    optimize: EinsumOptimize,
+) -> Result<Tensor<T, IxD, B>>
+where
+    T: Clone + Mul<T, Output = T> + Add<T, Output = T> + Num,
+    B: DeviceAPI<T> + DeviceCreationAnyAPI<T> + DeviceCreationNumAPI<T> + OpSumAxesAPI<T>,
+    B: OpAssignAPI<T, IxD> + DeviceMatMulAPI<T, T, T, IxD, IxD, IxD>,
+{
+    rstsr_assert!(!operands.is_empty(), InvalidValue, "No operands given to einsum.")?;
+    let operands = operands.iter().map(|op| op.einsum_view()).collect::<Result<Vec<_>>>()?;
+    let device = operands[0].device().clone();
+    for op in &operands {
+        rstsr_assert!(op.device().same_device(&device), DeviceMismatch)?;
+    }
+    let layouts = operands.iter().map(|op| op.layout()).collect_vec();
+    let shapes = layouts.iter().map(|l| l.shape().as_slice()).collect_vec();
+    let subscripts = einsum_parse(subscripts, &shapes)?;
+    let (labels, summed) = einsum_simplified_labels(&subscripts, &shapes);
+    let EinsumSubscripts { inputs, output, sizes } = &subscripts;
+
+    // simplify operands: drop broadcasted axes, and take diagonals
+    let mut tensors: Vec<TensorCow<'_, T, IxD, B>> = vec![];
+    for (op, layout, labels_raw, labels) in izip!(&operands, layouts, inputs, &labels) {
+        let mut shape = vec![0; labels.len()];
+        let mut stride = vec![0; labels.len()];
+        for (&l, &s, &st) in izip!(labels_raw, layout.shape(), layout.stride()) {
+            let Some(k) = labels.iter().position(|&x| x == l) else { continue };
+            if s == 1 && sizes[&l] != 1 {
+                continue;
+            }
+            rstsr_assert!(
+                shape[k] == 0 || shape[k] == s,
+                InvalidLayout,
+                "Diagonal of non-square axes."
+            )?;
+            shape[k] = s;
+            stride[k] += st;
+        }
+        let layout = unsafe { Layout::new_unchecked(shape, stride, layout.offset()) };
+        let data = DataCow::Ref(op.data().storage().into());
+        tensors.push(unsafe { TensorBase::new_unchecked(data, layout) });
+    }
+
+    // sum labels that appear in only one operand and not in output
+    let mut labels = labels;
+    for (tensor, labels, summed) in izip!(tensors.iter_mut(), labels.iter_mut(), &summed) {
+        if summed.is_empty() {
+            continue;
+        }
+        let (t, l) = einsum_sum_labels(tensor, labels, summed)?;
+        (*tensor, *labels) = (t, l);
+    }
+
+    // pairwise contraction
+    let path = einsum_search_path(&labels, output, sizes, optimize);
+    for (i, j) in path {
+        let (tensor_b, labels_b) = (tensors.remove(j), labels.remove(j));
+        let (tensor_a, labels_a) = (tensors.remove(i), labels.remove(i));
+        let kept = labels.iter().flatten().chain(output).cloned().collect();
+        let (t, l) = einsum_contract_pair(&tensor_a, &labels_a, &tensor_b, &labels_b, &kept)?;
+        tensors.push(t);
+        labels.push(l);
+    }
+
+    // transpose to output, and copy if necessary
+    let (tensor, labels) = (tensors.pop().unwrap(), labels.pop().unwrap());
+    let perm = output.iter().map(|l| labels.iter().position(|x| x == l).unwrap() as isize);
+    let layout = tensor.layout().transpose(&perm.collect_vec())?;
+    let layout_out = layout.shape().new_contig(None);
+    match tensor.data {
+        DataCow::Owned(data) if layout == layout_out => {
+            return Ok(unsafe { Tensor::new_unchecked(data, layout_out) });
+        },
+        data => {
+            let mut storage = unsafe { device.empty_impl(layout_out.size())? };
+            device.assign(&mut storage, &layout_out, data.storage(), &layout)?;
+            return Ok(unsafe { Tensor::new_unchecked(storage.into(), layout_out) });
+        },
+    }
+}
+
+/// Einstein summation over operands.
+///
+/// Subscripts are of format such as `"ijkl,jb->ikbl"`, with ellipsis `...`
+/// supported for broadcasted axes. If output (`->` and the part after) is not
+/// given, output labels are the ellipsis axes followed by labels appearing
+/// exactly once, in alphabetical order.
+///
+/// Operands may differ in ownership and dimensionality (see
+/// [`EinsumOperandAPI`]), but should share the same type and device.
+///
+/// Contraction order is searched to minimize FLOPs, and each pairwise
+/// contraction is performed by (batched) GEMM.
+///
+/// # See also
+///
+/// - [`numpy.einsum`](https://numpy.org/doc/stable/reference/generated/numpy.einsum.html)
+/// - [`einsum_with_optimize`], [`einsum_path`]
+pub fn einsum<T, B>(
+    subscripts: &str,
+    operands: &[&dyn EinsumOperandAPI<T, B>],
+) -> Result<Tensor<T, IxD, B>>
+where
+    T: Clone + Mul<T, Output = T> + Add<T, Output = T> + Num,
+    B: DeviceAPI<T> + DeviceCreationAnyAPI<T> + DeviceCreationNumAPI<T> + OpSumAxesAPI<T>,
+    B: OpAssignAPI<T, IxD> + DeviceMatMulAPI<T, T, T, IxD, IxD, IxD>,
+{
+    einsum_with_optimize(subscripts, operands, EinsumOptimize::Auto)
+}
+
+/* #endregion */
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn arange(n: usize, shape: &[usize]) -> (Vec<f64>, Tensor<f64, IxD>) {
+        let v = (0..n).map(|x| (x as f64) * 0.5 - 2.0).collect_vec();
+        let t =
+            Tensor::asarray(v.clone()).unwrap().into_shape_assume_contig(shape.to_vec()).unwrap();
+        return (v, t);
+    }
+
+    #[test]
+    fn test_einsum_parse() {
+        let s = einsum_parse("ij,jk", &[&[2, 3], &[3, 4]]).unwrap();
+        assert_eq!(s.output, vec!['i' as usize, 'k' as usize]);
+        let s = einsum_parse("...ij, j... -> ...", &[&[5, 2, 3], &[3, 4, 5]]).unwrap();
+        assert_eq!(s.inputs[0][0], ELLIPSIS_LABEL + 1);
+        assert_eq!(s.output, vec![ELLIPSIS_LABEL, ELLIPSIS_LABEL + 1]);
+        assert!(einsum_parse("ij,jk", &[&[2, 3]]).is_err());
+        assert!(einsum_parse("ij,jk", &[&[2, 3], &[4, 4]]).is_err());
+        assert!(einsum_parse("ij->ii", &[&[2, 2]]).is_err());
+        assert!(einsum_parse("ij->k", &[&[2, 2]]).is_err());
+        assert!(einsum_parse("i1", &[&[2, 2]]).is_err());
+        assert!(einsum_parse("ijk", &[&[2, 2]]).is_err());
+    }
+
+    #[test]
+    fn test_einsum_path() {
+        // (ab)(bc) first is much cheaper than contracting with large d
+        let path =
+            einsum_path("ab,bc,cd", &[&[2, 20], &[20, 2], &[2, 100]], EinsumOptimize::Optimal);
+        assert_eq!(path.unwrap(), vec![(0, 1), (0, 1)]);
+        let path =
+            einsum_path("cd,ab,bc", &[&[2, 100], &[2, 20], &[20, 2]], EinsumOptimize::Greedy);
+        assert_eq!(path.unwrap(), vec![(1, 2), (0, 1)]);
+        let path = einsum_path("cd,ab,bc", &[&[2, 100], &[2, 20], &[20, 2]], EinsumOptimize::None);
+        assert_eq!(path.unwrap(), vec![(0, 1), (0, 1)]);
+        // contracting the small bond first keeps intermediates small
+        let shapes: [&[usize]; 3] = [&[100, 2], &[2, 100], &[100, 2]];
+        let path = einsum_path("ij,jk,kl", &shapes, EinsumOptimize::Greedy);
+        assert_eq!(path.unwrap(), vec![(1, 2), (0, 1)]);
+        let path = einsum_path("ij,jk,kl", &shapes, EinsumOptimize::None);
+        assert_eq!(path.unwrap(), vec![(0, 1), (0, 1)]);
+        // trace is summed before contraction
+        let path = einsum_path("ii,jk", &[&[3, 3], &[2, 2]], EinsumOptimize::Auto);
+        assert_eq!(path.unwrap(), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn test_einsum_matmul() {
+        let (va, a) = arange(24, &[2, 3, 4]);
+        let (vb, b) = arange(40, &[2, 4, 5]);
+
+        // batched matmul
+        let c = einsum("bij,bjk->bik", &[&a, &b]).unwrap();
+        assert_eq!(c.shape(), &[2, 3, 5]);
+        let mut c_ref = vec![0.0; 30];
+        for (p, i, j, k) in itertools::iproduct!(0..2, 0..3, 0..4, 0..5) {
+            c_ref[p * 15 + i * 5 + k] += va[p * 12 + i * 4 + j] * vb[p * 20 + j * 5 + k];
+        }
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), c_ref);
+
+        // same by ellipsis, with transposed output
+        let c = einsum("...ij,...jk->...ki", &[&a, &b]).unwrap();
+        assert_eq!(c.shape(), &[2, 5, 3]);
+        let c = einsum("bki->bik", &[&c]).unwrap();
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), c_ref);
+
+        // batch label summed as well
+        let c = einsum("bij,bjk->ik", &[&a, &b]).unwrap();
+        let mut c_sum = vec![0.0; 15];
+        for (p, i, k) in itertools::iproduct!(0..2, 0..3, 0..5) {
+            c_sum[i * 5 + k] += c_ref[p * 15 + i * 5 + k];
+        }
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), c_sum);
+
+        // ellipsis broadcasting with size-1 axes
+        let (_, a1) = arange(12, &[1, 3, 4]);
+        let c = einsum("...ij,...jk->...ik", &[&a1, &b]).unwrap();
+        assert_eq!(c.shape(), &[2, 3, 5]);
+    }
+
+    #[test]
+    fn test_einsum_trace_diagonal() {
+        let (va, a) = arange(27, &[3, 3, 3]);
+
+        let c = einsum("iii->i", &[&a]).unwrap();
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), vec![va[0], va[13], va[26]]);
+
+        let c = einsum("iij", &[&a]).unwrap();
+        let c_ref = (0..3).map(|j| (0..3).map(|i| va[i * 12 + j]).sum::<f64>()).collect_vec();
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), c_ref);
+
+        let c = einsum("ijk->", &[&a]).unwrap();
+        assert_eq!(c.shape(), &[] as &[usize]);
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), vec![va.iter().sum::<f64>()]);
+
+        // outer product and dot product of vectors
+        let (vx, x) = arange(3, &[3]);
+        let (vy, y) = arange(2, &[2]);
+        let c = einsum("i,j", &[&x, &y]).unwrap();
+        let c_ref = itertools::iproduct!(&vx, &vy).map(|(x, y)| x * y).collect_vec();
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), c_ref);
+        let c = einsum("i,i", &[&x, &x]).unwrap();
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), vec![vx
+            .iter()
+            .map(|x| x * x)
+            .sum::<f64>()]);
+        let c = einsum("i,j->", &[&x, &y]).unwrap();
+        let c_ref = vx.iter().sum::<f64>() * vy.iter().sum::<f64>();
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), vec![c_ref]);
+    }
+
+    #[test]
+    fn test_einsum_mixed_operands() {
+        // 4-D owned tensor and 2-D (transposed) view
+        let (va, a) = arange(120, &[2, 3, 4, 5]);
+        let a = a.into_dim::<Ix4>().unwrap();
+        let (vb, b) = arange(12, &[4, 3]);
+        let b = b.into_dim::<Ix2>().unwrap();
+        let bt = b.reverse_axes();
+        let c = einsum("ijkl,jb->ikbl", &[&a, &bt]).unwrap();
+        assert_eq!(c.shape(), &[2, 4, 4, 5]);
+        let mut c_ref = vec![0.0; 160];
+        for (i, j, k, b, l) in itertools::iproduct!(0..2, 0..3, 0..4, 0..4, 0..5) {
+            c_ref[i * 80 + k * 20 + b * 5 + l] += va[i * 60 + j * 20 + k * 5 + l] * vb[b * 3 + j];
+        }
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), c_ref);
+    }
+
+    #[test]
+    fn test_einsum_optimize() {
+        let (_, a) = arange(12, &[3, 4]);
+        let (_, b) = arange(20, &[4, 5]);
+        let (_, c) = arange(30, &[5, 6]);
+        let (_, d) = arange(18, &[6, 3]);
+        let ops: [&dyn EinsumOperandAPI<f64, DeviceCpu>; 4] = [&a, &b, &c, &d];
+        let r = einsum_with_optimize("ij,jk,kl,lm->im", &ops, EinsumOptimize::None).unwrap();
+        let r = r.data().storage().to_cpu_vec().unwrap();
+        for optimize in [EinsumOptimize::Greedy, EinsumOptimize::Optimal, EinsumOptimize::Auto] {
+            let s = einsum_with_optimize("ij,jk,kl,lm->im", &ops, optimize).unwrap();
+            assert_eq!(s.shape(), &[3, 3]);
+            assert!(izip!(&r, s.data().storage().to_cpu_vec().unwrap())
+                .all(|(x, y)| (x - y).abs() < 1e-8));
+        }
+        // order matters for cost, but not for result
+        let (_, a) = arange(200, &[100, 2]);
+        let (_, b) = arange(200, &[2, 100]);
+        let (_, c) = arange(200, &[100, 2]);
+        let ops3: [&dyn EinsumOperandAPI<f64, DeviceCpu>; 3] = [&a, &b, &c];
+        let r = einsum_with_optimize("ij,jk,kl", &ops3, EinsumOptimize::None).unwrap();
+        let s = einsum_with_optimize("ij,jk,kl", &ops3, EinsumOptimize::Greedy).unwrap();
+        assert_eq!(s.shape(), &[100, 2]);
+        let (r, s) =
+            (r.data().storage().to_cpu_vec().unwrap(), s.data().storage().to_cpu_vec().unwrap());
+        assert!(izip!(&r, &s).all(|(x, y)| (x - y).abs() <= 1e-8 * x.abs().max(1.0)));
+        // closed loop: trace of product
+        let s = einsum("ij,jk,kl,li", &ops).unwrap();
+        let t = einsum("ii", &[&einsum("ij,jk,kl,lm->im", &ops).unwrap()]).unwrap();
+        let (s, t) =
+            (s.data().storage().to_cpu_vec().unwrap(), t.data().storage().to_cpu_vec().unwrap());
+        assert!((s[0] - t[0]).abs() < 1e-8);
+    }
+}
@@ -1 +1,570 @@
+//! Element iterators of tensor.
+//!
+//! Iteration order is given by [`TensorIterOrder`]:
+//! - C: row-major (last axis changes fastest),
+//! - F: column-major (first axis changes fastest),
+//! - K: memory order (axis of smallest absolute stride changes fastest),
+//! - A: C or F, whichever is closer to memory order.
+//!
+//! Orders G and B are not accepted, since they are not defined on
+//! multi-indices.
 
+use crate::prelude_dev::*;
+
+/* #region iteration layout */
+
+/// Axes of `layout` from fastest-changing to slowest-changing in `order`.
+fn iter_axes<D>(layout: &Layout<D>, order: TensorIterOrder) -> Result<Vec<isize>>
+where
+    D: DimAPI,
+{
+    let ndim = layout.ndim() as isize;
+    let fn_c = || (0..ndim).rev().collect_vec();
+    let fn_f = || (0..ndim).collect_vec();
+    let axes = match order {
+        TensorIterOrder::C => fn_c(),
+        TensorIterOrder::F => fn_f(),
+        TensorIterOrder::A => match (layout.c_prefer(), layout.f_prefer()) {
+            (true, false) => fn_c(),
+            (false, true) => fn_f(),
+            _ => match TensorOrder::default() {
+                TensorOrder::C => fn_c(),
+                TensorOrder::F => fn_f(),
+            },
+        },
+        TensorIterOrder::K => {
+            // stable sort keeps row-major for axes of same stride
+            let stride = layout.stride().as_ref();
+            let mut axes = fn_c();
+            axes.sort_by_key(|&i| stride[i as usize].unsigned_abs());
+            axes
+        },
+        _ => rstsr_invalid!(order, "Element iterator only accepts CFAK.")?,
+    };
+    return Ok(axes);
+}
+
+/// Column-major layout iterator of elements of `layout` in `order`, with axes
+/// of iteration.
+fn iter_layout<D>(layout: &Layout<D>, order: TensorIterOrder) -> Result<IterLayoutAxes<D>>
+where
+    D: DimAPI,
+{
+    let axes = iter_axes(layout, order)?;
+    let iter = IterLayoutColMajor::new(&layout.transpose(&axes)?)?;
+    return Ok(IterLayoutAxes { iter, axes });
+}
+
+/// Layout iterator that also gives multi-indices of original layout.
+#[derive(Debug, Clone)]
+struct IterLayoutAxes<D>
+where
+    D: DimDevAPI,
+{
+    iter: IterLayoutColMajor<D>,
+    axes: Vec<isize>,
+}
+
+impl<D> IterLayoutAxes<D>
+where
+    D: DimDevAPI,
+{
+    /// Multi-index of original layout from multi-index of iteration layout.
+    fn index(&self, index_iter: &D) -> D {
+        let mut index = index_iter.clone();
+        for (&axis, &i) in self.axes.iter().zip(index_iter.as_ref()) {
+            index[axis as usize] = i;
+        }
+        return index;
+    }
+
+    fn next_indexed(&mut self) -> Option<(D, usize)> {
+        let index = self.index(self.iter.index_start());
+        self.iter.next().map(|offset| (index, offset))
+    }
+
+    fn next_back_indexed(&mut self) -> Option<(D, usize)> {
+        let offset = self.iter.next_back()?;
+        Some((self.index(self.iter.index_end()), offset))
+    }
+}
+
+/* #endregion */
+
+/* #region iterator structs */
+
+/// Iterator of element references of tensor.
+#[derive(Debug, Clone)]
+pub struct IterTensor<'a, T, D>
+where
+    D: DimDevAPI,
+{
+    iter: IterLayoutColMajor<D>,
+    slc: CpuRawSlice<'a, T>,
+}
+
+/// Iterator of mutable element references of tensor.
+#[derive(Debug)]
+pub struct IterMutTensor<'a, T, D>
+where
+    D: DimDevAPI,
+{
+    iter: IterLayoutColMajor<D>,
+    ptr: *mut T,
+    len: usize,
+    _phantom: PhantomData<&'a mut T>,
+}
+
+// SAFETY: behaves as `&mut [T]`
+unsafe impl<T: Send, D: DimDevAPI> Send for IterMutTensor<'_, T, D> {}
+unsafe impl<T: Sync, D: DimDevAPI> Sync for IterMutTensor<'_, T, D> {}
+
+/// Iterator of multi-indices and element references of tensor.
+#[derive(Debug, Clone)]
+pub struct IndexedIterTensor<'a, T, D>
+where
+    D: DimDevAPI,
+{
+    iter: IterLayoutAxes<D>,
+    slc: CpuRawSlice<'a, T>,
+}
+
+/// Iterator of (cloned) element values of tensor, which owns the tensor.
+#[derive(Debug, Clone)]
+pub struct IntoIterTensor<R, D>
+where
+    D: DimDevAPI,
+{
+    iter: IterLayoutColMajor<D>,
+    data: R,
+}
+
+impl<'a, T, D> Iterator for IterTensor<'a, T, D>
+where
+    D: DimDevAPI,
+{
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|offset| self.slc.at(offset))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, D> DoubleEndedIterator for IterTensor<'_, T, D>
+where
+    D: DimDevAPI,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|offset| self.slc.at(offset))
+    }
+}
+
+impl<T, D> ExactSizeIterator for IterTensor<'_, T, D> where D: DimDevAPI {}
+
+impl<'a, T, D> IterMutTensor<'a, T, D>
+where
+    D: DimDevAPI,
+{
+    /// # Safety
+    ///
+    /// Each offset given by `iter` must be less than `len`; offsets must not
+    /// repeat.
+    #[inline]
+    unsafe fn get(&mut self, offset: usize) -> &'a mut T {
+        debug_assert!(offset < self.len);
+        &mut *self.ptr.add(offset)
+    }
+}
+
+impl<'a, T, D> Iterator for IterMutTensor<'a, T, D>
+where
+    D: DimDevAPI,
+{
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.iter.next()?;
+        Some(unsafe { self.get(offset) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, D> DoubleEndedIterator for IterMutTensor<'_, T, D>
+where
+    D: DimDevAPI,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let offset = self.iter.next_back()?;
+        Some(unsafe { self.get(offset) })
+    }
+}
+
+impl<T, D> ExactSizeIterator for IterMutTensor<'_, T, D> where D: DimDevAPI {}
+
+impl<'a, T, D> Iterator for IndexedIterTensor<'a, T, D>
+where
+    D: DimDevAPI,
+{
+    type Item = (D, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next_indexed().map(|(index, offset)| (index, self.slc.at(offset)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.iter.size_hint()
+    }
+}
+
+impl<T, D> DoubleEndedIterator for IndexedIterTensor<'_, T, D>
+where
+    D: DimDevAPI,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back_indexed().map(|(index, offset)| (index, self.slc.at(offset)))
+    }
+}
+
+impl<T, D> ExactSizeIterator for IndexedIterTensor<'_, T, D> where D: DimDevAPI {}
+
+impl<R, T, D, B> Iterator for IntoIterTensor<R, D>
+where
+    R: DataAPI<Data = Storage<T, B>>,
+    T: Clone,
+    D: DimDevAPI,
+    B: DeviceAPI<T>,
+    B::RawVec: CpuRawVecAPI<T>,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.iter.next()?;
+        Some(self.data.storage().rawvec().as_raw_slice()[offset].clone())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<R, T, D, B> DoubleEndedIterator for IntoIterTensor<R, D>
+where
+    R: DataAPI<Data = Storage<T, B>>,
+    T: Clone,
+    D: DimDevAPI,
+    B: DeviceAPI<T>,
+    B::RawVec: CpuRawVecAPI<T>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let offset = self.iter.next_back()?;
+        Some(self.data.storage().rawvec().as_raw_slice()[offset].clone())
+    }
+}
+
+impl<R, T, D, B> ExactSizeIterator for IntoIterTensor<R, D>
+where
+    R: DataAPI<Data = Storage<T, B>>,
+    T: Clone,
+    D: DimDevAPI,
+    B: DeviceAPI<T>,
+    B::RawVec: CpuRawVecAPI<T>,
+{
+}
+
+/* #endregion */
+
+/* #region split */
+
+/// Iterator that can be split into two at index, such that elements before
+/// index are given by the first one.
+///
+/// This is used for parallel iteration.
+pub trait IterSplitAtAPI: Sized + DoubleEndedIterator + ExactSizeIterator {
+    fn split_at(self, index: usize) -> (Self, Self);
+}
+
+impl<T, D> IterSplitAtAPI for IterTensor<'_, T, D>
+where
+    D: DimDevAPI,
+{
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let (lhs, rhs) = self.iter.split_at(index).unwrap();
+        return (Self { iter: lhs, slc: self.slc }, Self { iter: rhs, slc: self.slc });
+    }
+}
+
+impl<T, D> IterSplitAtAPI for IterMutTensor<'_, T, D>
+where
+    D: DimDevAPI,
+{
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let Self { iter, ptr, len, _phantom } = self;
+        let (lhs, rhs) = iter.split_at(index).unwrap();
+        // split iterators give disjoint elements
+        let lhs = Self { iter: lhs, ptr, len, _phantom };
+        let rhs = Self { iter: rhs, ptr, len, _phantom };
+        return (lhs, rhs);
+    }
+}
+
+impl<T, D> IterSplitAtAPI for IndexedIterTensor<'_, T, D>
+where
+    D: DimDevAPI,
+{
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let IterLayoutAxes { iter, axes } = self.iter;
+        let (lhs, rhs) = iter.split_at(index).unwrap();
+        let lhs = IterLayoutAxes { iter: lhs, axes: axes.clone() };
+        let rhs = IterLayoutAxes { iter: rhs, axes };
+        return (Self { iter: lhs, slc: self.slc }, Self { iter: rhs, slc: self.slc });
+    }
+}
+
+/* #endregion */
+
+/* #region tensor methods */
+
+/// Default element iteration order, defined by crate feature `c_prefer`.
+fn default_iter_order() -> TensorIterOrder {
+    match TensorOrder::default() {
+        TensorOrder::C => TensorIterOrder::C,
+        TensorOrder::F => TensorIterOrder::F,
+    }
+}
+
+impl<R, T, D, B> TensorBase<R, D>
+where
+    R: DataAPI<Data = Storage<T, B>>,
+    D: DimAPI,
+    B: DeviceAPI<T>,
+    B::RawVec: CpuRawVecAPI<T>,
+{
+    /// Iterator of element references in `order`.
+    ///
+    /// See [module documentation](self) for accepted orders.
+    pub fn iter_with_order<'a>(&'a self, order: TensorIterOrder) -> Result<IterTensor<'a, T, D>>
+    where
+        B: 'a,
+    {
+        let axes = iter_axes(self.layout(), order)?;
+        let iter = IterLayoutColMajor::new(&self.layout().transpose(&axes)?)?;
+        let slc = self.data().storage().rawvec().as_raw_slice();
+        return Ok(IterTensor { iter, slc });
+    }
+
+    /// Iterator of element references, in row-major order (or column-major
+    /// order if crate feature `c_prefer` is not enabled).
+    pub fn iter<'a>(&'a self) -> IterTensor<'a, T, D>
+    where
+        B: 'a,
+    {
+        self.iter_with_order(default_iter_order()).unwrap()
+    }
+
+    /// Iterator of multi-indices and element references in `order`.
+    ///
+    /// See [module documentation](self) for accepted orders.
+    pub fn indexed_iter_with_order<'a>(
+        &'a self,
+        order: TensorIterOrder,
+    ) -> Result<IndexedIterTensor<'a, T, D>>
+    where
+        B: 'a,
+    {
+        let iter = iter_layout(self.layout(), order)?;
+        let slc = self.data().storage().rawvec().as_raw_slice();
+        return Ok(IndexedIterTensor { iter, slc });
+    }
+
+    /// Iterator of multi-indices and element references, in row-major order
+    /// (or column-major order if crate feature `c_prefer` is not enabled).
+    pub fn indexed_iter<'a>(&'a self) -> IndexedIterTensor<'a, T, D>
+    where
+        B: 'a,
+    {
+        self.indexed_iter_with_order(default_iter_order()).unwrap()
+    }
+
+    /// Iterator of (cloned) element values in `order`, consuming tensor.
+    ///
+    /// See [module documentation](self) for accepted orders.
+    pub fn into_iter_with_order(self, order: TensorIterOrder) -> Result<IntoIterTensor<R, D>> {
+        let axes = iter_axes(self.layout(), order)?;
+        let iter = IterLayoutColMajor::new(&self.layout().transpose(&axes)?)?;
+        return Ok(IntoIterTensor { iter, data: self.data });
+    }
+}
+
+impl<R, T, D, B> TensorBase<R, D>
+where
+    R: DataMutAPI<Data = Storage<T, B>>,
+    D: DimAPI,
+    B: DeviceAPI<T>,
+    B::RawVec: CpuRawVecAPI<T>,
+{
+    /// Iterator of mutable element references in `order`.
+    ///
+    /// See [module documentation](self) for accepted orders. Raises error if
+    /// elements of tensor overlap in memory (such as broadcasted tensor).
+    pub fn iter_mut_with_order<'a>(
+        &'a mut self,
+        order: TensorIterOrder,
+    ) -> Result<IterMutTensor<'a, T, D>>
+    where
+        B: 'a,
+    {
+        // elements must not be given twice
+        self.layout().check_strides()?;
+        let axes = iter_axes(self.layout(), order)?;
+        let iter = IterLayoutColMajor::new(&self.layout().transpose(&axes)?)?;
+        let mut slc = unsafe { self.data_mut().storage_mut() }.rawvec_mut().as_raw_slice_mut();
+        let (ptr, len) = (slc.as_mut_ptr(), slc.len());
+        return Ok(IterMutTensor { iter, ptr, len, _phantom: PhantomData });
+    }
+
+    /// Iterator of mutable element references, in row-major order (or
+    /// column-major order if crate feature `c_prefer` is not enabled).
+    ///
+    /// # Panics
+    ///
+    /// - Elements of tensor overlap in memory (such as broadcasted tensor).
+    pub fn iter_mut<'a>(&'a mut self) -> IterMutTensor<'a, T, D>
+    where
+        B: 'a,
+    {
+        self.iter_mut_with_order(default_iter_order()).unwrap()
+    }
+}
+
+impl<R, T, D, B> IntoIterator for TensorBase<R, D>
+where
+    R: DataAPI<Data = Storage<T, B>>,
+    T: Clone,
+    D: DimAPI,
+    B: DeviceAPI<T>,
+    B::RawVec: CpuRawVecAPI<T>,
+{
+    type Item = T;
+    type IntoIter = IntoIterTensor<R, D>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_iter_with_order(default_iter_order()).unwrap()
+    }
+}
+
+impl<'a, R, T, D, B> IntoIterator for &'a TensorBase<R, D>
+where
+    T: 'a,
+    B: 'a,
+    R: DataAPI<Data = Storage<T, B>>,
+    D: DimAPI,
+    B: DeviceAPI<T>,
+    B::RawVec: CpuRawVecAPI<T>,
+{
+    type Item = &'a T;
+    type IntoIter = IterTensor<'a, T, D>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, R, T, D, B> IntoIterator for &'a mut TensorBase<R, D>
+where
+    T: 'a,
+    B: 'a,
+    R: DataMutAPI<Data = Storage<T, B>>,
+    D: DimAPI,
+    B: DeviceAPI<T>,
+    B::RawVec: CpuRawVecAPI<T>,
+{
+    type Item = &'a mut T;
+    type IntoIter = IterMutTensor<'a, T, D>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/* #endregion */
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_iter() {
+        // a = np.arange(24).reshape(2, 3, 4)[:, ::-1, 1:3]
+        let a = Tensor::from_shape_vec([2, 3, 4], (0..24).collect::<Vec<i32>>()).unwrap();
+        let layout = Layout::new([2, 3, 2], [12, -4, 1], 9);
+        let b = unsafe { TensorView::new_unchecked(a.data().as_ref(), layout) };
+
+        let c = b.iter_with_order(TensorIterOrder::C).unwrap().copied().collect_vec();
+        assert_eq!(c, [9, 10, 5, 6, 1, 2, 21, 22, 17, 18, 13, 14]);
+        let f = b.iter_with_order(TensorIterOrder::F).unwrap().copied().collect_vec();
+        assert_eq!(f, [9, 21, 5, 17, 1, 13, 10, 22, 6, 18, 2, 14]);
+        let k = b.iter_with_order(TensorIterOrder::K).unwrap().copied().collect_vec();
+        assert_eq!(k, [9, 10, 5, 6, 1, 2, 21, 22, 17, 18, 13, 14]);
+        let k =
+            b.reverse_axes().iter_with_order(TensorIterOrder::K).unwrap().copied().collect_vec();
+        assert_eq!(k, [9, 10, 5, 6, 1, 2, 21, 22, 17, 18, 13, 14]);
+        assert!(b.iter_with_order(TensorIterOrder::B).is_err());
+
+        let iter = b.iter_with_order(TensorIterOrder::C).unwrap();
+        assert_eq!(iter.len(), 12);
+        let r = iter.rev().copied().collect_vec();
+        assert_eq!(r, [14, 13, 18, 17, 22, 21, 2, 1, 6, 5, 10, 9]);
+        let mut iter = b.iter_with_order(TensorIterOrder::C).unwrap();
+        assert_eq!((iter.next(), iter.next_back(), iter.len()), (Some(&9), Some(&14), 10));
+
+        // indexed iteration gives indices of original axes in any order
+        let bt = b.reverse_axes();
+        for order in [TensorIterOrder::C, TensorIterOrder::F, TensorIterOrder::K] {
+            let mut iter = bt.indexed_iter_with_order(order).unwrap();
+            let (index, &val) = iter.next_back().unwrap();
+            assert_eq!(val, bt.iter_with_order(order).unwrap().next_back().copied().unwrap());
+            assert_eq!(val, 9 + 12 * index[2] as i32 - 4 * index[1] as i32 + index[0] as i32);
+            for (index, &val) in iter {
+                assert_eq!(val, 9 + 12 * index[2] as i32 - 4 * index[1] as i32 + index[0] as i32);
+            }
+        }
+
+        let s: i32 = b.into_iter().sum();
+        assert_eq!(s, 138);
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut a = Tensor::from_shape_vec([2, 3], vec![0; 6]).unwrap();
+        let layout = a.layout().reverse_axes();
+        let mut t = unsafe { TensorViewMut::new_unchecked(a.data_mut().as_ref_mut(), layout) };
+        for (i, x) in t.iter_mut().enumerate() {
+            *x = i;
+        }
+        assert_eq!(a.storage().rawvec(), &vec![0, 2, 4, 1, 3, 5]);
+        for x in &mut a {
+            *x *= 10;
+        }
+        assert_eq!((&a).into_iter().rev().copied().collect_vec(), [50, 30, 10, 40, 20, 0]);
+
+        // overlapping elements can not be mutably iterated
+        let layout = unsafe { Layout::new_unchecked([2, 3], [0, 1], 0) };
+        let mut b = unsafe { TensorViewMut::new_unchecked(a.data_mut().as_ref_mut(), layout) };
+        assert!(b.iter_mut_with_order(TensorIterOrder::K).is_err());
+    }
+}
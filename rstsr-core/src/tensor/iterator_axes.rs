@@ -0,0 +1,681 @@
+//! Iterators of sub-views of tensor along an axis.
+//!
+//! - [`axis_iter`](TensorBase::axis_iter): sub-views by selecting each index of
+//!   axis (one less dimension);
+//! - [`lanes`](TensorBase::lanes): 1-D views along axis, for each index of
+//!   other axes (in row-major order);
+//! - [`axis_chunks_iter`](TensorBase::axis_chunks_iter): sub-views by narrowing
+//!   axis to chunks of fixed size (the last chunk may be smaller);
+//! - [`windows`](TensorBase::windows): overlapping sub-views of fixed shape, at
+//!   each position where the window fits in tensor (in row-major order).
+//!
+//! Except for windows, each of them has a mutable counterpart, which is only
+//! available for tensors on CPU devices (raw vector [`CpuRawVec`]). Each
+//! mutable sub-view only borrows its own elements of buffer by pointer and
+//! layout, so sub-views may interleave in memory (such as columns of
+//! row-major matrix); tensors whose elements overlap are refused.
+
+use crate::prelude_dev::*;
+
+/* #region sub-layouts */
+
+#[derive(Debug, Clone)]
+pub(crate) enum SubLayoutKind {
+    Select,
+    Chunks(usize),
+    /// Layout of lane starts (axis of lanes eliminated).
+    Lanes(Layout<IxD>),
+    /// Layout of window starts (shape is number of windows of each axis).
+    Windows(Layout<IxD>),
+}
+
+/// Layouts of sub-views along an axis, given by index in `start..end`.
+#[derive(Debug, Clone)]
+pub(crate) struct SubLayouts {
+    pub(crate) layout: Layout<IxD>,
+    pub(crate) axis: usize,
+    pub(crate) kind: SubLayoutKind,
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+impl SubLayouts {
+    fn new_select<D>(layout: &Layout<D>, axis: isize) -> Result<Self>
+    where
+        D: DimAPI,
+    {
+        let (layout, axis) = Self::check_axis(layout, axis)?;
+        let end = layout.shape()[axis];
+        return Ok(Self { layout, axis, kind: SubLayoutKind::Select, start: 0, end });
+    }
+
+    fn new_chunks<D>(layout: &Layout<D>, axis: isize, size: usize) -> Result<Self>
+    where
+        D: DimAPI,
+    {
+        rstsr_assert!(size > 0, InvalidValue, "Chunk size should be positive.")?;
+        let (layout, axis) = Self::check_axis(layout, axis)?;
+        let end = layout.shape()[axis].div_ceil(size);
+        return Ok(Self { layout, axis, kind: SubLayoutKind::Chunks(size), start: 0, end });
+    }
+
+    fn new_lanes<D>(layout: &Layout<D>, axis: isize) -> Result<Self>
+    where
+        D: DimAPI,
+    {
+        let (layout, axis) = Self::check_axis(layout, axis)?;
+        let mut shape = layout.shape().clone();
+        let mut stride = layout.stride().clone();
+        shape.remove(axis);
+        stride.remove(axis);
+        let layout_rest = unsafe { Layout::new_unchecked(shape, stride, layout.offset()) };
+        let end = layout_rest.size();
+        return Ok(Self { layout, axis, kind: SubLayoutKind::Lanes(layout_rest), start: 0, end });
+    }
+
+    fn new_windows<D>(layout: &Layout<D>, window: &D) -> Result<Self>
+    where
+        D: DimAPI,
+    {
+        rstsr_assert_eq!(
+            window.ndim(),
+            layout.ndim(),
+            InvalidLayout,
+            "Window should have the same dimension as tensor."
+        )?;
+        rstsr_assert!(
+            window.as_ref().iter().all(|&w| w > 0),
+            InvalidValue,
+            "Window size should be positive."
+        )?;
+        let layout = layout.to_dim::<IxD>()?;
+        let shape = layout
+            .shape()
+            .iter()
+            .zip(window.as_ref())
+            .map(|(&n, &w)| (n + 1).saturating_sub(w))
+            .collect_vec();
+        let stride = layout.stride().clone();
+        let layout_starts = unsafe { Layout::new_unchecked(shape, stride, layout.offset()) };
+        let end = layout_starts.size();
+        let kind = SubLayoutKind::Windows(layout_starts);
+        let layout = unsafe {
+            Layout::new_unchecked(
+                window.as_ref().to_vec(),
+                layout.stride().clone(),
+                layout.offset(),
+            )
+        };
+        return Ok(Self { layout, axis: 0, kind, start: 0, end });
+    }
+
+    fn check_axis<D>(layout: &Layout<D>, axis: isize) -> Result<(Layout<IxD>, usize)>
+    where
+        D: DimAPI,
+    {
+        let ndim = layout.ndim() as isize;
+        let axis = if axis < 0 { ndim + axis } else { axis };
+        rstsr_pattern!(axis, 0..ndim, ValueOutOfRange)?;
+        return Ok((layout.to_dim::<IxD>()?, axis as usize));
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Layout of sub-view at index `index` (counted from zero, not from
+    /// `start`).
+    pub(crate) fn layout_at(&self, index: usize) -> Layout<IxD> {
+        let axis = self.axis;
+        match &self.kind {
+            SubLayoutKind::Select => self.layout.dim_select(axis as isize, index as isize).unwrap(),
+            SubLayoutKind::Chunks(size) => {
+                let start = (index * size) as isize;
+                let stop = start + *size as isize;
+                self.layout.dim_narrow(axis as isize, slice!(start, stop)).unwrap()
+            },
+            SubLayoutKind::Lanes(layout_rest) => {
+                let offset = unsafe {
+                    let index_rest = layout_rest.unravel_index_c(index);
+                    layout_rest.index_uncheck(index_rest.as_ref()) as usize
+                };
+                let shape = vec![self.layout.shape()[axis]];
+                let stride = vec![self.layout.stride()[axis]];
+                unsafe { Layout::new_unchecked(shape, stride, offset) }
+            },
+            SubLayoutKind::Windows(layout_starts) => {
+                let offset = unsafe {
+                    let index_start = layout_starts.unravel_index_c(index);
+                    layout_starts.index_uncheck(index_start.as_ref()) as usize
+                };
+                let shape = self.layout.shape().clone();
+                let stride = self.layout.stride().clone();
+                unsafe { Layout::new_unchecked(shape, stride, offset) }
+            },
+        }
+    }
+
+    /// Split into sub-layouts of `start..start + index` and the rest.
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let mid = self.start + index;
+        let lhs = Self { end: mid, ..self.clone() };
+        let rhs = Self { start: mid, ..self };
+        return (lhs, rhs);
+    }
+}
+
+impl Iterator for SubLayouts {
+    type Item = Layout<IxD>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start >= self.end {
+            return None;
+        }
+        self.start += 1;
+        Some(self.layout_at(self.start - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+impl DoubleEndedIterator for SubLayouts {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start >= self.end {
+            return None;
+        }
+        self.end -= 1;
+        Some(self.layout_at(self.end))
+    }
+}
+
+impl ExactSizeIterator for SubLayouts {}
+
+/* #endregion */
+
+/* #region iterator structs */
+
+/// Iterator of sub-views of tensor along an axis.
+#[derive(Clone)]
+pub struct IterAxisView<'a, T, D, B>
+where
+    B: DeviceAPI<T>,
+{
+    pub(crate) data: DataRef<'a, Storage<T, B>>,
+    pub(crate) layouts: SubLayouts,
+    _phantom: PhantomData<D>,
+}
+
+/// Iterator of mutable sub-views of tensor along an axis.
+///
+/// Sub-views borrow disjoint (but possibly interleaved) elements of the same
+/// buffer of CPU device.
+pub struct IterAxisViewMut<'a, T, D, B>
+where
+    B: DeviceAPI<T>,
+{
+    pub(crate) ptr: *mut T,
+    pub(crate) len: usize,
+    pub(crate) device: B,
+    pub(crate) layouts: SubLayouts,
+    _phantom: PhantomData<(&'a mut T, D)>,
+}
+
+// SAFETY: behaves as `&mut T` of disjoint elements of sub-views
+unsafe impl<T: Send, D, B: DeviceAPI<T> + Send> Send for IterAxisViewMut<'_, T, D, B> {}
+unsafe impl<T: Sync, D, B: DeviceAPI<T> + Sync> Sync for IterAxisViewMut<'_, T, D, B> {}
+
+impl<'a, T, D, B> IterAxisView<'a, T, D, B>
+where
+    D: DimAPI,
+    B: DeviceAPI<T>,
+    IxD: DimConvertAPI<D>,
+{
+    pub(crate) fn from_parts(data: DataRef<'a, Storage<T, B>>, layouts: SubLayouts) -> Self {
+        Self { data, layouts, _phantom: PhantomData }
+    }
+
+    fn view(&self, layout: Layout<IxD>) -> TensorView<'a, T, D, B> {
+        let layout = layout.into_dim::<D>().unwrap();
+        unsafe { TensorBase::new_unchecked(self.data.clone(), layout) }
+    }
+}
+
+impl<'a, T, D, B> IterAxisViewMut<'a, T, D, B>
+where
+    B: DeviceAPI<T>,
+{
+    /// # Safety
+    ///
+    /// `ptr` and `len` must be buffer of tensor mutably borrowed for `'a`;
+    /// layouts of `layouts` must not overlap with each other.
+    pub(crate) unsafe fn from_parts(
+        ptr: *mut T,
+        len: usize,
+        device: B,
+        layouts: SubLayouts,
+    ) -> Self {
+        Self { ptr, len, device, layouts, _phantom: PhantomData }
+    }
+}
+
+impl<'a, T, D, B> IterAxisViewMut<'a, T, D, B>
+where
+    T: Clone + 'a,
+    D: DimAPI,
+    B: DeviceAPI<T, RawVec = CpuRawVec<T>> + 'a,
+    IxD: DimConvertAPI<D>,
+{
+    fn view_mut(&self, mut layout: Layout<IxD>) -> TensorViewMut<'a, T, D, B> {
+        // sub-view only borrows its own elements in range of buffer, which are
+        // disjoint to elements of other sub-views
+        let (min, max) = layout.bounds_index().unwrap();
+        debug_assert!(max <= self.len);
+        unsafe { layout.set_offset(layout.offset() - min) };
+        let rawvec =
+            unsafe { CpuRawVec::from_raw_parts_mut(self.ptr.add(min), max - min, &layout) };
+        let layout = layout.into_dim::<D>().unwrap();
+        // the view does not own memory, so bypass memory accounting of `new`
+        let storage = Storage { rawvec, device: self.device.clone() };
+        let data = unsafe { DataRefMut::from_borrowed(storage) };
+        unsafe { TensorBase::new_unchecked(data, layout) }
+    }
+}
+
+impl<'a, T, D, B> Iterator for IterAxisView<'a, T, D, B>
+where
+    D: DimAPI,
+    B: DeviceAPI<T>,
+    IxD: DimConvertAPI<D>,
+{
+    type Item = TensorView<'a, T, D, B>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.layouts.next().map(|layout| self.view(layout))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.layouts.size_hint()
+    }
+}
+
+impl<T, D, B> DoubleEndedIterator for IterAxisView<'_, T, D, B>
+where
+    D: DimAPI,
+    B: DeviceAPI<T>,
+    IxD: DimConvertAPI<D>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.layouts.next_back().map(|layout| self.view(layout))
+    }
+}
+
+impl<T, D, B> ExactSizeIterator for IterAxisView<'_, T, D, B>
+where
+    D: DimAPI,
+    B: DeviceAPI<T>,
+    IxD: DimConvertAPI<D>,
+{
+}
+
+impl<'a, T, D, B> Iterator for IterAxisViewMut<'a, T, D, B>
+where
+    T: Clone + 'a,
+    D: DimAPI,
+    B: DeviceAPI<T, RawVec = CpuRawVec<T>> + 'a,
+    IxD: DimConvertAPI<D>,
+{
+    type Item = TensorViewMut<'a, T, D, B>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.layouts.next().map(|layout| self.view_mut(layout))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.layouts.size_hint()
+    }
+}
+
+impl<'a, T, D, B> DoubleEndedIterator for IterAxisViewMut<'a, T, D, B>
+where
+    T: Clone + 'a,
+    D: DimAPI,
+    B: DeviceAPI<T, RawVec = CpuRawVec<T>> + 'a,
+    IxD: DimConvertAPI<D>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.layouts.next_back().map(|layout| self.view_mut(layout))
+    }
+}
+
+impl<'a, T, D, B> ExactSizeIterator for IterAxisViewMut<'a, T, D, B>
+where
+    T: Clone + 'a,
+    D: DimAPI,
+    B: DeviceAPI<T, RawVec = CpuRawVec<T>> + 'a,
+    IxD: DimConvertAPI<D>,
+{
+}
+
+impl<T, D, B> IterSplitAtAPI for IterAxisView<'_, T, D, B>
+where
+    D: DimAPI,
+    B: DeviceAPI<T>,
+    IxD: DimConvertAPI<D>,
+{
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let (lhs, rhs) = self.layouts.split_at(index);
+        return (Self::from_parts(self.data.clone(), lhs), Self::from_parts(self.data, rhs));
+    }
+}
+
+impl<'a, T, D, B> IterSplitAtAPI for IterAxisViewMut<'a, T, D, B>
+where
+    T: Clone + 'a,
+    D: DimAPI,
+    B: DeviceAPI<T, RawVec = CpuRawVec<T>> + 'a,
+    IxD: DimConvertAPI<D>,
+{
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let Self { ptr, len, device, layouts, _phantom } = self;
+        let (lhs, rhs) = layouts.split_at(index);
+        // split iterators give sub-views of disjoint elements of buffer
+        let lhs = unsafe { Self::from_parts(ptr, len, device.clone(), lhs) };
+        let rhs = unsafe { Self::from_parts(ptr, len, device, rhs) };
+        return (lhs, rhs);
+    }
+}
+
+/* #endregion */
+
+/* #region tensor methods */
+
+impl<R, T, D, B> TensorBase<R, D>
+where
+    R: DataAPI<Data = Storage<T, B>>,
+    D: DimAPI,
+    B: DeviceAPI<T>,
+{
+    fn iter_sub_views<'a, D2>(&'a self, layouts: SubLayouts) -> IterAxisView<'a, T, D2, B>
+    where
+        D2: DimAPI,
+        IxD: DimConvertAPI<D2>,
+    {
+        IterAxisView::from_parts(self.data().as_ref(), layouts)
+    }
+
+    /// Iterator of sub-views by selecting each index of `axis`.
+    ///
+    /// Sub-views have one less dimension than tensor.
+    pub fn axis_iter<I>(&self, axis: I) -> Result<IterAxisView<'_, T, D::SmallerOne, B>>
+    where
+        D: DimSmallerOneAPI,
+        D::SmallerOne: DimAPI,
+        IxD: DimConvertAPI<D::SmallerOne>,
+        I: TryInto<isize>,
+    {
+        let axis = axis.try_into().map_err(|_| "Into isize failed").unwrap(); // almost safe to unwrap
+        let layouts = SubLayouts::new_select(self.layout(), axis)?;
+        return Ok(self.iter_sub_views(layouts));
+    }
+
+    /// Iterator of sub-views by selecting each index of the first axis.
+    ///
+    /// # See also
+    ///
+    /// [`TensorBase::axis_iter`]
+    pub fn outer_iter(&self) -> Result<IterAxisView<'_, T, D::SmallerOne, B>>
+    where
+        D: DimSmallerOneAPI,
+        D::SmallerOne: DimAPI,
+        IxD: DimConvertAPI<D::SmallerOne>,
+    {
+        self.axis_iter(0)
+    }
+
+    /// Iterator of 1-D views along `axis`, for each index of other axes in
+    /// row-major order.
+    pub fn lanes<I>(&self, axis: I) -> Result<IterAxisView<'_, T, Ix1, B>>
+    where
+        I: TryInto<isize>,
+    {
+        let axis = axis.try_into().map_err(|_| "Into isize failed").unwrap(); // almost safe to unwrap
+        let layouts = SubLayouts::new_lanes(self.layout(), axis)?;
+        return Ok(self.iter_sub_views(layouts));
+    }
+
+    /// Iterator of sub-views by narrowing `axis` to chunks of length `size`.
+    ///
+    /// The last chunk is smaller if length of `axis` is not divisible by
+    /// `size`.
+    pub fn axis_chunks_iter<I>(&self, axis: I, size: usize) -> Result<IterAxisView<'_, T, D, B>>
+    where
+        IxD: DimConvertAPI<D>,
+        I: TryInto<isize>,
+    {
+        let axis = axis.try_into().map_err(|_| "Into isize failed").unwrap(); // almost safe to unwrap
+        let layouts = SubLayouts::new_chunks(self.layout(), axis, size)?;
+        return Ok(self.iter_sub_views(layouts));
+    }
+
+    /// Iterator of overlapping sub-views of shape `window_shape`, for each
+    /// position where the window fits in tensor (in row-major order).
+    ///
+    /// No windows are given if any window length is larger than tensor.
+    /// Windows overlap, so there is no mutable counterpart.
+    ///
+    /// # See also
+    ///
+    /// [`TensorBase::sliding_window_view`]
+    pub fn windows<S>(&self, window_shape: S) -> Result<IterAxisView<'_, T, D, B>>
+    where
+        IxD: DimConvertAPI<D>,
+        S: Into<D>,
+    {
+        let layouts = SubLayouts::new_windows(self.layout(), &window_shape.into())?;
+        return Ok(self.iter_sub_views(layouts));
+    }
+}
+
+impl<R, T, D, B> TensorBase<R, D>
+where
+    T: Clone,
+    R: DataMutAPI<Data = Storage<T, B>>,
+    D: DimAPI,
+    B: DeviceAPI<T, RawVec = CpuRawVec<T>>,
+{
+    fn iter_sub_views_mut<'a, D2>(
+        &'a mut self,
+        layouts: SubLayouts,
+    ) -> Result<IterAxisViewMut<'a, T, D2, B>>
+    where
+        D2: DimAPI,
+        IxD: DimConvertAPI<D2>,
+    {
+        // elements of tensor must not overlap, then sub-views do not overlap
+        self.layout().check_strides()?;
+        let device = self.device().clone();
+        let rawvec = unsafe { self.data_mut().storage_mut() }.rawvec_mut();
+        let (ptr, len) = (rawvec.as_raw_slice_mut().as_mut_ptr(), rawvec.len());
+        return Ok(unsafe { IterAxisViewMut::from_parts(ptr, len, device, layouts) });
+    }
+
+    /// Iterator of mutable sub-views by selecting each index of `axis`.
+    ///
+    /// # See also
+    ///
+    /// [`TensorBase::axis_iter`]
+    pub fn axis_iter_mut<I>(&mut self, axis: I) -> Result<IterAxisViewMut<'_, T, D::SmallerOne, B>>
+    where
+        D: DimSmallerOneAPI,
+        D::SmallerOne: DimAPI,
+        IxD: DimConvertAPI<D::SmallerOne>,
+        I: TryInto<isize>,
+    {
+        let axis = axis.try_into().map_err(|_| "Into isize failed").unwrap(); // almost safe to unwrap
+        let layouts = SubLayouts::new_select(self.layout(), axis)?;
+        self.iter_sub_views_mut(layouts)
+    }
+
+    /// Iterator of mutable sub-views by selecting each index of the first
+    /// axis.
+    ///
+    /// # See also
+    ///
+    /// [`TensorBase::axis_iter`]
+    pub fn outer_iter_mut(&mut self) -> Result<IterAxisViewMut<'_, T, D::SmallerOne, B>>
+    where
+        D: DimSmallerOneAPI,
+        D::SmallerOne: DimAPI,
+        IxD: DimConvertAPI<D::SmallerOne>,
+    {
+        self.axis_iter_mut(0)
+    }
+
+    /// Iterator of mutable 1-D views along `axis`.
+    ///
+    /// # See also
+    ///
+    /// [`TensorBase::lanes`]
+    pub fn lanes_mut<I>(&mut self, axis: I) -> Result<IterAxisViewMut<'_, T, Ix1, B>>
+    where
+        I: TryInto<isize>,
+    {
+        let axis = axis.try_into().map_err(|_| "Into isize failed").unwrap(); // almost safe to unwrap
+        let layouts = SubLayouts::new_lanes(self.layout(), axis)?;
+        self.iter_sub_views_mut(layouts)
+    }
+
+    /// Iterator of mutable sub-views by narrowing `axis` to chunks of length
+    /// `size`.
+    ///
+    /// # See also
+    ///
+    /// [`TensorBase::axis_chunks_iter`]
+    pub fn axis_chunks_iter_mut<I>(
+        &mut self,
+        axis: I,
+        size: usize,
+    ) -> Result<IterAxisViewMut<'_, T, D, B>>
+    where
+        IxD: DimConvertAPI<D>,
+        I: TryInto<isize>,
+    {
+        let axis = axis.try_into().map_err(|_| "Into isize failed").unwrap(); // almost safe to unwrap
+        let layouts = SubLayouts::new_chunks(self.layout(), axis, size)?;
+        self.iter_sub_views_mut(layouts)
+    }
+}
+
+/* #endregion */
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::s;
+
+    #[test]
+    fn test_axis_iter() {
+        let a = Tensor::from_shape_vec([2, 3, 4], (0..24).collect::<Vec<i32>>()).unwrap();
+
+        let iter = a.axis_iter(1).unwrap();
+        assert_eq!(iter.len(), 3);
+        let views = iter.collect_vec();
+        assert_eq!(views[1].shape(), &[2, 4]);
+        assert_eq!(views[1].iter().copied().collect_vec(), [4, 5, 6, 7, 16, 17, 18, 19]);
+        let last = a.axis_iter(-1).unwrap().next_back().unwrap();
+        assert_eq!(last.iter().copied().collect_vec(), [3, 7, 11, 15, 19, 23]);
+        let outer = a.outer_iter().unwrap().map(|v| v.iter().sum::<i32>()).collect_vec();
+        assert_eq!(outer, [66, 210]);
+        assert!(a.axis_iter(3).is_err());
+
+        let lanes = a.lanes(1).unwrap().map(|v| v.iter().copied().collect_vec()).collect_vec();
+        assert_eq!(lanes.len(), 8);
+        assert_eq!(lanes[0], [0, 4, 8]);
+        assert_eq!(lanes[1], [1, 5, 9]);
+        assert_eq!(lanes[7], [15, 19, 23]);
+
+        let chunks = a.axis_chunks_iter(2, 3).unwrap().collect_vec();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].shape(), &[2, 3, 3]);
+        assert_eq!(chunks[1].shape(), &[2, 3, 1]);
+        assert_eq!(chunks[1].iter().copied().collect_vec(), [3, 7, 11, 15, 19, 23]);
+        assert!(a.axis_chunks_iter(2, 0).is_err());
+
+        // broadcasted tensor can be iterated (but not mutably)
+        let layout = unsafe { Layout::new_unchecked([3, 2], [0, 1], 0) };
+        let b = unsafe { TensorView::new_unchecked(a.data().as_ref(), layout) };
+        let rows = b.axis_iter(0).unwrap().map(|v| v.iter().copied().collect_vec()).collect_vec();
+        assert_eq!(rows, [[0, 1], [0, 1], [0, 1]]);
+    }
+
+    #[test]
+    fn test_windows() {
+        let a = Tensor::from_shape_vec([3, 4], (0..12).collect::<Vec<i32>>()).unwrap();
+        let windows = a.windows([2, 3]).unwrap().collect_vec();
+        assert_eq!(windows.len(), 4);
+        assert_eq!(windows[0].shape(), &[2, 3]);
+        assert_eq!(windows[0].iter().copied().collect_vec(), [0, 1, 2, 4, 5, 6]);
+        assert_eq!(windows[1].iter().copied().collect_vec(), [1, 2, 3, 5, 6, 7]);
+        assert_eq!(windows[3].iter().copied().collect_vec(), [5, 6, 7, 9, 10, 11]);
+
+        // windows of strided view
+        let b = a.slice(s![.., slice!(None, None, -2)]).unwrap();
+        let sums = b.windows([2, 1]).unwrap().map(|v| v.iter().sum::<i32>()).collect_vec();
+        assert_eq!(sums, [10, 6, 18, 14]);
+
+        assert_eq!(a.windows([4, 1]).unwrap().len(), 0);
+        assert!(a.windows([0, 1]).is_err());
+    }
+
+    #[test]
+    fn test_axis_iter_mut() {
+        let mut a = Tensor::from_shape_vec([2, 3, 4], vec![0; 24]).unwrap();
+        for (i, mut v) in a.axis_iter_mut(0).unwrap().enumerate() {
+            v.iter_mut().for_each(|x| *x += i);
+        }
+        for (i, mut v) in a.lanes_mut(-1).unwrap().rev().enumerate() {
+            v.iter_mut().for_each(|x| *x += 10 * i);
+        }
+        let b = a.slice(s![.., .., 3]).unwrap().iter().copied().collect_vec();
+        assert_eq!(b, [50, 40, 30, 21, 11, 1]);
+
+        let mut c = Tensor::from_shape_vec([5, 2], vec![0; 10]).unwrap();
+        for (i, mut v) in c.axis_chunks_iter_mut(0, 2).unwrap().enumerate() {
+            v.iter_mut().for_each(|x| *x = i);
+        }
+        assert_eq!(c.iter().copied().collect_vec(), [0, 0, 0, 0, 1, 1, 1, 1, 2, 2]);
+
+        // sub-views only borrow their own range of buffer
+        let mut views = a.outer_iter_mut().unwrap();
+        let (mut v0, mut v1) = (views.next().unwrap(), views.next().unwrap());
+        assert_eq!(v1.storage().rawvec().len(), 12);
+        v0.iter_mut().for_each(|x| *x = 0);
+        v1.iter_mut().for_each(|x| *x = 1);
+        assert_eq!(a.iter().filter(|&&x| x == 1).count(), 12);
+
+        // sub-views that interleave in memory
+        let a0 = a.to_owned();
+        let mut views = a.axis_iter_mut(1).unwrap();
+        let (mut v0, v1) = (views.next().unwrap(), views.next().unwrap());
+        v0.iter_mut().for_each(|x| *x += 100);
+        // copy of sub-view does not read elements of other sub-views
+        let v1 = v1.into_owned_keep_layout();
+        assert_eq!(v1.iter().copied().collect_vec(), [0, 0, 0, 0, 1, 1, 1, 1]);
+        for mut v in a.lanes_mut(0).unwrap() {
+            v.iter_mut().for_each(|x| *x *= 2);
+        }
+        for mut v in a.axis_chunks_iter_mut(2, 3).unwrap() {
+            v.iter_mut().for_each(|x| *x += 1);
+        }
+        let b = a.iter().copied().collect_vec();
+        let b0 = a0.indexed_iter().map(|(idx, &x)| 2 * (x + 100 * (idx[1] == 0) as usize) + 1);
+        assert_eq!(b, b0.collect_vec());
+
+        // tensor whose elements overlap can not be borrowed mutably
+        let layout = unsafe { Layout::new_unchecked([2, 3], [0, 1], 0) };
+        let mut c = unsafe { TensorViewMut::new_unchecked(c.data_mut().as_ref_mut(), layout) };
+        assert!(c.axis_iter_mut(0).is_err());
+    }
+}
@@ -376,6 +376,216 @@ where
 
 /* #endregion */
 
+/* #region slice */
+
+/// Slice tensor by indexers (such as `s![1, 2..5, None, ..]`).
+///
+/// Integer indexer selects (and eliminates) an axis, range narrows an axis,
+/// `None` inserts a new axis, and ellipsis expands to the remaining axes.
+/// No data is copied.
+pub fn slice<R, D>(tensor: TensorBase<R, D>, indexers: &[Indexer]) -> Result<TensorBase<R, IxD>>
+where
+    R: DataAPI,
+    D: DimAPI,
+{
+    let layout = tensor.layout().dim_slice(indexers)?;
+    unsafe { Ok(TensorBase::new_unchecked(tensor.data, layout)) }
+}
+
+impl<R, D> TensorBase<R, D>
+where
+    R: DataAPI,
+    D: DimAPI,
+{
+    /// Slice tensor by indexers.
+    ///
+    /// # See also
+    ///
+    /// [`slice`]
+    pub fn slice(&self, indexers: &[Indexer]) -> Result<TensorBase<DataRef<'_, R::Data>, IxD>> {
+        slice(self.view(), indexers)
+    }
+
+    /// Slice tensor by indexers, returning mutable view.
+    ///
+    /// # See also
+    ///
+    /// [`slice`]
+    pub fn slice_mut(
+        &mut self,
+        indexers: &[Indexer],
+    ) -> Result<TensorBase<DataRefMut<'_, R::Data>, IxD>>
+    where
+        R: DataMutAPI,
+    {
+        slice(self.view_mut(), indexers)
+    }
+
+    /// Slice tensor by indexers.
+    ///
+    /// # See also
+    ///
+    /// [`slice`]
+    pub fn into_slice(self, indexers: &[Indexer]) -> Result<TensorBase<R, IxD>> {
+        slice(self, indexers)
+    }
+}
+
+/* #endregion */
+
+/* #region sliding_window_view */
+
+/// Sliding window view of tensor, with window of shape `window_shape` along
+/// `axes`.
+///
+/// For each pair of window length `w` and axis, length of that axis becomes
+/// `n - w + 1`, and a new axis of length `w` (with the same stride) is
+/// appended. Axis may be repeated to make windows of several lengths along
+/// the same axis. If `axes` is empty, `window_shape` is applied to all axes.
+///
+/// Windows overlap in memory, so only immutable view is given. No data is
+/// copied.
+///
+/// # See also
+///
+/// [NumPy `sliding_window_view`](https://numpy.org/doc/stable/reference/generated/numpy.lib.stride_tricks.sliding_window_view.html)
+pub fn sliding_window_view<'a, I, R, D>(
+    tensor: &'a TensorBase<R, D>,
+    window_shape: &[usize],
+    axes: &[I],
+) -> Result<TensorBase<DataRef<'a, R::Data>, IxD>>
+where
+    R: DataAPI,
+    D: DimAPI,
+    I: TryInto<isize> + Copy,
+{
+    let ndim = tensor.ndim();
+    let axes = match axes.is_empty() {
+        true => (0..ndim as isize).collect_vec(),
+        false => axes
+            .iter()
+            .map(|&x| x.try_into().map_err(|_| "Into isize failed").unwrap())
+            .collect_vec(),
+    };
+    rstsr_assert_eq!(
+        window_shape.len(),
+        axes.len(),
+        InvalidLayout,
+        "Window shape and axes should have the same length."
+    )?;
+
+    let layout = tensor.layout();
+    let mut shape = layout.shape().as_ref().to_vec();
+    let mut stride = layout.stride().as_ref().to_vec();
+    for (&w, &axis) in window_shape.iter().zip(axes.iter()) {
+        let axis = if axis < 0 { ndim as isize + axis } else { axis };
+        rstsr_pattern!(axis, 0..ndim as isize, ValueOutOfRange)?;
+        let axis = axis as usize;
+        rstsr_pattern!(w, 1..=shape[axis], ValueOutOfRange, "Window does not fit in tensor.")?;
+        shape[axis] -= w - 1;
+        shape.push(w);
+        stride.push(stride[axis]);
+    }
+    // windows overlap, but all elements are in bounds of the original layout
+    let layout = unsafe { Layout::new_unchecked(shape, stride, layout.offset()) };
+    unsafe { Ok(TensorBase::new_unchecked(tensor.view().data, layout)) }
+}
+
+impl<R, D> TensorBase<R, D>
+where
+    R: DataAPI,
+    D: DimAPI,
+{
+    /// Sliding window view of tensor, with window of shape `window_shape`
+    /// along `axes`.
+    ///
+    /// # See also
+    ///
+    /// [`sliding_window_view`]
+    pub fn sliding_window_view<I>(
+        &self,
+        window_shape: &[usize],
+        axes: &[I],
+    ) -> Result<TensorBase<DataRef<'_, R::Data>, IxD>>
+    where
+        I: TryInto<isize> + Copy,
+    {
+        sliding_window_view(self, window_shape, axes)
+    }
+}
+
+/* #endregion */
+
+/* #region as_strided */
+
+impl<R, T, D, B> TensorBase<R, D>
+where
+    R: DataAPI<Data = Storage<T, B>>,
+    D: DimAPI,
+    B: DeviceAPI<T>,
+{
+    /// View of tensor with arbitrary shape and strides, starting from the
+    /// first element of tensor (offset of layout).
+    ///
+    /// Strides may be zero or repeated, so that elements of view overlap.
+    ///
+    /// # Safety
+    ///
+    /// Bounds of view is checked against storage (returning error when out of
+    /// bounds), but the view may contain elements outside of the original
+    /// tensor, and elements of view may overlap in memory. Caller should make
+    /// sure that the view is meaningful; functions that assume non-overlapping
+    /// layout (such as in-place or parallel operations) must not be applied
+    /// to view whose elements overlap.
+    ///
+    /// # See also
+    ///
+    /// [NumPy `as_strided`](https://numpy.org/doc/stable/reference/generated/numpy.lib.stride_tricks.as_strided.html)
+    pub unsafe fn as_strided<D2>(
+        &self,
+        shape: D2,
+        stride: D2::Stride,
+    ) -> Result<TensorBase<DataRef<'_, R::Data>, D2>>
+    where
+        D2: DimAPI,
+    {
+        let layout = self.layout_as_strided(shape, stride)?;
+        Ok(TensorBase::new_unchecked(self.view().data, layout))
+    }
+
+    /// Mutable view of tensor with arbitrary shape and strides.
+    ///
+    /// # Safety
+    ///
+    /// See [`TensorBase::as_strided`]. Writing to elements that overlap in
+    /// memory is allowed, but the result depends on order of writes.
+    pub unsafe fn as_strided_mut<D2>(
+        &mut self,
+        shape: D2,
+        stride: D2::Stride,
+    ) -> Result<TensorBase<DataRefMut<'_, R::Data>, D2>>
+    where
+        R: DataMutAPI,
+        D2: DimAPI,
+    {
+        let layout = self.layout_as_strided(shape, stride)?;
+        Ok(TensorBase::new_unchecked(self.view_mut().data, layout))
+    }
+
+    fn layout_as_strided<D2>(&self, shape: D2, stride: D2::Stride) -> Result<Layout<D2>>
+    where
+        D2: DimAPI,
+    {
+        let layout = unsafe { Layout::new_unchecked(shape, stride, self.layout().offset()) };
+        let (_, idx_max) = layout.bounds_index()?;
+        let len = self.storage().len();
+        rstsr_pattern!(idx_max, ..=len, ValueOutOfRange, "Strided view out of storage bounds.")?;
+        return Ok(layout);
+    }
+}
+
+/* #endregion */
+
 /* #region squeeze */
 
 /// Removes singleton dimensions (axes) from `x`.
@@ -569,6 +779,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::s;
     use crate::storage::Storage;
     use crate::Tensor;
 
@@ -586,10 +797,10 @@ mod tests {
     // }
     #[test]
     fn test_flip() {
-        let device = DeviceCpu {};
+        let device = DeviceCpu::default();
         let a = Tensor::<f64, _>::new(
             Storage::<f64, DeviceCpu>::new(
-                (0..24).map(|v| v as f64).collect::<Vec<_>>(),
+                (0..24).map(|v| v as f64).collect::<Vec<_>>().into(),
                 device.clone(),
             )
             .into(),
@@ -624,4 +835,44 @@ mod tests {
         assert_eq!(a.layout(), unsafe { &Layout::new_unchecked([6, 4, 3, 4], [0, 4, 0, 1], 0) });
         println!("{:?}", a);
     }
+
+    #[test]
+    fn test_sliding_window_view() {
+        let a = Tensor::from_shape_vec([3, 4], (0..12).collect::<Vec<i32>>()).unwrap();
+        let b = a.sliding_window_view(&[2, 3], &[] as &[isize]).unwrap();
+        assert_eq!(b.shape(), &[2, 2, 2, 3]);
+        assert_eq!(b.layout().stride(), &[4, 1, 4, 1]);
+        let c = b.slice(s![1, 1]).unwrap();
+        assert_eq!(c.iter().copied().collect_vec(), [5, 6, 7, 9, 10, 11]);
+
+        // central difference stencil along last axis
+        let d = a.sliding_window_view(&[3], &[-1]).unwrap();
+        assert_eq!(d.shape(), &[3, 2, 3]);
+        let diff =
+            d.lanes(2).unwrap().map(|v| v.iter().next_back().unwrap() - v.iter().next().unwrap());
+        let diff = diff.collect_vec();
+        assert_eq!(diff, [2; 6]);
+
+        // repeated axis
+        let e = a.sliding_window_view(&[2, 2], &[1, 1]).unwrap();
+        assert_eq!(e.shape(), &[3, 2, 2, 2]);
+        assert!(a.sliding_window_view(&[5], &[1]).is_err());
+        assert!(a.sliding_window_view(&[1], &[2]).is_err());
+    }
+
+    #[test]
+    fn test_as_strided() {
+        let a = Tensor::from_shape_vec([6], (0..6).collect::<Vec<i32>>()).unwrap();
+        let b = unsafe { a.as_strided([4, 3], [1, 1]).unwrap() };
+        let rows = b.outer_iter().unwrap().map(|v| v.iter().copied().collect_vec()).collect_vec();
+        assert_eq!(rows, [[0, 1, 2], [1, 2, 3], [2, 3, 4], [3, 4, 5]]);
+        assert!(unsafe { a.as_strided([5, 3], [1, 1]) }.is_err());
+        assert!(unsafe { a.as_strided([2], [-1]) }.is_err());
+
+        // offset of view is kept
+        let c = a.slice(s![2..]).unwrap();
+        let d = unsafe { c.as_strided([2, 2], [0, 3]).unwrap() };
+        assert_eq!(d.iter().copied().collect_vec(), [2, 5, 2, 5]);
+        assert!(unsafe { c.as_strided([2], [4]) }.is_err());
+    }
 }
@@ -43,7 +43,7 @@ where
     {
         let (la, _) = greedy_layout(self.layout(), false);
         let device = self.device().clone();
-        let storage_a = self.data_mut().storage_mut();
+        let storage_a = unsafe { self.data_mut().storage_mut() };
         device.op_muta_func(storage_a, &la, &mut f).unwrap();
     }
 
@@ -142,7 +142,7 @@ where
     {
         let (la, _) = greedy_layout(self.layout(), false);
         let device = self.device().clone();
-        let storage_a = self.data_mut().storage_mut();
+        let storage_a = unsafe { self.data_mut().storage_mut() };
         let mut f_inner = move |x: &mut T| *x = f(x.clone());
         device.op_muta_func(storage_a, &la, &mut f_inner).unwrap();
         return self;
@@ -2,7 +2,8 @@
 
 use crate::prelude_dev::*;
 use core::ops::{Add, Mul, Rem};
-use num::{One, Zero};
+use num::complex::ComplexFloat;
+use num::{Num, One, Zero};
 
 pub fn op_mutc_refa_refb_matmul<RA, RB, RC, TA, TB, TC, DA, DB, DC, B>(
     c: &mut TensorBase<RC, DC>,
@@ -33,7 +34,7 @@ where
     let lc = c.layout().clone();
     let sa = a.data().storage();
     let sb = b.data().storage();
-    let sc = c.data_mut().storage_mut();
+    let sc = unsafe { c.data_mut().storage_mut() };
     device.matmul(sc, &lc, sa, la, sb, lb, alpha, beta)
 }
 
@@ -93,6 +94,275 @@ where
     }
 }
 
+/// Convert elements of tensor to type `TP`.
+fn promote_tensor<R, T, TP, D, B>(a: &TensorBase<R, D>) -> Result<Tensor<TP, D, B>>
+where
+    R: DataAPI<Data = Storage<T, B>>,
+    D: DimAPI,
+    T: Clone + Into<TP>,
+    B: DeviceAPI<T> + DeviceAPI<TP> + DeviceCreationAnyAPI<TP>,
+    B: DeviceOp_MutA_RefB_API<TP, T, D, dyn Fn(&mut TP, &T) + Send + Sync>,
+{
+    let la = a.layout();
+    let lc = layout_for_array_copy(la, TensorIterOrder::default())?;
+    let device = a.device();
+    let mut storage_c = unsafe { device.empty_impl(lc.bounds_index()?.1)? };
+    let f: &mut (dyn Fn(&mut TP, &T) + Send + Sync) = &mut |c, a| *c = a.clone().into();
+    device.op_muta_refb_func(&mut storage_c, &lc, a.data().storage(), la, f)?;
+    return Tensor::new(DataOwned::from(storage_c), lc);
+}
+
+/// Matrix multiplication with explicit type promotion.
+///
+/// Both `a` and `b` are converted to `TC` before multiplication. This covers
+/// combinations that [`Mul`] does not define, such as `f32 × f64 → f64`; the
+/// multiplication itself is then performed in `TC`.
+pub fn matmul_promote<TC, RA, RB, TA, TB, DA, DB, DC, B>(
+    a: &TensorBase<RA, DA>,
+    b: &TensorBase<RB, DB>,
+) -> Result<Tensor<TC, DC, B>>
+where
+    // storage
+    RA: DataAPI<Data = Storage<TA, B>>,
+    RB: DataAPI<Data = Storage<TB, B>>,
+    // dimension
+    DA: DimAPI,
+    DB: DimAPI,
+    DC: DimAPI,
+    // operation specific
+    TA: Clone + Into<TC>,
+    TB: Clone + Into<TC>,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero + One,
+    B: DeviceAPI<TA> + DeviceAPI<TB> + DeviceAPI<TC>,
+    B: DeviceCreationAnyAPI<TC>,
+    B: DeviceOp_MutA_RefB_API<TC, TA, DA, dyn Fn(&mut TC, &TA) + Send + Sync>,
+    B: DeviceOp_MutA_RefB_API<TC, TB, DB, dyn Fn(&mut TC, &TB) + Send + Sync>,
+    LayoutMatMulConfig<DA, DB>: LayoutMatMulAPI<DA, DB, DC = DC>,
+    B: DeviceMatMulAPI<TC, TC, TC, DA, DB, DC>,
+{
+    rstsr_assert!(a.device().same_device(b.device()), DeviceMismatch)?;
+    let a = promote_tensor::<_, _, TC, _, _>(a)?;
+    let b = promote_tensor::<_, _, TC, _, _>(b)?;
+    op_refa_refb_matmul(&a, &b, TC::one())
+}
+
+/* #region symm, syrk, herk */
+
+/// Symmetric matrix multiplication.
+///
+/// Matrix `a` is symmetric, and only its triangle `uplo` is referenced.
+/// - side L: returns `a @ b`
+/// - side R: returns `b @ a`
+///
+/// # See also
+///
+/// - BLAS routine `?symm`
+pub fn symm<RA, RB, TA, TB, TC, DA, DB, B>(
+    a: &TensorBase<RA, DA>,
+    b: &TensorBase<RB, DB>,
+    side: TensorSide,
+    uplo: TensorUpLo,
+) -> Result<Tensor<TC, Ix2, B>>
+where
+    RA: DataAPI<Data = Storage<TA, B>>,
+    RB: DataAPI<Data = Storage<TB, B>>,
+    DA: DimAPI,
+    DB: DimAPI,
+    TA: Mul<TB, Output = TC>,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Zero + One,
+    B: DeviceAPI<TA> + DeviceAPI<TB> + DeviceAPI<TC>,
+    B: DeviceCreationAnyAPI<TC>,
+    B: DeviceSYMMAPI<TA, TB, TC>,
+{
+    rstsr_assert!(a.device().same_device(b.device()), DeviceMismatch)?;
+    let la = a.layout().to_dim::<Ix2>()?;
+    let lb = b.layout().to_dim::<Ix2>()?;
+    let lc = lb.shape().new_contig(None);
+    let mut c = unsafe { Tensor::<TC, _, B>::empty(lc.clone(), a.device()) };
+    let device = c.device().clone();
+    let sc = unsafe { c.data_mut().storage_mut() };
+    let (sa, sb) = (a.data().storage(), b.data().storage());
+    device.symm(sc, &lc, sa, &la, sb, &lb, side, uplo, TC::one(), TC::zero())?;
+    return Ok(c);
+}
+
+/// Symmetric rank-k update `a @ a^T`.
+///
+/// Only the triangle `uplo` of output is computed; the other triangle is
+/// filled with zeros.
+///
+/// # See also
+///
+/// - BLAS routine `?syrk`
+pub fn syrk<RA, TA, TC, DA, B>(
+    a: &TensorBase<RA, DA>,
+    uplo: TensorUpLo,
+) -> Result<Tensor<TC, Ix2, B>>
+where
+    RA: DataAPI<Data = Storage<TA, B>>,
+    DA: DimAPI,
+    TA: Mul<TA, Output = TC>,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Num,
+    B: DeviceAPI<TA> + DeviceAPI<TC>,
+    B: DeviceCreationNumAPI<TC>,
+    B: DeviceSYRKAPI<TA, TC>,
+{
+    let la = a.layout().to_dim::<Ix2>()?;
+    let n = la.shape()[0];
+    let mut c = Tensor::<TC, Ix2, B>::zeros([n, n], a.device());
+    let lc = c.layout().clone();
+    let device = c.device().clone();
+    let sc = unsafe { c.data_mut().storage_mut() };
+    device.syrk(sc, &lc, a.data().storage(), &la, uplo, TC::one(), TC::zero())?;
+    return Ok(c);
+}
+
+/// Hermitian rank-k update `a @ a^H`.
+///
+/// Only the triangle `uplo` of output is computed; the other triangle is
+/// filled with zeros.
+///
+/// # See also
+///
+/// - BLAS routine `?herk`
+pub fn herk<RA, T, DA, B>(a: &TensorBase<RA, DA>, uplo: TensorUpLo) -> Result<Tensor<T, Ix2, B>>
+where
+    RA: DataAPI<Data = Storage<T, B>>,
+    DA: DimAPI,
+    T: ComplexFloat,
+    B: DeviceAPI<T> + DeviceCreationNumAPI<T>,
+    B: DeviceHERKAPI<T, T>,
+{
+    let la = a.layout().to_dim::<Ix2>()?;
+    let n = la.shape()[0];
+    let mut c = Tensor::<T, Ix2, B>::zeros([n, n], a.device());
+    let lc = c.layout().clone();
+    let device = c.device().clone();
+    let sc = unsafe { c.data_mut().storage_mut() };
+    device.herk(sc, &lc, a.data().storage(), &la, uplo, T::one(), T::zero())?;
+    return Ok(c);
+}
+
+/* #endregion */
+
+/* #region congruence */
+
+fn congruence_f<RA, RB, T, DA, DB, B>(
+    a: &TensorBase<RA, DA>,
+    b: &TensorBase<RB, DB>,
+    uplo: Option<TensorUpLo>,
+) -> Result<Tensor<T, IxD, B>>
+where
+    RA: DataAPI<Data = Storage<T, B>>,
+    RB: DataAPI<Data = Storage<T, B>>,
+    DA: DimAPI,
+    DB: DimAPI,
+    T: Mul<T, Output = T> + Add<T, Output = T> + Num,
+    B: DeviceAPI<T> + DeviceCreationNumAPI<T>,
+    B: DeviceCongruenceAPI<T>,
+{
+    rstsr_assert!(a.device().same_device(b.device()), DeviceMismatch)?;
+    let la = a.layout().to_dim::<Ix2>()?;
+    let lb = b.layout().to_dim::<IxD>()?;
+    rstsr_assert!(lb.ndim() >= 2, InvalidLayout, "Matrix b should be at least 2-D.")?;
+    let m = la.shape()[1];
+    let mut sc = lb.shape().clone();
+    let nd = sc.len();
+    sc[nd - 2] = m;
+    sc[nd - 1] = m;
+    let mut c = Tensor::<T, IxD, B>::zeros(sc, a.device());
+    let lc = c.layout().clone();
+    let device = c.device().clone();
+    let sc = unsafe { c.data_mut().storage_mut() };
+    device.congruence(sc, &lc, a.data().storage(), &la, b.data().storage(), &lb, uplo)?;
+    return Ok(c);
+}
+
+/// Matrix congruence `a^T @ b @ a`.
+///
+/// Matrix `a` is of shape `[n, m]`, and `b` is of shape `[n, n]`; output is of
+/// shape `[m, m]`. Note that `a` is not conjugated for complex values.
+pub fn congruence<RA, RB, T, DA, DB, B>(
+    a: &TensorBase<RA, DA>,
+    b: &TensorBase<RB, DB>,
+) -> Result<Tensor<T, Ix2, B>>
+where
+    RA: DataAPI<Data = Storage<T, B>>,
+    RB: DataAPI<Data = Storage<T, B>>,
+    DA: DimAPI,
+    DB: DimAPI,
+    T: Mul<T, Output = T> + Add<T, Output = T> + Num,
+    B: DeviceAPI<T> + DeviceCreationNumAPI<T>,
+    B: DeviceCongruenceAPI<T>,
+{
+    rstsr_assert_eq!(b.ndim(), 2, InvalidLayout, "Use `congruence_batched` for batched b.")?;
+    congruence_f(a, b, None)?.into_dim::<Ix2>()
+}
+
+/// Batched matrix congruence `a^T @ b @ a`.
+///
+/// Matrix `a` is of shape `[n, m]`, and `b` is of shape `[..., n, n]`; output
+/// is of shape `[..., m, m]`, with the same leading dimensions of `b`.
+pub fn congruence_batched<RA, RB, T, DA, DB, B>(
+    a: &TensorBase<RA, DA>,
+    b: &TensorBase<RB, DB>,
+) -> Result<Tensor<T, IxD, B>>
+where
+    RA: DataAPI<Data = Storage<T, B>>,
+    RB: DataAPI<Data = Storage<T, B>>,
+    DA: DimAPI,
+    DB: DimAPI,
+    T: Mul<T, Output = T> + Add<T, Output = T> + Num,
+    B: DeviceAPI<T> + DeviceCreationNumAPI<T>,
+    B: DeviceCongruenceAPI<T>,
+{
+    congruence_f(a, b, None)
+}
+
+/// Matrix congruence `a^T @ b @ a` with symmetric `b`.
+///
+/// Only the triangle `uplo` of `b` is referenced. Output is symmetric, and
+/// both triangles are filled.
+pub fn congruence_symm<RA, RB, T, DA, DB, B>(
+    a: &TensorBase<RA, DA>,
+    b: &TensorBase<RB, DB>,
+    uplo: TensorUpLo,
+) -> Result<Tensor<T, Ix2, B>>
+where
+    RA: DataAPI<Data = Storage<T, B>>,
+    RB: DataAPI<Data = Storage<T, B>>,
+    DA: DimAPI,
+    DB: DimAPI,
+    T: Mul<T, Output = T> + Add<T, Output = T> + Num,
+    B: DeviceAPI<T> + DeviceCreationNumAPI<T>,
+    B: DeviceCongruenceAPI<T>,
+{
+    rstsr_assert_eq!(b.ndim(), 2, InvalidLayout, "Use `congruence_symm_batched` for batched b.")?;
+    congruence_f(a, b, Some(uplo))?.into_dim::<Ix2>()
+}
+
+/// Batched matrix congruence `a^T @ b @ a` with symmetric `b`.
+///
+/// Only the triangle `uplo` of each matrix in `b` is referenced.
+pub fn congruence_symm_batched<RA, RB, T, DA, DB, B>(
+    a: &TensorBase<RA, DA>,
+    b: &TensorBase<RB, DB>,
+    uplo: TensorUpLo,
+) -> Result<Tensor<T, IxD, B>>
+where
+    RA: DataAPI<Data = Storage<T, B>>,
+    RB: DataAPI<Data = Storage<T, B>>,
+    DA: DimAPI,
+    DB: DimAPI,
+    T: Mul<T, Output = T> + Add<T, Output = T> + Num,
+    B: DeviceAPI<T> + DeviceCreationNumAPI<T>,
+    B: DeviceCongruenceAPI<T>,
+{
+    congruence_f(a, b, Some(uplo))
+}
+
+/* #endregion */
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -129,4 +399,82 @@ mod test {
         let b = Tensor::linspace_cpu(0.0, 14.0, 15).into_shape_assume_contig([5, 3]).unwrap();
         println!("{:}", &a % &b);
     }
+
+    #[test]
+    fn test_symm_syrk_herk() {
+        use crate::dev_utilities::allclose_f64;
+        use num::complex::Complex64;
+
+        // symmetric matrix, and its upper triangle with garbage in lower triangle
+        let s = Tensor::asarray(vec![1.0, 2.0, 3.0, 2.0, 4.0, 5.0, 3.0, 5.0, 6.0]).unwrap();
+        let s = s.into_shape_assume_contig([3, 3]).unwrap();
+        let s_u = Tensor::asarray(vec![1.0, 2.0, 3.0, -1.0, 4.0, 5.0, -1.0, -1.0, 6.0]).unwrap();
+        let s_u = s_u.into_shape_assume_contig([3, 3]).unwrap();
+        let b = Tensor::linspace_cpu(0.0, 11.0, 12).into_shape_assume_contig([3, 4]).unwrap();
+        let c = symm(&s_u, &b, TensorSide::L, TensorUpLo::U).unwrap();
+        assert!(allclose_f64(&c, &(&s % &b)));
+        let b = b.into_reverse_axes();
+        let c = symm(&s_u.reverse_axes(), &b, TensorSide::R, TensorUpLo::L).unwrap();
+        assert!(allclose_f64(&c, &(&b % &s)));
+
+        // syrk: only lower triangle is computed
+        let a = Tensor::linspace_cpu(0.0, 11.0, 12).into_shape_assume_contig([3, 4]).unwrap();
+        let c = syrk(&a, TensorUpLo::L).unwrap();
+        let c_full = &a % &a.reverse_axes();
+        let c_full = c_full.data().storage().to_cpu_vec().unwrap();
+        let c_syrk = c.data().storage().to_cpu_vec().unwrap();
+        for i in 0..3 {
+            for j in 0..3 {
+                let expect = if i >= j { c_full[i * 3 + j] } else { 0.0 };
+                assert_eq!(c_syrk[i * 3 + j], expect);
+            }
+        }
+
+        // herk: only upper triangle is computed
+        let a = Tensor::linspace_cpu(Complex64::new(1.0, 2.0), Complex64::new(-3.0, 0.5), 12);
+        let a = a.into_shape_assume_contig([3, 4]).unwrap();
+        let c = herk(&a, TensorUpLo::U).unwrap();
+        let a_h = a.mapv(|x| x.conj()).into_reverse_axes();
+        let c_full = (&a % &a_h).data().storage().to_cpu_vec().unwrap();
+        let c_herk = c.data().storage().to_cpu_vec().unwrap();
+        for i in 0..3 {
+            for j in 0..3 {
+                let expect = if i <= j { c_full[i * 3 + j] } else { Complex64::new(0.0, 0.0) };
+                assert!((c_herk[i * 3 + j] - expect).norm() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_congruence() {
+        use crate::dev_utilities::allclose_f64;
+
+        let a = Tensor::linspace_cpu(0.0, 11.0, 12).into_shape_assume_contig([4, 3]).unwrap();
+        let b = Tensor::linspace_cpu(-3.0, 12.0, 16).into_shape_assume_contig([4, 4]).unwrap();
+        let c = congruence(&a, &b).unwrap();
+        let c_ref = &(&a.reverse_axes() % &b) % &a;
+        assert!(allclose_f64(&c, &c_ref));
+
+        // batched
+        let b = Tensor::linspace_cpu(-3.0, 28.0, 32).into_shape_assume_contig([2, 4, 4]).unwrap();
+        let c = congruence_batched(&a, &b).unwrap();
+        assert_eq!(c.shape(), &[2, 3, 3]);
+        let c_ref = &(&a.reverse_axes() % &b) % &a;
+        assert!(allclose_f64(&c, &c_ref.into_dim::<IxD>().unwrap()));
+
+        // symmetric: only lower triangle of b is referenced
+        let s = &b + &b.swapaxes(-1, -2);
+        let mut v = s.data().storage().to_cpu_vec().unwrap();
+        for k in 0..2 {
+            for i in 0..4 {
+                for j in i + 1..4 {
+                    v[k * 16 + i * 4 + j] = f64::NAN;
+                }
+            }
+        }
+        let s_l = Tensor::asarray(v).unwrap().into_shape_assume_contig([2, 4, 4]).unwrap();
+        let c = congruence_symm_batched(&a, &s_l, TensorUpLo::L).unwrap();
+        let c_ref = congruence_batched(&a, &s).unwrap();
+        assert!(allclose_f64(&c, &c_ref));
+    }
 }
@@ -2,12 +2,17 @@ pub mod asarray;
 pub mod creation;
 pub mod data;
 pub mod iterator;
+pub mod iterator_axes;
 pub mod manuplication;
 pub mod map_elementwise;
 pub mod matmul;
 pub mod operators;
 pub mod ownership_conversion;
+pub mod einops;
+pub mod einsum;
+pub mod product;
 pub mod device_conversion;
 pub mod ext_conversion;
+pub mod zip;
 
 pub use data::*;
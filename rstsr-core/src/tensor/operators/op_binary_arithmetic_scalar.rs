@@ -90,7 +90,7 @@ macro_rules! impl_arithmetic_scalar_lhs {
                 let a = T::from(a);
                 let device = b.device().clone();
                 let lb = b.layout().clone();
-                let storage_b = b.data_mut().storage_mut();
+                let storage_b = unsafe { b.data_mut().storage_mut() };
                 device.op_muta_numb(storage_b, &lb, a)?;
                 return Ok(b);
             }
@@ -236,7 +236,7 @@ macro_rules! impl_arithmetic_scalar_rhs {
                 let b = T::from(b);
                 let device = a.device().clone();
                 let la = a.layout().clone();
-                let storage_a = a.data_mut().storage_mut();
+                let storage_a = unsafe { a.data_mut().storage_mut() };
                 device.op_muta_numb(storage_a, &la, b)?;
                 return Ok(a);
             }
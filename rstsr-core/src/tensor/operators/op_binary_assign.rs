@@ -90,7 +90,7 @@ macro_rules! impl_binary_assign {
                 rstsr_assert_eq!(la_b, *la, InvalidLayout)?;
                 // op provided by device
                 let device = a.device().clone();
-                let storage_a = a.data_mut().storage_mut();
+                let storage_a = unsafe { a.data_mut().storage_mut() };
                 let storage_b = b.data().storage();
                 device.op_muta_refb(storage_a, &la_b, storage_b, &lb_b)
             }
@@ -132,7 +132,7 @@ macro_rules! impl_binary_assign {
             fn $op(a: Self, b: TB) -> Result<()> {
                 let la = a.layout().clone();
                 let device = a.device().clone();
-                let storage_a = a.data_mut().storage_mut();
+                let storage_a = unsafe { a.data_mut().storage_mut() };
                 device.op_muta_numb(storage_a, &la, b)
             }
         }
@@ -41,7 +41,7 @@ macro_rules! impl_binary_with_output {
             rstsr_assert_eq!(lc_b, *lc, InvalidLayout)?;
             // op provided by device
             let device = c.device().clone();
-            let storage_c = c.data_mut().storage_mut();
+            let storage_c = unsafe { c.data_mut().storage_mut() };
             let storage_a = a.data().storage();
             let storage_b = b.data().storage();
             device.op_mutc_refa_refb(storage_c, &lc_b, storage_a, &la_b, storage_b, &lb_b)
@@ -106,7 +106,7 @@ macro_rules! impl_unary {
             fn $op(mut self) -> Result<Self::Output> {
                 let layout = self.layout().clone();
                 let device = self.device().clone();
-                let storage = self.data_mut().storage_mut();
+                let storage = unsafe { self.data_mut().storage_mut() };
                 // generate empty output tensor
                 device.op_muta(storage, &layout)?;
                 return Ok(self);
@@ -36,7 +36,7 @@ where
     rstsr_assert_eq!(lc_b, *lc, InvalidLayout)?;
     // op provided by device
     let device = c.device().clone();
-    let storage_c = c.data_mut().storage_mut();
+    let storage_c = unsafe { c.data_mut().storage_mut() };
     let storage_a = a.data().storage();
     let storage_b = b.data().storage();
     device.op_mutc_refa_refb_func(storage_c, &lc_b, storage_a, &la_b, storage_b, &lb_b, f)
@@ -115,7 +115,7 @@ where
     rstsr_assert_eq!(la_b, *la, InvalidLayout)?;
     // op provided by device
     let device = a.device().clone();
-    let storage_a = a.data_mut().storage_mut();
+    let storage_a = unsafe { a.data_mut().storage_mut() };
     let storage_b = b.data().storage();
     device.op_muta_refb_func(storage_a, &la_b, storage_b, &lb_b, f)
 }
@@ -130,7 +130,7 @@ where
 {
     let la = a.layout().clone();
     let device = a.device().clone();
-    let storage_a = a.data_mut().storage_mut();
+    let storage_a = unsafe { a.data_mut().storage_mut() };
     device.op_muta_func(storage_a, &la, f)
 }
 
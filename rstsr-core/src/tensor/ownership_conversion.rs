@@ -38,6 +38,21 @@ where
         let data = data.into_owned();
         unsafe { TensorBase::new_unchecked(data, layout) }
     }
+
+    /// Copy tensor into owned tensor, checked against memory limit of
+    /// device.
+    ///
+    /// Unlike [`TensorBase::into_owned_keep_layout`], data is always copied,
+    /// and this function fails if the copy exceeds memory limit.
+    pub fn try_to_owned_keep_layout<T, B>(&self) -> Result<TensorBase<DataOwned<R::Data>, D>>
+    where
+        R: DataAPI<Data = Storage<T, B>>,
+        B: DeviceStorageAPI<T> + DeviceMemoryAPI,
+    {
+        let data = DataOwned::from(self.data().storage().try_clone()?);
+        let layout = self.layout().clone();
+        unsafe { Ok(TensorBase::new_unchecked(data, layout)) }
+    }
 }
 
 /* #region DataCow */
@@ -68,6 +83,68 @@ where
 
 /* #endregion */
 
+/* #region DataArc */
+
+impl<R, D> TensorBase<R, D>
+where
+    R: DataAPI,
+    D: DimAPI,
+{
+    /// Convert tensor into shared (reference-counted) tensor.
+    ///
+    /// Data of owned tensor is moved; otherwise data is cloned.
+    pub fn into_shared(self) -> TensorBase<DataArc<R::Data>, D> {
+        let TensorBase { data, layout } = self;
+        let data = DataArc::from(data.into_owned());
+        unsafe { TensorBase::new_unchecked(data, layout) }
+    }
+}
+
+impl<S, D> TensorBase<DataArc<S>, D>
+where
+    S: Clone,
+    D: DimAPI,
+{
+    /// Get a mutable view of shared tensor.
+    ///
+    /// Data is cloned only if it is shared with other tensors; the clone then
+    /// belongs to this tensor.
+    pub fn make_mut(&mut self) -> TensorBase<DataRefMut<'_, S>, D> {
+        self.view_mut()
+    }
+
+    /// Number of tensors sharing the data.
+    pub fn strong_count(&self) -> usize {
+        self.data().strong_count()
+    }
+}
+
+impl<S, D> From<TensorBase<DataOwned<S>, D>> for TensorBase<DataArc<S>, D>
+where
+    D: DimAPI,
+{
+    #[inline]
+    fn from(tensor: TensorBase<DataOwned<S>, D>) -> Self {
+        let TensorBase { data, layout } = tensor;
+        let data = DataArc::from(data);
+        unsafe { TensorBase::new_unchecked(data, layout) }
+    }
+}
+
+impl<S, D> From<TensorBase<DataArc<S>, D>> for TensorBase<DataOwned<S>, D>
+where
+    S: Clone,
+    D: DimAPI,
+{
+    /// Data is moved if not shared, otherwise cloned.
+    #[inline]
+    fn from(tensor: TensorBase<DataArc<S>, D>) -> Self {
+        tensor.into_owned_keep_layout()
+    }
+}
+
+/* #endregion */
+
 /* #region operation API */
 
 /// This trait is used for implementing operations that involves view-only
@@ -79,7 +156,7 @@ where
     fn tsr_view(&self) -> TensorBase<DataRef<'_, S>, D>;
 }
 
-impl<'a, R, S, D> TensorRefAPI<S, D> for &'a TensorBase<R, D>
+impl<R, S, D> TensorRefAPI<S, D> for &TensorBase<R, D>
 where
     R: DataAPI<Data = S>,
     D: DimAPI,
@@ -110,7 +187,7 @@ where
     fn tsr_view(&self) -> TensorBase<DataRef<'_, S>, D>;
 }
 
-impl<'a, R, S, D> TensorRefOrOwnedAPI<S, D> for &'a TensorBase<R, D>
+impl<R, S, D> TensorRefOrOwnedAPI<S, D> for &TensorBase<R, D>
 where
     R: DataAPI<Data = S>,
     D: DimAPI,
@@ -152,7 +229,7 @@ where
     fn tsr_view_mut(&mut self) -> TensorBase<DataRefMut<'_, S>, D>;
 }
 
-impl<'a, R, S, D> TensorRefMutAPI<S, D> for &'a mut TensorBase<R, D>
+impl<R, S, D> TensorRefMutAPI<S, D> for &mut TensorBase<R, D>
 where
     R: DataMutAPI<Data = S>,
     D: DimAPI,
@@ -175,3 +252,40 @@ where
 }
 
 /* #endregion */
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tensor_arc() {
+        let device = DeviceCpu::default();
+        let a = Tensor::<f64, _>::linspace(0.0, 5.0, 6, &device);
+        let ptr = a.storage().rawvec().as_ptr();
+        let a = TensorArc::from(a.into_shape_assume_contig([2, 3]).unwrap());
+        assert_eq!(a.storage().rawvec().as_ptr(), ptr);
+
+        // clones share data, and can be sent to other threads
+        let mut b = a.clone();
+        assert_eq!(a.strong_count(), 2);
+        let c = std::thread::spawn(move || &a.reverse_axes() * 2.0).join().unwrap();
+        assert_eq!(c.shape(), &[3, 2]);
+        assert_eq!(b.strong_count(), 1);
+
+        // unique data is mutated in place
+        b.make_mut().mapv_inplace(|x| x + 1.0);
+        assert_eq!(b.storage().rawvec().as_ptr(), ptr);
+
+        // shared data is copied on write
+        let d = b.clone();
+        b.make_mut().mapv_inplace(|x| -x);
+        assert_ne!(b.storage().rawvec().as_ptr(), ptr);
+        assert_eq!(d.storage().rawvec(), &vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(b.storage().rawvec().as_raw_slice()[5], -6.0);
+
+        let d: Tensor<f64, _> = d.into();
+        assert_eq!(d.storage().rawvec().as_ptr(), ptr);
+        let e = b.view().into_shared().into_owned_keep_layout();
+        assert_ne!(e.storage().rawvec().as_ptr(), b.storage().rawvec().as_ptr());
+    }
+}
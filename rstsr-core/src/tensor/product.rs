@@ -0,0 +1,533 @@
+//! Tensor products: `tensordot`, `outer`, `kron`, `inner`, `vdot` and
+//! `cross`.
+
+use crate::prelude_dev::*;
+use core::ops::{Add, Mul, SubAssign};
+use num::complex::ComplexFloat;
+use num::Num;
+
+/* #region tensordot */
+
+/// Axes to be contracted in [`tensordot`].
+///
+/// Values are kept as given and validated in [`tensordot`], so that invalid
+/// axes (such as negative number of axes) give an error instead of panic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TensorDotAxes {
+    /// Contract the last `n` axes of `a` with the first `n` axes of `b`.
+    Num(isize),
+    /// Contract axes of `a` with the corresponding axes of `b`.
+    Pair(Vec<isize>, Vec<isize>),
+}
+
+/// Cast to `isize`; values that do not fit are saturated, and then rejected as
+/// out of range in [`tensordot`].
+fn cast_axis<I>(x: I) -> isize
+where
+    I: TryInto<isize> + PartialOrd + Default,
+{
+    let negative = x < I::default();
+    x.try_into().unwrap_or(if negative { isize::MIN } else { isize::MAX })
+}
+
+impl From<usize> for TensorDotAxes {
+    fn from(n: usize) -> Self {
+        TensorDotAxes::Num(cast_axis(n))
+    }
+}
+
+impl From<i32> for TensorDotAxes {
+    fn from(n: i32) -> Self {
+        TensorDotAxes::Num(cast_axis(n))
+    }
+}
+
+impl<I> From<(Vec<I>, Vec<I>)> for TensorDotAxes
+where
+    I: TryInto<isize> + PartialOrd + Default + Copy,
+{
+    fn from(axes: (Vec<I>, Vec<I>)) -> Self {
+        TensorDotAxes::from((axes.0.as_slice(), axes.1.as_slice()))
+    }
+}
+
+impl<I, const N: usize> From<([I; N], [I; N])> for TensorDotAxes
+where
+    I: TryInto<isize> + PartialOrd + Default + Copy,
+{
+    fn from(axes: ([I; N], [I; N])) -> Self {
+        TensorDotAxes::from((axes.0.as_slice(), axes.1.as_slice()))
+    }
+}
+
+impl<I> From<(&[I], &[I])> for TensorDotAxes
+where
+    I: TryInto<isize> + PartialOrd + Default + Copy,
+{
+    fn from(axes: (&[I], &[I])) -> Self {
+        let cast = |axes: &[I]| axes.iter().map(|&x| cast_axis(x)).collect_vec();
+        TensorDotAxes::Pair(cast(axes.0), cast(axes.1))
+    }
+}
+
+fn normalize_axes(axes: &[isize], ndim: usize) -> Result<Vec<usize>> {
+    axes.iter()
+        .map(|&p| {
+            let p = if p < 0 { p + ndim as isize } else { p };
+            rstsr_pattern!(p, 0..ndim as isize, InvalidLayout)?;
+            Ok(p as usize)
+        })
+        .collect()
+}
+
+/// View of storage as batched matrices `[..., m, n]`, or its contiguous copy if
+/// such view does not exist.
+///
+/// # See also
+///
+/// [`layout_as_batched_matrix`]
+pub(crate) fn storage_as_batched_matrix<'a, T, B>(
+    storage: &'a Storage<T, B>,
+    layout: &Layout<IxD>,
+    nbatch: usize,
+    nrow: usize,
+) -> Result<TensorCow<'a, T, IxD, B>>
+where
+    T: Clone,
+    B: DeviceAPI<T> + DeviceCreationAnyAPI<T> + OpAssignAPI<T, IxD>,
+{
+    let order = TensorOrder::default();
+    if let Some(layout_mat) = layout_as_batched_matrix(layout, nbatch, nrow, order) {
+        let data = DataCow::Ref(storage.into());
+        return Ok(unsafe { TensorBase::new_unchecked(data, layout_mat) });
+    }
+    let device = storage.device();
+    let layout_new = layout.shape().new_contig(None);
+    let mut storage_new = unsafe { device.empty_impl(layout_new.size())? };
+    device.assign(&mut storage_new, &layout_new, storage, layout)?;
+    let layout_mat = layout_as_batched_matrix(&layout_new, nbatch, nrow, order).unwrap();
+    let data = DataCow::Owned(storage_new.into());
+    return Ok(unsafe { TensorBase::new_unchecked(data, layout_mat) });
+}
+
+fn tensordot_f<TA, TB, TC, B>(
+    sa: &Storage<TA, B>,
+    la: &Layout<IxD>,
+    sb: &Storage<TB, B>,
+    lb: &Layout<IxD>,
+    axes: TensorDotAxes,
+) -> Result<Tensor<TC, IxD, B>>
+where
+    TA: Clone + Mul<TB, Output = TC>,
+    TB: Clone,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Num,
+    B: DeviceAPI<TA> + DeviceCreationAnyAPI<TA> + OpAssignAPI<TA, IxD>,
+    B: DeviceAPI<TB> + DeviceCreationAnyAPI<TB> + OpAssignAPI<TB, IxD>,
+    B: DeviceAPI<TC> + DeviceCreationNumAPI<TC>,
+    B: DeviceGEMMAPI<TA, TB, TC>,
+{
+    rstsr_assert!(sa.device().same_device(sb.device()), DeviceMismatch)?;
+    let (na, nb) = (la.ndim(), lb.ndim());
+    let (axes_a, axes_b) = match axes {
+        TensorDotAxes::Num(n) => {
+            rstsr_assert!(
+                n >= 0,
+                InvalidLayout,
+                "Number of contracted axes should be non-negative."
+            )?;
+            let n = n as usize;
+            rstsr_assert!(
+                n <= na && n <= nb,
+                InvalidLayout,
+                "Number of contracted axes should not be larger than number of dimensions."
+            )?;
+            ((na - n..na).collect_vec(), (0..n).collect_vec())
+        },
+        TensorDotAxes::Pair(axes_a, axes_b) => {
+            rstsr_assert_eq!(axes_a.len(), axes_b.len(), InvalidLayout)?;
+            (normalize_axes(&axes_a, na)?, normalize_axes(&axes_b, nb)?)
+        },
+    };
+    for (&pa, &pb) in izip!(&axes_a, &axes_b) {
+        rstsr_assert_eq!(
+            la.shape()[pa],
+            lb.shape()[pb],
+            InvalidLayout,
+            "Shape of contracted axes should be the same."
+        )?;
+    }
+
+    // permute to [free_a, axes_a] and [axes_b, free_b]
+    // duplicated axes are checked by transpose
+    let free_a = (0..na).filter(|p| !axes_a.contains(p)).collect_vec();
+    let free_b = (0..nb).filter(|p| !axes_b.contains(p)).collect_vec();
+    let perm_a = free_a.iter().chain(&axes_a).map(|&p| p as isize).collect_vec();
+    let perm_b = axes_b.iter().chain(&free_b).map(|&p| p as isize).collect_vec();
+    let la = la.transpose(&perm_a)?;
+    let lb = lb.transpose(&perm_b)?;
+
+    // reshape to matrices, and perform gemm
+    let a_mat = storage_as_batched_matrix(sa, &la, 0, free_a.len())?;
+    let b_mat = storage_as_batched_matrix(sb, &lb, 0, axes_b.len())?;
+    let (sa, la_mat) = (a_mat.data().storage(), &a_mat.layout().to_dim::<Ix2>()?);
+    let (sb, lb_mat) = (b_mat.data().storage(), &b_mat.layout().to_dim::<Ix2>()?);
+
+    let shape_c =
+        la.shape()[..free_a.len()].iter().chain(&lb.shape()[axes_b.len()..]).cloned().collect_vec();
+    let lc = shape_c.new_contig(None);
+    let lc_mat = layout_as_matrix(&lc, free_a.len(), TensorOrder::default()).unwrap();
+    let device = sa.device();
+    let mut c = Tensor::<TC, IxD, B>::zeros(lc, device);
+    let sc = unsafe { c.data_mut().storage_mut() };
+    device.gemm(sc, &lc_mat, sa, la_mat, sb, lb_mat, TC::one(), TC::zero())?;
+    return Ok(c);
+}
+
+/// Tensor contraction over specified axes.
+///
+/// - `axes` as number `n`: contract the last `n` axes of `a` with the first `n`
+///   axes of `b`;
+/// - `axes` as pair of axes lists: contract the listed axes of `a` with the
+///   corresponding listed axes of `b`.
+///
+/// Output axes are the uncontracted axes of `a` followed by the uncontracted
+/// axes of `b`. The contraction is performed by GEMM; operands are copied only
+/// when they could not be viewed as matrices.
+///
+/// # See also
+///
+/// - [Python array API standard: `tensordot`](https://data-apis.org/array-api/2023.12/API_specification/generated/array_api.tensordot.html)
+pub fn tensordot<RA, RB, TA, TB, TC, DA, DB, B>(
+    a: &TensorBase<RA, DA>,
+    b: &TensorBase<RB, DB>,
+    axes: impl Into<TensorDotAxes>,
+) -> Result<Tensor<TC, IxD, B>>
+where
+    RA: DataAPI<Data = Storage<TA, B>>,
+    RB: DataAPI<Data = Storage<TB, B>>,
+    DA: DimAPI,
+    DB: DimAPI,
+    TA: Clone + Mul<TB, Output = TC>,
+    TB: Clone,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Num,
+    B: DeviceAPI<TA> + DeviceCreationAnyAPI<TA> + OpAssignAPI<TA, IxD>,
+    B: DeviceAPI<TB> + DeviceCreationAnyAPI<TB> + OpAssignAPI<TB, IxD>,
+    B: DeviceAPI<TC> + DeviceCreationNumAPI<TC>,
+    B: DeviceGEMMAPI<TA, TB, TC>,
+{
+    let la = a.layout().to_dim::<IxD>()?;
+    let lb = b.layout().to_dim::<IxD>()?;
+    tensordot_f(a.data().storage(), &la, b.data().storage(), &lb, axes.into())
+}
+
+/* #endregion */
+
+/* #region outer, inner, vdot, kron */
+
+/// Outer product of two vectors.
+///
+/// # See also
+///
+/// - [Python array API standard: `linalg.outer`](https://data-apis.org/array-api/2023.12/extensions/generated/array_api.linalg.outer.html)
+pub fn outer<RA, RB, TA, TB, TC, DA, DB, B>(
+    a: &TensorBase<RA, DA>,
+    b: &TensorBase<RB, DB>,
+) -> Result<Tensor<TC, Ix2, B>>
+where
+    RA: DataAPI<Data = Storage<TA, B>>,
+    RB: DataAPI<Data = Storage<TB, B>>,
+    DA: DimAPI,
+    DB: DimAPI,
+    TA: Clone + Mul<TB, Output = TC>,
+    TB: Clone,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Num,
+    B: DeviceAPI<TA> + DeviceCreationAnyAPI<TA> + OpAssignAPI<TA, IxD>,
+    B: DeviceAPI<TB> + DeviceCreationAnyAPI<TB> + OpAssignAPI<TB, IxD>,
+    B: DeviceAPI<TC> + DeviceCreationNumAPI<TC>,
+    B: DeviceGEMMAPI<TA, TB, TC>,
+{
+    rstsr_assert_eq!(a.ndim(), 1, InvalidLayout, "Outer product requires 1-D tensors.")?;
+    rstsr_assert_eq!(b.ndim(), 1, InvalidLayout, "Outer product requires 1-D tensors.")?;
+    tensordot(a, b, 0)?.into_dim::<Ix2>()
+}
+
+/// Inner product of two tensors over their last axes.
+///
+/// # See also
+///
+/// - [`numpy.inner`](https://numpy.org/doc/stable/reference/generated/numpy.inner.html)
+pub fn inner<RA, RB, TA, TB, TC, DA, DB, B>(
+    a: &TensorBase<RA, DA>,
+    b: &TensorBase<RB, DB>,
+) -> Result<Tensor<TC, IxD, B>>
+where
+    RA: DataAPI<Data = Storage<TA, B>>,
+    RB: DataAPI<Data = Storage<TB, B>>,
+    DA: DimAPI,
+    DB: DimAPI,
+    TA: Clone + Mul<TB, Output = TC>,
+    TB: Clone,
+    TC: Mul<TC, Output = TC> + Add<TC, Output = TC> + Num,
+    B: DeviceAPI<TA> + DeviceCreationAnyAPI<TA> + OpAssignAPI<TA, IxD>,
+    B: DeviceAPI<TB> + DeviceCreationAnyAPI<TB> + OpAssignAPI<TB, IxD>,
+    B: DeviceAPI<TC> + DeviceCreationNumAPI<TC>,
+    B: DeviceGEMMAPI<TA, TB, TC>,
+{
+    tensordot(a, b, ([-1], [-1]))
+}
+
+/// Dot product of two tensors, with the first argument complex conjugated.
+///
+/// Tensors `a` and `b` should be of the same shape; all axes are contracted.
+///
+/// # See also
+///
+/// - [`numpy.vdot`](https://numpy.org/doc/stable/reference/generated/numpy.vdot.html)
+pub fn vdot<RA, RB, T, DA, DB, B>(
+    a: &TensorBase<RA, DA>,
+    b: &TensorBase<RB, DB>,
+) -> Result<Tensor<T, Ix0, B>>
+where
+    RA: DataAPI<Data = Storage<T, B>>,
+    RB: DataAPI<Data = Storage<T, B>>,
+    DA: DimAPI,
+    DB: DimAPI,
+    T: ComplexFloat,
+    B: DeviceAPI<T> + DeviceCreationAnyAPI<T> + DeviceCreationNumAPI<T>,
+    B: OpAssignAPI<T, IxD> + DeviceGEMMAPI<T, T, T>,
+    B: DeviceOp_MutA_RefB_API<T, T, IxD, dyn Fn(&mut T, &T) + Send + Sync>,
+{
+    rstsr_assert!(a.device().same_device(b.device()), DeviceMismatch)?;
+    let la = a.layout().to_dim::<IxD>()?;
+    let lb = b.layout().to_dim::<IxD>()?;
+    rstsr_assert_eq!(la.shape(), lb.shape(), InvalidLayout)?;
+
+    // conjugate of a
+    let device = a.device();
+    let la_conj = layout_for_array_copy(&la, TensorIterOrder::K)?;
+    let mut sa_conj = unsafe { device.empty_impl(la_conj.size())? };
+    let f = &mut |x: &mut T, y: &T| *x = y.conj();
+    device.op_muta_refb_func(&mut sa_conj, &la_conj, a.data().storage(), &la, f)?;
+
+    let axes = (0..la.ndim() as isize).collect_vec();
+    let axes = TensorDotAxes::Pair(axes.clone(), axes);
+    tensordot_f(&sa_conj, &la_conj, b.data().storage(), &lb, axes)?.into_dim::<Ix0>()
+}
+
+/// Kronecker product of two tensors.
+///
+/// If numbers of dimensions differ, the tensor with fewer dimensions is
+/// prepended with axes of size 1.
+///
+/// # See also
+///
+/// - [`numpy.kron`](https://numpy.org/doc/stable/reference/generated/numpy.kron.html)
+pub fn kron<RA, RB, TA, TB, TC, DA, DB, B>(
+    a: &TensorBase<RA, DA>,
+    b: &TensorBase<RB, DB>,
+) -> Result<Tensor<TC, IxD, B>>
+where
+    RA: DataAPI<Data = Storage<TA, B>>,
+    RB: DataAPI<Data = Storage<TB, B>>,
+    DA: DimAPI,
+    DB: DimAPI,
+    TA: Clone + Mul<TB, Output = TC>,
+    TB: Clone,
+    TC: Clone + Mul<TC, Output = TC> + Add<TC, Output = TC> + Num,
+    B: DeviceAPI<TA> + DeviceCreationAnyAPI<TA> + OpAssignAPI<TA, IxD>,
+    B: DeviceAPI<TB> + DeviceCreationAnyAPI<TB> + OpAssignAPI<TB, IxD>,
+    B: DeviceAPI<TC> + DeviceCreationAnyAPI<TC> + DeviceCreationNumAPI<TC>,
+    B: OpAssignAPI<TC, IxD> + DeviceGEMMAPI<TA, TB, TC>,
+{
+    let la = a.layout().to_dim::<IxD>()?;
+    let lb = b.layout().to_dim::<IxD>()?;
+    let nd = la.ndim().max(lb.ndim());
+    let pad = |l: Layout<IxD>| {
+        let npad = nd - l.ndim();
+        let shape = [vec![1; npad], l.shape().clone()].concat();
+        let stride = [vec![1; npad], l.stride().clone()].concat();
+        unsafe { Layout::new_unchecked(shape, stride, l.offset()) }
+    };
+    let (la, lb) = (pad(la), pad(lb));
+
+    // outer product, of shape [a0, a1, ..., b0, b1, ...]
+    let outer = tensordot_f(a.data().storage(), &la, b.data().storage(), &lb, 0.into())?;
+
+    // interleave axes and copy, then merge each pair of axes
+    // - c-contiguous [a0, b0, a1, b1, ...] gives index `ia * nb + ib` for each pair
+    // - f-contiguous [b0, a0, b1, a1, ...] gives the same index
+    let order = TensorOrder::default();
+    let perm = (0..nd as isize)
+        .flat_map(|k| match order {
+            TensorOrder::C => [k, k + nd as isize],
+            TensorOrder::F => [k + nd as isize, k],
+        })
+        .collect_vec();
+    let l_outer = outer.layout().transpose(&perm)?;
+    let l_kron = l_outer.shape().new_contig(None);
+    let device = outer.device();
+    let mut s_kron = unsafe { device.empty_impl(l_kron.size())? };
+    device.assign(&mut s_kron, &l_kron, outer.data().storage(), &l_outer)?;
+    let shape = izip!(la.shape(), lb.shape()).map(|(&sa, &sb)| sa * sb).collect_vec();
+    let l_kron = shape.new_contig(None);
+    return Ok(unsafe { Tensor::new_unchecked(s_kron.into(), l_kron) });
+}
+
+/* #endregion */
+
+/* #region cross */
+
+/// Cross product of 3-element vectors.
+///
+/// Tensors `a` and `b` are broadcasted, and the size of axis `axis` should be
+/// 3.
+///
+/// # See also
+///
+/// - [Python array API standard: `linalg.cross`](https://data-apis.org/array-api/2023.12/extensions/generated/array_api.linalg.cross.html)
+pub fn cross<RA, RB, T, DA, DB, B>(
+    a: &TensorBase<RA, DA>,
+    b: &TensorBase<RB, DB>,
+    axis: isize,
+) -> Result<Tensor<T, IxD, B>>
+where
+    RA: DataAPI<Data = Storage<T, B>>,
+    RB: DataAPI<Data = Storage<T, B>>,
+    DA: DimAPI,
+    DB: DimAPI,
+    T: Clone + Mul<T, Output = T> + SubAssign<T>,
+    B: DeviceAPI<T> + DeviceCreationAnyAPI<T>,
+    B: DeviceMulAPI<T, T, T, IxD> + DeviceSubAssignAPI<T, T, IxD>,
+{
+    rstsr_assert!(a.device().same_device(b.device()), DeviceMismatch)?;
+    let la = a.layout().to_dim::<IxD>()?;
+    let lb = b.layout().to_dim::<IxD>()?;
+    let (la, lb) = broadcast_layout(&la, &lb)?;
+    let nd = la.ndim();
+    let axis = normalize_axes(&[axis], nd)?[0];
+    rstsr_assert_eq!(la.shape()[axis], 3, InvalidLayout, "Cross product requires 3-vectors.")?;
+    let axis = axis as isize;
+
+    let (sa, sb) = (a.data().storage(), b.data().storage());
+    let device = a.device();
+    let lc = la.shape().new_contig(None);
+    let mut sc = unsafe { device.empty_impl(lc.size())? };
+    let lt = lc.dim_select(axis, 0)?.shape().new_contig(None);
+    let mut st = unsafe { device.empty_impl(lt.size())? };
+    for (i, j, k) in [(0, 1, 2), (1, 2, 0), (2, 0, 1)] {
+        // c_i = a_j b_k - a_k b_j
+        let lc_i = lc.dim_select(axis, i)?;
+        let (la_j, la_k) = (la.dim_select(axis, j)?, la.dim_select(axis, k)?);
+        let (lb_j, lb_k) = (lb.dim_select(axis, j)?, lb.dim_select(axis, k)?);
+        device.op_mutc_refa_refb(&mut sc, &lc_i, sa, &la_j, sb, &lb_k)?;
+        device.op_mutc_refa_refb(&mut st, &lt, sa, &la_k, sb, &lb_j)?;
+        device.op_muta_refb(&mut sc, &lc_i, &st, &lt)?;
+    }
+    return Ok(unsafe { Tensor::new_unchecked(sc.into(), lc) });
+}
+
+/* #endregion */
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use num::complex::Complex64;
+
+    #[test]
+    fn test_tensordot() {
+        let va = (0..24).map(|x| x as f64).collect_vec();
+        let vb = (0..24).map(|x| (x as f64) * 0.5 - 3.0).collect_vec();
+        let a = Tensor::asarray(va.clone()).unwrap().into_shape_assume_contig([2, 3, 4]).unwrap();
+        let b = Tensor::asarray(vb.clone()).unwrap().into_shape_assume_contig([4, 3, 2]).unwrap();
+
+        // c[i, l] = sum_{j, k} a[i, j, k] b[k, j, l]
+        let c = tensordot(&a, &b, ([1, 2], [1, 0])).unwrap();
+        assert_eq!(c.shape(), &[2, 2]);
+        let mut c_ref = vec![0.0; 4];
+        for (i, j, k, l) in itertools::iproduct!(0..2, 0..3, 0..4, 0..2) {
+            c_ref[i * 2 + l] += va[i * 12 + j * 4 + k] * vb[k * 6 + j * 2 + l];
+        }
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), c_ref);
+
+        // same contraction in number form: a[i, j, k] b[j, k, l] after permuting b
+        let b_t = b.transpose(&[1, 0, 2]).unwrap();
+        let c = tensordot(&a.swapaxes(1, 2), &b_t.swapaxes(0, 1), 2).unwrap();
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), c_ref);
+
+        // no contraction
+        let c = tensordot(&a, &b, 0).unwrap();
+        assert_eq!(c.shape(), &[2, 3, 4, 4, 3, 2]);
+
+        // errors
+        assert!(tensordot(&a, &b, ([0], [0])).is_err());
+        assert!(tensordot(&a, &b, ([1, 1], [1, 1])).is_err());
+        assert!(tensordot(&a, &b, 4).is_err());
+        assert!(tensordot(&a, &b, -1).is_err());
+        assert!(tensordot(&a, &b, ([-4], [0])).is_err());
+        assert!(tensordot(&a, &b, ([usize::MAX], [0])).is_err());
+        assert!(tensordot(&a, &b, ([i64::MIN], [0])).is_err());
+    }
+
+    #[test]
+    fn test_outer_inner_vdot() {
+        let a = Tensor::asarray(vec![1.0, 2.0, 3.0]).unwrap();
+        let b = Tensor::asarray(vec![4.0, 5.0]).unwrap();
+        let c = outer(&a, &b).unwrap();
+        assert_eq!(c.shape(), &[3, 2]);
+        let c = c.data().storage().to_cpu_vec().unwrap();
+        assert_eq!(c, vec![4.0, 5.0, 8.0, 10.0, 12.0, 15.0]);
+
+        let a = Tensor::linspace_cpu(0.0, 5.0, 6).into_shape_assume_contig([2, 3]).unwrap();
+        let b = Tensor::linspace_cpu(0.0, 2.0, 3);
+        let c = inner(&a, &b).unwrap();
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), vec![5.0, 14.0]);
+
+        let a = Tensor::asarray(vec![Complex64::new(1.0, 2.0), Complex64::new(3.0, -1.0)]).unwrap();
+        let b = Tensor::asarray(vec![Complex64::new(2.0, -1.0), Complex64::new(1.0, 1.0)]).unwrap();
+        let c = vdot(&a, &b).unwrap();
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), vec![Complex64::new(2.0, -1.0)]);
+    }
+
+    #[test]
+    fn test_kron() {
+        let a = Tensor::asarray(vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+        let a = a.into_shape_assume_contig([2, 2]).unwrap();
+        let b = Tensor::asarray(vec![0.0, 5.0, 6.0, 7.0]).unwrap();
+        let b = b.into_shape_assume_contig([2, 2]).unwrap();
+        let c = kron(&a, &b).unwrap();
+        assert_eq!(c.shape(), &[4, 4]);
+        #[rustfmt::skip]
+        let c_ref = vec![
+             0.0,  5.0,  0.0, 10.0,
+             6.0,  7.0, 12.0, 14.0,
+             0.0, 15.0,  0.0, 20.0,
+            18.0, 21.0, 24.0, 28.0,
+        ];
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), c_ref);
+
+        // different number of dimensions
+        let b = Tensor::asarray(vec![1.0, -1.0]).unwrap();
+        let c = kron(&a, &b).unwrap();
+        assert_eq!(c.shape(), &[2, 4]);
+        let c_ref = vec![1.0, -1.0, 2.0, -2.0, 3.0, -3.0, 4.0, -4.0];
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), c_ref);
+    }
+
+    #[test]
+    fn test_cross() {
+        let a = Tensor::asarray(vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0]).unwrap();
+        let a = a.into_shape_assume_contig([2, 3]).unwrap();
+        let b = Tensor::asarray(vec![0.0, 0.0, 1.0]).unwrap();
+        let c = cross(&a, &b, -1).unwrap();
+        assert_eq!(c.shape(), &[2, 3]);
+        let c_ref = vec![0.0, -1.0, 0.0, 1.0, 0.0, 0.0];
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), c_ref);
+
+        // vectors along axis 0
+        let c = cross(&a.reverse_axes(), &b.to_shape_assume_contig([3, 1]).unwrap(), 0).unwrap();
+        assert_eq!(c.shape(), &[3, 2]);
+        let c_ref = vec![0.0, 1.0, -1.0, 0.0, 0.0, 0.0];
+        assert_eq!(c.data().storage().to_cpu_vec().unwrap(), c_ref);
+        assert!(cross(&a, &b, 0).is_err());
+    }
+}
@@ -0,0 +1,447 @@
+//! Lock-step elementwise iteration of multiple tensors.
+//!
+//! [`Zip`] takes up to 6 tensors, by reference (element given as `&T`) or by
+//! mutable reference (element given as `&mut T`). Tensors are broadcasted to
+//! a common shape; mutable tensors can not be broadcasted, and must not have
+//! overlapping elements. Iteration order is the most efficient one shared by
+//! all tensors (see [`TensorIterOrder::K`]), so closures should not depend on
+//! it.
+//!
+//! ```
+//! use rstsr::prelude_dev::*;
+//!
+//! let rho = Tensor::from_shape_vec([2, 3], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+//! let weights = Tensor::from_shape_vec([3], vec![0.5, 1.0, 2.0]).unwrap();
+//! let mut exc = Tensor::from_shape_vec([2, 3], vec![0.0; 6]).unwrap();
+//! Zip::from(&mut exc).and(&rho).and(&weights).for_each(|e, &r, &w| *e = r * w);
+//! assert_eq!(exc.iter().copied().collect::<Vec<_>>(), [0.5, 2.0, 6.0, 2.0, 5.0, 12.0]);
+//! ```
+
+use crate::prelude_dev::*;
+
+// this value is used to determine whether to use contiguous inner iteration
+const CONTIG_SWITCH: usize = 16;
+
+/* #region zip parts */
+
+/// Element access of a tensor in [`Zip`] by memory offset.
+pub trait ZipPartAPI {
+    type Item;
+
+    /// Whether items are mutable references.
+    const MUTABLE: bool;
+
+    fn layout(&self) -> &Layout<IxD>;
+
+    fn layout_mut(&mut self) -> &mut Layout<IxD>;
+
+    /// # Safety
+    ///
+    /// `offset` must be in bounds of buffer. For mutable parts, item of the
+    /// same offset must not be given twice.
+    unsafe fn item(&self, offset: usize) -> Self::Item;
+}
+
+/// Conversion of (mutable) reference of tensor into part of [`Zip`].
+pub trait IntoZipPartAPI {
+    type Part: ZipPartAPI;
+    type Device: DeviceBaseAPI;
+    fn into_zip_part(self) -> (Self::Part, Self::Device);
+}
+
+/// Part of [`Zip`] that gives element references.
+#[derive(Debug, Clone)]
+pub struct ZipRef<'a, T> {
+    slc: CpuRawSlice<'a, T>,
+    layout: Layout<IxD>,
+}
+
+/// Part of [`Zip`] that gives mutable element references.
+#[derive(Debug)]
+pub struct ZipMut<'a, T> {
+    ptr: *mut T,
+    len: usize,
+    layout: Layout<IxD>,
+    _phantom: PhantomData<&'a mut T>,
+}
+
+// SAFETY: behaves as `&mut [T]`, whose elements are given to one thread each
+unsafe impl<T: Send> Send for ZipMut<'_, T> {}
+unsafe impl<T: Send> Sync for ZipMut<'_, T> {}
+
+impl<'a, T> ZipPartAPI for ZipRef<'a, T> {
+    type Item = &'a T;
+    const MUTABLE: bool = false;
+
+    fn layout(&self) -> &Layout<IxD> {
+        &self.layout
+    }
+
+    fn layout_mut(&mut self) -> &mut Layout<IxD> {
+        &mut self.layout
+    }
+
+    #[inline]
+    unsafe fn item(&self, offset: usize) -> Self::Item {
+        self.slc.get_unchecked(offset)
+    }
+}
+
+impl<'a, T> ZipPartAPI for ZipMut<'a, T> {
+    type Item = &'a mut T;
+    const MUTABLE: bool = true;
+
+    fn layout(&self) -> &Layout<IxD> {
+        &self.layout
+    }
+
+    fn layout_mut(&mut self) -> &mut Layout<IxD> {
+        &mut self.layout
+    }
+
+    #[inline]
+    unsafe fn item(&self, offset: usize) -> Self::Item {
+        debug_assert!(offset < self.len);
+        &mut *self.ptr.add(offset)
+    }
+}
+
+impl<'a, R, T, D, B> IntoZipPartAPI for &'a TensorBase<R, D>
+where
+    T: 'a,
+    R: DataAPI<Data = Storage<T, B>>,
+    D: DimAPI,
+    B: DeviceAPI<T> + 'a,
+    B::RawVec: CpuRawVecAPI<T>,
+{
+    type Part = ZipRef<'a, T>;
+    type Device = B;
+
+    fn into_zip_part(self) -> (Self::Part, Self::Device) {
+        let layout = self.layout().to_dim::<IxD>().unwrap();
+        let slc = self.data().storage().rawvec().as_raw_slice();
+        return (ZipRef { slc, layout }, self.device().clone());
+    }
+}
+
+impl<'a, R, T, D, B> IntoZipPartAPI for &'a mut TensorBase<R, D>
+where
+    T: 'a,
+    R: DataMutAPI<Data = Storage<T, B>>,
+    D: DimAPI,
+    B: DeviceAPI<T> + 'a,
+    B::RawVec: CpuRawVecAPI<T>,
+{
+    type Part = ZipMut<'a, T>;
+    type Device = B;
+
+    fn into_zip_part(self) -> (Self::Part, Self::Device) {
+        let layout = self.layout().to_dim::<IxD>().unwrap();
+        let device = self.device().clone();
+        let mut slc = unsafe { self.data_mut().storage_mut() }.rawvec_mut().as_raw_slice_mut();
+        let (ptr, len) = (slc.as_mut_ptr(), slc.len());
+        return (ZipMut { ptr, len, layout, _phantom: PhantomData }, device);
+    }
+}
+
+/* #endregion */
+
+/* #region zip layouts */
+
+/// Broadcast layouts of parts to a common shape, and check that mutable parts
+/// are neither broadcasted nor overlapping.
+fn broadcast_zip_layouts(layouts: &mut [&mut Layout<IxD>], mutable: &[bool]) -> Result<IxD> {
+    let mut shape = layouts[0].shape().clone();
+    for layout in layouts.iter().skip(1) {
+        shape = broadcast_shape(&shape, layout.shape())?.0;
+    }
+    let layout_shape = shape.c();
+    for (layout, &is_mut) in layouts.iter_mut().zip(mutable) {
+        if is_mut {
+            rstsr_assert_eq!(
+                layout.shape(),
+                &shape,
+                InvalidLayout,
+                "Mutable tensor in zip can not be broadcasted."
+            )?;
+            layout.check_strides()?;
+        }
+        **layout = broadcast_layout(&**layout, &layout_shape)?.0;
+    }
+    return Ok(shape);
+}
+
+/// Layouts of parts that iterate in lock-step.
+///
+/// Outer iteration is col-major iteration of `layouts`; each outer iteration
+/// has `size_contig` contiguous elements.
+struct ZipLayouts {
+    layouts: Vec<Layout<IxD>>,
+    size_contig: usize,
+}
+
+impl ZipLayouts {
+    fn new(layouts: &[&Layout<IxD>]) -> Result<Self> {
+        let layouts_full = translate_to_col_major(layouts, TensorIterOrder::K)?;
+        let layouts_full_ref = layouts_full.iter().collect_vec();
+        let (layouts_contig, size_contig) = translate_to_col_major_with_contig(&layouts_full_ref);
+        if size_contig >= CONTIG_SWITCH {
+            return Ok(Self { layouts: layouts_contig, size_contig });
+        } else {
+            return Ok(Self { layouts: layouts_full, size_contig: 1 });
+        }
+    }
+
+    /// Number of outer iterations.
+    fn len(&self) -> usize {
+        self.layouts[0].size()
+    }
+
+    /// Call `f` on offsets of parts, for outer iterations `start..end`.
+    fn for_each_range(&self, start: usize, end: usize, f: &mut dyn FnMut(&[usize])) {
+        let mut iters = self
+            .layouts
+            .iter()
+            .map(|layout| {
+                let iter = IterLayoutColMajor::new(layout).unwrap();
+                let iter = iter.split_at(start).unwrap().1;
+                iter.split_at(end - start).unwrap().0
+            })
+            .collect_vec();
+        let mut offsets_outer = vec![0; iters.len()];
+        let mut offsets = vec![0; iters.len()];
+        for _ in start..end {
+            for (offset, iter) in offsets_outer.iter_mut().zip(iters.iter_mut()) {
+                *offset = iter.next().unwrap();
+            }
+            for i in 0..self.size_contig {
+                offsets.iter_mut().zip(&offsets_outer).for_each(|(o, &o_outer)| *o = o_outer + i);
+                f(&offsets);
+            }
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    fn par_for_each(&self, f: &(dyn Fn(&[usize]) + Sync)) {
+        use rayon::prelude::*;
+        let len = self.len();
+        let nchunk = (4 * rayon::current_num_threads()).min(len);
+        (0..nchunk).into_par_iter().for_each(|k| {
+            let (start, end) = (len * k / nchunk, len * (k + 1) / nchunk);
+            self.for_each_range(start, end, &mut |offsets| f(offsets));
+        });
+    }
+}
+
+/* #endregion */
+
+/* #region zip */
+
+/// Lock-step elementwise iteration of up to 6 tensors.
+///
+/// See [module documentation](self).
+pub struct Zip<P, B> {
+    parts: P,
+    device: B,
+}
+
+impl<P1, B> Zip<(P1,), B>
+where
+    P1: ZipPartAPI,
+    B: DeviceBaseAPI,
+{
+    /// Start zip from (mutable) reference of tensor.
+    pub fn from<I>(part: I) -> Self
+    where
+        I: IntoZipPartAPI<Part = P1, Device = B>,
+    {
+        let (part, device) = part.into_zip_part();
+        Zip { parts: (part,), device }
+    }
+}
+
+macro_rules! impl_zip {
+    ([$($P:ident $idx:tt),+], $PNext:ident) => {
+        impl<$($P),+, B> Zip<($($P,)+), B>
+        where
+            $($P: ZipPartAPI),+,
+            B: DeviceBaseAPI,
+        {
+            /// Add (mutable) reference of tensor to zip.
+            ///
+            /// # Panics
+            ///
+            /// - Tensor is on a different device.
+            pub fn and<I, $PNext>(self, part: I) -> Zip<($($P,)+ $PNext,), B>
+            where
+                I: IntoZipPartAPI<Part = $PNext, Device = B>,
+                $PNext: ZipPartAPI,
+            {
+                let (part, device) = part.into_zip_part();
+                rstsr_assert!(self.device.same_device(&device), DeviceMismatch).unwrap();
+                Zip { parts: ($(self.parts.$idx,)+ part,), device: self.device }
+            }
+        }
+    };
+}
+
+impl_zip!([P1 0], P2);
+impl_zip!([P1 0, P2 1], P3);
+impl_zip!([P1 0, P2 1, P3 2], P4);
+impl_zip!([P1 0, P2 1, P3 2, P4 3], P5);
+impl_zip!([P1 0, P2 1, P3 2, P4 3, P5 4], P6);
+
+macro_rules! impl_zip_apply {
+    ([$($P:ident $idx:tt),+]) => {
+        impl<$($P),+, B> Zip<($($P,)+), B>
+        where
+            $($P: ZipPartAPI),+,
+            B: DeviceBaseAPI,
+        {
+            fn broadcast(&mut self) -> Result<IxD> {
+                broadcast_zip_layouts(
+                    &mut [$(self.parts.$idx.layout_mut()),+],
+                    &[$($P::MUTABLE),+],
+                )
+            }
+
+            /// Call `f` on elements of all tensors.
+            ///
+            /// # Panics
+            ///
+            /// - Tensors can not be broadcasted to a common shape.
+            /// - Mutable tensor is broadcasted, or has overlapping elements.
+            pub fn for_each(mut self, mut f: impl FnMut($($P::Item),+)) {
+                self.broadcast().unwrap();
+                let layouts = ZipLayouts::new(&[$(self.parts.$idx.layout()),+]).unwrap();
+                let parts = &self.parts;
+                layouts.for_each_range(0, layouts.len(), &mut |offsets| unsafe {
+                    f($(parts.$idx.item(offsets[$idx])),+)
+                });
+            }
+
+            /// Call `f` on elements of all tensors in parallel.
+            ///
+            /// # Panics
+            ///
+            /// See [`Zip::for_each`].
+            #[cfg(feature = "rayon")]
+            pub fn par_for_each(mut self, f: impl Fn($($P::Item),+) + Sync + Send)
+            where
+                $($P: Sync),+,
+            {
+                self.broadcast().unwrap();
+                let layouts = ZipLayouts::new(&[$(self.parts.$idx.layout()),+]).unwrap();
+                let parts = &self.parts;
+                layouts.par_for_each(&|offsets| unsafe {
+                    f($(parts.$idx.item(offsets[$idx])),+)
+                });
+            }
+
+            /// Call `f` on elements of all tensors, and collect results into a new
+            /// tensor of the broadcasted shape.
+            ///
+            /// # Panics
+            ///
+            /// See [`Zip::for_each`].
+            pub fn map_collect<TOut>(
+                mut self,
+                mut f: impl FnMut($($P::Item),+) -> TOut,
+            ) -> Tensor<TOut, IxD, B>
+            where
+                B: DeviceAPI<TOut>,
+                B::RawVec: From<Vec<TOut>>,
+            {
+                self.broadcast().unwrap();
+                // output is another (mutable) part of zip, with memory order of the input of
+                // largest non-broadcasted size
+                let layouts_parts = [$(self.parts.$idx.layout()),+];
+                let mut layout_ref = layouts_parts[0];
+                for &layout in layouts_parts.iter().skip(1) {
+                    if layout.size_non_broadcast() > layout_ref.size_non_broadcast() {
+                        layout_ref = layout;
+                    }
+                }
+                let lc = layout_for_array_copy(layout_ref, TensorIterOrder::K).unwrap();
+                let layouts = ZipLayouts::new(&[$(self.parts.$idx.layout()),+, &lc]).unwrap();
+                let size = lc.size();
+                let mut vec_c: Vec<TOut> = Vec::with_capacity(size);
+                let ptr_c = vec_c.as_mut_ptr();
+                let parts = &self.parts;
+                let idx_c = layouts.layouts.len() - 1;
+                layouts.for_each_range(0, layouts.len(), &mut |offsets| unsafe {
+                    ptr_c.add(offsets[idx_c]).write(f($(parts.$idx.item(offsets[$idx])),+));
+                });
+                // all elements of output are written once
+                unsafe { vec_c.set_len(size) };
+                let storage_c = Storage::new(vec_c.into(), self.device);
+                return unsafe { Tensor::new_unchecked(DataOwned::from(storage_c), lc) };
+            }
+        }
+    };
+}
+
+impl_zip_apply!([P1 0]);
+impl_zip_apply!([P1 0, P2 1]);
+impl_zip_apply!([P1 0, P2 1, P3 2]);
+impl_zip_apply!([P1 0, P2 1, P3 2, P4 3]);
+impl_zip_apply!([P1 0, P2 1, P3 2, P4 3, P5 4]);
+impl_zip_apply!([P1 0, P2 1, P3 2, P4 3, P5 4, P6 5]);
+
+/* #endregion */
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_zip() {
+        let rho = Tensor::from_shape_vec([4, 8, 5], (0..160).map(|x| x as f64).collect()).unwrap();
+        let sigma = rho.mapv(|x| 2.0 * x);
+        let tau = Tensor::from_shape_vec([8, 1], (0..8).map(|x| x as f64).collect()).unwrap();
+        let weights = Tensor::from_shape_vec([5], vec![1.0, 0.5, 0.25, 0.125, 0.0625]).unwrap();
+
+        let mut exc = Tensor::from_shape_vec([4, 8, 5], vec![0.0; 160]).unwrap();
+        let mut vrho = Tensor::from_shape_vec([4, 8, 5], vec![0.0; 160]).unwrap();
+        Zip::from(&mut exc).and(&rho).and(&sigma).and(&tau).and(&weights).and(&mut vrho).for_each(
+            |e, &r, &s, &t, &w, v| {
+                *e = (r + s + t) * w;
+                *v = r * w;
+            },
+        );
+        for (idx, &e) in exc.indexed_iter() {
+            let (i, j, k) = (idx[0], idx[1], idx[2]);
+            let r = (40 * i + 5 * j + k) as f64;
+            assert_eq!(e, (3.0 * r + j as f64) * 0.5_f64.powi(k as i32));
+        }
+
+        let c = Zip::from(&rho).and(&tau).map_collect(|&r, &t| r - t);
+        assert_eq!(c.shape(), &vec![4, 8, 5]);
+        assert!(c.indexed_iter().all(|(idx, &x)| x == (40 * idx[0] + 4 * idx[1] + idx[2]) as f64));
+
+        // output follows memory order of inputs
+        let rho_t = rho.reverse_axes();
+        let c = Zip::from(&tau).and(&rho_t).map_collect(|&t, &r| r - t);
+        assert!(c.layout().f_contig());
+        assert!(c.indexed_iter().all(|(idx, &x)| x == (40 * idx[2] + 4 * idx[1] + idx[0]) as f64));
+
+        // mutable tensor can not be broadcasted
+        let mut w = weights.to_owned();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            Zip::from(&mut w).and(&rho).for_each(|w, &r| *w = r);
+        }));
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_zip_par() {
+        let a = Tensor::from_shape_vec([64, 64], (0..4096).collect::<Vec<usize>>()).unwrap();
+        let b = Tensor::from_shape_vec([64], (0..64).collect::<Vec<usize>>()).unwrap();
+        let mut c = Tensor::from_shape_vec([64, 64], vec![0; 4096]).unwrap();
+        let mut d = Tensor::from_shape_vec([64, 64], vec![0; 4096]).unwrap();
+        Zip::from(&mut c).and(&a).and(&b).par_for_each(|c, &a, &b| *c = a + b);
+        Zip::from(&mut d).and(&a).and(&b).for_each(|d, &a, &b| *d = a + b);
+        assert_eq!(c.iter().collect_vec(), d.iter().collect_vec());
+    }
+}
@@ -81,7 +81,7 @@ where
         // check pointer exceed
         let len_data = data.storage().len();
         let (_, idx_max) = layout.bounds_index()?;
-        rstsr_pattern!(idx_max, len_data.., ValueOutOfRange)?;
+        rstsr_pattern!(idx_max, ..=len_data, ValueOutOfRange)?;
         return Ok(Self { data, layout });
     }
 
@@ -101,6 +101,7 @@ pub type Tensor<T, D, B = DeviceCpu> = TensorBase<DataOwned<Storage<T, B>>, D>;
 pub type TensorView<'a, T, D, B = DeviceCpu> = TensorBase<DataRef<'a, Storage<T, B>>, D>;
 pub type TensorViewMut<'a, T, D, B = DeviceCpu> = TensorBase<DataRefMut<'a, Storage<T, B>>, D>;
 pub type TensorCow<'a, T, D, B = DeviceCpu> = TensorBase<DataCow<'a, Storage<T, B>>, D>;
+pub type TensorArc<T, D, B = DeviceCpu> = TensorBase<DataArc<Storage<T, B>>, D>;
 
 impl<T, D, B> Tensor<T, D, B>
 where